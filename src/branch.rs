@@ -0,0 +1,101 @@
+use crate::error::{Error, Result};
+use crate::template::{self, VERSION_VARS};
+use crate::version::Version;
+use regex::Regex;
+
+/// Outcome of checking a branch name against `git.branch_pattern`
+#[derive(Debug, PartialEq, Eq)]
+pub enum BranchRule {
+    /// The branch does not look like a versioned branch; no constraint applies
+    NotApplicable,
+    /// The branch matches the pattern for the current version
+    Consistent,
+    /// The branch is a versioned branch for a different version
+    Inconsistent,
+}
+
+/// Evaluate a branch pattern for a branch name and version
+///
+/// The pattern is a regex in which `{major}`, `{minor}`, `{patch}`,
+/// `{version}`, `{prerelease}` and `{build}` stand for version components.
+/// It only applies to branches matching the pattern with the placeholders
+/// replaced by wildcards; such branches must then match it with the actual
+/// components of `version`.
+pub fn evaluate(pattern: &str, branch: &str, version: &Version) -> Result<BranchRule> {
+    let wildcard = template::render(pattern, |name| {
+        VERSION_VARS
+            .contains(&name)
+            .then(|| "[0-9A-Za-z.+-]+".to_string())
+    });
+    let specific = template::render(pattern, |name| {
+        template::version_var(version, name).map(|value| regex::escape(&value))
+    });
+
+    if !compile(&wildcard)?.is_match(branch) {
+        return Ok(BranchRule::NotApplicable);
+    }
+
+    if compile(&specific)?.is_match(branch) {
+        Ok(BranchRule::Consistent)
+    } else {
+        Ok(BranchRule::Inconsistent)
+    }
+}
+
+fn compile(pattern: &str) -> Result<Regex> {
+    Regex::new(pattern)
+        .map_err(|e| Error::ConfigParse(format!("Invalid git.branch_pattern: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATTERN: &str = r"^release/{major}\.{minor}\.x$";
+
+    fn evaluate_for(branch: &str, version: &str) -> BranchRule {
+        evaluate(PATTERN, branch, &Version::parse(version).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_consistent_branch() {
+        assert_eq!(
+            evaluate_for("release/1.2.x", "1.2.0"),
+            BranchRule::Consistent
+        );
+        assert_eq!(
+            evaluate_for("release/1.2.x", "1.2.7-rc.1"),
+            BranchRule::Consistent
+        );
+    }
+
+    #[test]
+    fn test_inconsistent_branch() {
+        assert_eq!(
+            evaluate_for("release/1.3.x", "1.2.0"),
+            BranchRule::Inconsistent
+        );
+        assert_eq!(
+            evaluate_for("release/2.2.x", "1.2.0"),
+            BranchRule::Inconsistent
+        );
+    }
+
+    #[test]
+    fn test_unrelated_branch() {
+        assert_eq!(evaluate_for("main", "1.2.0"), BranchRule::NotApplicable);
+        assert_eq!(
+            evaluate_for("feature/release", "1.2.0"),
+            BranchRule::NotApplicable
+        );
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        let version = Version::parse("1.0.0").unwrap();
+        assert!(matches!(
+            evaluate("release/(", "main", &version),
+            Err(Error::ConfigParse(_))
+        ));
+    }
+}
@@ -0,0 +1,210 @@
+use crate::config::NetworkConfig;
+use crate::error::Result;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// On-disk cache for responses of remote lookups
+///
+/// Entries are plain files named after a hash of their key; an entry is
+/// fresh while its modification time is within the TTL. The cache lives in
+/// `$XDG_CACHE_HOME/versync` (or `~/.cache/versync`) and is disabled when
+/// neither is available or the TTL is zero.
+#[derive(Debug)]
+pub struct Cache {
+    dir: Option<PathBuf>,
+    ttl: Duration,
+    refresh: bool,
+}
+
+impl Cache {
+    /// Create the cache configured by `[network]` (`cache_ttl`, `--refresh`)
+    pub fn new(network: &NetworkConfig) -> Self {
+        Self::at(
+            default_dir(),
+            Duration::from_secs(network.cache_ttl),
+            network.refresh,
+        )
+    }
+
+    fn at(dir: Option<PathBuf>, ttl: Duration, refresh: bool) -> Self {
+        Self { dir, ttl, refresh }
+    }
+
+    /// Return the cached value for `key`, or fetch and store it
+    ///
+    /// With `--refresh` the cached value is ignored but the fresh one is
+    /// still stored. Failing to write the cache is not an error.
+    pub fn get_or_fetch(
+        &self,
+        key: &str,
+        fetch: impl FnOnce() -> Result<String>,
+    ) -> Result<String> {
+        let Some(path) = self.path(key) else {
+            return fetch();
+        };
+
+        if !self.refresh {
+            if let Some(value) = self.read_fresh(&path) {
+                return Ok(value);
+            }
+        }
+
+        let value = fetch()?;
+        if let Some(dir) = path.parent() {
+            let tmp = path.with_extension("tmp");
+            let _ = fs::create_dir_all(dir)
+                .and_then(|()| fs::write(&tmp, &value))
+                .and_then(|()| fs::rename(&tmp, &path));
+        }
+        Ok(value)
+    }
+
+    /// Drop the cached value for `key`, e.g. after changing what it describes
+    pub fn invalidate(&self, key: &str) {
+        if let Some(path) = self.path(key) {
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    fn path(&self, key: &str) -> Option<PathBuf> {
+        if self.ttl.is_zero() {
+            return None;
+        }
+        let dir = self.dir.as_ref()?;
+        Some(dir.join(format!("{:016x}", fnv1a(key))))
+    }
+
+    fn read_fresh(&self, path: &Path) -> Option<String> {
+        let modified = fs::metadata(path).ok()?.modified().ok()?;
+        let age = SystemTime::now().duration_since(modified).ok()?;
+        if age > self.ttl {
+            return None;
+        }
+        fs::read_to_string(path).ok()
+    }
+}
+
+fn default_dir() -> Option<PathBuf> {
+    let base = match env::var_os("XDG_CACHE_HOME").filter(|v| !v.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env::var_os("HOME").filter(|v| !v.is_empty())?).join(".cache"),
+    };
+    Some(base.join("versync"))
+}
+
+/// FNV-1a, used for file names that stay stable across builds
+fn fnv1a(key: &str) -> u64 {
+    key.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn fetch_counting<'a>(
+        calls: &'a Cell<u32>,
+        value: &'a str,
+    ) -> impl FnOnce() -> Result<String> + 'a {
+        move || {
+            calls.set(calls.get() + 1);
+            Ok(value.to_string())
+        }
+    }
+
+    #[test]
+    fn test_hit_within_ttl() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::at(
+            Some(dir.path().to_path_buf()),
+            Duration::from_secs(60),
+            false,
+        );
+        let calls = Cell::new(0);
+
+        assert_eq!(
+            cache
+                .get_or_fetch("k", fetch_counting(&calls, "a"))
+                .unwrap(),
+            "a"
+        );
+        assert_eq!(
+            cache
+                .get_or_fetch("k", fetch_counting(&calls, "b"))
+                .unwrap(),
+            "a"
+        );
+        assert_eq!(
+            cache
+                .get_or_fetch("other", fetch_counting(&calls, "c"))
+                .unwrap(),
+            "c"
+        );
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn test_refresh_bypasses_and_updates() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = Some(dir.path().to_path_buf());
+        let ttl = Duration::from_secs(60);
+        let calls = Cell::new(0);
+
+        Cache::at(path.clone(), ttl, false)
+            .get_or_fetch("k", fetch_counting(&calls, "old"))
+            .unwrap();
+        let refreshed = Cache::at(path.clone(), ttl, true)
+            .get_or_fetch("k", fetch_counting(&calls, "new"))
+            .unwrap();
+        assert_eq!(refreshed, "new");
+        let cached = Cache::at(path, ttl, false)
+            .get_or_fetch("k", fetch_counting(&calls, "unused"))
+            .unwrap();
+        assert_eq!(cached, "new");
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn test_invalidate() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::at(
+            Some(dir.path().to_path_buf()),
+            Duration::from_secs(60),
+            false,
+        );
+        let calls = Cell::new(0);
+
+        cache
+            .get_or_fetch("k", fetch_counting(&calls, "old"))
+            .unwrap();
+        cache.invalidate("k");
+        cache.invalidate("missing");
+        assert_eq!(
+            cache
+                .get_or_fetch("k", fetch_counting(&calls, "new"))
+                .unwrap(),
+            "new"
+        );
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn test_zero_ttl_disables_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::at(Some(dir.path().to_path_buf()), Duration::ZERO, false);
+        let calls = Cell::new(0);
+
+        cache
+            .get_or_fetch("k", fetch_counting(&calls, "a"))
+            .unwrap();
+        cache
+            .get_or_fetch("k", fetch_counting(&calls, "a"))
+            .unwrap();
+        assert_eq!(calls.get(), 2);
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+}
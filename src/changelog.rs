@@ -0,0 +1,171 @@
+use crate::commits::Conventional;
+use crate::git::Commit;
+
+/// Changelog groups, in output order: heading and the commit types it holds
+const GROUPS: &[(&str, &[&str])] = &[
+    ("Features", &["feat"]),
+    ("Bug Fixes", &["fix"]),
+    ("Performance", &["perf"]),
+    ("Reverts", &["revert"]),
+];
+
+/// Heading for commits with breaking changes, listed first
+const BREAKING: &str = "Breaking Changes";
+
+/// Render a changelog section for a version
+///
+/// Conventional commits are grouped by type; breaking changes are listed in
+/// their own group first. Other types (docs, chore, ...) and commits that
+/// are not conventional are left out.
+pub fn render_section(version: &str, date: &str, commits: &[Commit]) -> String {
+    let parsed: Vec<(Conventional, &Commit)> = commits
+        .iter()
+        .filter_map(|commit| Some((Conventional::parse(&commit.message)?, commit)))
+        .collect();
+
+    let mut section = format!("## {} - {}\n", version, date);
+
+    let breaking: Vec<_> = parsed.iter().filter(|(c, _)| c.breaking).collect();
+    push_group(&mut section, BREAKING, &breaking);
+
+    for (heading, kinds) in GROUPS {
+        let entries: Vec<_> = parsed
+            .iter()
+            .filter(|(c, _)| !c.breaking && kinds.contains(&c.kind.as_str()))
+            .collect();
+        push_group(&mut section, heading, &entries);
+    }
+
+    section
+}
+
+fn push_group(section: &mut String, heading: &str, entries: &[&(Conventional, &Commit)]) {
+    if entries.is_empty() {
+        return;
+    }
+    section.push_str(&format!("\n### {}\n\n", heading));
+    for (conventional, commit) in entries {
+        let scope = match &conventional.scope {
+            Some(scope) => format!("**{}:** ", scope),
+            None => String::new(),
+        };
+        let sha = &commit.sha[..commit.sha.len().min(7)];
+        section.push_str(&format!(
+            "- {}{} ({})\n",
+            scope, conventional.description, sha
+        ));
+    }
+}
+
+/// Whether a changelog already has a section for a version
+pub fn has_section(changelog: &str, version: &str) -> bool {
+    let heading = format!("## {}", version);
+    changelog.lines().any(|line| {
+        line.strip_prefix(&heading)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+    })
+}
+
+/// Insert a section above the existing entries
+///
+/// The section goes after a leading title (`# Changelog` and its intro
+/// text) if there is one, before the first `## ` heading.
+pub fn prepend(changelog: &str, section: &str) -> String {
+    if changelog.trim().is_empty() {
+        return format!("# Changelog\n\n{}", section);
+    }
+
+    let insert_at = if changelog.starts_with("# ") {
+        let mut offset = 0;
+        let mut found = None;
+        for line in changelog.split_inclusive('\n') {
+            if line.starts_with("## ") {
+                found = Some(offset);
+                break;
+            }
+            offset += line.len();
+        }
+        found.unwrap_or(changelog.len())
+    } else {
+        0
+    };
+
+    let (head, tail) = changelog.split_at(insert_at);
+    let mut output = head.to_string();
+    if !output.is_empty() && !output.ends_with("\n\n") {
+        output.push_str(if output.ends_with('\n') { "\n" } else { "\n\n" });
+    }
+    output.push_str(section);
+    if !tail.is_empty() {
+        output.push('\n');
+        output.push_str(tail);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(sha: &str, message: &str) -> Commit {
+        Commit {
+            sha: sha.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_render_section() {
+        let commits = [
+            commit("aaaaaaaaaa", "feat(cli): add bump"),
+            commit("bbbbbbbbbb", "fix: handle empty files"),
+            commit("cccccccccc", "docs: update README"),
+            commit("dddddddddd", "feat!: drop JSON5"),
+            commit("eeeeeeeeee", "Merge pull request #1"),
+        ];
+        assert_eq!(
+            render_section("1.2.0", "2024-05-01", &commits),
+            "\
+## 1.2.0 - 2024-05-01
+
+### Breaking Changes
+
+- drop JSON5 (ddddddd)
+
+### Features
+
+- **cli:** add bump (aaaaaaa)
+
+### Bug Fixes
+
+- handle empty files (bbbbbbb)
+"
+        );
+    }
+
+    #[test]
+    fn test_prepend() {
+        let section = "## 1.1.0 - 2024-05-01\n\n- new\n";
+        assert_eq!(
+            prepend("", section),
+            "# Changelog\n\n## 1.1.0 - 2024-05-01\n\n- new\n"
+        );
+        assert_eq!(
+            prepend("# Changelog\n\nAll notable changes.\n\n## 1.0.0\n\n- old\n", section),
+            "# Changelog\n\nAll notable changes.\n\n## 1.1.0 - 2024-05-01\n\n- new\n\n## 1.0.0\n\n- old\n"
+        );
+        assert_eq!(
+            prepend("## 1.0.0\n\n- old\n", section),
+            "## 1.1.0 - 2024-05-01\n\n- new\n\n## 1.0.0\n\n- old\n"
+        );
+    }
+
+    #[test]
+    fn test_has_section() {
+        let changelog = "# Changelog\n\n## 1.2.0 - 2024-05-01\n\n## 1.2.0-rc.1\n";
+        assert!(has_section(changelog, "1.2.0"));
+        assert!(has_section(changelog, "1.2.0-rc.1"));
+        assert!(!has_section(changelog, "1.2"));
+        assert!(!has_section(changelog, "1.3.0"));
+    }
+}
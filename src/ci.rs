@@ -0,0 +1,188 @@
+use crate::error::Error;
+use std::sync::OnceLock;
+
+/// CI systems recognized from their environment variables
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Provider {
+    GithubActions,
+    GitlabCi,
+    CircleCi,
+    Buildkite,
+    Jenkins,
+    AzurePipelines,
+    /// Any other system setting `CI`
+    Generic,
+}
+
+impl Provider {
+    /// Detect the CI system from the environment
+    fn detect(env: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        let set = |name: &str| env(name).is_some_and(|v| !v.is_empty());
+
+        if env("GITHUB_ACTIONS").is_some_and(|v| v == "true") {
+            Some(Provider::GithubActions)
+        } else if set("GITLAB_CI") {
+            Some(Provider::GitlabCi)
+        } else if set("CIRCLECI") {
+            Some(Provider::CircleCi)
+        } else if set("BUILDKITE") {
+            Some(Provider::Buildkite)
+        } else if set("JENKINS_URL") {
+            Some(Provider::Jenkins)
+        } else if set("TF_BUILD") {
+            Some(Provider::AzurePipelines)
+        } else if env("CI").is_some_and(|v| !v.is_empty() && v != "0" && v != "false") {
+            Some(Provider::Generic)
+        } else {
+            None
+        }
+    }
+}
+
+/// CI mode, enabled by `--ci` or a detected CI environment
+///
+/// In CI mode prompts are never shown (pass `--yes` to confirm), output is
+/// plain lines without colors, tables or unicode glyphs, and errors are
+/// printed with their failure class. `--ci` additionally makes warnings
+/// fail (`doctor`, `config validate`). The default is CI mode off.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Ci {
+    /// Whether CI mode is active
+    pub enabled: bool,
+    /// Whether warnings fail the command
+    pub strict: bool,
+    /// The detected CI system
+    pub provider: Option<Provider>,
+}
+
+impl Ci {
+    /// Detect CI mode for the current process
+    pub fn detect(flag: bool, disabled: bool) -> Self {
+        Self::resolve(flag, disabled, |name| std::env::var(name).ok())
+    }
+
+    /// Resolve CI mode from `--ci`, `--no-ci` and the environment
+    fn resolve(flag: bool, disabled: bool, env: impl Fn(&str) -> Option<String>) -> Self {
+        let provider = Provider::detect(env);
+        if disabled {
+            return Self {
+                provider,
+                ..Default::default()
+            };
+        }
+        Self {
+            enabled: flag || provider.is_some(),
+            strict: flag,
+            provider,
+        }
+    }
+
+    /// Format an error for stderr
+    ///
+    /// Outside CI mode this is `Error: message`. On GitHub Actions it is an
+    /// `::error` workflow command, so the error shows up as an annotation;
+    /// elsewhere it is `error[class]: message`.
+    pub fn error_line(&self, error: &Error) -> String {
+        if !self.enabled {
+            return format!("Error: {}", error);
+        }
+
+        let class = error.class().name();
+        match self.provider {
+            Some(Provider::GithubActions) => format!(
+                "::error title=versync ({})::{}",
+                class,
+                escape_workflow_data(&error.to_string())
+            ),
+            _ => format!("error[{}]: {}", class, error),
+        }
+    }
+}
+
+/// Escape a message for a GitHub Actions workflow command
+fn escape_workflow_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+static CI: OnceLock<Ci> = OnceLock::new();
+
+/// Set the process-wide CI mode (first call wins)
+pub fn init(ci: Ci) {
+    let _ = CI.set(ci);
+}
+
+/// Get the process-wide CI mode (off if never initialized)
+pub fn get() -> Ci {
+    CI.get().copied().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn resolve(flag: bool, disabled: bool, vars: &[(&str, &str)]) -> Ci {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        Ci::resolve(flag, disabled, |name| vars.get(name).cloned())
+    }
+
+    #[test]
+    fn test_detect_provider() {
+        let ci = resolve(false, false, &[("CI", "true"), ("GITHUB_ACTIONS", "true")]);
+        assert_eq!(ci.provider, Some(Provider::GithubActions));
+        assert!(ci.enabled);
+        assert!(!ci.strict);
+
+        let ci = resolve(false, false, &[("GITLAB_CI", "true")]);
+        assert_eq!(ci.provider, Some(Provider::GitlabCi));
+
+        let ci = resolve(false, false, &[("CI", "1")]);
+        assert_eq!(ci.provider, Some(Provider::Generic));
+    }
+
+    #[test]
+    fn test_not_ci() {
+        assert_eq!(resolve(false, false, &[]), Ci::default());
+        assert!(!resolve(false, false, &[("CI", "false")]).enabled);
+        assert!(!resolve(false, false, &[("CI", "")]).enabled);
+    }
+
+    #[test]
+    fn test_flags() {
+        let ci = resolve(true, false, &[]);
+        assert!(ci.enabled);
+        assert!(ci.strict);
+        assert_eq!(ci.provider, None);
+
+        let ci = resolve(false, true, &[("CI", "true")]);
+        assert!(!ci.enabled);
+        assert!(!ci.strict);
+    }
+
+    #[test]
+    fn test_error_line() {
+        let error = Error::TagNotFound("v1.0.0".to_string());
+        assert_eq!(
+            Ci::default().error_line(&error),
+            "Error: Tag not found: v1.0.0"
+        );
+        assert_eq!(
+            resolve(true, false, &[]).error_line(&error),
+            "error[git]: Tag not found: v1.0.0"
+        );
+        assert_eq!(
+            resolve(false, false, &[("GITHUB_ACTIONS", "true")]).error_line(&error),
+            "::error title=versync (git)::Tag not found: v1.0.0"
+        );
+    }
+
+    #[test]
+    fn test_escape_workflow_data() {
+        assert_eq!(escape_workflow_data("50%\nnext"), "50%25%0Anext");
+    }
+}
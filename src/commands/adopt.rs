@@ -0,0 +1,96 @@
+use crate::commands::apply::apply;
+use crate::config::{self, Config, Target};
+use crate::error::{Error, Result};
+use crate::format;
+use crate::version::Version;
+use std::path::Path;
+
+/// Options for the adopt command
+#[derive(Debug, Default)]
+pub struct AdoptOptions {
+    /// Key of the target, needed when the file has several targets
+    pub key: Option<String>,
+    /// Apply the adopted version to the other targets
+    pub apply: bool,
+}
+
+/// Find the configured target for a file (relative to the working
+/// directory) and key
+fn find_target<'a>(config: &'a Config, file: &Path, key: Option<&str>) -> Result<&'a Target> {
+    let path = config::resolve_path(file);
+    let candidates: Vec<&Target> = config
+        .targets
+        .iter()
+        .filter(|target| target.file == path)
+        .filter(|target| key.is_none_or(|key| target.key == key))
+        .collect();
+
+    match candidates.as_slice() {
+        [target] => Ok(target),
+        [] => Err(Error::Usage(match key {
+            Some(key) => format!("{} {} is not a configured target", file.display(), key),
+            None => format!("{} is not a configured target", file.display()),
+        })),
+        _ => Err(Error::Usage(format!(
+            "{} has several targets; pick one with --key ({})",
+            file.display(),
+            candidates
+                .iter()
+                .map(|target| target.key.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))),
+    }
+}
+
+/// Take the version stored in a target file as the new source of truth
+///
+/// The version must be valid semver. It is written to the config file and,
+/// with `apply`, to all targets.
+pub fn adopt(
+    config: &Config,
+    config_path: &Path,
+    file: &Path,
+    options: &AdoptOptions,
+    quiet: bool,
+) -> Result<Version> {
+    let target = find_target(config, file, options.key.as_deref())?;
+    let format = target
+        .effective_format()
+        .ok_or_else(|| Error::UnknownFormat(target.file.clone()))?;
+    let value = format::read_version(&target.file, &target.key, format)?;
+    let actual = target.version_from_value(&value).ok_or_else(|| {
+        Error::Usage(format!(
+            "{} {} holds '{}', which its template cannot be undone for; adopt a target that holds the version",
+            target.file.display(),
+            target.key,
+            value
+        ))
+    })?;
+    let version = Version::parse(&actual)?;
+
+    if actual == config.version {
+        if !quiet {
+            println!("NO CHANGE {} ({})", actual, config_path.display());
+        }
+    } else {
+        config.write_version(config_path, &actual)?;
+        if !quiet {
+            println!(
+                "ADOPTED {} from {} {} ({})",
+                actual,
+                target.file.display(),
+                target.key,
+                config_path.display()
+            );
+        }
+    }
+
+    if options.apply {
+        let mut config = config.clone();
+        config.version = actual;
+        apply(&config, quiet)?;
+    }
+
+    Ok(version)
+}
@@ -1,6 +1,10 @@
 use crate::config::{Config, Target};
 use crate::error::{Error, Result};
 use crate::format;
+use crate::policy;
+use crate::template::Context;
+use crate::term::{self, Style};
+use std::fs;
 
 /// Result of applying version to a single target
 #[derive(Debug)]
@@ -11,9 +15,19 @@ pub enum ApplyResult {
         old_version: String,
         new_version: String,
     },
+    /// A missing key inserted by a target with `create = true`
+    Created {
+        file: String,
+        key: String,
+        new_version: String,
+    },
     NoChange {
         file: String,
     },
+    /// An optional target whose file doesn't exist
+    Skipped {
+        file: String,
+    },
 }
 
 impl std::fmt::Display for ApplyResult {
@@ -31,50 +45,132 @@ impl std::fmt::Display for ApplyResult {
                     file, key, old_version, new_version
                 )
             }
+            ApplyResult::Created {
+                file,
+                key,
+                new_version,
+            } => {
+                write!(f, "CREATED {} {}: {}", file, key, new_version)
+            }
             ApplyResult::NoChange { file } => {
                 write!(f, "NO CHANGE {}", file)
             }
+            ApplyResult::Skipped { file } => {
+                write!(f, "SKIPPED {}: file not found", file)
+            }
         }
     }
 }
 
+/// Set the version and any `extra` keys of a target in its content
+///
+/// With `create`, a missing version key is inserted.
+pub fn update_content(target: &Target, content: &str, context: &Context) -> Result<String> {
+    let format = target
+        .effective_format()
+        .ok_or_else(|| Error::UnknownFormat(target.file.clone()))?;
+
+    let update = if target.create {
+        format::insert_version
+    } else {
+        format::update_version
+    };
+    let mut updated = update(
+        content,
+        &target.file,
+        &target.key,
+        &target.expected_value(context)?,
+        format,
+    )?;
+
+    for (key, template) in &target.extra {
+        let value = context.render(template)?;
+        updated = format::update_version(&updated, &target.file, key, &value, format)?;
+    }
+
+    Ok(updated)
+}
+
 /// Apply version to a single target file
-fn apply_target(target: &Target, new_version: &str) -> Result<ApplyResult> {
+///
+/// An optional target whose file is missing is skipped.
+pub fn apply_target(target: &Target, context: &Context) -> Result<ApplyResult> {
+    if !target.required && !target.file.exists() {
+        return Ok(ApplyResult::Skipped {
+            file: target.file.display().to_string(),
+        });
+    }
+
     let format = target
         .effective_format()
         .ok_or_else(|| Error::UnknownFormat(target.file.clone()))?;
 
-    let current_version = format::read_version(&target.file, &target.key, format)?;
+    let content =
+        fs::read_to_string(&target.file).map_err(|_| Error::TargetNotFound(target.file.clone()))?;
+    let expected = target.expected_value(context)?;
     let file = target.file.display().to_string();
+    let current_version = match format::parse_version(&content, &target.file, &target.key, format) {
+        Err(Error::KeyNotFound { .. }) if target.create => {
+            fs::write(&target.file, update_content(target, &content, context)?)?;
+            return Ok(ApplyResult::Created {
+                file,
+                key: target.key.clone(),
+                new_version: expected,
+            });
+        }
+        result => result?,
+    };
 
-    if current_version == new_version {
+    if target.holds(&current_version, &expected) {
         return Ok(ApplyResult::NoChange { file });
     }
 
-    format::write_version(&target.file, &target.key, new_version, format)?;
+    fs::write(&target.file, update_content(target, &content, context)?)?;
 
     Ok(ApplyResult::Updated {
         file,
         key: target.key.clone(),
         old_version: current_version,
-        new_version: new_version.to_string(),
+        new_version: expected,
     })
 }
 
+/// Apply version to all targets and return the per-target results
+///
+/// With `allow_downgrade = false`, nothing is written if a target holds a
+/// higher version.
+pub fn apply_all(config: &Config) -> Result<Vec<ApplyResult>> {
+    policy::enforce_targets(config)?;
+    let context = Context::new(&config.version);
+    config
+        .targets
+        .iter()
+        .map(|target| apply_target(target, &context))
+        .collect()
+}
+
 /// Apply version to all targets in the configuration
 pub fn apply(config: &Config, quiet: bool) -> Result<()> {
-    let mut results = Vec::new();
-
-    for target in &config.targets {
-        let result = apply_target(target, &config.version)?;
-        results.push(result);
-    }
+    let results = apply_all(config)?;
 
     if !quiet {
-        for result in &results {
-            println!("{}", result);
-        }
+        report(&results);
     }
 
     Ok(())
 }
+
+/// Print apply results, highlighting updated targets
+pub fn report(results: &[ApplyResult]) {
+    let term = term::get();
+    for result in results {
+        match result {
+            ApplyResult::Updated { .. } | ApplyResult::Created { .. } => {
+                println!("{}", term.paint(&result.to_string(), Style::Warning))
+            }
+            ApplyResult::NoChange { .. } | ApplyResult::Skipped { .. } => {
+                println!("{}", result)
+            }
+        }
+    }
+}
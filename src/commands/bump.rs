@@ -0,0 +1,96 @@
+use crate::commands::apply::apply;
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::policy;
+use crate::version::{Level, Version};
+use std::path::Path;
+
+/// What to increment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum BumpLevel {
+    Major,
+    Minor,
+    Patch,
+    /// The prerelease number (`1.2.0-rc.1` -> `1.2.0-rc.2`)
+    #[value(alias = "pre")]
+    Prerelease,
+}
+
+/// Options for the bump command
+#[derive(Debug)]
+pub struct BumpOptions {
+    /// Component to increment
+    pub level: BumpLevel,
+    /// Prerelease label: starts a prerelease of the bumped version, or
+    /// switches label for `prerelease` (`Some(None)` for the default label)
+    pub pre: Option<Option<String>>,
+    /// Apply the new version to all targets afterwards
+    pub apply: bool,
+}
+
+/// Check a label against `[prerelease] labels`, defaulting to the first
+fn resolve_label(config: &Config, label: Option<&str>) -> Result<String> {
+    let labels = &config.prerelease.labels;
+    let unknown = |label: &str| Error::UnknownPrereleaseLabel {
+        label: label.to_string(),
+        allowed: labels.join(", "),
+    };
+    match label {
+        Some(label) if labels.iter().any(|l| l == label) => Ok(label.to_string()),
+        Some(label) => Err(unknown(label)),
+        None => labels.first().cloned().ok_or_else(|| unknown("")),
+    }
+}
+
+/// Compute the version following `current`
+pub fn next_version(config: &Config, current: &Version, options: &BumpOptions) -> Result<Version> {
+    let label = match &options.pre {
+        Some(label) => Some(resolve_label(config, label.as_deref())?),
+        None => None,
+    };
+
+    let level = match options.level {
+        BumpLevel::Major => Level::Major,
+        BumpLevel::Minor => Level::Minor,
+        BumpLevel::Patch => Level::Patch,
+        BumpLevel::Prerelease => return current.bump_prerelease(label.as_deref()),
+    };
+
+    match label {
+        Some(label) => current.bump_to_prerelease(level, &label),
+        None => Ok(current.bump(level)),
+    }
+}
+
+/// Increment the version in the config file
+///
+/// The version must be valid semver. With `apply`, the new version is
+/// written to all targets as well.
+pub fn bump(
+    config: &Config,
+    config_path: &Path,
+    options: &BumpOptions,
+    quiet: bool,
+) -> Result<Version> {
+    let current = Version::parse(&config.version)?;
+    let next = next_version(config, &current, options)?;
+    policy::enforce_downgrade(
+        config,
+        &config.version_key(),
+        &config.version,
+        &next.to_string(),
+    )?;
+
+    config.write_version(config_path, &next.to_string())?;
+    if !quiet {
+        println!("BUMPED {} -> {} ({})", current, next, config_path.display());
+    }
+
+    if options.apply {
+        let mut config = config.clone();
+        config.version = next.to_string();
+        apply(&config, quiet)?;
+    }
+
+    Ok(next)
+}
@@ -0,0 +1,65 @@
+use crate::changelog;
+use crate::config::Config;
+use crate::error::Result;
+use crate::git;
+use crate::version::Version;
+use std::fs;
+use std::path::Path;
+
+/// Render the changelog section for the configured version
+///
+/// Covers the commits since the latest tag (among tags with
+/// `git.tag_prefix`) whose version is lower than the configured one in the
+/// config's `scheme`, so a version that is already tagged still gets its own
+/// section.
+pub fn release_notes(config: &Config) -> Result<String> {
+    git::ensure_git_repository()?;
+
+    let version = Version::parse(&config.version)?;
+    let scheme = config.effective_scheme();
+    let previous = git::tagged_versions(&config.git.tag_prefix, scheme)?
+        .into_iter()
+        .rfind(|(_, tagged)| scheme.compare(tagged, &version).is_lt())
+        .map(|(tag, _)| tag);
+    let commits = git::commits_since(previous.as_deref())?;
+
+    let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+    Ok(changelog::render_section(&config.version, &date, &commits))
+}
+
+/// Print the changelog section for the current version, or prepend it to
+/// a changelog file
+///
+/// A file that already has a section for the version is left unchanged.
+pub fn changelog(config: &Config, write_to: Option<&Path>, quiet: bool) -> Result<()> {
+    let section = release_notes(config)?;
+
+    let Some(path) = write_to else {
+        if !quiet {
+            print!("{}", section);
+        }
+        return Ok(());
+    };
+
+    let existing = if path.exists() {
+        fs::read_to_string(path)?
+    } else {
+        String::new()
+    };
+    if changelog::has_section(&existing, &config.version) {
+        if !quiet {
+            println!(
+                "NO CHANGE {} (already has {})",
+                path.display(),
+                config.version
+            );
+        }
+        return Ok(());
+    }
+
+    fs::write(path, changelog::prepend(&existing, &section))?;
+    if !quiet {
+        println!("UPDATED {}: added {}", path.display(), config.version);
+    }
+    Ok(())
+}
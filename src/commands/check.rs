@@ -1,6 +1,15 @@
+use crate::branch::{self, BranchRule};
+use crate::commands::apply::apply_target;
+use crate::commands::tag::tag_matches;
 use crate::config::{Config, Target};
 use crate::error::{Error, Result};
 use crate::format;
+use crate::git;
+use crate::policy;
+use crate::table::Table;
+use crate::template::Context;
+use crate::term::{self, Glyph, Style};
+use crate::version::Version;
 
 /// Result of checking a single target
 #[derive(Debug)]
@@ -15,11 +24,26 @@ pub enum CheckResult {
         expected: String,
         actual: String,
     },
+    /// A mismatch that was rewritten to the expected version (`--fix`)
+    Fixed {
+        file: String,
+        key: String,
+        expected: String,
+        actual: String,
+    },
+    /// An optional target whose file doesn't exist
+    Skipped {
+        file: String,
+        key: String,
+    },
 }
 
 impl CheckResult {
     pub fn is_ok(&self) -> bool {
-        matches!(self, CheckResult::Ok { .. })
+        matches!(
+            self,
+            CheckResult::Ok { .. } | CheckResult::Fixed { .. } | CheckResult::Skipped { .. }
+        )
     }
 }
 
@@ -37,28 +61,189 @@ impl std::fmt::Display for CheckResult {
             } => {
                 write!(f, "MISMATCH {} {}: {} != {}", file, key, actual, expected)
             }
+            CheckResult::Fixed {
+                file,
+                key,
+                expected,
+                actual,
+            } => {
+                write!(f, "FIXED {} {}: {} -> {}", file, key, actual, expected)
+            }
+            CheckResult::Skipped { file, key } => {
+                write!(f, "SKIPPED {} {}: file not found", file, key)
+            }
         }
     }
 }
 
+/// Result of a repository-state check (frozen mode)
+#[derive(Debug)]
+pub enum RepoCheck {
+    Clean,
+    Dirty,
+    TagAtHead {
+        tag: String,
+    },
+    TagMissing {
+        tag: String,
+    },
+    TagElsewhere {
+        tag: String,
+        tag_commit: String,
+        head_commit: String,
+    },
+    BranchOk {
+        branch: String,
+    },
+    BranchMismatch {
+        branch: String,
+        version: String,
+    },
+}
+
+impl RepoCheck {
+    pub fn is_ok(&self) -> bool {
+        matches!(
+            self,
+            RepoCheck::Clean | RepoCheck::TagAtHead { .. } | RepoCheck::BranchOk { .. }
+        )
+    }
+}
+
+impl std::fmt::Display for RepoCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RepoCheck::Clean => write!(f, "OK working tree clean"),
+            RepoCheck::Dirty => write!(f, "DIRTY working tree or index"),
+            RepoCheck::TagAtHead { tag } => write!(f, "OK tag {} at HEAD", tag),
+            RepoCheck::TagMissing { tag } => write!(f, "MISSING tag {}", tag),
+            RepoCheck::TagElsewhere {
+                tag,
+                tag_commit,
+                head_commit,
+            } => write!(
+                f,
+                "MOVED tag {}: points at {}, HEAD is {}",
+                tag,
+                short_sha(tag_commit),
+                short_sha(head_commit)
+            ),
+            RepoCheck::BranchOk { branch } => write!(f, "OK branch {}", branch),
+            RepoCheck::BranchMismatch { branch, version } => write!(
+                f,
+                "MISMATCH branch {}: not consistent with version {}",
+                branch, version
+            ),
+        }
+    }
+}
+
+fn short_sha(sha: &str) -> &str {
+    &sha[..sha.len().min(7)]
+}
+
+/// Options for the check command
+#[derive(Debug, Default)]
+pub struct CheckOptions {
+    /// Also require a clean checkout whose HEAD carries the release tag
+    pub frozen: bool,
+    /// Rewrite mismatching targets to the expected version
+    pub fix: bool,
+}
+
+/// Check that the working tree is clean and the release tag points at HEAD
+pub fn check_repository(config: &Config) -> Result<Vec<RepoCheck>> {
+    git::ensure_git_repository()?;
+
+    let mut results = Vec::new();
+
+    if git::is_working_tree_clean()? && git::is_index_clean()? {
+        results.push(RepoCheck::Clean);
+    } else {
+        results.push(RepoCheck::Dirty);
+    }
+
+    // For a template with a `{n}` counter, any counter at HEAD will do
+    let tag = config.tag_name();
+    if let Some(tag) = git::tags_at("HEAD")?
+        .into_iter()
+        .find(|t| tag_matches(&tag, t))
+    {
+        results.push(RepoCheck::TagAtHead { tag });
+        return Ok(results);
+    }
+    let head_commit = git::resolve_commit("HEAD")?
+        .ok_or_else(|| Error::GitCommand("HEAD does not point at a commit".to_string()))?;
+    match git::resolve_commit(&tag)? {
+        None => results.push(RepoCheck::TagMissing { tag }),
+        Some(tag_commit) if tag_commit == head_commit => results.push(RepoCheck::TagAtHead { tag }),
+        Some(tag_commit) => results.push(RepoCheck::TagElsewhere {
+            tag,
+            tag_commit,
+            head_commit,
+        }),
+    }
+
+    Ok(results)
+}
+
+/// Check the current branch against `git.branch_pattern`
+///
+/// Returns `None` when no pattern is configured, outside a git repository
+/// (there is no branch to check), when HEAD is detached, or when the branch
+/// is not a versioned branch.
+pub fn check_branch(config: &Config) -> Result<Option<RepoCheck>> {
+    let Some(pattern) = &config.git.branch_pattern else {
+        return Ok(None);
+    };
+
+    if !git::is_inside_work_tree()? {
+        return Ok(None);
+    }
+    let Some(branch) = git::current_branch()? else {
+        return Ok(None);
+    };
+
+    let version = Version::parse(&config.version)?;
+    match branch::evaluate(pattern, &branch, &version)? {
+        BranchRule::NotApplicable => Ok(None),
+        BranchRule::Consistent => Ok(Some(RepoCheck::BranchOk { branch })),
+        BranchRule::Inconsistent => Ok(Some(RepoCheck::BranchMismatch {
+            branch,
+            version: config.version.clone(),
+        })),
+    }
+}
+
 /// Check a single target file
-fn check_target(target: &Target, expected_version: &str) -> Result<CheckResult> {
+///
+/// The key must hold the version rendered through the target's `template`.
+/// An optional target whose file is missing is skipped.
+pub fn check_target(target: &Target, version: &str) -> Result<CheckResult> {
+    let file = target.file.display().to_string();
+    let key = target.key.clone();
+    if !target.required && !target.file.exists() {
+        return Ok(CheckResult::Skipped { file, key });
+    }
+
     let format = target
         .effective_format()
         .ok_or_else(|| Error::UnknownFormat(target.file.clone()))?;
 
-    let actual_version = format::read_version(&target.file, &target.key, format)?;
-
-    let file = target.file.display().to_string();
-    let key = target.key.clone();
+    let expected = target.expected_value(&Context::new(version))?;
+    // A key apply would create is reported as missing rather than an error
+    let actual_version = match format::read_version(&target.file, &target.key, format) {
+        Err(Error::KeyNotFound { .. }) if target.create => "(missing)".to_string(),
+        result => result?,
+    };
 
-    if actual_version == expected_version {
+    if target.holds(&actual_version, &expected) {
         Ok(CheckResult::Ok { file, key })
     } else {
         Ok(CheckResult::Mismatch {
             file,
             key,
-            expected: expected_version.to_string(),
+            expected,
             actual: actual_version,
         })
     }
@@ -66,13 +251,35 @@ fn check_target(target: &Target, expected_version: &str) -> Result<CheckResult>
 
 /// Check all targets in the configuration
 ///
-/// Returns a list of check results and whether all checks passed
-pub fn check(config: &Config, quiet: bool) -> Result<bool> {
+/// Returns whether all checks passed. In frozen mode the repository must
+/// also be clean with the release tag at HEAD. With `fix`, mismatching
+/// targets are rewritten (like `apply`) and count as passed.
+pub fn check(config: &Config, options: &CheckOptions, quiet: bool) -> Result<bool> {
     let mut all_ok = true;
     let mut results = Vec::new();
+    let context = Context::new(&config.version);
+    if options.fix {
+        policy::enforce_targets(config)?;
+    }
 
     for target in &config.targets {
-        match check_target(target, &config.version) {
+        match check_target(target, &config.version).and_then(|result| match result {
+            CheckResult::Mismatch {
+                file,
+                key,
+                expected,
+                actual,
+            } if options.fix => {
+                apply_target(target, &context)?;
+                Ok(CheckResult::Fixed {
+                    file,
+                    key,
+                    expected,
+                    actual,
+                })
+            }
+            result => Ok(result),
+        }) {
             Ok(result) => {
                 if !result.is_ok() {
                     all_ok = false;
@@ -86,24 +293,104 @@ pub fn check(config: &Config, quiet: bool) -> Result<bool> {
     }
 
     if !quiet {
-        for result in &results {
+        if term::tables() {
+            print!("{}", results_table(&results, &config.version).render());
+        } else {
+            for result in &results {
+                println!("{}", result);
+            }
+        }
+    }
+
+    let mut repo_results: Vec<RepoCheck> = check_branch(config)?.into_iter().collect();
+    if options.frozen {
+        repo_results.extend(check_repository(config)?);
+    }
+
+    for result in repo_results {
+        if !result.is_ok() {
+            all_ok = false;
+        }
+        if !quiet {
             println!("{}", result);
         }
     }
 
+    for violation in policy::check(config)? {
+        all_ok = false;
+        if !quiet {
+            println!("{}", violation);
+        }
+    }
+
     Ok(all_ok)
 }
 
+/// Build an aligned table of check results for terminal output
+fn results_table(results: &[CheckResult], expected_version: &str) -> Table {
+    let term = term::get();
+    let mut table = Table::new(&["FILE", "KEY", "EXPECTED", "ACTUAL", "STATUS"]);
+
+    for result in results {
+        let row = match result {
+            CheckResult::Ok { file, key } => {
+                let status = format!("{} OK", term.glyph(Glyph::Check));
+                [
+                    file.clone(),
+                    key.clone(),
+                    expected_version.to_string(),
+                    expected_version.to_string(),
+                    term.paint(&status, Style::Success),
+                ]
+            }
+            CheckResult::Mismatch {
+                file,
+                key,
+                expected,
+                actual,
+            } => {
+                let status = format!("{} MISMATCH", term.glyph(Glyph::Cross));
+                [
+                    file.clone(),
+                    key.clone(),
+                    expected.clone(),
+                    actual.clone(),
+                    term.paint(&status, Style::Failure),
+                ]
+            }
+            CheckResult::Fixed {
+                file,
+                key,
+                expected,
+                actual,
+            } => {
+                let status = format!("{} FIXED", term.glyph(Glyph::Check));
+                [
+                    file.clone(),
+                    key.clone(),
+                    expected.clone(),
+                    actual.clone(),
+                    term.paint(&status, Style::Warning),
+                ]
+            }
+            CheckResult::Skipped { file, key } => [
+                file.clone(),
+                key.clone(),
+                expected_version.to_string(),
+                "-".to_string(),
+                term.paint("SKIPPED", Style::Warning),
+            ],
+        };
+        table.add_row(row.to_vec());
+    }
+
+    table
+}
+
 /// Check all targets without printing (for internal use)
 pub fn check_silent(config: &Config) -> Result<bool> {
     for target in &config.targets {
-        let format = target
-            .effective_format()
-            .ok_or_else(|| Error::UnknownFormat(target.file.clone()))?;
-
-        let actual_version = format::read_version(&target.file, &target.key, format)?;
-
-        if actual_version != config.version {
+        if !check_target(target, &config.version)?.is_ok() {
             return Ok(false);
         }
     }
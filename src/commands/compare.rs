@@ -0,0 +1,43 @@
+use crate::error::Result;
+use crate::range::Range;
+use crate::scheme::Scheme;
+use crate::version::Version;
+use std::cmp::Ordering;
+
+/// Compare two versions by semver precedence and print the relation
+/// (`1.2.0 < 1.10.0`)
+///
+/// Build metadata is ignored, so `1.0.0+a` and `1.0.0+b` are equal, except
+/// for the post-releases of the `pep440` scheme.
+pub fn compare(a: &str, b: &str, scheme: Scheme, quiet: bool) -> Result<Ordering> {
+    let a = Version::parse(a)?;
+    let b = Version::parse(b)?;
+    let ordering = scheme.compare(&a, &b);
+
+    if !quiet {
+        let symbol = match ordering {
+            Ordering::Less => "<",
+            Ordering::Equal => "=",
+            Ordering::Greater => ">",
+        };
+        println!("{} {} {}", a, symbol, b);
+    }
+
+    Ok(ordering)
+}
+
+/// Check whether a version satisfies a range (see [`Range`])
+pub fn satisfies(version: &str, range: &str, quiet: bool) -> Result<bool> {
+    let version = Version::parse(version)?;
+    let satisfied = Range::parse(range)?.satisfies(&version);
+
+    if !quiet {
+        if satisfied {
+            println!("OK {} satisfies {}", version, range);
+        } else {
+            println!("MISMATCH {} does not satisfy {}", version, range);
+        }
+    }
+
+    Ok(satisfied)
+}
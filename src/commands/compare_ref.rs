@@ -0,0 +1,114 @@
+use crate::config::{config_files, extract_config, Config, Source, Target};
+use crate::error::{Error, Result};
+use crate::format;
+use crate::git;
+use crate::version::Version;
+use std::cmp::Ordering;
+use std::path::PathBuf;
+
+/// Result of comparing our version with a version found at another ref
+#[derive(Debug)]
+pub struct RefComparison {
+    /// What was compared (`version.toml` or `file key`)
+    pub subject: String,
+    pub ours: String,
+    pub theirs: String,
+    pub ordering: Ordering,
+}
+
+impl std::fmt::Display for RefComparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.ordering {
+            Ordering::Less => write!(
+                f,
+                "BEHIND {}: {} < {}",
+                self.subject, self.ours, self.theirs
+            ),
+            Ordering::Equal => write!(f, "EQUAL {}: {}", self.subject, self.ours),
+            Ordering::Greater => {
+                write!(f, "AHEAD {}: {} > {}", self.subject, self.ours, self.theirs)
+            }
+        }
+    }
+}
+
+fn compare(subject: String, ours: &Version, theirs: &str) -> Result<RefComparison> {
+    let ordering = ours.precedence(&Version::parse(theirs)?);
+    Ok(RefComparison {
+        subject,
+        ours: ours.to_string(),
+        theirs: theirs.to_string(),
+        ordering,
+    })
+}
+
+/// Read a target's version at a ref
+fn read_target_at(reference: &str, target: &Target) -> Result<String> {
+    let format = target
+        .effective_format()
+        .ok_or_else(|| Error::UnknownFormat(target.file.clone()))?;
+    let content = git::show_file(reference, &target.file)?;
+    format::parse_version(&content, &target.file, &target.key, format)
+}
+
+/// Compare the current version with the configuration (and optionally the
+/// targets) at another git ref
+///
+/// Returns the ordering of our version relative to the ref's configured
+/// version: `Less` means this checkout is behind.
+pub fn compare_ref(
+    config: &Config,
+    config_paths: &[PathBuf],
+    reference: &str,
+    include_targets: bool,
+    quiet: bool,
+) -> Result<Ordering> {
+    git::ensure_git_repository()?;
+
+    let ours = Version::parse(&config.version)?;
+    let their_contents = config_files(config_paths)?
+        .iter()
+        .map(|path| extract_config(path, &git::show_file(reference, path)?))
+        .collect::<Result<Vec<_>>>()?;
+    let mut their_config = Config::parse_merged(&their_contents)?;
+    match &their_config.source {
+        Source::Config => {}
+        Source::Git => {
+            return Err(Error::Usage(format!(
+                "The config at {} derives the version from git tags; compare the tags instead",
+                reference
+            )))
+        }
+        // The source file is read at the ref, like the targets
+        Source::File(target) => {
+            let value = read_target_at(reference, target)?;
+            their_config.version = target.version_from_value(&value).unwrap_or(value);
+        }
+    }
+    if let Some(component) = &config.component {
+        their_config = their_config.for_component(component)?;
+    }
+    let version_file = config.version_file(config_paths)?;
+
+    let mut results = vec![compare(
+        format!("{} {}", reference, version_file.display()),
+        &ours,
+        &their_config.version,
+    )?];
+
+    if include_targets {
+        for target in &their_config.targets {
+            let theirs = read_target_at(reference, target)?;
+            let subject = format!("{} {} {}", reference, target.file.display(), target.key);
+            results.push(compare(subject, &ours, &theirs)?);
+        }
+    }
+
+    if !quiet {
+        for result in &results {
+            println!("{}", result);
+        }
+    }
+
+    Ok(results[0].ordering)
+}
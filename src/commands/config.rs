@@ -0,0 +1,53 @@
+use crate::config::{config_files, read_config, unknown_keys, Config, Issue, Severity};
+use crate::error::Result;
+use crate::schema::config_schema;
+use std::path::PathBuf;
+
+/// Validate the configuration and print every problem found
+///
+/// Unknown keys are reported as warnings since they are ignored; anything
+/// that prevents loading the config, or that `Config::validate` finds, is
+/// an error. Returns whether there were no errors, or with `strict` no
+/// problems at all.
+pub fn validate(config_paths: &[PathBuf], strict: bool, quiet: bool) -> Result<bool> {
+    let files = config_files(config_paths)?;
+    let contents = files
+        .iter()
+        .map(|path| read_config(path))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut issues: Vec<Issue> = Vec::new();
+    if let Ok(keys) = unknown_keys(&contents) {
+        issues.extend(
+            keys.into_iter()
+                .map(|key| Issue::warning(key, "unknown key, ignored")),
+        );
+    }
+    match Config::parse_merged(&contents).and_then(Config::resolve_source) {
+        Ok(config) => issues.extend(config.validate()),
+        Err(e) => issues.push(Issue::error("config", e.to_string())),
+    }
+
+    if !quiet {
+        if issues.is_empty() {
+            let names: Vec<String> = files.iter().map(|f| f.display().to_string()).collect();
+            println!("OK {}", names.join(", "));
+        }
+        for issue in &issues {
+            println!("{}", issue);
+        }
+    }
+
+    if strict {
+        return Ok(issues.is_empty());
+    }
+    Ok(issues.iter().all(|issue| issue.severity < Severity::Error))
+}
+
+/// Print the JSON Schema of the configuration file
+pub fn schema() -> Result<()> {
+    let schema =
+        serde_json::to_string_pretty(&config_schema()).expect("a JSON value always serializes");
+    println!("{}", schema);
+    Ok(())
+}
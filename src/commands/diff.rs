@@ -0,0 +1,155 @@
+use crate::commands::apply::update_content;
+use crate::config::{self, Config, Target};
+use crate::diff::unified_diff;
+use crate::error::{Error, Result};
+use crate::format;
+use crate::template::Context;
+use crate::term::{self, Style};
+use std::fs;
+
+/// Content of a target file before and after applying the version
+#[derive(Debug)]
+pub struct PendingChange {
+    /// Path of the file relative to the config's directory, as `git apply`
+    /// expects when run there
+    pub file: String,
+    pub old: String,
+    pub new: String,
+}
+
+impl PendingChange {
+    /// Unified diff of this change (empty when nothing would change)
+    pub fn diff(&self) -> String {
+        unified_diff(&self.file, &self.old, &self.new)
+    }
+}
+
+/// Apply the version to a target's content in memory
+///
+/// Content that already carries the version is returned unchanged, so
+/// reformatting (e.g. JSON pretty printing) never shows up as a change.
+fn updated_content(target: &Target, content: &str, context: &Context) -> Result<String> {
+    let format = target
+        .effective_format()
+        .ok_or_else(|| Error::UnknownFormat(target.file.clone()))?;
+
+    let current = match format::parse_version(content, &target.file, &target.key, format) {
+        Err(Error::KeyNotFound { .. }) if target.create => {
+            return update_content(target, content, context);
+        }
+        result => result?,
+    };
+    if target.holds(&current, &target.expected_value(context)?) {
+        Ok(content.to_string())
+    } else {
+        update_content(target, content, context)
+    }
+}
+
+/// Compute pending changes for all targets without writing anything
+///
+/// Targets sharing a file are applied in sequence to the same content.
+pub fn pending_changes(config: &Config) -> Result<Vec<PendingChange>> {
+    let context = Context::new(&config.version);
+    let mut changes: Vec<PendingChange> = Vec::new();
+
+    for target in &config.targets {
+        if !target.required && !target.file.exists() {
+            continue;
+        }
+        let root = config::root();
+        let file = target.file.strip_prefix(&root).unwrap_or(&target.file);
+        let file = file.display().to_string();
+        let index = match changes.iter().position(|c| c.file == file) {
+            Some(index) => index,
+            None => {
+                let old = fs::read_to_string(&target.file)
+                    .map_err(|_| Error::TargetNotFound(target.file.clone()))?;
+                changes.push(PendingChange {
+                    file,
+                    new: old.clone(),
+                    old,
+                });
+                changes.len() - 1
+            }
+        };
+
+        let change = &mut changes[index];
+        change.new = updated_content(target, &change.new, &context)?;
+    }
+
+    Ok(changes)
+}
+
+/// Preview for `apply --dry-run`
+///
+/// Prints the diff for every file apply would change and `NO CHANGE` for
+/// the others, without writing anything. Returns whether anything would
+/// change.
+pub fn apply_dry_run(config: &Config, quiet: bool) -> Result<bool> {
+    let changes = pending_changes(config)?;
+    let mut changed = false;
+
+    for change in &changes {
+        let diff = change.diff();
+        if !diff.is_empty() {
+            changed = true;
+        }
+        if quiet {
+            continue;
+        }
+        if diff.is_empty() {
+            println!("NO CHANGE {}", change.file);
+        } else {
+            print!("{}", paint_diff(&diff));
+        }
+    }
+
+    Ok(changed)
+}
+
+/// Colorize diff lines for terminal output
+fn paint_diff(diff: &str) -> String {
+    let term = term::get();
+    diff.lines()
+        .map(|line| {
+            if line.starts_with("+++") || line.starts_with("---") || line.starts_with("diff ") {
+                term.paint(line, Style::Bold)
+            } else if line.starts_with('+') {
+                term.paint(line, Style::Success)
+            } else if line.starts_with('-') {
+                term.paint(line, Style::Failure)
+            } else {
+                line.to_string()
+            }
+        })
+        .map(|line| line + "\n")
+        .collect()
+}
+
+/// Print the changes `apply` would make as a unified diff
+///
+/// With `patch`, the output is plain (never colored) so it can be saved and
+/// applied with `git apply`. Returns whether any target would change.
+pub fn diff(config: &Config, patch: bool, quiet: bool) -> Result<bool> {
+    let changes = pending_changes(config)?;
+    let mut changed = false;
+
+    for change in &changes {
+        let diff = change.diff();
+        if diff.is_empty() {
+            continue;
+        }
+        changed = true;
+
+        if !quiet {
+            if patch {
+                print!("{}", diff);
+            } else {
+                print!("{}", paint_diff(&diff));
+            }
+        }
+    }
+
+    Ok(changed)
+}
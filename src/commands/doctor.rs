@@ -0,0 +1,321 @@
+use crate::commands::check::{check_branch, RepoCheck};
+use crate::config::{self, config_files, Config};
+use crate::error::Error;
+use crate::format;
+use crate::git;
+use crate::policy;
+use crate::template::Context;
+use crate::term::{self, Style};
+use crate::version::Version;
+use std::path::{Path, PathBuf};
+
+/// How serious a diagnostic is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Ok,
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    fn label(self) -> &'static str {
+        match self {
+            Severity::Ok => "OK",
+            Severity::Info => "INFO",
+            Severity::Warning => "WARNING",
+            Severity::Error => "ERROR",
+        }
+    }
+
+    fn style(self) -> Option<Style> {
+        match self {
+            Severity::Ok => Some(Style::Success),
+            Severity::Info => None,
+            Severity::Warning => Some(Style::Warning),
+            Severity::Error => Some(Style::Failure),
+        }
+    }
+}
+
+/// A single finding, with an optional hint on how to fix it
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub hint: Option<String>,
+}
+
+impl Diagnostic {
+    fn new(severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            message: message.into(),
+            hint: None,
+        }
+    }
+
+    fn hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+}
+
+/// Diagnose the configuration, the target files and the git environment
+pub fn diagnose(config_paths: &[PathBuf]) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    let config = diagnose_config(config_paths, &mut diagnostics);
+    if let Some(config) = &config {
+        diagnose_targets(config, config_paths, &mut diagnostics);
+    }
+    diagnose_git(config.as_ref(), &mut diagnostics);
+
+    diagnostics
+}
+
+fn diagnose_config(config_paths: &[PathBuf], diagnostics: &mut Vec<Diagnostic>) -> Option<Config> {
+    let config = match Config::load_all(config_paths) {
+        Ok(config) => config,
+        Err(e) => {
+            diagnostics.push(
+                Diagnostic::new(Severity::Error, format!("config: {}", e))
+                    .hint("create one with `versync init` or fix the reported problem"),
+            );
+            return None;
+        }
+    };
+    let files = config_files(config_paths).unwrap_or_default();
+    let names: Vec<String> = files.iter().map(|f| f.display().to_string()).collect();
+    diagnostics.push(Diagnostic::new(
+        Severity::Ok,
+        format!("config {}", names.join(", ")),
+    ));
+
+    match Version::parse(&config.version) {
+        Ok(_) => diagnostics.push(Diagnostic::new(
+            Severity::Ok,
+            format!("version {}", config.version),
+        )),
+        Err(e) => diagnostics.push(
+            Diagnostic::new(Severity::Error, format!("version: {}", e))
+                .hint("use MAJOR.MINOR.PATCH, optionally with -prerelease and +build"),
+        ),
+    }
+
+    for channel in config.channels.keys() {
+        if let Err(e) = config.for_channel(channel) {
+            diagnostics.push(Diagnostic::new(
+                Severity::Error,
+                format!("channel {}: {}", channel, e),
+            ));
+        }
+    }
+
+    match policy::check(&config) {
+        Ok(violations) => diagnostics.extend(violations.into_iter().map(|violation| {
+            Diagnostic::new(Severity::Warning, violation.to_string())
+                .hint("`tag` and `release` refuse to run until the policy is met")
+        })),
+        Err(e) => diagnostics.push(Diagnostic::new(Severity::Error, format!("policy: {}", e))),
+    }
+
+    Some(config)
+}
+
+/// What target paths are relative to, for hints
+///
+/// That is the directory of a config found in a parent directory, but the
+/// working directory for one passed with `--config` from elsewhere.
+fn target_base(config_paths: &[PathBuf]) -> String {
+    let root = config::root();
+    if !root.as_os_str().is_empty() {
+        return format!(
+            "the config file's directory ({}, where it was found)",
+            root.display()
+        );
+    }
+    let elsewhere = config_paths
+        .iter()
+        .any(|path| path.parent().is_some_and(|dir| dir != Path::new("")));
+    if elsewhere {
+        "the working directory, not the config file's".to_string()
+    } else {
+        "the config file's directory".to_string()
+    }
+}
+
+fn diagnose_targets(config: &Config, config_paths: &[PathBuf], diagnostics: &mut Vec<Diagnostic>) {
+    for target in &config.targets {
+        let name = format!("target {} {}", target.file.display(), target.key);
+
+        if !target.required && !target.file.exists() {
+            diagnostics.push(Diagnostic::new(
+                Severity::Info,
+                format!("{}: optional file not found, skipped", name),
+            ));
+            continue;
+        }
+        if !target.file.exists() {
+            diagnostics.push(
+                Diagnostic::new(Severity::Error, format!("{}: file not found", name)).hint(
+                    format!(
+                        "fix the path in [[targets]]; paths are relative to {}",
+                        target_base(config_paths)
+                    ),
+                ),
+            );
+            continue;
+        }
+        let Some(format) = target.effective_format() else {
+            diagnostics.push(
+                Diagnostic::new(Severity::Error, format!("{}: unknown file format", name))
+                    .hint("set `format` on the target"),
+            );
+            continue;
+        };
+
+        let expected = match target.expected_value(&Context::new(&config.version)) {
+            Ok(expected) => expected,
+            Err(e) => {
+                diagnostics.push(
+                    Diagnostic::new(Severity::Error, format!("{} template: {}", name, e))
+                        .hint("see README for the placeholders templates can use"),
+                );
+                continue;
+            }
+        };
+        match format::read_version(&target.file, &target.key, format) {
+            Ok(version) if target.holds(&version, &expected) => {
+                diagnostics.push(Diagnostic::new(Severity::Ok, name.clone()));
+            }
+            Ok(version) => diagnostics.push(
+                Diagnostic::new(
+                    Severity::Warning,
+                    format!("{}: holds {}, expected {}", name, version, expected),
+                )
+                .hint("run `versync apply`, or `versync check --resolve` to pick a version"),
+            ),
+            Err(Error::KeyNotFound { .. }) if target.create => {
+                diagnostics.push(
+                    Diagnostic::new(Severity::Warning, format!("{}: key missing", name))
+                        .hint("run `versync apply` to create it"),
+                );
+                continue;
+            }
+            Err(e) => {
+                diagnostics.push(
+                    Diagnostic::new(Severity::Error, format!("{}: {}", name, e))
+                        .hint("check that `key` is a dot-separated path to a string"),
+                );
+                continue;
+            }
+        }
+
+        for key in target.extra.keys() {
+            if let Err(e) = format::read_version(&target.file, key, format) {
+                diagnostics.push(
+                    Diagnostic::new(Severity::Error, format!("{} extra {}: {}", name, key, e))
+                        .hint("extra keys must already exist and hold strings"),
+                );
+            }
+        }
+    }
+}
+
+fn diagnose_git(config: Option<&Config>, diagnostics: &mut Vec<Diagnostic>) {
+    let Some(version) = git::version() else {
+        diagnostics.push(
+            Diagnostic::new(Severity::Warning, "git: not found")
+                .hint("install git to use tag, release and the repository checks"),
+        );
+        return;
+    };
+    diagnostics.push(Diagnostic::new(Severity::Ok, version));
+
+    if !git::is_inside_work_tree().unwrap_or(false) {
+        diagnostics.push(
+            Diagnostic::new(Severity::Warning, "git: not inside a repository")
+                .hint("tag, release and the repository checks need a git repository"),
+        );
+        return;
+    }
+
+    let clean =
+        git::is_working_tree_clean().unwrap_or(false) && git::is_index_clean().unwrap_or(false);
+    if clean {
+        diagnostics.push(Diagnostic::new(Severity::Ok, "working tree clean"));
+    } else {
+        diagnostics.push(Diagnostic::new(
+            Severity::Info,
+            "working tree has uncommitted changes",
+        ));
+    }
+
+    let Some(config) = config else {
+        return;
+    };
+
+    let remote = &config.git.remote;
+    let looks_like_url = remote.contains(':') || remote.contains('/');
+    if looks_like_url || git::remote_exists(remote).unwrap_or(false) {
+        diagnostics.push(Diagnostic::new(Severity::Ok, format!("remote {}", remote)));
+    } else {
+        diagnostics.push(
+            Diagnostic::new(
+                Severity::Warning,
+                format!("remote {}: not configured", remote),
+            )
+            .hint("add it with `git remote add` or set `git.remote`"),
+        );
+    }
+
+    match check_branch(config) {
+        Ok(Some(result @ RepoCheck::BranchMismatch { .. })) => diagnostics.push(
+            Diagnostic::new(Severity::Warning, result.to_string())
+                .hint("`tag` refuses to run on this branch; check `git.branch_pattern`"),
+        ),
+        Ok(_) => {}
+        Err(e) => diagnostics.push(Diagnostic::new(Severity::Error, format!("branch: {}", e))),
+    }
+
+    let tag = config.tag_name();
+    if git::tag_exists(&tag).unwrap_or(false) {
+        diagnostics.push(
+            Diagnostic::new(Severity::Info, format!("tag {} already exists", tag))
+                .hint("bump the version before the next release"),
+        );
+    }
+}
+
+/// Print diagnostics for the environment and configuration
+///
+/// Returns whether no errors were found; warnings only fail the run with
+/// `strict` (`--ci`).
+pub fn doctor(config_paths: &[PathBuf], strict: bool, quiet: bool) -> bool {
+    let diagnostics = diagnose(config_paths);
+
+    if !quiet {
+        let term = term::get();
+        for diagnostic in &diagnostics {
+            let label = diagnostic.severity.label();
+            let label = match diagnostic.severity.style() {
+                Some(style) => term.paint(label, style),
+                None => label.to_string(),
+            };
+            println!("{} {}", label, diagnostic.message);
+            if let Some(hint) = &diagnostic.hint {
+                println!("  hint: {}", hint);
+            }
+        }
+    }
+
+    let limit = if strict {
+        Severity::Warning
+    } else {
+        Severity::Error
+    };
+    diagnostics
+        .iter()
+        .all(|diagnostic| diagnostic.severity < limit)
+}
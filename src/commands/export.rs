@@ -0,0 +1,75 @@
+use crate::commands::tag::tag_at_head;
+use crate::config::Config;
+use crate::error::Result;
+use crate::version::Version;
+
+/// Output formats of the export command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    /// The bare version
+    Plain,
+    /// A JSON object with the version, tag and components
+    Json,
+    /// `VERSION=...` lines for `.env` files and `eval`
+    Env,
+    /// `version=...` lines for `$GITHUB_OUTPUT`
+    GithubOutput,
+}
+
+/// Print the version, the tag name and the version components for CI
+///
+/// The version is written in the config's `scheme`, as the tag name is. For
+/// tag templates with a `{n}` counter, the tag is the one at HEAD; when HEAD
+/// carries none, it is empty (`null` in JSON).
+pub fn export(config: &Config, format: ExportFormat, quiet: bool) -> Result<()> {
+    let version = Version::parse(&config.version)?;
+    let written = config.version_in_scheme(&config.version)?;
+    let tag = tag_at_head(config)?;
+    let fields = [
+        ("version", written.clone()),
+        ("tag", tag.clone().unwrap_or_default()),
+        ("major", version.major.to_string()),
+        ("minor", version.minor.to_string()),
+        ("patch", version.patch.to_string()),
+        ("prerelease", version.prerelease()),
+    ];
+
+    if quiet {
+        return Ok(());
+    }
+    match format {
+        ExportFormat::Plain => println!("{}", written),
+        ExportFormat::Json => {
+            let object = serde_json::json!({
+                "version": written,
+                "tag": tag,
+                "major": version.major,
+                "minor": version.minor,
+                "patch": version.patch,
+                "prerelease": version.prerelease(),
+            });
+            println!("{}", object);
+        }
+        ExportFormat::Env => {
+            for (name, value) in &fields {
+                println!("{}={}", env_name(name), value);
+            }
+        }
+        ExportFormat::GithubOutput => {
+            for (name, value) in &fields {
+                println!("{}={}", name, value);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Environment variable name for a field (`version` -> `VERSION`,
+/// `major` -> `VERSION_MAJOR`)
+fn env_name(field: &str) -> String {
+    match field {
+        "version" | "tag" => field.to_uppercase(),
+        _ => format!("VERSION_{}", field.to_uppercase()),
+    }
+}
@@ -0,0 +1,25 @@
+use crate::config::FileFormat;
+use crate::error::Result;
+use crate::format;
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+/// Name used for stdin in error messages
+const STDIN: &str = "<stdin>";
+
+/// Set the version at a key path in content read from stdin
+///
+/// The edited content is written to stdout using the same format-preserving
+/// edits as `apply`; nothing is read from or written to disk, and no config
+/// file is needed.
+pub fn filter(format: FileFormat, key: &str, version: &str) -> Result<()> {
+    let mut content = String::new();
+    io::stdin().read_to_string(&mut content)?;
+
+    let updated = format::update_version(&content, Path::new(STDIN), key, version, format)?;
+
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(updated.as_bytes())?;
+    stdout.flush()?;
+    Ok(())
+}
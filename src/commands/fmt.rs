@@ -0,0 +1,39 @@
+use crate::config::{config_files, embedding_name};
+use crate::error::Result;
+use crate::formatter::format_config;
+use std::fs;
+use std::path::PathBuf;
+
+/// Format the configuration files in place
+///
+/// With `check`, files are only reported (`WOULD REFORMAT`) and left
+/// untouched. A configuration embedded in another tool's file (e.g.
+/// pyproject.toml) is left alone. Returns whether every file was already
+/// formatted, or was formatted now.
+pub fn fmt(config_paths: &[PathBuf], check: bool, quiet: bool) -> Result<bool> {
+    let mut ok = true;
+    for file in config_files(config_paths)? {
+        if embedding_name(&file).is_some() {
+            if !quiet {
+                println!("SKIPPED {}: embedded configuration", file.display());
+            }
+            continue;
+        }
+        let content = fs::read_to_string(&file)?;
+        let formatted = format_config(&content)?;
+
+        let status = if formatted == content {
+            "NO CHANGE"
+        } else if check {
+            ok = false;
+            "WOULD REFORMAT"
+        } else {
+            fs::write(&file, &formatted)?;
+            "FORMATTED"
+        };
+        if !quiet {
+            println!("{} {}", status, file.display());
+        }
+    }
+    Ok(ok)
+}
@@ -0,0 +1,73 @@
+use crate::commands::apply::apply;
+use crate::commands::release::{preflight, release, ReleaseOptions};
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::version::Version;
+use std::path::PathBuf;
+
+/// Options for the graduate command
+#[derive(Debug, Default)]
+pub struct GraduateOptions {
+    /// Apply the stable version to all targets
+    pub apply: bool,
+    /// Apply, commit and tag the stable version (`release --no-push`)
+    pub tag: bool,
+    /// Skip confirmation prompts
+    pub yes: bool,
+}
+
+/// Promote a prerelease to the stable version (`1.4.0-rc.3` -> `1.4.0`)
+///
+/// Strips the prerelease and build metadata and writes the result to the
+/// config file. Fails for a version that has neither, or where that would
+/// lower the version in the config's `scheme` (a PEP 440 post-release).
+/// With `--tag`, the release preflight runs before the config is written.
+pub fn graduate(
+    config: &Config,
+    config_paths: &[PathBuf],
+    options: &GraduateOptions,
+    quiet: bool,
+) -> Result<Version> {
+    let current = Version::parse(&config.version)?;
+    let stable = current.core();
+    if (current.pre.is_empty() && current.build.is_empty())
+        || config.effective_scheme().compare(&stable, &current).is_lt()
+    {
+        return Err(Error::Usage(format!(
+            "{} is already a stable version",
+            current
+        )));
+    }
+
+    let mut config = config.clone();
+    config.version = stable.to_string();
+    let release_options = ReleaseOptions {
+        no_push: true,
+        yes: options.yes,
+        ..Default::default()
+    };
+    if options.tag {
+        // Fail before writing the config rather than leave it graduated
+        // without a release
+        preflight(&config, config_paths, &release_options)?;
+    }
+
+    let config_path = config.version_file(config_paths)?;
+    config.write_version(&config_path, &stable.to_string())?;
+    if !quiet {
+        println!(
+            "GRADUATED {} -> {} ({})",
+            current,
+            stable,
+            config_path.display()
+        );
+    }
+
+    if options.tag {
+        release(&config, config_paths, &release_options, quiet)?;
+    } else if options.apply {
+        apply(&config, quiet)?;
+    }
+
+    Ok(stable)
+}
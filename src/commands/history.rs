@@ -0,0 +1,50 @@
+use crate::config::Config;
+use crate::error::Result;
+use crate::git;
+use crate::table::Table;
+use crate::term;
+
+/// List the released versions from the tags matching `git.tag_prefix`,
+/// newest version first, with the date and commit of each
+///
+/// The configured version is marked with `*`.
+pub fn history(config: &Config, quiet: bool) -> Result<()> {
+    git::ensure_git_repository()?;
+    let releases = git::tagged_releases(&config.git.tag_prefix, config.effective_scheme())?;
+
+    if quiet {
+        return Ok(());
+    }
+
+    let rows: Vec<[String; 4]> = releases
+        .iter()
+        .rev()
+        .map(|release| {
+            let marker = if release.version.to_string() == config.version {
+                " *"
+            } else {
+                ""
+            };
+            [
+                format!("{}{}", release.version, marker),
+                release.tag.clone(),
+                release.date.clone(),
+                release.commit[..release.commit.len().min(7)].to_string(),
+            ]
+        })
+        .collect();
+
+    if term::tables() {
+        let mut table = Table::new(&["VERSION", "TAG", "DATE", "COMMIT"]);
+        for row in rows {
+            table.add_row(row.to_vec());
+        }
+        print!("{}", table.render());
+    } else {
+        for row in rows {
+            println!("{}", row.join(" "));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use crate::config;
+use crate::error::{Error, Result};
+use crate::git;
+use crate::hooks::{self, Hook};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Options for `hooks install`
+#[derive(Debug, Default)]
+pub struct InstallOptions {
+    /// Also install the pre-push hook
+    pub pre_push: bool,
+    /// Replace hooks that were not installed by versync
+    pub force: bool,
+}
+
+/// Write a hook script and make it executable
+fn write_hook(path: &Path, script: &str) -> Result<()> {
+    fs::write(path, script)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(())
+}
+
+/// Install the pre-commit hook (and optionally the pre-push hook)
+///
+/// Hooks go to the repository's hooks directory (honouring
+/// `core.hooksPath`). Existing hooks that were not written by versync are
+/// only replaced with `force`. Hooks run at the top of the working tree, so
+/// the config paths are made relative to it.
+pub fn install(config_paths: &[PathBuf], options: &InstallOptions, quiet: bool) -> Result<()> {
+    git::ensure_git_repository()?;
+    let prefix = git::prefix()?;
+    let config_paths: Vec<PathBuf> = config_paths
+        .iter()
+        .map(|path| config::normalize(&prefix.join(path)))
+        .collect();
+    let dir = git::hooks_dir()?;
+    fs::create_dir_all(&dir)?;
+
+    let mut selected = vec![Hook::PreCommit];
+    if options.pre_push {
+        selected.push(Hook::PrePush);
+    }
+
+    let command = hooks::command(&config_paths);
+    for hook in selected {
+        let path = dir.join(hook.name());
+        let script = hook.script(&command);
+
+        let existing = fs::read_to_string(&path).ok();
+        if let Some(existing) = &existing {
+            if *existing == script {
+                if !quiet {
+                    println!("NO CHANGE {}", path.display());
+                }
+                continue;
+            }
+            if !hooks::is_managed(existing) && !options.force {
+                return Err(Error::HookExists(path));
+            }
+        }
+
+        write_hook(&path, &script)?;
+        if !quiet {
+            let action = if existing.is_some() {
+                "REPLACED"
+            } else {
+                "INSTALLED"
+            };
+            println!("{} {}", action, path.display());
+        }
+    }
+
+    Ok(())
+}
+
+/// Remove the hooks installed by versync, leaving any other hooks alone
+pub fn uninstall(quiet: bool) -> Result<()> {
+    git::ensure_git_repository()?;
+    let dir = git::hooks_dir()?;
+
+    for hook in Hook::ALL {
+        let path = dir.join(hook.name());
+        let Ok(existing) = fs::read_to_string(&path) else {
+            continue;
+        };
+        if hooks::is_managed(&existing) {
+            fs::remove_file(&path)?;
+            if !quiet {
+                println!("REMOVED {}", path.display());
+            }
+        } else if !quiet {
+            println!("SKIPPED {}: not installed by versync", path.display());
+        }
+    }
+
+    Ok(())
+}
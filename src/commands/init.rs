@@ -0,0 +1,117 @@
+use crate::config::FileFormat;
+use crate::error::{Error, Result};
+use crate::format;
+use crate::version::Version;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Manifests detected by init, with the keys that may hold their version
+/// (the first present key is used)
+const MANIFESTS: &[(&str, &[&str])] = &[
+    (
+        "Cargo.toml",
+        &["package.version", "workspace.package.version"],
+    ),
+    ("package.json", &["version"]),
+    (
+        "pyproject.toml",
+        &["project.version", "tool.poetry.version"],
+    ),
+];
+
+/// A manifest found in the working directory
+#[derive(Debug)]
+pub struct Detected {
+    pub file: PathBuf,
+    pub key: String,
+    pub version: String,
+}
+
+impl std::fmt::Display for Detected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "FOUND {} {}: {}",
+            self.file.display(),
+            self.key,
+            self.version
+        )
+    }
+}
+
+/// Find supported manifests in the current directory and read their versions
+pub fn detect() -> Vec<Detected> {
+    let mut detected = Vec::new();
+    for (file, keys) in MANIFESTS {
+        let path = Path::new(file);
+        let Some(format) = FileFormat::from_path(path).filter(|_| path.is_file()) else {
+            continue;
+        };
+        if let Some((key, version)) = keys.iter().find_map(|key| {
+            format::read_version(path, key, format)
+                .ok()
+                .map(|version| (key, version))
+        }) {
+            detected.push(Detected {
+                file: path.to_path_buf(),
+                key: key.to_string(),
+                version,
+            });
+        }
+    }
+    detected
+}
+
+/// Pick the canonical version: the highest valid semver, else the first
+fn canonical_version(detected: &[Detected]) -> &str {
+    detected
+        .iter()
+        .filter_map(|d| Version::parse(&d.version).ok().map(|v| (v, &d.version)))
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, version)| version.as_str())
+        .unwrap_or(&detected[0].version)
+}
+
+/// Render the initial configuration
+fn render(version: &str, detected: &[Detected]) -> String {
+    let mut content = format!(
+        "# versync configuration: `version` is the single source of truth\nversion = \"{}\"\n",
+        version
+    );
+    for d in detected {
+        content.push_str(&format!(
+            "\n[[targets]]\nfile = \"{}\"\nkey = \"{}\"\n",
+            d.file.display(),
+            d.key
+        ));
+    }
+    content
+}
+
+/// Scaffold a config from the manifests in the current directory
+///
+/// When the manifests disagree, the highest version becomes the source of
+/// truth; `versync check` then reports the others. An existing config is
+/// only overwritten with `force`.
+pub fn init(config_path: &Path, force: bool, quiet: bool) -> Result<()> {
+    if config_path.exists() && !force {
+        return Err(Error::ConfigExists(config_path.to_path_buf()));
+    }
+
+    let detected = detect();
+    if detected.is_empty() {
+        let names: Vec<&str> = MANIFESTS.iter().map(|(file, _)| *file).collect();
+        return Err(Error::NoManifestsFound(names.join(", ")));
+    }
+
+    let version = canonical_version(&detected);
+    fs::write(config_path, render(version, &detected))?;
+
+    if !quiet {
+        for d in &detected {
+            println!("{}", d);
+        }
+        println!("CREATED {} (version {})", config_path.display(), version);
+    }
+    Ok(())
+}
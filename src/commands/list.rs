@@ -0,0 +1,46 @@
+use crate::config::Config;
+use crate::format;
+use crate::table::Table;
+use crate::term;
+
+/// Print every target with its file, key, format and stored version
+///
+/// Unlike `check`, the stored values are not compared with the configured
+/// version. Targets that cannot be read are listed with the error in place
+/// of the value.
+pub fn list(config: &Config, quiet: bool) {
+    if quiet {
+        return;
+    }
+
+    let rows: Vec<[String; 4]> = config
+        .targets
+        .iter()
+        .map(|target| {
+            let format = target.effective_format();
+            let value = match format {
+                Some(format) => format::read_version(&target.file, &target.key, format)
+                    .unwrap_or_else(|e| format!("({})", e)),
+                None => "(unknown format)".to_string(),
+            };
+            [
+                target.file.display().to_string(),
+                target.key.clone(),
+                format.map_or("-", |format| format.name()).to_string(),
+                value,
+            ]
+        })
+        .collect();
+
+    if term::tables() {
+        let mut table = Table::new(&["FILE", "KEY", "FORMAT", "VALUE"]);
+        for row in rows {
+            table.add_row(row.to_vec());
+        }
+        print!("{}", table.render());
+    } else {
+        for row in rows {
+            println!("{}", row.join(" "));
+        }
+    }
+}
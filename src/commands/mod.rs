@@ -1,7 +1,64 @@
+pub mod adopt;
 pub mod apply;
+pub mod bump;
+pub mod changelog;
 pub mod check;
+pub mod compare;
+pub mod compare_ref;
+pub mod config;
+pub mod diff;
+pub mod doctor;
+pub mod export;
+pub mod filter;
+pub mod fmt;
+pub mod graduate;
+pub mod history;
+pub mod hooks;
+pub mod init;
+pub mod list;
+pub mod next;
+pub mod push;
+pub mod release;
+pub mod resolve;
+pub mod self_update;
+pub mod set;
+pub mod show;
+pub mod status;
 pub mod tag;
+pub mod target;
+#[cfg(feature = "tui")]
+pub mod ui;
+pub mod untag;
+pub mod verify_tag;
+pub mod watch;
 
+pub use adopt::adopt;
 pub use apply::apply;
+pub use bump::bump;
+pub use changelog::changelog;
 pub use check::check;
+pub use compare::compare;
+pub use compare_ref::compare_ref;
+pub use diff::diff;
+pub use doctor::doctor;
+pub use export::export;
+pub use filter::filter;
+pub use fmt::fmt;
+pub use graduate::graduate;
+pub use history::history;
+pub use init::init;
+pub use list::list;
+pub use next::next;
+pub use push::push;
+pub use release::release;
+pub use resolve::resolve;
+pub use self_update::self_update;
+pub use set::set;
+pub use show::show;
+pub use status::status;
 pub use tag::tag;
+#[cfg(feature = "tui")]
+pub use ui::ui;
+pub use untag::untag;
+pub use verify_tag::verify_tag;
+pub use watch::watch;
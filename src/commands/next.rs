@@ -0,0 +1,48 @@
+use crate::commits;
+use crate::config::Config;
+use crate::error::Result;
+use crate::git;
+use crate::version::Version;
+use std::path::Path;
+
+/// Suggest the next version from the conventional commits since the latest
+/// tag
+///
+/// The latest tag (by version, among tags with `git.tag_prefix`) is bumped
+/// by the largest increment the commits call for; without tags, the
+/// configured version is the base and all commits count. The suggestion is
+/// printed bare. With `apply_to`, it is written to that config file when it
+/// is newer than the configured version.
+pub fn next(config: &Config, apply_to: Option<&Path>, quiet: bool) -> Result<Version> {
+    git::ensure_git_repository()?;
+
+    let latest = git::tagged_versions(&config.git.tag_prefix, config.effective_scheme())?.pop();
+    let (base, commits) = match &latest {
+        Some((tag, version)) => (version.clone(), git::commits_since(Some(tag))?),
+        None => (Version::parse(&config.version)?, git::commits_since(None)?),
+    };
+
+    let next = match commits::required_level(&commits) {
+        Some(level) => base.bump(level),
+        None => base,
+    };
+
+    let Some(config_path) = apply_to else {
+        if !quiet {
+            println!("{}", next);
+        }
+        return Ok(next);
+    };
+
+    let current = Version::parse(&config.version)?;
+    if config.effective_scheme().compare(&next, &current).is_gt() {
+        config.write_version(config_path, &next.to_string())?;
+        if !quiet {
+            println!("BUMPED {} -> {} ({})", current, next, config_path.display());
+        }
+    } else if !quiet {
+        println!("NO CHANGE {} (already {})", config_path.display(), current);
+    }
+
+    Ok(next)
+}
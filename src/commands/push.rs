@@ -0,0 +1,47 @@
+use crate::commands::tag::current_tag_name;
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::git;
+use crate::network::{self, Online};
+
+/// Push the tag for the configured version
+///
+/// The tag is pushed to `remote`, or `git.remote` if not given. For tag
+/// templates with a `{n}` counter, the tag at HEAD is pushed.
+pub fn push(config: &Config, remote: Option<&str>, quiet: bool) -> Result<()> {
+    git::ensure_git_repository()?;
+    push_tag(config, &current_tag_name(config)?, remote, quiet)
+}
+
+/// Push a local tag after verifying that it points at HEAD
+///
+/// Pushing is skipped in offline mode.
+pub fn push_tag(config: &Config, tag_name: &str, remote: Option<&str>, quiet: bool) -> Result<()> {
+    let remote = remote.unwrap_or(&config.git.remote);
+
+    let tag_commit =
+        git::resolve_commit(tag_name)?.ok_or_else(|| Error::TagNotFound(tag_name.to_string()))?;
+    let head_commit = git::resolve_commit("HEAD")?
+        .ok_or_else(|| Error::GitCommand("HEAD does not point at a commit".to_string()))?;
+    if tag_commit != head_commit {
+        return Err(Error::TagNotAtHead(tag_name.to_string()));
+    }
+
+    let refspec = format!("refs/tags/{}", tag_name);
+    match network::run(&config.network, || {
+        git::push(&config.network, remote, std::slice::from_ref(&refspec))
+    })? {
+        Online::Ran(()) => {
+            if !quiet {
+                println!("PUSHED {} to {}", tag_name, remote);
+            }
+        }
+        Online::Skipped => {
+            if !quiet {
+                println!("{}", network::skipped("push"));
+            }
+        }
+    }
+
+    Ok(())
+}
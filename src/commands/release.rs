@@ -0,0 +1,190 @@
+use crate::commands::apply::{apply_all, report, ApplyResult};
+use crate::commands::check::{check_branch, RepoCheck};
+use crate::commands::diff::pending_changes;
+use crate::commands::tag::{resolve_tag_name, tag, TagOptions};
+use crate::config::{config_files, Config};
+use crate::error::{Error, Result};
+use crate::git;
+use crate::network::{self, Online};
+use crate::policy;
+use crate::prompt;
+use crate::template::{self, Context};
+use std::fs;
+use std::path::PathBuf;
+
+/// Options for the release command
+#[derive(Debug, Default)]
+pub struct ReleaseOptions {
+    /// Do not commit the updated target files
+    pub no_commit: bool,
+    /// Do not create the tag
+    pub no_tag: bool,
+    /// Do not push the commit and tag
+    pub no_push: bool,
+    /// Skip confirmation prompts
+    pub yes: bool,
+}
+
+/// Uncommitted changes to the config files; any other change is an error
+fn uncommitted_config_changes(config_paths: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let config_files = config_files(config_paths)?
+        .iter()
+        .map(fs::canonicalize)
+        .collect::<std::io::Result<Vec<_>>>()?;
+
+    let (config_changes, other): (Vec<PathBuf>, Vec<PathBuf>) = git::changed_paths()?
+        .into_iter()
+        .partition(|path| fs::canonicalize(path).is_ok_and(|path| config_files.contains(&path)));
+    if !other.is_empty() {
+        return Err(Error::DirtyWorkingTree);
+    }
+    Ok(config_changes)
+}
+
+/// What the preflight of a release found
+pub struct Preflight {
+    /// Uncommitted changes to the config files, committed with the targets
+    pub config_changes: Vec<PathBuf>,
+    /// The tag to create, unless `--no-tag`
+    pub tag_name: Option<String>,
+}
+
+/// Check everything a release can check up front, without touching any file
+pub fn preflight(
+    config: &Config,
+    config_paths: &[PathBuf],
+    options: &ReleaseOptions,
+) -> Result<Preflight> {
+    git::ensure_git_repository()?;
+    let config_changes = uncommitted_config_changes(config_paths)?;
+    if let Some(RepoCheck::BranchMismatch { branch, version }) = check_branch(config)? {
+        return Err(Error::BranchMismatch { branch, version });
+    }
+    policy::enforce(config)?;
+    let tag_name = if options.no_tag {
+        None
+    } else {
+        let tag_name = resolve_tag_name(config)?;
+        git::ensure_tag_not_exists(&tag_name)?;
+        Some(tag_name)
+    };
+    if options.no_commit
+        && !options.no_tag
+        && (!config_changes.is_empty() || !pending_changes(config)?.is_empty())
+    {
+        return Err(Error::Usage(
+            "release --no-commit would leave the tree dirty for tagging; pass --no-tag as well"
+                .to_string(),
+        ));
+    }
+
+    Ok(Preflight {
+        config_changes,
+        tag_name,
+    })
+}
+
+/// Apply, commit, tag and push a release in one go
+///
+/// Everything that can be checked up front (clean tree, branch, policy,
+/// tag name) is checked before any file is touched, so a failing release
+/// leaves the repository unchanged. The tree may only contain uncommitted
+/// changes to the config files (e.g. from `bump`), which are committed along
+/// with the targets. Each stage can be turned off. Pushing asks for
+/// confirmation, and declining leaves the commit and tag local; it is
+/// skipped in offline mode.
+pub fn release(
+    config: &Config,
+    config_paths: &[PathBuf],
+    options: &ReleaseOptions,
+    quiet: bool,
+) -> Result<()> {
+    let Preflight {
+        config_changes,
+        tag_name,
+    } = preflight(config, config_paths, options)?;
+
+    // Apply
+    let results = apply_all(config)?;
+    if !quiet {
+        report(&results);
+    }
+    let mut changed = config_changes;
+    for result in &results {
+        if let ApplyResult::Updated { file, .. } | ApplyResult::Created { file, .. } = result {
+            let file = PathBuf::from(file);
+            if !changed.contains(&file) {
+                changed.push(file);
+            }
+        }
+    }
+
+    // Commit
+    let mut committed = false;
+    if !options.no_commit && !changed.is_empty() {
+        let message = commit_message(config, tag_name.as_deref())?;
+        git::commit_paths(&changed, &message)?;
+        committed = true;
+        if !quiet {
+            println!("COMMITTED {}", message.lines().next().unwrap_or_default());
+        }
+    }
+
+    // Tag
+    let tag_name = match tag_name {
+        Some(_) => {
+            let tag_options = TagOptions {
+                yes: options.yes,
+                ..Default::default()
+            };
+            Some(tag(config, &tag_options, quiet)?)
+        }
+        None => None,
+    };
+
+    // Push
+    if options.no_push {
+        return Ok(());
+    }
+    let mut refspecs = Vec::new();
+    if committed {
+        refspecs.push("HEAD".to_string());
+    }
+    if let Some(tag_name) = &tag_name {
+        refspecs.push(format!("refs/tags/{}", tag_name));
+    }
+    if refspecs.is_empty() {
+        return Ok(());
+    }
+
+    let remote = &config.git.remote;
+    match network::run(&config.network, || {
+        prompt::confirm(
+            &format!("Push {} to {}?", refspecs.join(" "), remote),
+            options.yes,
+        )?;
+        git::push(&config.network, remote, &refspecs)
+    })? {
+        Online::Ran(()) => {
+            if !quiet {
+                println!("PUSHED {} to {}", refspecs.join(" "), remote);
+            }
+        }
+        Online::Skipped => {
+            if !quiet {
+                println!("{}", network::skipped("push"));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Render `release.commit_message`
+fn commit_message(config: &Config, tag_name: Option<&str>) -> Result<String> {
+    let message = Context::new(&config.version).render(&config.release.commit_message)?;
+    let tag_name = tag_name.map_or_else(|| config.tag_name(), str::to_string);
+    Ok(template::render(&message, |name| {
+        (name == "tag").then(|| tag_name.clone())
+    }))
+}
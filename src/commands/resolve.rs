@@ -0,0 +1,94 @@
+use crate::commands::apply::apply_target;
+use crate::commands::check::{check_target, CheckResult};
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::prompt;
+use crate::template::Context;
+use std::path::Path;
+
+/// Interactively resolve version mismatches target by target
+///
+/// For each mismatching target the user can adopt the target's version as
+/// the new source of truth (rewriting the config file), overwrite the target
+/// with the configured version, or skip it. Adopting a version re-opens
+/// targets that matched the old one. Returns whether all targets match at
+/// the end. Targets with a `template` can only be overwritten or skipped.
+pub fn resolve(config: &Config, config_path: &Path, quiet: bool) -> Result<bool> {
+    let mut config = config.clone();
+    let mut skipped = vec![false; config.targets.len()];
+
+    loop {
+        let mut next = None;
+        for (i, target) in config.targets.iter().enumerate() {
+            if skipped[i] {
+                continue;
+            }
+            if let CheckResult::Mismatch { .. } = check_target(target, &config.version)? {
+                next = Some(i);
+                break;
+            }
+        }
+        let Some(i) = next else { break };
+        let target = &config.targets[i];
+
+        let result = check_target(target, &config.version)?;
+        let CheckResult::Mismatch {
+            actual, expected, ..
+        } = &result
+        else {
+            unreachable!("target was just found mismatching");
+        };
+
+        // A value rendered from a lossy template cannot be adopted
+        let version = target.version_from_value(actual);
+        let adopt = format!(
+            "adopt {} as the version in {}",
+            version.as_deref().unwrap_or(actual),
+            config_path.display()
+        );
+        let overwrite = format!("overwrite {} with {}", target.file.display(), expected);
+        let mut choices = Vec::new();
+        if version.is_some() {
+            choices.push(('a', adopt.as_str()));
+        }
+        choices.extend([('o', overwrite.as_str()), ('s', "skip"), ('q', "quit")]);
+        let choice = prompt::choose(&result.to_string(), &choices)?;
+
+        match choice {
+            'a' => {
+                let version = version.unwrap_or_else(|| actual.clone());
+                config.write_version(config_path, &version)?;
+                if !quiet {
+                    println!(
+                        "ADOPTED {} from {} {}",
+                        version,
+                        target.file.display(),
+                        target.key
+                    );
+                }
+                config.version = version;
+            }
+            'o' => {
+                let result = apply_target(target, &Context::new(&config.version))?;
+                if !quiet {
+                    println!("{}", result);
+                }
+            }
+            's' => skipped[i] = true,
+            _ => return Err(Error::Aborted),
+        }
+    }
+
+    let mut all_ok = true;
+    for target in &config.targets {
+        let result = check_target(target, &config.version)?;
+        if !result.is_ok() {
+            all_ok = false;
+        }
+        if !quiet {
+            println!("{}", result);
+        }
+    }
+
+    Ok(all_ok)
+}
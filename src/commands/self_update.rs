@@ -0,0 +1,72 @@
+use crate::config::NetworkConfig;
+use crate::error::Result;
+use crate::network::{self, Online};
+use crate::prompt;
+use crate::term::{self, Style};
+use crate::update;
+use crate::version::Version;
+
+/// Options for the self-update command
+#[derive(Debug, Default)]
+pub struct SelfUpdateOptions {
+    /// Only report whether a newer release exists
+    pub check: bool,
+    /// Skip the confirmation prompt
+    pub yes: bool,
+}
+
+/// Update the running binary to the latest GitHub release
+///
+/// The downloaded binary is verified against the release's signed
+/// `SHA256SUMS` before it replaces the executable. Returns whether versync
+/// is (now) up to date; with `--check` nothing is downloaded. Skipped in
+/// offline mode.
+pub fn self_update(
+    network: &NetworkConfig,
+    options: &SelfUpdateOptions,
+    quiet: bool,
+) -> Result<bool> {
+    let release = match network::run(network, || update::latest_release(network))? {
+        Online::Ran(release) => release,
+        Online::Skipped => {
+            if !quiet {
+                println!("{}", network::skipped("self-update"));
+            }
+            return Ok(true);
+        }
+    };
+
+    let current = Version::parse(env!("CARGO_PKG_VERSION"))?;
+    let latest = release.version()?;
+    if latest.precedence(&current).is_le() {
+        if !quiet {
+            println!("UP TO DATE versync {}", current);
+        }
+        return Ok(true);
+    }
+
+    if options.check {
+        if !quiet {
+            println!("UPDATE AVAILABLE versync {} -> {}", current, latest);
+        }
+        return Ok(false);
+    }
+
+    prompt::confirm(
+        &format!("Update versync {} to {}?", current, latest),
+        options.yes,
+    )?;
+    let binary = release.download_binary(network)?;
+    let exe = update::replace_executable(&binary)?;
+
+    if !quiet {
+        let line = format!(
+            "UPDATED versync {} -> {} ({})",
+            current,
+            latest,
+            exe.display()
+        );
+        println!("{}", term::get().paint(&line, Style::Success));
+    }
+    Ok(true)
+}
@@ -0,0 +1,44 @@
+use crate::commands::apply::apply;
+use crate::config::Config;
+use crate::error::Result;
+use crate::policy;
+use crate::version::Version;
+use std::path::Path;
+
+/// Set an explicit version in the config file
+///
+/// The version must be valid in the config's `scheme` (semver by default).
+/// With `apply`, it is written to all targets as well.
+pub fn set(
+    config: &Config,
+    config_path: &Path,
+    version: &str,
+    apply_targets: bool,
+    quiet: bool,
+) -> Result<Version> {
+    let version = Version::parse(&config.version_from_scheme(version)?)?;
+    policy::enforce_downgrade(
+        config,
+        &config.version_key(),
+        &config.version,
+        &version.to_string(),
+    )?;
+
+    config.write_version(config_path, &version.to_string())?;
+    if !quiet {
+        println!(
+            "SET {} -> {} ({})",
+            config.version,
+            version,
+            config_path.display()
+        );
+    }
+
+    if apply_targets {
+        let mut config = config.clone();
+        config.version = version.to_string();
+        apply(&config, quiet)?;
+    }
+
+    Ok(version)
+}
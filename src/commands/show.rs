@@ -0,0 +1,26 @@
+use crate::commands::tag::tag_at_head;
+use crate::config::Config;
+use crate::error::Result;
+
+/// Print the source-of-truth version in the config's `scheme`, or the tag
+/// name with `tag`
+///
+/// The value is printed bare on a single line so scripts can capture it
+/// (`VERSION=$(versync show)`). For tag templates with a `{n}` counter, the
+/// tag is the one at HEAD, and nothing is printed when HEAD carries none
+/// (see [`tag_at_head`]).
+pub fn show(config: &Config, tag: bool, quiet: bool) -> Result<()> {
+    if tag {
+        if let Some(tag) = tag_at_head(config)? {
+            if !quiet {
+                println!("{}", tag);
+            }
+        }
+    } else if !quiet {
+        let version = config
+            .version_in_scheme(&config.version)
+            .unwrap_or_else(|_| config.version.clone());
+        println!("{}", version);
+    }
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use crate::commands::check::{check_branch, check_repository, check_target, RepoCheck};
+use crate::commands::tag::tag_matches;
+use crate::config::Config;
+use crate::error::Result;
+use crate::git;
+use crate::network::{self, Online};
+use crate::term::{self, Style};
+
+/// Whether the release tag is on the remote
+///
+/// The remote's tag listing is cached for `network.cache_ttl` seconds, so
+/// this may lag behind a push from elsewhere until `--refresh`. A failed
+/// lookup is reported rather than failing the status.
+fn published(config: &Config, remote: &str) -> String {
+    let template = config.tag_name();
+    match network::run(&config.network, || {
+        git::remote_tags(&config.network, remote)
+    }) {
+        Ok(Online::Ran(tags)) => match tags.iter().find(|tag| tag_matches(&template, tag)) {
+            Some(tag) => format!("PUBLISHED {} on '{}'", tag, remote),
+            None => format!("UNPUBLISHED {} on '{}'", template, remote),
+        },
+        Ok(Online::Skipped) => network::skipped("remote tag lookup"),
+        Err(e) => format!("UNKNOWN remote tags on '{}': {}", remote, e),
+    }
+}
+
+/// Print a consolidated report of the version and repository state
+///
+/// Shows the source-of-truth version, every target's match state, the
+/// branch rule, whether the tree is dirty, where the release tag is, which
+/// tags point at HEAD and whether the tag is on `git.remote`. Returns
+/// whether all targets match; the git state is informational only.
+pub fn status(config: &Config, quiet: bool) -> Result<bool> {
+    let term = term::get();
+    let paint = |line: String, ok: bool| {
+        if ok {
+            term.paint(&line, Style::Success)
+        } else {
+            term.paint(&line, Style::Failure)
+        }
+    };
+
+    let mut lines = vec![term.paint(&format!("VERSION {}", config.version), Style::Bold)];
+
+    let mut all_ok = true;
+    for target in &config.targets {
+        let result = check_target(target, &config.version)?;
+        all_ok &= result.is_ok();
+        lines.push(paint(result.to_string(), result.is_ok()));
+    }
+
+    if git::is_inside_work_tree()? {
+        let repo: Vec<RepoCheck> = check_branch(config)?
+            .into_iter()
+            .chain(check_repository(config)?)
+            .collect();
+        for result in repo {
+            lines.push(paint(result.to_string(), result.is_ok()));
+        }
+
+        let head_tags = git::tags_at("HEAD")?;
+        if head_tags.is_empty() {
+            lines.push("UNTAGGED HEAD".to_string());
+        } else {
+            lines.push(format!("TAGGED HEAD {}", head_tags.join(", ")));
+        }
+
+        let remote = &config.git.remote;
+        if git::remote_exists(remote)? {
+            lines.push(published(config, remote));
+        }
+    } else {
+        lines.push("SKIPPED git state: not a git repository".to_string());
+    }
+
+    if !quiet {
+        for line in lines {
+            println!("{}", line);
+        }
+    }
+
+    Ok(all_ok)
+}
@@ -1,16 +1,107 @@
-use crate::commands::check::check_silent;
+use crate::commands::changelog::release_notes;
+use crate::commands::check::{check_branch, check_silent, RepoCheck};
+use crate::commands::push::push_tag;
 use crate::config::Config;
 use crate::error::{Error, Result};
 use crate::git;
+use crate::network::{self, Online};
+use crate::policy;
+use crate::prompt;
+use crate::term::{self, Style};
+
+/// Options for the tag command
+#[derive(Debug, Default)]
+pub struct TagOptions {
+    /// Replace the tag if it already exists
+    pub force: bool,
+    /// Skip confirmation prompts
+    pub yes: bool,
+    /// Run every precondition and report the tag without creating it
+    pub dry_run: bool,
+    /// Use the changelog section for the version as the tag message
+    pub changelog: bool,
+    /// Push the tag to `git.remote` after creating it
+    pub push: bool,
+}
+
+/// Placeholder in channel tag templates replaced by a counter
+const COUNTER: &str = "{n}";
+
+/// Resolve the tag name to create
+///
+/// For tag templates containing `{n}`, the first number from 1 that gives a
+/// tag which does not exist yet is used (e.g. `v1.2.3-hotfix.1`, `.2`, ...).
+pub fn resolve_tag_name(config: &Config) -> Result<String> {
+    let template = config.tag_name();
+
+    if !template.contains(COUNTER) {
+        return Ok(template);
+    }
+
+    let mut n = 1;
+    loop {
+        let candidate = template.replace(COUNTER, &n.to_string());
+        if !git::tag_exists(&candidate)? {
+            return Ok(candidate);
+        }
+        n += 1;
+    }
+}
+
+/// The existing tag for the configured version
+///
+/// A tag template with `{n}` names no single tag, so this is the tag it gave
+/// HEAD; fails with `UntaggedCounter` when HEAD carries none.
+pub fn current_tag_name(config: &Config) -> Result<String> {
+    let template = config.tag_name();
+
+    if !template.contains(COUNTER) {
+        return Ok(template);
+    }
+
+    git::tags_at("HEAD")?
+        .into_iter()
+        .find(|tag| tag_matches(&template, tag))
+        .ok_or(Error::UntaggedCounter(template))
+}
+
+/// Like [`current_tag_name`], but `None` when a `{n}` template has no tag at
+/// HEAD instead of an error
+pub fn tag_at_head(config: &Config) -> Result<Option<String>> {
+    match current_tag_name(config) {
+        Ok(tag) => Ok(Some(tag)),
+        Err(Error::UntaggedCounter(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Whether a tag name was produced by a tag template
+///
+/// A `{n}` counter in the template matches any number.
+pub fn tag_matches(template: &str, tag: &str) -> bool {
+    match template.split_once(COUNTER) {
+        None => template == tag,
+        Some((prefix, suffix)) => tag
+            .strip_prefix(prefix)
+            .and_then(|rest| rest.strip_suffix(suffix))
+            .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit())),
+    }
+}
 
 /// Create a git tag based on the configuration
 ///
+/// For release channels, pass the config derived with [`Config::for_channel`].
+///
 /// Prerequisites:
 /// 1. Must be inside a git repository
-/// 2. versync check must pass
+/// 2. versync check must pass (including `git.branch_pattern` and `[policy]`)
 /// 3. Working tree and index must be clean
-/// 4. Tag must not already exist
-pub fn tag(config: &Config, quiet: bool) -> Result<()> {
+/// 4. Tag must not already exist (unless `--force` is given and confirmed),
+///    including on the remote when `git.check_remote` is set (without `--force`)
+///
+/// The remote check is skipped in offline mode. With `dry_run`, all of the
+/// above is checked but the tag is only reported. Returns the tag name.
+pub fn tag(config: &Config, options: &TagOptions, quiet: bool) -> Result<String> {
     // 1. Ensure we're in a git repository
     git::ensure_git_repository()?;
 
@@ -18,21 +109,80 @@ pub fn tag(config: &Config, quiet: bool) -> Result<()> {
     if !check_silent(config)? {
         return Err(Error::VersionMismatch);
     }
+    if let Some(RepoCheck::BranchMismatch { branch, version }) = check_branch(config)? {
+        return Err(Error::BranchMismatch { branch, version });
+    }
+    policy::enforce(config)?;
 
     // 3. Ensure working tree and index are clean
     git::ensure_clean()?;
 
-    // 4. Ensure tag doesn't exist
-    let tag_name = config.tag_name();
-    git::ensure_tag_not_exists(&tag_name)?;
+    // 4. Ensure tag doesn't exist, or confirm replacing it
+    let tag_name = resolve_tag_name(config)?;
+    let replace = options.force && git::tag_exists(&tag_name)?;
+    if replace {
+        if !options.dry_run {
+            prompt::confirm(
+                &format!("Tag {} already exists. Replace it?", tag_name),
+                options.yes,
+            )?;
+        }
+    } else {
+        git::ensure_tag_not_exists(&tag_name)?;
+    }
+
+    if config.git.check_remote && !options.force {
+        let remote = &config.git.remote;
+        // A cached listing may predate a tag pushed from elsewhere
+        let mut network = config.network.clone();
+        network.refresh = true;
+        let checked = network::run(&network, || {
+            git::ensure_remote_tag_not_exists(&network, remote, &tag_name)
+        })?;
+        if checked == Online::Skipped && !quiet {
+            println!("{}", network::skipped("remote tag check"));
+        }
+    }
 
     // Create the tag
-    let message = format!("Release {}", config.version);
-    git::create_annotated_tag(&tag_name, &message)?;
+    let message = if options.changelog {
+        release_notes(config)?
+    } else {
+        format!("Release {}", config.version)
+    };
+    if options.dry_run {
+        if !quiet {
+            let action = if replace { "REPLACE" } else { "CREATE" };
+            match message.trim_end().split_once('\n') {
+                None => println!("WOULD {} TAG {} (message: {})", action, tag_name, message),
+                Some(_) => println!(
+                    "WOULD {} TAG {} with message:\n{}",
+                    action,
+                    tag_name,
+                    message.trim_end()
+                ),
+            }
+        }
+        return Ok(tag_name);
+    }
+    if replace {
+        git::force_annotated_tag(&tag_name, &message)?;
+    } else {
+        git::create_annotated_tag(&tag_name, &message)?;
+    }
 
     if !quiet {
-        println!("CREATED TAG {}", tag_name);
+        let line = if replace {
+            format!("REPLACED TAG {}", tag_name)
+        } else {
+            format!("CREATED TAG {}", tag_name)
+        };
+        println!("{}", term::get().paint(&line, Style::Success));
+    }
+
+    if options.push {
+        push_tag(config, &tag_name, None, quiet)?;
     }
 
-    Ok(())
+    Ok(tag_name)
 }
@@ -0,0 +1,63 @@
+use crate::config::{self, Config, FileFormat, Target};
+use crate::error::{Error, Result};
+use crate::format;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Append a target to the config file
+///
+/// The file is relative to the working directory and is written to the
+/// config relative to the config's directory. The file must exist and the
+/// key must resolve to a string in it. A target with the same file and key
+/// as an existing one is refused. The format is only written to the config
+/// when given explicitly.
+pub fn add(
+    config: &Config,
+    config_path: &Path,
+    file: &Path,
+    key: &str,
+    format: Option<FileFormat>,
+    quiet: bool,
+) -> Result<()> {
+    let path = config::resolve_path(file);
+    if config
+        .targets
+        .iter()
+        .any(|target| target.file == path && target.key == key)
+    {
+        return Err(Error::Usage(format!(
+            "{} {} is already a target",
+            file.display(),
+            key
+        )));
+    }
+
+    let target = Target {
+        file: path,
+        key: key.to_string(),
+        template: None,
+        scheme: None,
+        group: None,
+        required: true,
+        create: false,
+        format,
+        extra: BTreeMap::new(),
+    };
+    let effective_format = target
+        .effective_format()
+        .ok_or_else(|| Error::UnknownFormat(target.file.clone()))?;
+    let current = format::read_version(&target.file, &target.key, effective_format)?;
+
+    Config::add_target(config_path, &target)?;
+    if !quiet {
+        println!(
+            "ADDED {} {} ({}, currently {})",
+            file.display(),
+            key,
+            config_path.display(),
+            current
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,296 @@
+use crate::commands::apply::{apply_all, ApplyResult};
+use crate::commands::bump::{bump, BumpLevel, BumpOptions};
+use crate::commands::check::{check_target, CheckResult};
+use crate::commands::tag::{tag, TagOptions};
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::git;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Cell, List, Paragraph, Row, Table};
+use ratatui::{DefaultTerminal, Frame};
+use std::path::PathBuf;
+
+/// Status of a single target as shown in the dashboard
+enum TargetStatus {
+    Ok,
+    Mismatch(String),
+    Skipped,
+    Error(String),
+}
+
+struct TargetRow {
+    file: String,
+    key: String,
+    status: TargetStatus,
+}
+
+/// Snapshot of the git repository state
+#[derive(Default)]
+struct GitState {
+    repository: bool,
+    clean_tree: bool,
+    clean_index: bool,
+    tag_exists: bool,
+    tags: Vec<String>,
+}
+
+/// Dashboard state, rebuilt on every refresh
+struct Dashboard {
+    config_paths: Vec<PathBuf>,
+    config: Config,
+    targets: Vec<TargetRow>,
+    git: GitState,
+    message: String,
+}
+
+impl Dashboard {
+    fn new(config_paths: &[PathBuf], config: &Config) -> Self {
+        let mut dashboard = Self {
+            config_paths: config_paths.to_vec(),
+            config: config.clone(),
+            targets: Vec::new(),
+            git: GitState::default(),
+            message: String::new(),
+        };
+        dashboard.refresh();
+        dashboard
+    }
+
+    /// Reload the configuration and re-read every target and the git state
+    fn refresh(&mut self) {
+        match Config::load_all(&self.config_paths) {
+            Ok(config) => self.config = config,
+            Err(e) => self.message = format!("Config reload failed: {}", e),
+        }
+
+        self.targets = self
+            .config
+            .targets
+            .iter()
+            .map(|target| {
+                let status = match check_target(target, &self.config.version) {
+                    Ok(CheckResult::Ok { .. } | CheckResult::Fixed { .. }) => TargetStatus::Ok,
+                    Ok(CheckResult::Skipped { .. }) => TargetStatus::Skipped,
+                    Ok(CheckResult::Mismatch { actual, .. }) => TargetStatus::Mismatch(actual),
+                    Err(e) => TargetStatus::Error(e.to_string()),
+                };
+                TargetRow {
+                    file: target.file.display().to_string(),
+                    key: target.key.clone(),
+                    status,
+                }
+            })
+            .collect();
+
+        self.git = read_git_state(&self.config);
+    }
+
+    fn run_apply(&mut self) {
+        self.message = match apply_all(&self.config) {
+            Ok(results) => {
+                let updated = results
+                    .iter()
+                    .filter(|r| {
+                        matches!(r, ApplyResult::Updated { .. } | ApplyResult::Created { .. })
+                    })
+                    .count();
+                format!(
+                    "Applied {}: {} file(s) updated",
+                    self.config.version, updated
+                )
+            }
+            Err(e) => format!("Apply failed: {}", e),
+        };
+        self.refresh();
+    }
+
+    /// Bump the version in the config file and reload it; targets are left
+    /// for `apply`
+    fn run_bump(&mut self, level: BumpLevel) {
+        let options = BumpOptions {
+            level,
+            pre: None,
+            apply: false,
+        };
+        self.message = match self
+            .config
+            .version_file(&self.config_paths)
+            .and_then(|path| bump(&self.config, &path, &options, true))
+        {
+            Ok(version) => format!("Bumped {} -> {}", self.config.version, version),
+            Err(e) => format!("Bump failed: {}", e),
+        };
+        self.refresh();
+    }
+
+    fn run_tag(&mut self) {
+        self.message = match tag(&self.config, &TagOptions::default(), true) {
+            Ok(tag_name) => format!("Created tag {}", tag_name),
+            Err(e) => format!("Tag failed: {}", e),
+        };
+        self.refresh();
+    }
+}
+
+fn read_git_state(config: &Config) -> GitState {
+    if !git::is_inside_work_tree().unwrap_or(false) {
+        return GitState::default();
+    }
+
+    GitState {
+        repository: true,
+        clean_tree: git::is_working_tree_clean().unwrap_or(false),
+        clean_index: git::is_index_clean().unwrap_or(false),
+        tag_exists: git::tag_exists(&config.tag_name()).unwrap_or(false),
+        tags: git::list_tags(&config.git.tag_prefix).unwrap_or_default(),
+    }
+}
+
+/// Run the interactive status dashboard until the user quits
+pub fn ui(config_paths: &[PathBuf], config: &Config) -> Result<()> {
+    let mut dashboard = Dashboard::new(config_paths, config);
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut dashboard);
+    ratatui::restore();
+
+    result.map_err(|e| Error::Terminal(e.to_string()))
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, dashboard: &mut Dashboard) -> std::io::Result<()> {
+    loop {
+        terminal.draw(|frame| draw(frame, dashboard))?;
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Char('r') => {
+                    dashboard.refresh();
+                    dashboard.message = "Refreshed".to_string();
+                }
+                KeyCode::Char('a') => dashboard.run_apply(),
+                KeyCode::Char('M') => dashboard.run_bump(BumpLevel::Major),
+                KeyCode::Char('m') => dashboard.run_bump(BumpLevel::Minor),
+                KeyCode::Char('p') => dashboard.run_bump(BumpLevel::Patch),
+                KeyCode::Char('t') => dashboard.run_tag(),
+                _ => {}
+            }
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, dashboard: &Dashboard) {
+    let [header, targets, bottom, footer] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(5),
+        Constraint::Length(8),
+        Constraint::Length(2),
+    ])
+    .areas(frame.area());
+
+    let title = Paragraph::new(Line::from(format!(
+        "Version {}  (tag {})",
+        dashboard.config.version,
+        dashboard.config.tag_name()
+    )))
+    .style(Style::default().add_modifier(Modifier::BOLD))
+    .block(Block::default().borders(Borders::ALL).title(" versync "));
+    frame.render_widget(title, header);
+
+    frame.render_widget(targets_table(dashboard), targets);
+
+    let [git_area, tags_area] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(bottom);
+    frame.render_widget(git_panel(&dashboard.git), git_area);
+
+    let tags = List::new(dashboard.git.tags.iter().map(String::as_str))
+        .block(Block::default().borders(Borders::ALL).title(" Tags "));
+    frame.render_widget(tags, tags_area);
+
+    let help = Paragraph::new(vec![
+        Line::from(dashboard.message.as_str()),
+        Line::from("[M/m/p] bump major/minor/patch  [a] apply  [t] tag  [r] refresh  [q] quit")
+            .style(Style::default().fg(Color::DarkGray)),
+    ]);
+    frame.render_widget(help, footer);
+}
+
+fn targets_table(dashboard: &Dashboard) -> Table<'_> {
+    let rows = dashboard.targets.iter().map(|row| {
+        let (actual, status) = match &row.status {
+            TargetStatus::Ok => (
+                dashboard.config.version.clone(),
+                Cell::from("OK").style(Style::default().fg(Color::Green)),
+            ),
+            TargetStatus::Mismatch(actual) => (
+                actual.clone(),
+                Cell::from("MISMATCH").style(Style::default().fg(Color::Red)),
+            ),
+            TargetStatus::Skipped => (
+                "-".to_string(),
+                Cell::from("SKIPPED").style(Style::default().fg(Color::Yellow)),
+            ),
+            TargetStatus::Error(message) => (
+                message.clone(),
+                Cell::from("ERROR").style(Style::default().fg(Color::Yellow)),
+            ),
+        };
+        Row::new(vec![
+            Cell::from(row.file.as_str()),
+            Cell::from(row.key.as_str()),
+            Cell::from(actual),
+            status,
+        ])
+    });
+
+    Table::new(
+        rows,
+        [
+            Constraint::Percentage(35),
+            Constraint::Percentage(25),
+            Constraint::Percentage(25),
+            Constraint::Percentage(15),
+        ],
+    )
+    .header(
+        Row::new(vec!["File", "Key", "Version", "Status"])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+    )
+    .block(Block::default().borders(Borders::ALL).title(" Targets "))
+}
+
+fn git_panel(state: &GitState) -> Paragraph<'static> {
+    let yes_no = |ok: bool, yes: &str, no: &str| -> Line<'static> {
+        if ok {
+            Line::from(yes.to_string()).style(Style::default().fg(Color::Green))
+        } else {
+            Line::from(no.to_string()).style(Style::default().fg(Color::Red))
+        }
+    };
+
+    let lines = if state.repository {
+        vec![
+            yes_no(
+                state.clean_tree,
+                "Working tree: clean",
+                "Working tree: dirty",
+            ),
+            yes_no(state.clean_index, "Index: clean", "Index: staged changes"),
+            yes_no(
+                !state.tag_exists,
+                "Tag: not created yet",
+                "Tag: already exists",
+            ),
+        ]
+    } else {
+        vec![yes_no(false, "", "Not inside a git repository")]
+    };
+
+    Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(" Git "))
+}
@@ -0,0 +1,84 @@
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::git;
+use crate::network::{self, Online};
+use crate::prompt;
+use crate::version::Version;
+
+/// Options for the untag command
+#[derive(Debug, Default)]
+pub struct UntagOptions {
+    /// Delete the tag for this version instead of the configured one
+    pub version: Option<String>,
+    /// Also delete the tag on `git.remote`
+    pub remote: bool,
+    /// Skip confirmation prompts
+    pub yes: bool,
+}
+
+/// Delete the tag for a version locally and, optionally, on the remote
+///
+/// Fails with `TagNotFound` when the tag exists in neither place. The
+/// remote's tag listing may come from the cache (see `--refresh`); in
+/// offline mode the remote deletion is skipped.
+pub fn untag(config: &Config, options: &UntagOptions, quiet: bool) -> Result<()> {
+    git::ensure_git_repository()?;
+
+    let tag_name = match &options.version {
+        Some(version) => {
+            Version::parse(version)?;
+            let mut config = config.clone();
+            config.version = version.clone();
+            config.tag_name()
+        }
+        None => config.tag_name(),
+    };
+    let remote = &config.git.remote;
+
+    let local = git::tag_exists(&tag_name)?;
+    let on_remote = if options.remote {
+        match network::run(&config.network, || {
+            git::remote_tag_exists(&config.network, remote, &tag_name)
+        })? {
+            Online::Ran(exists) => exists,
+            Online::Skipped => {
+                if !quiet {
+                    println!("{}", network::skipped("remote tag deletion"));
+                }
+                false
+            }
+        }
+    } else {
+        false
+    };
+
+    if !local && !on_remote {
+        return Err(Error::TagNotFound(tag_name));
+    }
+
+    let places = match (local, on_remote) {
+        (true, true) => format!("locally and on '{}'", remote),
+        (true, false) => "locally".to_string(),
+        (false, _) => format!("on '{}'", remote),
+    };
+    prompt::confirm(&format!("Delete tag {} {}?", tag_name, places), options.yes)?;
+
+    if local {
+        git::delete_tag(&tag_name)?;
+        if !quiet {
+            println!("DELETED TAG {}", tag_name);
+        }
+    }
+    if on_remote {
+        git::push(
+            &config.network,
+            remote,
+            &[format!(":refs/tags/{}", tag_name)],
+        )?;
+        if !quiet {
+            println!("DELETED TAG {} on {}", tag_name, remote);
+        }
+    }
+
+    Ok(())
+}
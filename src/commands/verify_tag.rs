@@ -0,0 +1,37 @@
+use crate::commands::tag::tag_matches;
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::git;
+use crate::term::{self, Style};
+
+/// Verify that HEAD carries the tag for the configured version
+///
+/// Intended for release-publish jobs that must only run on tagged commits.
+/// For channel tag templates with a `{n}` counter, any counter matches.
+/// Fails with `TagNotAtHead` when the tag exists elsewhere and with
+/// `TagNotFound` when it does not exist at all. Returns the tag name.
+pub fn verify_tag(config: &Config, quiet: bool) -> Result<String> {
+    git::ensure_git_repository()?;
+
+    let template = config.tag_name();
+    let head_tags = git::tags_at("HEAD")?;
+    if let Some(tag) = head_tags.iter().find(|t| tag_matches(&template, t)) {
+        if !quiet {
+            let line = format!("OK {} at HEAD", tag);
+            println!("{}", term::get().paint(&line, Style::Success));
+        }
+        return Ok(tag.clone());
+    }
+
+    if !quiet {
+        match git::describe("HEAD")? {
+            Some(description) => println!("MISMATCH HEAD is {}", description),
+            None => println!("MISMATCH HEAD is not tagged"),
+        }
+    }
+    if git::tag_exists(&template)? {
+        Err(Error::TagNotAtHead(template))
+    } else {
+        Err(Error::TagNotFound(template))
+    }
+}
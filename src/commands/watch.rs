@@ -0,0 +1,120 @@
+use crate::ci;
+use crate::commands::check::{check, CheckOptions};
+use crate::config::{config_files, Config};
+use crate::error::{Error, Result};
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::convert::Infallible;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// How long to wait for further events before re-running the check, so an
+/// editor saving several files (or one file in several steps) triggers a
+/// single run
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Absolute path of a file that may not exist (yet)
+fn absolute(path: &Path) -> PathBuf {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let parent = fs::canonicalize(parent).unwrap_or_else(|_| parent.to_path_buf());
+    match path.file_name() {
+        Some(name) => parent.join(name),
+        None => parent,
+    }
+}
+
+/// The config files and the target files of a config
+fn watched_files(config_paths: &[PathBuf], config: Option<&Config>) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = config_files(config_paths).unwrap_or_default();
+    if let Some(config) = config {
+        // A missing optional file may sit in a directory that doesn't exist
+        files.extend(
+            config
+                .targets
+                .iter()
+                .filter(|target| target.required || target.file.exists())
+                .map(|target| target.file.clone()),
+        );
+    }
+    let mut files: Vec<PathBuf> = files.iter().map(|file| absolute(file)).collect();
+    files.sort();
+    files.dedup();
+    files
+}
+
+/// Re-run the check whenever the config or a target file changes
+///
+/// `load` re-reads the configuration before every run, so edits to the
+/// config (including added or removed targets) take effect immediately.
+/// Errors are printed and the watch continues. Runs until interrupted.
+pub fn watch(
+    config_paths: &[PathBuf],
+    load: impl Fn() -> Result<Config>,
+    options: &CheckOptions,
+    quiet: bool,
+) -> Result<Infallible> {
+    let (tx, rx) = mpsc::channel();
+
+    loop {
+        if !quiet {
+            println!("--- {} ---", chrono::Local::now().format("%H:%M:%S"));
+        }
+        let config = load();
+        let error = match &config {
+            Ok(config) => check(config, options, quiet)
+                .err()
+                .map(|e| ci::get().error_line(&e)),
+            Err(e) => Some(ci::get().error_line(e)),
+        };
+        if let (Some(line), false) = (error, quiet) {
+            eprintln!("{}", line);
+        }
+
+        // Directories are watched rather than files, since editors often
+        // replace a file instead of writing it in place
+        let files = watched_files(config_paths, config.as_ref().ok());
+        let mut watcher =
+            notify::recommended_watcher(tx.clone()).map_err(|e| Error::Watch(e.to_string()))?;
+        let mut dirs: Vec<&Path> = files.iter().filter_map(|file| file.parent()).collect();
+        dirs.sort();
+        dirs.dedup();
+        for dir in dirs {
+            watcher
+                .watch(dir, RecursiveMode::NonRecursive)
+                .map_err(|e| Error::Watch(format!("{}: {}", dir.display(), e)))?;
+        }
+
+        wait_for_change(&rx, &files)?;
+        drop(watcher);
+        while rx.try_recv().is_ok() {}
+    }
+}
+
+/// Block until one of the files changes, then until events settle
+fn wait_for_change(
+    rx: &mpsc::Receiver<notify::Result<notify::Event>>,
+    files: &[PathBuf],
+) -> Result<()> {
+    let is_relevant = |event: &notify::Result<notify::Event>| match event {
+        Ok(event) => {
+            !matches!(event.kind, EventKind::Access(_))
+                && event.paths.iter().any(|path| files.contains(path))
+        }
+        Err(_) => false,
+    };
+
+    loop {
+        let event = rx
+            .recv()
+            .map_err(|_| Error::Watch("file watcher stopped".to_string()))?;
+        if is_relevant(&event) {
+            break;
+        }
+    }
+    while rx.recv_timeout(DEBOUNCE).is_ok() {}
+    Ok(())
+}
@@ -0,0 +1,137 @@
+use crate::git::Commit;
+use crate::version::Level;
+
+/// A commit message following the Conventional Commits format
+/// (`type(scope)!: description`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conventional {
+    /// Commit type, lowercased (`feat`, `fix`, ...)
+    pub kind: String,
+    pub scope: Option<String>,
+    /// Marked with `!` or a `BREAKING CHANGE:` footer
+    pub breaking: bool,
+    pub description: String,
+}
+
+impl Conventional {
+    /// Parse a commit message; `None` if the header is not conventional
+    pub fn parse(message: &str) -> Option<Self> {
+        let mut lines = message.lines();
+        let header = lines.next()?.trim();
+        let (prefix, description) = header.split_once(':')?;
+        let description = description.trim();
+
+        let (prefix, bang) = match prefix.strip_suffix('!') {
+            Some(prefix) => (prefix, true),
+            None => (prefix, false),
+        };
+        let (kind, scope) = match prefix.split_once('(') {
+            Some((kind, scope)) => (kind, Some(scope.strip_suffix(')')?.trim())),
+            None => (prefix, None),
+        };
+
+        if kind.is_empty()
+            || !kind.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            || description.is_empty()
+        {
+            return None;
+        }
+
+        let breaking_footer = lines.any(|line| {
+            line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:")
+        });
+
+        Some(Self {
+            kind: kind.to_ascii_lowercase(),
+            scope: scope.filter(|s| !s.is_empty()).map(str::to_string),
+            breaking: bang || breaking_footer,
+            description: description.to_string(),
+        })
+    }
+
+    /// The version increment this commit calls for, if any
+    ///
+    /// Breaking changes are major, `feat` is minor, `fix` and `perf` are
+    /// patch; other types (docs, chore, ...) do not require a release.
+    pub fn level(&self) -> Option<Level> {
+        if self.breaking {
+            return Some(Level::Major);
+        }
+        match self.kind.as_str() {
+            "feat" => Some(Level::Minor),
+            "fix" | "perf" => Some(Level::Patch),
+            _ => None,
+        }
+    }
+}
+
+/// The largest increment called for by a set of commits
+///
+/// Commits that are not conventional are ignored.
+pub fn required_level(commits: &[Commit]) -> Option<Level> {
+    commits
+        .iter()
+        .filter_map(|commit| Conventional::parse(&commit.message)?.level())
+        .max_by_key(|level| match level {
+            Level::Patch => 0,
+            Level::Minor => 1,
+            Level::Major => 2,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(message: &str) -> Commit {
+        Commit {
+            sha: "0".repeat(40),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let parsed = Conventional::parse("feat(cli)!: add bump\n\nbody").unwrap();
+        assert_eq!(parsed.kind, "feat");
+        assert_eq!(parsed.scope.as_deref(), Some("cli"));
+        assert!(parsed.breaking);
+        assert_eq!(parsed.description, "add bump");
+
+        let parsed = Conventional::parse("Fix: typo").unwrap();
+        assert_eq!(parsed.kind, "fix");
+        assert!(!parsed.breaking);
+
+        assert!(Conventional::parse("Update README").is_none());
+        assert!(Conventional::parse("Merge branch 'x': y").is_none());
+        assert!(Conventional::parse("feat:").is_none());
+        assert!(Conventional::parse("feat(cli: x").is_none());
+    }
+
+    #[test]
+    fn test_breaking_footer() {
+        let parsed =
+            Conventional::parse("refactor: drop v1 API\n\nBREAKING CHANGE: v1 is gone").unwrap();
+        assert_eq!(parsed.level(), Some(Level::Major));
+    }
+
+    #[test]
+    fn test_required_level() {
+        assert_eq!(
+            required_level(&[commit("docs: x"), commit("chore: y")]),
+            None
+        );
+        assert_eq!(
+            required_level(&[commit("fix: x"), commit("feat: y"), commit("docs: z")]),
+            Some(Level::Minor)
+        );
+        assert_eq!(
+            required_level(&[commit("fix!: x"), commit("feat: y")]),
+            Some(Level::Major)
+        );
+        assert_eq!(
+            required_level(&[commit("perf: x"), commit("wip")]),
+            Some(Level::Patch)
+        );
+    }
+}
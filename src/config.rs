@@ -1,23 +1,282 @@
-use crate::error::{Error, Result};
+use crate::error::{Error, FailureClass, Result};
+use crate::format;
+use crate::git;
+use crate::glob;
+use crate::scheme::Scheme;
+use crate::template::{self, Context};
+use crate::version::{Level, Version};
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+use toml_edit::{ArrayOfTables, DocumentMut, Item, Table, TableLike};
 
 /// Supported file formats for version targets
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
-#[serde(rename_all = "lowercase")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FileFormat {
     Toml,
     Json,
+    Xml,
+    Ini,
+    Python,
+    Rust,
+    Dockerfile,
+    Markdown,
+    Properties,
+    Cmake,
+    CargoLock,
+    Dotenv,
+    Jsonc,
+    Hcl,
+    Shell,
+    Makefile,
+    Plist,
+    Go,
+    Cff,
+    DebianChangelog,
+    RpmSpec,
+    Appstream,
+    Raw,
+    Openapi,
+    Image,
+    Mix,
+    Gradle,
+    Wix,
+    Yaml,
+    /// A format registered with [`format::register`], by name
+    Custom(&'static str),
+}
+
+impl<'de> Deserialize<'de> for FileFormat {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let name = String::deserialize(deserializer)?;
+        FileFormat::from_name(&name).map_err(serde::de::Error::custom)
+    }
 }
 
 impl FileFormat {
-    /// Infer format from file extension
+    /// The name used in the config (`format = "..."`)
+    pub fn name(self) -> &'static str {
+        match self {
+            FileFormat::Toml => "toml",
+            FileFormat::Json => "json",
+            FileFormat::Xml => "xml",
+            FileFormat::Ini => "ini",
+            FileFormat::Python => "python",
+            FileFormat::Rust => "rust",
+            FileFormat::Dockerfile => "dockerfile",
+            FileFormat::Markdown => "markdown",
+            FileFormat::Properties => "properties",
+            FileFormat::Cmake => "cmake",
+            FileFormat::CargoLock => "cargo-lock",
+            FileFormat::Dotenv => "dotenv",
+            FileFormat::Jsonc => "jsonc",
+            FileFormat::Hcl => "hcl",
+            FileFormat::Shell => "shell",
+            FileFormat::Makefile => "makefile",
+            FileFormat::Plist => "plist",
+            FileFormat::Go => "go",
+            FileFormat::Cff => "cff",
+            FileFormat::DebianChangelog => "debian-changelog",
+            FileFormat::RpmSpec => "rpm-spec",
+            FileFormat::Appstream => "appstream",
+            FileFormat::Raw => "raw",
+            FileFormat::Openapi => "openapi",
+            FileFormat::Image => "image",
+            FileFormat::Mix => "mix",
+            FileFormat::Gradle => "gradle",
+            FileFormat::Wix => "wix",
+            FileFormat::Yaml => "yaml",
+            FileFormat::Custom(name) => name,
+        }
+    }
+
+    /// The key a target of the format has if it sets neither `key` nor `keys`
+    pub fn default_key(self) -> Option<&'static str> {
+        match self {
+            // The whole file of a raw target is the version
+            FileFormat::Raw => Some(""),
+            FileFormat::Openapi => Some("info.version"),
+            FileFormat::Wix => Some("Version"),
+            _ => None,
+        }
+    }
+
+    /// Built-in formats, in the order they are listed in help and errors
+    pub const BUILTIN: [FileFormat; 29] = [
+        FileFormat::Toml,
+        FileFormat::Json,
+        FileFormat::Xml,
+        FileFormat::Ini,
+        FileFormat::Python,
+        FileFormat::Rust,
+        FileFormat::Dockerfile,
+        FileFormat::Markdown,
+        FileFormat::Properties,
+        FileFormat::Cmake,
+        FileFormat::CargoLock,
+        FileFormat::Dotenv,
+        FileFormat::Jsonc,
+        FileFormat::Hcl,
+        FileFormat::Shell,
+        FileFormat::Makefile,
+        FileFormat::Plist,
+        FileFormat::Go,
+        FileFormat::Cff,
+        FileFormat::DebianChangelog,
+        FileFormat::RpmSpec,
+        FileFormat::Appstream,
+        FileFormat::Raw,
+        FileFormat::Openapi,
+        FileFormat::Image,
+        FileFormat::Mix,
+        FileFormat::Gradle,
+        FileFormat::Wix,
+        FileFormat::Yaml,
+    ];
+
+    /// Look up a format by its config name, built-in or registered
+    pub fn from_name(name: &str) -> std::result::Result<Self, String> {
+        if let Some(format) = Self::BUILTIN.into_iter().find(|f| f.name() == name) {
+            return Ok(format);
+        }
+        match format::lookup(name) {
+            Some(format) => Ok(FileFormat::Custom(format.name())),
+            None => Err(format!(
+                "unknown format '{}', expected one of: {}",
+                name,
+                Self::BUILTIN.map(FileFormat::name).join(", ")
+            )),
+        }
+    }
+
+    /// Infer format from the file name or extension, or else from the content
+    /// of a file without an extension
+    pub fn detect(path: &Path) -> Option<Self> {
+        Self::from_path(path).or_else(|| {
+            path.extension()
+                .is_none()
+                .then(|| fs::read_to_string(path).ok())
+                .flatten()
+                .and_then(|content| Self::sniff(&content))
+        })
+    }
+
+    /// Infer format from content: JSON (or JSONC) if it starts with `{`, YAML
+    /// if it starts with `---` or `%YAML`, TOML if it starts with a table
+    /// header or `key = value` and parses as TOML
+    pub fn sniff(content: &str) -> Option<Self> {
+        let first = content
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))?;
+
+        if first.starts_with('{') {
+            return if serde_json::from_str::<serde_json::Value>(content).is_ok() {
+                Some(FileFormat::Json)
+            } else {
+                Some(FileFormat::Jsonc)
+            };
+        }
+        if first.starts_with("---") || first.starts_with("%YAML") {
+            return Some(FileFormat::Yaml);
+        }
+        let toml_line = (first.starts_with('[') && first.ends_with(']'))
+            || first.split_once('=').is_some_and(|(key, _)| {
+                let key = key.trim();
+                !key.is_empty() && !key.contains(char::is_whitespace)
+            });
+        if toml_line && content.parse::<DocumentMut>().is_ok() {
+            return Some(FileFormat::Toml);
+        }
+        None
+    }
+
+    /// Infer format from file name or extension
     pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+        if name == "Dockerfile" || name.starts_with("Dockerfile.") || name == "Containerfile" {
+            return Some(FileFormat::Dockerfile);
+        }
+        if name == "CMakeLists.txt" {
+            return Some(FileFormat::Cmake);
+        }
+        if name == "Cargo.lock" {
+            return Some(FileFormat::CargoLock);
+        }
+        if name == ".env" || name.starts_with(".env.") {
+            return Some(FileFormat::Dotenv);
+        }
+        let (stem, extension) = name.rsplit_once('.').unwrap_or((name, ""));
+        if matches!(extension, "yaml" | "yml" | "json")
+            && ["openapi", "asyncapi", "swagger"]
+                .iter()
+                .any(|spec| stem == *spec || stem.ends_with(&format!(".{}", spec)))
+        {
+            return Some(FileFormat::Openapi);
+        }
+        if matches!(extension, "yaml" | "yml")
+            && (stem == "compose"
+                || stem == "docker-compose"
+                || stem.starts_with("compose.")
+                || stem.starts_with("docker-compose."))
+        {
+            return Some(FileFormat::Image);
+        }
+        if name.ends_with(".gradle.kts") {
+            return Some(FileFormat::Gradle);
+        }
+        if name == "mix.exs" {
+            return Some(FileFormat::Mix);
+        }
+        if name == "VERSION" {
+            return Some(FileFormat::Raw);
+        }
+        if matches!(name, "Makefile" | "makefile" | "GNUmakefile") {
+            return Some(FileFormat::Makefile);
+        }
+        if name.ends_with(".metainfo.xml") || name.ends_with(".appdata.xml") {
+            return Some(FileFormat::Appstream);
+        }
+        let parent = path.parent().and_then(Path::file_name);
+        if name == "changelog" && parent.is_some_and(|p| p == "debian") {
+            return Some(FileFormat::DebianChangelog);
+        }
+
         match path.extension().and_then(|e| e.to_str()) {
             Some("toml") => Some(FileFormat::Toml),
             Some("json") => Some(FileFormat::Json),
-            _ => None,
+            Some("xml" | "csproj" | "fsproj" | "vbproj" | "props" | "nuspec") => {
+                Some(FileFormat::Xml)
+            }
+            Some("ini" | "cfg") => Some(FileFormat::Ini),
+            Some("py") => Some(FileFormat::Python),
+            Some("rs") => Some(FileFormat::Rust),
+            Some("dockerfile") => Some(FileFormat::Dockerfile),
+            Some("md" | "markdown") => Some(FileFormat::Markdown),
+            Some("properties") => Some(FileFormat::Properties),
+            Some("cmake") => Some(FileFormat::Cmake),
+            Some("env") => Some(FileFormat::Dotenv),
+            Some("jsonc" | "json5") => Some(FileFormat::Jsonc),
+            Some("tf" | "tfvars" | "hcl") => Some(FileFormat::Hcl),
+            Some("sh" | "bash" | "zsh") => Some(FileFormat::Shell),
+            Some("mk" | "make") => Some(FileFormat::Makefile),
+            Some("plist") => Some(FileFormat::Plist),
+            Some("go") => Some(FileFormat::Go),
+            Some("cff") => Some(FileFormat::Cff),
+            Some("spec") => Some(FileFormat::RpmSpec),
+            Some("gradle") => Some(FileFormat::Gradle),
+            Some("wxs") => Some(FileFormat::Wix),
+            Some("yaml" | "yml") => Some(FileFormat::Yaml),
+            Some(extension) => format::name_for_extension(extension).map(FileFormat::Custom),
+            None => None,
         }
     }
 }
@@ -29,14 +288,251 @@ pub struct Target {
     pub file: PathBuf,
     /// Dot-separated key path (e.g., "project.version")
     pub key: String,
+    /// Template for the value of the key (default `{version}`), e.g. `v{version}`
+    pub template: Option<String>,
+    /// Notation the version is written in (e.g. `pep440` for `1.2.0rc1`)
+    pub scheme: Option<Scheme>,
+    /// Group for selecting targets on the command line (e.g. `docs`)
+    pub group: Option<String>,
+    /// Whether a missing file is an error; optional targets are skipped
+    #[serde(default = "default_required")]
+    pub required: bool,
+    /// Whether apply inserts the key, and any tables along it, when missing
+    #[serde(default)]
+    pub create: bool,
     /// File format (inferred from extension if not specified)
     pub format: Option<FileFormat>,
+    /// Additional keys set from templates whenever the version is applied
+    /// (e.g. `release_date = "{date}"`, `commit = "{commit}"`)
+    #[serde(default)]
+    pub extra: BTreeMap<String, String>,
 }
 
 impl Target {
-    /// Get the effective format (explicit or inferred from extension)
+    /// Get the effective format (explicit, or inferred from the file name,
+    /// extension or content)
     pub fn effective_format(&self) -> Option<FileFormat> {
-        self.format.or_else(|| FileFormat::from_path(&self.file))
+        self.format.or_else(|| FileFormat::detect(&self.file))
+    }
+
+    /// The value the key should hold: the version in the target's `scheme`,
+    /// rendered into `template`
+    pub fn expected_value(&self, context: &Context) -> Result<String> {
+        let version = match self.scheme {
+            Some(scheme) => scheme.from_semver(context.version())?,
+            None => context.version().to_string(),
+        };
+        match &self.template {
+            Some(template) => context.render(&template.replace("{version}", &version)),
+            None => Ok(version),
+        }
+    }
+
+    /// Whether the key holds the expected value, in any spelling of the
+    /// version its `scheme` accepts (`1.2.0-RC1` for `1.2.0rc1` in PEP 440)
+    pub fn holds(&self, actual: &str, expected: &str) -> bool {
+        if actual == expected {
+            return true;
+        }
+        let Some(scheme) = self.scheme else {
+            return false;
+        };
+        match (self.untemplate(actual), self.untemplate(expected)) {
+            (Some(actual), Some(expected)) => scheme.equivalent(actual, expected),
+            _ => false,
+        }
+    }
+
+    /// The version a value of the key stands for, undoing `template` and
+    /// `scheme`
+    ///
+    /// Only templates made of `{version}` and literal text (`v{version}`,
+    /// `{version}-final`) can be undone; others, such as `{major}.{minor}`,
+    /// drop information and give `None`.
+    pub fn version_from_value(&self, value: &str) -> Option<String> {
+        let version = self.untemplate(value)?;
+        match self.scheme {
+            Some(scheme) => scheme.to_semver(version).ok(),
+            None => Some(version.to_string()),
+        }
+    }
+
+    /// The part of a value that `{version}` was rendered into
+    fn untemplate<'a>(&self, value: &'a str) -> Option<&'a str> {
+        let Some(template) = &self.template else {
+            return Some(value);
+        };
+        let (prefix, suffix) = template.split_once("{version}")?;
+        let literal = |text: &str| template::render(text, |_| Some(String::new())) == text;
+        if !literal(prefix) || !literal(suffix) {
+            return None;
+        }
+        value.strip_prefix(prefix)?.strip_suffix(suffix)
+    }
+}
+
+/// A `[[targets]]` entry as written in the config
+///
+/// `keys` maps several keys of one file to their templates (e.g. a Helm
+/// chart's `version` and `appVersion`); each becomes a [`Target`] of its own.
+/// `lockfile = true` on a `package.json` target adds targets for the version
+/// entries of the `package-lock.json` next to it. A `file` with wildcards
+/// (`crates/*/Cargo.toml`) expands to an entry per matching file, except
+/// for files matching an `exclude` pattern of the entry or the config.
+#[derive(Debug, Clone, Deserialize)]
+struct TargetEntry {
+    file: PathBuf,
+    key: Option<String>,
+    template: Option<String>,
+    scheme: Option<Scheme>,
+    group: Option<String>,
+    #[serde(default = "default_required")]
+    required: bool,
+    #[serde(default)]
+    create: bool,
+    #[serde(default)]
+    keys: BTreeMap<String, String>,
+    format: Option<FileFormat>,
+    #[serde(default)]
+    extra: BTreeMap<String, String>,
+    #[serde(default)]
+    lockfile: bool,
+    #[serde(default)]
+    exclude: Vec<String>,
+}
+
+/// Keys of the project version in an npm lockfile: the top-level `version`
+/// and the version of the root package, keyed by the empty string
+const NPM_LOCKFILE_KEYS: &[&str] = &["version", r#"packages."".version"#];
+
+impl TargetEntry {
+    fn expand(mut self, exclude: &[String]) -> std::result::Result<Vec<Target>, String> {
+        let file = self.file.display().to_string();
+        let root = root();
+        // A glob stands for an entry per matching file
+        if glob::is_glob(&self.file) {
+            let files: Vec<PathBuf> = glob::expand(&root.join(&self.file))
+                .into_iter()
+                .map(|path| {
+                    path.strip_prefix(&root)
+                        .map_or(path.clone(), Path::to_path_buf)
+                })
+                .filter(|path| {
+                    !exclude
+                        .iter()
+                        .chain(&self.exclude)
+                        .any(|pattern| glob::matches(pattern, path))
+                })
+                .collect();
+            if files.is_empty() && self.required {
+                return Err(format!("target '{}' matches no files", file));
+            }
+            let mut targets = Vec::new();
+            for path in files {
+                let mut entry = self.clone();
+                entry.file = path;
+                entry.exclude.clear();
+                targets.extend(entry.expand(&[])?);
+            }
+            return Ok(targets);
+        }
+        if !self.exclude.is_empty() {
+            return Err(format!(
+                "target '{}': `exclude` applies to a `file` with wildcards",
+                file
+            ));
+        }
+        if self.key.is_none() && self.keys.is_empty() {
+            self.key = self
+                .format
+                .or_else(|| FileFormat::from_path(&self.file))
+                .and_then(FileFormat::default_key)
+                .map(str::to_string);
+        }
+        if self.key.is_none() && self.keys.is_empty() {
+            return Err(format!("target '{}' needs `key` or `keys`", file));
+        }
+        if self.key.is_none() && self.template.is_some() {
+            return Err(format!(
+                "target '{}': `template` applies to `key`; put templates in `keys`",
+                file
+            ));
+        }
+
+        self.file = root.join(&self.file);
+        let lockfile = if self.lockfile {
+            if self.format.or_else(|| FileFormat::from_path(&self.file)) != Some(FileFormat::Json)
+                || self.key.is_none()
+            {
+                return Err(format!(
+                    "target '{}': `lockfile` applies to a JSON target with `key`",
+                    file
+                ));
+            }
+            Some((
+                self.file.with_file_name("package-lock.json"),
+                self.template.clone(),
+            ))
+        } else {
+            None
+        };
+
+        let keys = self
+            .key
+            .map(|key| (key, self.template))
+            .into_iter()
+            .chain(self.keys.into_iter().map(|(k, t)| (k, Some(t))));
+        let mut extra = Some(self.extra);
+        let mut targets: Vec<Target> = keys
+            .map(|(key, template)| Target {
+                file: self.file.clone(),
+                key,
+                template,
+                scheme: self.scheme,
+                group: self.group.clone(),
+                required: self.required,
+                create: self.create,
+                format: self.format,
+                // Extra keys are set once, along with the first key
+                extra: extra.take().unwrap_or_default(),
+            })
+            .collect();
+
+        if let Some((file, template)) = lockfile {
+            targets.extend(NPM_LOCKFILE_KEYS.iter().map(|key| Target {
+                file: file.clone(),
+                key: key.to_string(),
+                template: template.clone(),
+                scheme: self.scheme,
+                group: self.group.clone(),
+                required: self.required,
+                create: self.create,
+                format: Some(FileFormat::Json),
+                extra: BTreeMap::new(),
+            }));
+        }
+
+        // CITATION.cff records the release date along with the version
+        if self.format.or_else(|| FileFormat::from_path(&self.file)) == Some(FileFormat::Cff) {
+            if let Some(target) = targets.iter_mut().find(|t| t.key == "version") {
+                target
+                    .extra
+                    .entry("date-released".to_string())
+                    .or_insert_with(|| "{date}".to_string());
+            }
+        }
+
+        if self.create
+            && !targets
+                .iter()
+                .all(|target| target.effective_format().is_some_and(format::can_create))
+        {
+            return Err(format!(
+                "target '{}' sets `create`, which only TOML and JSON targets support",
+                file
+            ));
+        }
+        Ok(targets)
     }
 }
 
@@ -46,30 +542,359 @@ pub struct GitConfig {
     /// Prefix for git tags (default: "v")
     #[serde(default = "default_tag_prefix")]
     pub tag_prefix: String,
+    /// Template for tag names (default: "{prefix}{version}")
+    #[serde(default = "default_tag_template")]
+    pub tag_template: String,
+    /// Regex that versioned branches must match, with version placeholders
+    /// (e.g. `'^release/{major}\.{minor}\.x$'`)
+    pub branch_pattern: Option<String>,
+    /// Remote used for remote tag operations (default: "origin")
+    #[serde(default = "default_remote")]
+    pub remote: String,
+    /// Refuse to tag when the tag already exists on the remote (uses `git ls-remote`)
+    #[serde(default)]
+    pub check_remote: bool,
+}
+
+fn default_required() -> bool {
+    true
 }
 
 fn default_tag_prefix() -> String {
     "v".to_string()
 }
 
+fn default_tag_template() -> String {
+    "{prefix}{version}".to_string()
+}
+
+fn default_remote() -> String {
+    "origin".to_string()
+}
+
 impl Default for GitConfig {
     fn default() -> Self {
         Self {
             tag_prefix: default_tag_prefix(),
+            tag_template: default_tag_template(),
+            branch_pattern: None,
+            remote: default_remote(),
+            check_remote: false,
+        }
+    }
+}
+
+/// Network-related configuration
+#[derive(Debug, Clone, Deserialize)]
+pub struct NetworkConfig {
+    /// Skip every network-touching feature (also `--offline`)
+    #[serde(default)]
+    pub offline: bool,
+    /// Timeout for a single remote operation, in seconds (default: 30)
+    #[serde(default = "default_timeout")]
+    pub timeout: u64,
+    /// Retries after a network failure (default: 2)
+    #[serde(default = "default_retries")]
+    pub retries: u32,
+    /// Delay before the first retry in milliseconds, doubled on each retry (default: 500)
+    #[serde(default = "default_retry_delay")]
+    pub retry_delay: u64,
+    /// Proxy URL for remote operations (default: `HTTPS_PROXY`/`HTTP_PROXY` from the environment)
+    pub proxy: Option<String>,
+    /// How long responses of remote lookups are cached, in seconds (default: 300, 0 disables)
+    #[serde(default = "default_cache_ttl")]
+    pub cache_ttl: u64,
+    /// Ignore cached responses (`--refresh`)
+    #[serde(skip)]
+    pub refresh: bool,
+}
+
+fn default_timeout() -> u64 {
+    30
+}
+
+fn default_retries() -> u32 {
+    2
+}
+
+fn default_retry_delay() -> u64 {
+    500
+}
+
+fn default_cache_ttl() -> u64 {
+    300
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            offline: false,
+            timeout: default_timeout(),
+            retries: default_retries(),
+            retry_delay: default_retry_delay(),
+            proxy: None,
+            cache_ttl: default_cache_ttl(),
+            refresh: false,
+        }
+    }
+}
+
+/// Release policy rules (`[policy]`), enforced by check and tag
+#[derive(Debug, Clone, Deserialize)]
+pub struct PolicyConfig {
+    /// Regex the version must match
+    pub version_pattern: Option<String>,
+    /// Regex the tag prefix must match
+    pub tag_prefix_pattern: Option<String>,
+    /// Branches on which prerelease versions must not be tagged
+    #[serde(default)]
+    pub forbid_prerelease_on: Vec<String>,
+    /// Largest increment allowed over the latest tagged version
+    pub max_jump: Option<Level>,
+    /// Whether apply may write a version lower than a target holds, and
+    /// bump and set may lower the version (default: true)
+    #[serde(default = "default_allow_downgrade")]
+    pub allow_downgrade: bool,
+}
+
+fn default_allow_downgrade() -> bool {
+    true
+}
+
+impl Default for PolicyConfig {
+    fn default() -> Self {
+        Self {
+            version_pattern: None,
+            tag_prefix_pattern: None,
+            forbid_prerelease_on: Vec::new(),
+            max_jump: None,
+            allow_downgrade: default_allow_downgrade(),
+        }
+    }
+}
+
+/// Settings for `versync release` (`[release]`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReleaseConfig {
+    /// Commit message template; version placeholders, `{date}` and `{tag}`
+    /// are available (default: "Release {version}")
+    #[serde(default = "default_commit_message")]
+    pub commit_message: String,
+}
+
+fn default_commit_message() -> String {
+    "Release {version}".to_string()
+}
+
+impl Default for ReleaseConfig {
+    fn default() -> Self {
+        Self {
+            commit_message: default_commit_message(),
         }
     }
 }
 
+/// Prerelease settings (`[prerelease]`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct PrereleaseConfig {
+    /// Prerelease labels accepted by `bump --pre`; the first is the default
+    /// (default: alpha, beta, rc)
+    #[serde(default = "default_prerelease_labels")]
+    pub labels: Vec<String>,
+}
+
+fn default_prerelease_labels() -> Vec<String> {
+    vec!["alpha".to_string(), "beta".to_string(), "rc".to_string()]
+}
+
+impl Default for PrereleaseConfig {
+    fn default() -> Self {
+        Self {
+            labels: default_prerelease_labels(),
+        }
+    }
+}
+
+/// A release channel (e.g. `[channels.staging]`)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ChannelConfig {
+    /// Tag template for this channel (e.g. "{prefix}{version}+staging");
+    /// `{n}` is replaced by the first number giving an unused tag
+    pub tag: Option<String>,
+    /// Version template deriving the channel version from the canonical one
+    /// (e.g. "{version}-nightly.{date}")
+    pub version: Option<String>,
+    /// Target files the channel applies to, as paths or globs (all targets
+    /// if empty)
+    #[serde(default)]
+    pub targets: Vec<PathBuf>,
+}
+
+/// Where the authoritative version comes from
+#[derive(Debug, Clone, Default)]
+pub enum Source {
+    /// The `version` field of the config
+    #[default]
+    Config,
+    /// The nearest tag reachable from HEAD (`git describe --tags`)
+    Git,
+    /// A key of a file such as a manifest, read like a target
+    /// (`[source] file = "Cargo.toml"`)
+    File(Box<Target>),
+}
+
+impl<'de> Deserialize<'de> for Source {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct SourceVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for SourceVisitor {
+            type Value = Source;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("\"config\", \"git\" or a table with `file` and `key`")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, name: &str) -> std::result::Result<Source, E> {
+                match name {
+                    "config" => Ok(Source::Config),
+                    "git" => Ok(Source::Git),
+                    _ => Err(E::invalid_value(serde::de::Unexpected::Str(name), &self)),
+                }
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                map: A,
+            ) -> std::result::Result<Source, A::Error> {
+                let target =
+                    Target::deserialize(serde::de::value::MapAccessDeserializer::new(map))?;
+                Ok(Source::File(Box::new(target)))
+            }
+        }
+
+        deserializer.deserialize_any(SourceVisitor)
+    }
+}
+
+/// An independently versioned component of a monorepo (e.g.
+/// `[components.api]`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct ComponentConfig {
+    /// The authoritative version string of the component
+    pub version: String,
+    /// Prefix for the component's git tags (default: the component name, a
+    /// `-` and `git.tag_prefix`, e.g. "api-v")
+    pub tag_prefix: Option<String>,
+    /// Patterns of files that the component's wildcard targets skip
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// The `[[components.<name>.targets]]` entries as written
+    #[serde(rename = "targets", default)]
+    entries: Vec<TargetEntry>,
+    /// Target files of the component
+    #[serde(skip)]
+    pub targets: Vec<Target>,
+}
+
+/// How serious a configuration issue is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Suspicious but usable (e.g. an unknown field, which is ignored)
+    Warning,
+    /// The configuration will not work as intended
+    Error,
+}
+
+/// A problem found while validating the configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub severity: Severity,
+    /// Where the problem is (`git.tag_prefix`, `targets[1]`, ...)
+    pub location: String,
+    pub message: String,
+}
+
+impl Issue {
+    pub fn error(location: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            location: location.into(),
+            message: message.into(),
+        }
+    }
+
+    pub fn warning(location: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            location: location.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self.severity {
+            Severity::Warning => "WARNING",
+            Severity::Error => "ERROR",
+        };
+        write!(f, "{} {}: {}", label, self.location, self.message)
+    }
+}
+
 /// Main configuration structure (version.toml)
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
-    /// The authoritative version string
+    /// The authoritative version string (empty when only components are
+    /// versioned; derived at load time with `source = "git"`)
+    #[serde(default)]
     pub version: String,
+    /// Notation `version` is written in (e.g. `pep440`), also used by
+    /// targets without a `scheme` of their own; `version` holds the semver
+    /// equivalent once parsed
+    pub scheme: Option<Scheme>,
+    /// Where the version comes from (default: the `version` field)
+    #[serde(default)]
+    pub source: Source,
+    /// Patterns of files that wildcard targets skip (e.g. `vendor/**`)
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// The `[[targets]]` entries as written, expanded into `targets`
+    #[serde(rename = "targets", default)]
+    entries: Vec<TargetEntry>,
     /// List of target files to sync
+    #[serde(skip)]
     pub targets: Vec<Target>,
     /// Git configuration
     #[serde(default)]
     pub git: GitConfig,
+    /// Network configuration
+    #[serde(default)]
+    pub network: NetworkConfig,
+    /// Named release channels
+    #[serde(default)]
+    pub channels: BTreeMap<String, ChannelConfig>,
+    /// Release policy rules
+    #[serde(default)]
+    pub policy: PolicyConfig,
+    /// Prerelease settings
+    #[serde(default)]
+    pub prerelease: PrereleaseConfig,
+    /// Release command settings
+    #[serde(default)]
+    pub release: ReleaseConfig,
+    /// Exit code overrides per failure class (e.g. `network = 0`)
+    #[serde(default)]
+    pub exit_codes: BTreeMap<FailureClass, u8>,
+    /// Independently versioned components, each with its own targets
+    #[serde(default)]
+    pub components: BTreeMap<String, ComponentConfig>,
+    /// The component this configuration was derived for, if any
+    #[serde(skip)]
+    pub component: Option<String>,
 }
 
 impl Config {
@@ -79,84 +904,2176 @@ impl Config {
             return Err(Error::ConfigNotFound(path.to_path_buf()));
         }
 
-        let content = fs::read_to_string(path)?;
-        Self::parse(&content)
+        Self::parse(&read_config(path)?)?.resolve_source()
     }
 
     /// Parse configuration from a TOML string
+    ///
+    /// A value that doesn't fit the schema is reported with its key path
+    /// (`targets.0.required: invalid type: ...`), along with any unknown
+    /// keys, since those are often a misspelling of a missing one.
     pub fn parse(content: &str) -> Result<Self> {
-        let config: Config =
-            toml_edit::de::from_str(content).map_err(|e| Error::ConfigParse(e.to_string()))?;
+        let config: Config = toml_edit::de::from_str(content)
+            .map_err(|e| describe_error(&[content.to_string()], e))?;
+        config.ensure_targets()
+    }
+
+    fn ensure_targets(mut self) -> Result<Self> {
+        for entry in std::mem::take(&mut self.entries) {
+            let targets = entry.expand(&self.exclude).map_err(Error::ConfigParse)?;
+            self.targets.extend(targets);
+        }
+        for (name, component) in &mut self.components {
+            let exclude = [self.exclude.as_slice(), &component.exclude].concat();
+            for entry in std::mem::take(&mut component.entries) {
+                let targets = entry.expand(&exclude).map_err(Error::ConfigParse)?;
+                component.targets.extend(targets);
+            }
+            if component.targets.is_empty() {
+                return Err(Error::ConfigParse(format!(
+                    "At least one [[components.{}.targets]] entry is required",
+                    name
+                )));
+            }
+        }
+        if let Source::File(target) = &mut self.source {
+            target.file = root().join(&target.file);
+        }
+        if let Some(scheme) = self.scheme {
+            self.apply_scheme(scheme)?;
+        }
+
+        // A config made only of components has no top-level version
+        if self.version.is_empty() && self.targets.is_empty() && !self.components.is_empty() {
+            return Ok(self);
+        }
+        let from_config = matches!(self.source, Source::Config);
+        if !from_config && !self.version.is_empty() {
+            return Err(Error::ConfigParse(
+                "`version` cannot be set along with `source`, which provides it".to_string(),
+            ));
+        }
+        if self.version.is_empty() && from_config {
+            return Err(Error::ConfigParse("missing field `version`".to_string()));
+        }
 
         // Validate: at least one target is required
-        if config.targets.is_empty() {
+        if self.targets.is_empty() {
             return Err(Error::ConfigParse(
                 "At least one [[targets]] entry is required".to_string(),
             ));
         }
 
-        Ok(config)
+        Ok(self)
     }
 
-    /// Get the full tag name (prefix + version)
-    pub fn tag_name(&self) -> String {
-        format!("{}{}", self.git.tag_prefix, self.version)
+    /// Make `scheme` the default of the targets and read the versions
+    /// written in it as semver
+    ///
+    /// A version the scheme doesn't accept is an error, so with `pep440`
+    /// only versions pip accepts get through, and every target writes the
+    /// normalized form (`1.2.0-RC1` is written `1.2.0rc1`).
+    fn apply_scheme(&mut self, scheme: Scheme) -> Result<()> {
+        let to_semver = |key: &str, version: &str| {
+            scheme
+                .to_semver(version)
+                .map_err(|e| Error::ConfigParse(format!("{}: {}", key, e)))
+        };
+
+        if !self.version.is_empty() {
+            self.version = to_semver("version", &self.version)?;
+        }
+        for target in &mut self.targets {
+            target.scheme.get_or_insert(scheme);
+        }
+        if let Source::File(target) = &mut self.source {
+            target.scheme.get_or_insert(scheme);
+        }
+        for (name, component) in &mut self.components {
+            let key = format!("components.{}.version", name);
+            component.version = to_semver(&key, &component.version)?;
+            for target in &mut component.targets {
+                target.scheme.get_or_insert(scheme);
+            }
+        }
+        Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// The config's `scheme`, semver by default
+    pub fn effective_scheme(&self) -> Scheme {
+        self.scheme.unwrap_or(Scheme::Semver)
+    }
 
-    #[test]
-    fn test_parse_minimal_config() {
-        let content = r#"
-version = "1.0.0"
+    /// Write a semver version in the config's `scheme`, as `version` holds it
+    pub fn version_in_scheme(&self, version: &str) -> Result<String> {
+        self.effective_scheme().from_semver(version)
+    }
 
-[[targets]]
-file = "pyproject.toml"
-key = "project.version"
-"#;
-        let config = Config::parse(content).unwrap();
-        assert_eq!(config.version, "1.0.0");
-        assert_eq!(config.targets.len(), 1);
-        assert_eq!(config.git.tag_prefix, "v");
+    /// Read a version written in the config's `scheme` as semver
+    pub fn version_from_scheme(&self, value: &str) -> Result<String> {
+        self.effective_scheme().to_semver(value)
     }
 
-    #[test]
-    fn test_parse_full_config() {
-        let content = r#"
-version = "0.7.3"
+    /// Load and merge several configuration files or directories
+    ///
+    /// Directories contribute their `*.toml` files in name order. See
+    /// [`Config::parse_merged`] for the precedence rules.
+    pub fn load_all(paths: &[PathBuf]) -> Result<Self> {
+        let contents = config_files(paths)?
+            .iter()
+            .map(|path| read_config(path))
+            .collect::<Result<Vec<_>>>()?;
+        Self::parse_merged(&contents)?.resolve_source()
+    }
 
-[[targets]]
-file = "pyproject.toml"
-key = "project.version"
+    /// Derive the version from its source, for sources other than the
+    /// config itself
+    ///
+    /// With `source = "git"`, the version is that of the nearest tag with
+    /// the tag prefix reachable from HEAD; commits since the tag are added
+    /// as build metadata (`1.2.3+4.gabc1234`). A file source is read like a
+    /// target, undoing its `template` and `scheme`.
+    pub fn resolve_source(mut self) -> Result<Self> {
+        match &self.source {
+            Source::Config => {}
+            Source::Git => {
+                let prefix = &self.git.tag_prefix;
+                let described = git::describe_long(prefix)?.ok_or_else(|| {
+                    Error::GitCommand(format!(
+                        "No tag matching '{}*' is reachable from HEAD (needed by `source = \"git\"`)",
+                        prefix
+                    ))
+                })?;
+                self.version = described_version(prefix, self.effective_scheme(), &described)?;
+            }
+            Source::File(target) => {
+                let format = target
+                    .effective_format()
+                    .ok_or_else(|| Error::UnknownFormat(target.file.clone()))?;
+                let value = format::read_version(&target.file, &target.key, format)?;
+                let version = target.version_from_value(&value).ok_or_else(|| {
+                    Error::ConfigParse(format!(
+                        "The source {} {} holds '{}', which its template cannot be undone for",
+                        target.file.display(),
+                        target.key,
+                        value
+                    ))
+                })?;
+                Version::parse(&version)?;
+                self.version = version;
+            }
+        }
+        Ok(self)
+    }
 
-[[targets]]
-file = "Cargo.toml"
-key = "workspace.package.version"
+    /// Parse several configuration sources, later ones taking precedence
+    ///
+    /// Tables are merged key by key and other values are replaced. Targets
+    /// are concatenated, except that a target with the same `file` and `key`
+    /// as an earlier one replaces it. Only the merged result is validated.
+    pub fn parse_merged(contents: &[String]) -> Result<Self> {
+        if let [content] = contents {
+            // A single source keeps source locations in error messages
+            return Self::parse(content);
+        }
 
-[[targets]]
-file = "package.json"
-key = "version"
-format = "json"
+        let merged = merge_documents(contents)?;
+        let config: Config =
+            toml_edit::de::from_document(merged).map_err(|e| describe_error(contents, e))?;
+        config.ensure_targets()
+    }
 
-[git]
-tag_prefix = "v"
-"#;
-        let config = Config::parse(content).unwrap();
-        assert_eq!(config.version, "0.7.3");
-        assert_eq!(config.targets.len(), 3);
-        assert_eq!(config.tag_name(), "v0.7.3");
+    /// Find the file that sets the version among merged configs (the last
+    /// one that does): the top-level `version`, or the component's. With a
+    /// file source, this is the source file.
+    pub fn version_file(&self, paths: &[PathBuf]) -> Result<PathBuf> {
+        match (&self.source, &self.component) {
+            (Source::Git, None) => return Err(Error::Usage(
+                "The version comes from git tags (`source = \"git\"`); tag a commit to change it"
+                    .to_string(),
+            )),
+            (Source::File(target), None) => return Ok(target.file.clone()),
+            _ => {}
+        }
+        for file in config_files(paths)?.into_iter().rev() {
+            let document = parse_document(&read_config(&file)?)?;
+            let sets_version = match &self.component {
+                Some(name) => document
+                    .get("components")
+                    .and_then(|components| components.get(name))
+                    .and_then(|component| component.get("version"))
+                    .is_some(),
+                None => document.contains_key("version"),
+            };
+            if sets_version {
+                return Ok(file);
+            }
+        }
+        Err(Error::ConfigParse(format!(
+            "No configuration file sets `{}`",
+            self.version_key()
+        )))
     }
 
-    #[test]
-    fn test_parse_empty_targets() {
-        let content = r#"
+    /// Key path of the version in the config file (`version`, or
+    /// `components."<name>".version` for a component)
+    pub fn version_key(&self) -> String {
+        match &self.component {
+            Some(name) => format!(
+                "components.\"{}\".version",
+                name.replace('\\', "\\\\").replace('"', "\\\"")
+            ),
+            None => "version".to_string(),
+        }
+    }
+
+    /// Append a `[[targets]]` entry to a config file, preserving formatting
+    pub fn add_target(path: &Path, target: &Target) -> Result<()> {
+        if !path.exists() {
+            return Err(Error::ConfigNotFound(path.to_path_buf()));
+        }
+
+        let content = fs::read_to_string(path)?;
+        let embedded = embedded_key(path, &content)?;
+        if embedded.is_some() && is_json(path) {
+            return Err(Error::Usage(format!(
+                "Targets can't be added to the configuration in {}; add it to \"versync\" by hand",
+                path.display()
+            )));
+        }
+        // Target paths are relative to the config's directory
+        let file = target.file.strip_prefix(root()).unwrap_or(&target.file);
+        let mut document = parse_document(&content)?;
+        let root = match embedded {
+            Some(key) => table_at_mut(&mut document, key).expect("embedded table exists"),
+            None => document.as_table_mut(),
+        };
+        let mut table = Table::new();
+        table.insert("file", toml_edit::value(file.display().to_string()));
+        table.insert("key", toml_edit::value(target.key.as_str()));
+        if let Some(format) = target.format {
+            table.insert("format", toml_edit::value(format.name()));
+        }
+
+        let targets = root
+            .entry("targets")
+            .or_insert_with(|| toml_edit::Item::ArrayOfTables(ArrayOfTables::new()))
+            .as_array_of_tables_mut()
+            .ok_or_else(|| Error::ConfigParse("`targets` is not an array of tables".to_string()))?;
+        targets.push(table);
+
+        fs::write(path, document.to_string())?;
+        Ok(())
+    }
+
+    /// Rewrite the version field of a config file (see
+    /// [`Config::version_key`]), preserving formatting
+    ///
+    /// The version is written in the config's `scheme`. With a file source,
+    /// it is written to the source instead, in the source's `scheme` and
+    /// `template`.
+    pub fn write_version(&self, path: &Path, version: &str) -> Result<()> {
+        if let (Source::File(target), None) = (&self.source, &self.component) {
+            let format = target
+                .effective_format()
+                .ok_or_else(|| Error::UnknownFormat(target.file.clone()))?;
+            let value = target.expected_value(&Context::new(version))?;
+            return format::write_version(&target.file, &target.key, &value, format);
+        }
+        if !path.exists() {
+            return Err(Error::ConfigNotFound(path.to_path_buf()));
+        }
+
+        let version = &self.version_in_scheme(version)?;
+        let content = fs::read_to_string(path)?;
+        let key = match embedded_key(path, &content)? {
+            Some(table) => format!("{}.{}", table, self.version_key()),
+            None => self.version_key(),
+        };
+        // JSON is edited in place, keeping the rest of the file as it is
+        let update = if is_json(path) {
+            format::jsonc::update_version
+        } else {
+            format::toml::update_version
+        };
+        let updated =
+            update(&content, path, &key, version).map_err(|e| Error::ConfigParse(e.to_string()))?;
+        fs::write(path, updated)?;
+        Ok(())
+    }
+
+    /// The exit code for a failure class, honouring `[exit_codes]`
+    pub fn exit_code(&self, class: FailureClass) -> u8 {
+        self.exit_codes
+            .get(&class)
+            .copied()
+            .unwrap_or(class.default_code() as u8)
+    }
+
+    /// The exit code for an error, honouring `[exit_codes]`
+    pub fn exit_code_for(&self, error: &Error) -> u8 {
+        self.exit_code(error.class())
+    }
+
+    /// Get the full tag name (rendered from `git.tag_template`)
+    pub fn tag_name(&self) -> String {
+        self.render_tag(&self.git.tag_template)
+    }
+
+    /// Get the tag name for a release channel
+    ///
+    /// Falls back to `git.tag_template` if the channel has no tag template.
+    pub fn channel_tag_name(&self, channel: &str) -> Result<String> {
+        Ok(self.for_channel(channel)?.tag_name())
+    }
+
+    /// Derive the configuration seen by a release channel
+    ///
+    /// The channel's version template is rendered from the canonical version,
+    /// the targets are narrowed to the channel's files and its tag template
+    /// replaces `git.tag_template`. The canonical config is left untouched.
+    pub fn for_channel(&self, channel: &str) -> Result<Config> {
+        let channel_config = self
+            .channels
+            .get(channel)
+            .ok_or_else(|| Error::UnknownChannel(channel.to_string()))?;
+
+        let mut config = self.clone();
+        if let Some(template) = &channel_config.version {
+            config.version = Context::new(&self.version).render(template)?;
+        }
+        if let Some(template) = &channel_config.tag {
+            config.git.tag_template = template.clone();
+        }
+        if !channel_config.targets.is_empty() {
+            // Entries are paths or globs relative to the config's directory
+            let root = root();
+            let lists = |pattern: &PathBuf, target: &Target| {
+                let file = target.file.strip_prefix(&root).unwrap_or(&target.file);
+                glob::matches(&pattern.to_string_lossy(), file)
+            };
+            if let Some(pattern) = channel_config
+                .targets
+                .iter()
+                .find(|pattern| !self.targets.iter().any(|t| lists(pattern, t)))
+            {
+                return Err(Error::ConfigParse(format!(
+                    "Channel '{}' lists '{}', which matches no target",
+                    channel,
+                    pattern.display()
+                )));
+            }
+            config.targets.retain(|target| {
+                channel_config
+                    .targets
+                    .iter()
+                    .any(|pattern| lists(pattern, target))
+            });
+        }
+
+        Ok(config)
+    }
+
+    /// Replace the version for this run, e.g. with one injected by CI
+    ///
+    /// A tag name or tag ref is accepted too: `refs/tags/` and the tag
+    /// prefix are stripped (`refs/tags/v1.2.3` is `1.2.3`). The version must
+    /// be valid in the config's `scheme` (semver by default).
+    pub fn override_version(&mut self, value: &str) -> Result<()> {
+        let value = value.trim();
+        let value = value.strip_prefix("refs/tags/").unwrap_or(value);
+        let version = match value.strip_prefix(self.git.tag_prefix.as_str()) {
+            Some(version) if self.version_from_scheme(version).is_ok() => version,
+            _ => value,
+        };
+        self.version = self.version_from_scheme(version)?;
+        Ok(())
+    }
+
+    /// Narrow the targets, including those of components, to the ones in
+    /// `groups` (all if empty) and not in `exclude`
+    ///
+    /// Fails for a group name no target uses, which is most likely a typo.
+    pub fn select_groups(&mut self, groups: &[String], exclude: &[String]) -> Result<()> {
+        let known: Vec<&str> = self
+            .targets
+            .iter()
+            .chain(self.components.values().flat_map(|c| &c.targets))
+            .filter_map(|target| target.group.as_deref())
+            .collect();
+        if let Some(unknown) = groups
+            .iter()
+            .chain(exclude)
+            .find(|group| !known.contains(&group.as_str()))
+        {
+            return Err(Error::Usage(format!("No target is in group '{}'", unknown)));
+        }
+
+        let selected = |target: &Target| {
+            let group = target.group.as_ref();
+            (groups.is_empty() || group.is_some_and(|g| groups.contains(g)))
+                && !group.is_some_and(|g| exclude.contains(g))
+        };
+        self.targets.retain(selected);
+        for component in self.components.values_mut() {
+            component.targets.retain(selected);
+        }
+        Ok(())
+    }
+
+    /// Derive the configuration of a component
+    ///
+    /// The component's version, targets and tag prefix replace the top-level
+    /// ones; everything else is shared. The canonical config is left
+    /// untouched.
+    pub fn for_component(&self, name: &str) -> Result<Config> {
+        let component = self
+            .components
+            .get(name)
+            .ok_or_else(|| Error::UnknownComponent(name.to_string()))?;
+
+        let mut config = self.clone();
+        config.version = component.version.clone();
+        config.targets = component.targets.clone();
+        config.git.tag_prefix = component
+            .tag_prefix
+            .clone()
+            .unwrap_or_else(|| format!("{}-{}", name, self.git.tag_prefix));
+        config.component = Some(name.to_string());
+        Ok(config)
+    }
+
+    /// The top-level configuration (if it has a version) followed by the
+    /// configuration of each component
+    pub fn each_component(&self) -> Vec<Config> {
+        let root = (!self.version.is_empty()).then(|| self.clone());
+        root.into_iter()
+            .chain(
+                self.components
+                    .keys()
+                    .filter_map(|name| self.for_component(name).ok()),
+            )
+            .collect()
+    }
+
+    /// Check the configuration and its target files, collecting every
+    /// problem instead of stopping at the first
+    ///
+    /// Covers the version, duplicate targets, missing or unparsable target
+    /// files, key paths (including extra keys) that do not resolve to a
+    /// string, tag prefixes and templates that do not give valid tag names,
+    /// channels and components.
+    pub fn validate(&self) -> Vec<Issue> {
+        let mut issues = Vec::new();
+        for config in self.each_component() {
+            issues.extend(config.validate_version());
+        }
+
+        if !self.version.is_empty() {
+            for name in self.channels.keys() {
+                if let Err(e) = self.for_channel(name) {
+                    issues.push(Issue::error(format!("channels.{}", name), e.to_string()));
+                }
+            }
+        }
+
+        issues
+    }
+
+    /// Validate the version, targets and tag name of this configuration,
+    /// locating issues in a component's table when derived for one
+    fn validate_version(&self) -> Vec<Issue> {
+        let mut issues = Vec::new();
+        let at = |location: &str| match &self.component {
+            Some(name) => format!("components.{}.{}", name, location),
+            None => location.to_string(),
+        };
+
+        if let Err(e) = Version::parse(&self.version) {
+            issues.push(Issue::error(at("version"), e.to_string()));
+        }
+
+        for (i, target) in self.targets.iter().enumerate() {
+            let location = at(&format!("targets[{}]", i));
+            if let Some(first) = self.targets[..i]
+                .iter()
+                .position(|t| t.file == target.file && t.key == target.key)
+            {
+                issues.push(Issue::error(
+                    &location,
+                    format!(
+                        "duplicate of targets[{}] ({} {})",
+                        first,
+                        target.file.display(),
+                        target.key
+                    ),
+                ));
+                continue;
+            }
+            let Some(format) = target.effective_format() else {
+                issues.push(Issue::error(
+                    &location,
+                    Error::UnknownFormat(target.file.clone()).to_string(),
+                ));
+                continue;
+            };
+            let keys = std::iter::once(&target.key).chain(target.extra.keys());
+            let invalid: Vec<Error> = keys
+                .filter_map(|key| format::check_key(key, format).err())
+                .collect();
+            if !invalid.is_empty() {
+                for e in invalid {
+                    issues.push(Issue::error(&location, e.to_string()));
+                }
+                continue;
+            }
+            if !target.file.exists() {
+                issues.push(Issue::error(
+                    &location,
+                    Error::TargetNotFound(target.file.clone()).to_string(),
+                ));
+                continue;
+            }
+            let keys = std::iter::once(&target.key).chain(target.extra.keys());
+            for key in keys {
+                if let Err(e) = format::read_version(&target.file, key, format) {
+                    issues.push(Issue::error(&location, e.to_string()));
+                }
+            }
+            if let Err(e) = target.expected_value(&Context::new(&self.version)) {
+                issues.push(Issue::error(&location, format!("template: {}", e)));
+            }
+        }
+
+        let prefix_location = match self.component {
+            Some(_) => at("tag_prefix"),
+            None => "git.tag_prefix".to_string(),
+        };
+        if let Err(reason) = check_ref_name(&self.git.tag_prefix, true) {
+            issues.push(Issue::error(
+                prefix_location,
+                format!("'{}' {}", self.git.tag_prefix, reason),
+            ));
+        } else if let Err(reason) = check_ref_name(&self.tag_name(), false) {
+            issues.push(Issue::error(
+                "git.tag_template",
+                format!("gives the tag name '{}', which {}", self.tag_name(), reason),
+            ));
+        }
+
+        issues
+    }
+
+    /// Render a tag template with `{prefix}` and the version placeholders
+    ///
+    /// Component placeholders such as `{major}` are only replaced when the
+    /// version is valid semver; unknown placeholders are left as-is.
+    fn render_tag(&self, template: &str) -> String {
+        let version = Version::parse(&self.version).ok();
+        template::render(template, |name| match name {
+            "prefix" => Some(self.git.tag_prefix.clone()),
+            "version" => Some(
+                self.version_in_scheme(&self.version)
+                    .unwrap_or_else(|_| self.version.clone()),
+            ),
+            _ => version
+                .as_ref()
+                .and_then(|v| template::version_var(v, name)),
+        })
+    }
+}
+
+/// The version described by `git describe --long` output
+/// (`v1.2.3-4-gabc1234` with the prefix `v` is `1.2.3+4.gabc1234`), with
+/// the tag written in `scheme`
+fn described_version(prefix: &str, scheme: Scheme, described: &str) -> Result<String> {
+    let invalid = || Error::GitCommand(format!("Unexpected git describe output '{}'", described));
+    let mut parts = described.rsplitn(3, '-');
+    let (Some(sha), Some(distance), Some(tag)) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(invalid());
+    };
+    let distance: u64 = distance.parse().map_err(|_| invalid())?;
+    let version = Version::parse(&scheme.to_semver(tag.strip_prefix(prefix).unwrap_or(tag))?)?;
+
+    if distance == 0 {
+        return Ok(version.to_string());
+    }
+    let mut build = version.build.clone();
+    build.extend([distance.to_string(), sha.to_string()]);
+    Ok(Version { build, ..version }.to_string())
+}
+
+/// Name of the configuration file used when `--config` is not given
+pub const DEFAULT_CONFIG: &str = "version.toml";
+
+static WORKING_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Set the process-wide working directory relative to the directory of the
+/// configuration (first call wins), for a configuration found in a parent
+/// directory
+///
+/// Target paths in the configuration are relative to its directory; they
+/// are resolved against this when parsed, so paths on the command line stay
+/// relative to the working directory.
+pub fn init_working_dir(dir: PathBuf) {
+    let _ = WORKING_DIR.set(dir);
+}
+
+/// The directory of the configuration relative to the working directory
+/// (`../..`, or empty when they are the same)
+pub fn root() -> PathBuf {
+    WORKING_DIR
+        .get()
+        .map(|dir| dir.components().map(|_| Component::ParentDir).collect())
+        .unwrap_or_default()
+}
+
+/// A path relative to the working directory, made relative to the
+/// directory of the configuration
+pub fn config_relative(path: &Path) -> PathBuf {
+    match WORKING_DIR.get() {
+        Some(dir) => normalize(&dir.join(path)),
+        None => normalize(path),
+    }
+}
+
+/// Drop `.` components and the directories `..` components lead out of
+/// (`crates/foo/../../Cargo.toml` is `Cargo.toml`), without touching the
+/// file system
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if normalized.file_name().is_some() => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// A path given on the command line, in the form target paths take once
+/// parsed (so `Cargo.toml` in `crates/foo` is `../../crates/foo/Cargo.toml`
+/// with the configuration two levels up)
+pub fn resolve_path(path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+    root().join(config_relative(path))
+}
+
+/// Files of other tools that can embed the configuration, with the key path
+/// of the table holding it; the first table a file has is used
+const EMBEDDED: &[(&str, &str)] = &[
+    ("pyproject.toml", "tool.versync"),
+    ("Cargo.toml", "package.metadata.versync"),
+    ("Cargo.toml", "workspace.metadata.versync"),
+    ("package.json", "versync"),
+];
+
+/// Find the default configuration file in a directory or its parents
+///
+/// In each directory, `version.toml` is preferred over a file embedding the
+/// configuration (`[tool.versync]` in pyproject.toml, then
+/// `[package.metadata.versync]` or `[workspace.metadata.versync]` in
+/// Cargo.toml, then `"versync"` in package.json). The search stops at the
+/// root of the git repository the directory is in (the first directory with
+/// a `.git`), so a config outside the repository is never picked up.
+pub fn discover(start: &Path) -> Option<PathBuf> {
+    for dir in start.ancestors() {
+        let path = dir.join(DEFAULT_CONFIG);
+        if path.is_file() {
+            return Some(path);
+        }
+        let embedding = EMBEDDED
+            .iter()
+            .map(|(name, _)| dir.join(name))
+            .find(|path| {
+                fs::read_to_string(path)
+                    .is_ok_and(|content| matches!(embedded_key(path, &content), Ok(Some(_))))
+            });
+        if embedding.is_some() {
+            return embedding;
+        }
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+    None
+}
+
+/// Read the configuration in a file, see [`extract_config`]
+pub fn read_config(path: &Path) -> Result<String> {
+    extract_config(path, &fs::read_to_string(path)?)
+}
+
+/// The configuration in the content of a file: all of it for a config file
+/// of its own, or the embedded table as a document of its own (e.g.
+/// `[tool.versync]` in pyproject.toml)
+///
+/// A config embedded in JSON is converted to TOML, so it is read, merged and
+/// validated like any other.
+pub fn extract_config(path: &Path, content: &str) -> Result<String> {
+    let Some(key) = embedded_key(path, content)? else {
+        return Ok(content.to_string());
+    };
+
+    let mut config = DocumentMut::new();
+    if is_json(path) {
+        let value = parse_json(path, content)?;
+        let object = object_at(&value, key).expect("embedded object exists");
+        config = toml_edit::ser::to_document(object)
+            .map_err(|e| Error::ConfigParse(format!("{}: {}", path.display(), e)))?;
+        expand_inline(config.as_table_mut());
+    } else {
+        let document = parse_document(content)?;
+        for (key, item) in table_at(&document, key).into_iter().flat_map(Table::iter) {
+            config.insert(key, item.clone());
+        }
+    }
+    Ok(config.to_string())
+}
+
+/// The name of the file if it is one that can embed the configuration
+pub fn embedding_name(path: &Path) -> Option<&str> {
+    let name = path.file_name()?.to_str()?;
+    EMBEDDED
+        .iter()
+        .any(|(embedding, _)| *embedding == name)
+        .then_some(name)
+}
+
+/// Key path of the table holding the configuration in a file of another
+/// tool, or `None` for a config file of its own
+///
+/// Fails for a file that can embed the configuration but doesn't.
+fn embedded_key(path: &Path, content: &str) -> Result<Option<&'static str>> {
+    let Some(name) = embedding_name(path) else {
+        return Ok(None);
+    };
+    let keys: Vec<&str> = EMBEDDED
+        .iter()
+        .filter(|(embedding, _)| *embedding == name)
+        .map(|(_, key)| *key)
+        .collect();
+
+    let found = if is_json(path) {
+        let value = parse_json(path, content)?;
+        keys.iter().find(|key| object_at(&value, key).is_some())
+    } else {
+        let document = parse_document(content)?;
+        keys.iter().find(|key| table_at(&document, key).is_some())
+    };
+    match found {
+        Some(key) => Ok(Some(key)),
+        None if is_json(path) => Err(Error::ConfigParse(format!(
+            "{} has no \"{}\" object",
+            name,
+            keys.join("\" or \"")
+        ))),
+        None => Err(Error::ConfigParse(format!(
+            "{} has no [{}] table",
+            name,
+            keys.join("] or [")
+        ))),
+    }
+}
+
+fn is_json(path: &Path) -> bool {
+    FileFormat::from_path(path) == Some(FileFormat::Json)
+}
+
+fn parse_json(path: &Path, content: &str) -> Result<serde_json::Value> {
+    serde_json::from_str(content)
+        .map_err(|e| Error::ConfigParse(format!("{}: {}", path.display(), e)))
+}
+
+/// The table at a dotted key path
+fn table_at<'a>(document: &'a DocumentMut, key: &str) -> Option<&'a Table> {
+    key.split('.')
+        .try_fold(document.as_table(), |table, segment| {
+            table.get(segment)?.as_table()
+        })
+}
+
+/// The table at a dotted key path, for modification
+fn table_at_mut<'a>(document: &'a mut DocumentMut, key: &str) -> Option<&'a mut Table> {
+    key.split('.')
+        .try_fold(document.as_table_mut(), |table, segment| {
+            table.get_mut(segment)?.as_table_mut()
+        })
+}
+
+/// The JSON object at a dotted key path
+fn object_at<'a>(value: &'a serde_json::Value, key: &str) -> Option<&'a serde_json::Value> {
+    key.split('.')
+        .try_fold(value, |value, segment| value.get(segment))
+        .filter(|value| value.is_object())
+}
+
+/// Turn inline tables, and arrays made of them, into regular tables and
+/// arrays of tables, so `targets` from JSON merge like `[[targets]]`
+fn expand_inline(table: &mut Table) {
+    for (_, item) in table.iter_mut() {
+        let tables = item
+            .as_array()
+            .is_some_and(|array| !array.is_empty() && array.iter().all(|v| v.is_inline_table()));
+        if tables {
+            *item = std::mem::take(item)
+                .into_array_of_tables()
+                .map_or_else(|item| item, Item::ArrayOfTables);
+        } else if item.is_inline_table() {
+            *item = std::mem::take(item)
+                .into_table()
+                .map_or_else(|item| item, Item::Table);
+        }
+        match item {
+            Item::Table(table) => expand_inline(table),
+            Item::ArrayOfTables(tables) => tables.iter_mut().for_each(expand_inline),
+            _ => {}
+        }
+    }
+}
+
+/// Expand configuration paths, replacing directories by their `*.toml` files
+pub fn config_files(paths: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            let mut entries = fs::read_dir(path)?
+                .map(|entry| entry.map(|e| e.path()))
+                .collect::<std::io::Result<Vec<_>>>()?;
+            entries.retain(|p| p.is_file() && FileFormat::from_path(p) == Some(FileFormat::Toml));
+            if entries.is_empty() {
+                return Err(Error::ConfigNotFound(path.clone()));
+            }
+            entries.sort();
+            files.extend(entries);
+        } else if path.exists() {
+            files.push(path.clone());
+        } else {
+            return Err(Error::ConfigNotFound(path.clone()));
+        }
+    }
+    Ok(files)
+}
+
+/// Keys in the configuration sources that versync does not know and ignores
+/// (e.g. `git.tag_prefx`, `targets.0.fiel`)
+///
+/// Keys are still reported when the configuration is otherwise invalid, as
+/// far as deserialization got; only unparsable TOML is an error.
+pub fn unknown_keys(contents: &[String]) -> Result<Vec<String>> {
+    let mut keys = Vec::new();
+    let deserializer = toml_edit::de::Deserializer::from(merge_documents(contents)?);
+    let _: std::result::Result<Config, _> =
+        serde_ignored::deserialize(deserializer, |path| keys.push(path.to_string()));
+    Ok(keys)
+}
+
+/// Describe configuration sources that don't deserialize: by the key path at
+/// fault where the error has a location, followed by the unknown keys
+///
+/// Merged sources have no locations, but the error names the key already.
+fn describe_error(contents: &[String], error: toml_edit::de::Error) -> Error {
+    let path = match (contents, error.span()) {
+        ([content], Some(span)) => toml_edit::ImDocument::parse(content.as_str())
+            .ok()
+            .and_then(|document| item_path(document.as_item(), span.start)),
+        _ => None,
+    };
+    let mut message = match path {
+        Some(path) if !path.is_empty() => {
+            format!("{}: {}", path.join("."), error.message().trim_end())
+        }
+        _ => error.to_string().trim_end().to_string(),
+    };
+
+    let unknown = unknown_keys(contents).unwrap_or_default();
+    if !unknown.is_empty() {
+        message.push_str(&format!(
+            " (unknown keys, possibly misspelled: {})",
+            unknown.join(", ")
+        ));
+    }
+    Error::ConfigParse(message)
+}
+
+/// Key path, as in [`unknown_keys`], of the innermost item whose source
+/// spans `offset`
+fn item_path(item: &Item, offset: usize) -> Option<Vec<String>> {
+    match item {
+        Item::Table(table) => {
+            table_path(table, offset).or_else(|| spans(table.span(), offset).then(Vec::new))
+        }
+        Item::ArrayOfTables(tables) => tables.iter().enumerate().find_map(|(i, table)| {
+            let mut path =
+                table_path(table, offset).or_else(|| spans(table.span(), offset).then(Vec::new))?;
+            path.insert(0, i.to_string());
+            Some(path)
+        }),
+        Item::Value(value) => value_path(value, offset),
+        Item::None => None,
+    }
+}
+
+fn table_path(table: &dyn TableLike, offset: usize) -> Option<Vec<String>> {
+    table.iter().find_map(|(key, item)| {
+        let mut path = item_path(item, offset)?;
+        path.insert(0, key.to_string());
+        Some(path)
+    })
+}
+
+fn value_path(value: &toml_edit::Value, offset: usize) -> Option<Vec<String>> {
+    let inner = match value {
+        toml_edit::Value::InlineTable(table) => table_path(table, offset),
+        toml_edit::Value::Array(array) => array.iter().enumerate().find_map(|(i, value)| {
+            let mut path = value_path(value, offset)?;
+            path.insert(0, i.to_string());
+            Some(path)
+        }),
+        _ => None,
+    };
+    inner.or_else(|| spans(value.span(), offset).then(Vec::new))
+}
+
+fn spans(span: Option<std::ops::Range<usize>>, offset: usize) -> bool {
+    span.is_some_and(|span| span.contains(&offset))
+}
+
+/// Check a tag name (or, with `prefix`, the start of one) against git's
+/// rules for reference names
+fn check_ref_name(name: &str, prefix: bool) -> std::result::Result<(), &'static str> {
+    if name.is_empty() {
+        return if prefix { Ok(()) } else { Err("is empty") };
+    }
+    if name
+        .chars()
+        .any(|c| c.is_ascii_control() || " ~^:?*[\\".contains(c))
+    {
+        return Err("contains a space, control character or one of ~^:?*[\\");
+    }
+    if name.contains("..") || name.contains("@{") || name.contains("//") {
+        return Err("contains '..', '@{' or '//'");
+    }
+    if name.starts_with(['-', '/', '.']) {
+        return Err("starts with '-', '/' or '.'");
+    }
+    if !prefix && (name.ends_with(['.', '/']) || name.ends_with(".lock") || name == "@") {
+        return Err("ends with '.', '/' or '.lock'");
+    }
+    Ok(())
+}
+
+fn merge_documents(contents: &[String]) -> Result<DocumentMut> {
+    let mut merged = DocumentMut::new();
+    for content in contents {
+        let document = parse_document(content)?;
+        merge_table(merged.as_table_mut(), document.as_table(), true);
+    }
+    Ok(merged)
+}
+
+fn parse_document(content: &str) -> Result<DocumentMut> {
+    content
+        .parse()
+        .map_err(|e: toml_edit::TomlError| Error::ConfigParse(e.to_string()))
+}
+
+fn merge_table(base: &mut dyn TableLike, overlay: &dyn TableLike, root: bool) {
+    for (key, item) in overlay.iter() {
+        let Some(existing) = base.get_mut(key) else {
+            base.insert(key, item.clone());
+            continue;
+        };
+        if root && key == "targets" {
+            if let (Some(targets), Some(overlay_targets)) =
+                (existing.as_array_of_tables_mut(), item.as_array_of_tables())
+            {
+                merge_targets(targets, overlay_targets);
+                continue;
+            }
+        }
+        if let (Some(table), Some(overlay_table)) =
+            (existing.as_table_like_mut(), item.as_table_like())
+        {
+            merge_table(table, overlay_table, false);
+            continue;
+        }
+        *existing = item.clone();
+    }
+}
+
+fn merge_targets(base: &mut ArrayOfTables, overlay: &ArrayOfTables) {
+    for target in overlay.iter() {
+        let id = target_id(target);
+        let position = base
+            .iter()
+            .position(|existing| id.is_some() && target_id(existing) == id);
+        match position.and_then(|i| base.get_mut(i)) {
+            Some(existing) => *existing = target.clone(),
+            None => base.push(target.clone()),
+        }
+    }
+}
+
+fn target_id(target: &Table) -> Option<(&str, &str)> {
+    Some((target.get("file")?.as_str()?, target.get("key")?.as_str()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_minimal_config() {
+        let content = r#"
+version = "1.0.0"
+
+[[targets]]
+file = "pyproject.toml"
+key = "project.version"
+"#;
+        let config = Config::parse(content).unwrap();
+        assert_eq!(config.version, "1.0.0");
+        assert_eq!(config.targets.len(), 1);
+        assert_eq!(config.git.tag_prefix, "v");
+        assert_eq!(config.git.remote, "origin");
+        assert!(!config.network.offline);
+    }
+
+    #[test]
+    fn test_parse_full_config() {
+        let content = r#"
+version = "0.7.3"
+
+[[targets]]
+file = "pyproject.toml"
+key = "project.version"
+
+[[targets]]
+file = "Cargo.toml"
+key = "workspace.package.version"
+
+[[targets]]
+file = "package.json"
+key = "version"
+format = "json"
+
+[git]
+tag_prefix = "v"
+"#;
+        let config = Config::parse(content).unwrap();
+        assert_eq!(config.version, "0.7.3");
+        assert_eq!(config.targets.len(), 3);
+        assert_eq!(config.tag_name(), "v0.7.3");
+    }
+
+    #[test]
+    fn test_parse_target_keys() {
+        let content = r#"
+version = "1.2.3"
+
+[[targets]]
+file = "Chart.json"
+keys = { version = "{version}", appVersion = "v{version}" }
+
+[[targets]]
+file = "image.toml"
+key = "tag"
+template = "{major}.{minor}"
+"#;
+        let config = Config::parse(content).unwrap();
+        let targets: Vec<(&str, Option<&str>)> = config
+            .targets
+            .iter()
+            .map(|t| (t.key.as_str(), t.template.as_deref()))
+            .collect();
+        assert_eq!(
+            targets,
+            [
+                ("appVersion", Some("v{version}")),
+                ("version", Some("{version}")),
+                ("tag", Some("{major}.{minor}")),
+            ]
+        );
+
+        let context = Context::new(&config.version);
+        assert_eq!(
+            config.targets[0].expected_value(&context).unwrap(),
+            "v1.2.3"
+        );
+        assert_eq!(config.targets[2].expected_value(&context).unwrap(), "1.2");
+
+        assert_eq!(
+            config.targets[0].version_from_value("v1.3.0").as_deref(),
+            Some("1.3.0")
+        );
+        assert_eq!(config.targets[0].version_from_value("1.3.0"), None);
+        assert_eq!(config.targets[2].version_from_value("1.3"), None);
+    }
+
+    #[test]
+    fn test_target_scheme() {
+        let content = r#"
+version = "1.2.0-rc.1"
+
+[[targets]]
+file = "pyproject.toml"
+key = "project.version"
+scheme = "pep440"
+
+[[targets]]
+file = "docs/conf.py"
+key = "release"
+template = "v{version}"
+scheme = "pep440"
+"#;
+        let config = Config::parse(content).unwrap();
+        let context = Context::new(&config.version);
+        let [python, docs] = &config.targets[..] else {
+            panic!("expected two targets");
+        };
+        assert_eq!(python.scheme, Some(Scheme::Pep440));
+        assert_eq!(python.expected_value(&context).unwrap(), "1.2.0rc1");
+        assert_eq!(docs.expected_value(&context).unwrap(), "v1.2.0rc1");
+
+        assert!(python.holds("1.2.0-RC1", "1.2.0rc1"));
+        assert!(docs.holds("v1.2.0.rc1", "v1.2.0rc1"));
+        assert!(!python.holds("1.2.0rc2", "1.2.0rc1"));
+        assert_eq!(
+            docs.version_from_value("v1.3.0b2").as_deref(),
+            Some("1.3.0-beta.2")
+        );
+
+        let context = Context::new("1.2.0-nightly.1");
+        assert!(python.expected_value(&context).is_err());
+        assert!(Config::parse(&content.replacen("pep440", "calver", 1)).is_err());
+    }
+
+    #[test]
+    fn test_optional_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let pattern = dir.path().join("packages/*/package.json");
+        let content = format!(
+            "version = \"1.0.0\"\n[[targets]]\nfile = \"Cargo.toml\"\nkey = \"package.version\"\n\
+             [[targets]]\nfile = {:?}\nkey = \"version\"\n",
+            pattern.display().to_string()
+        );
+        assert!(matches!(
+            Config::parse(&content),
+            Err(Error::ConfigParse(msg)) if msg.contains("matches no files")
+        ));
+
+        // An optional glob may match nothing
+        let config = Config::parse(&format!("{}required = false\n", content)).unwrap();
+        assert_eq!(config.targets.len(), 1);
+        assert!(config.targets[0].required);
+
+        let config = Config::parse(
+            "version = \"1.0.0\"\n[[targets]]\nfile = \"docs/index.md\"\nkey = \"version\"\n\
+             format = \"markdown\"\nrequired = false\n",
+        )
+        .unwrap();
+        assert!(!config.targets[0].required);
+    }
+
+    #[test]
+    fn test_create_target() {
+        let content = "version = \"1.0.0\"\n[[targets]]\nfile = \"pyproject.toml\"\nkey = \"project.version\"\ncreate = true\n";
+        let config = Config::parse(content).unwrap();
+        assert!(config.targets[0].create);
+
+        let content = content.replace("pyproject.toml", "setup.py");
+        assert!(matches!(
+            Config::parse(&content),
+            Err(Error::ConfigParse(msg)) if msg.contains("only TOML and JSON")
+        ));
+    }
+
+    #[test]
+    fn test_parse_error_key_path() {
+        let content =
+            "version = \"1.0.0\"\n[[targets]]\nfile = \"a.toml\"\nkey = \"v\"\nrequired = \"no\"\n";
+        assert!(matches!(
+            Config::parse(content),
+            Err(Error::ConfigParse(msg)) if msg.starts_with("targets.0.required: invalid type")
+        ));
+
+        let content = "version = \"1.0.0\"\n[[targets]]\nfiel = \"a.toml\"\nkey = \"v\"\n";
+        assert!(matches!(
+            Config::parse(content),
+            Err(Error::ConfigParse(msg)) if msg
+                == "targets.0: missing field `file` (unknown keys, possibly misspelled: targets.0.fiel)"
+        ));
+
+        let content = "version = \"1.0.0\"\ngit = { tag_prefix = 1 }\n";
+        assert!(matches!(
+            Config::parse(content),
+            Err(Error::ConfigParse(msg)) if msg.starts_with("git.tag_prefix: ")
+        ));
+    }
+
+    #[test]
+    fn test_select_groups() {
+        let content = r#"
+version = "1.0.0"
+
+[[targets]]
+file = "Cargo.toml"
+key = "package.version"
+group = "manifests"
+
+[[targets]]
+file = "docs/index.md"
+key = "version"
+format = "markdown"
+group = "docs"
+
+[[targets]]
+file = "VERSION"
+
+[components.api]
+version = "2.0.0"
+
+[[components.api.targets]]
+file = "api/README.md"
+key = "version"
+format = "markdown"
+group = "docs"
+"#;
+        let config = Config::parse(content).unwrap();
+        let files = |config: &Config| -> Vec<PathBuf> {
+            config.targets.iter().map(|t| t.file.clone()).collect()
+        };
+
+        let mut docs = config.clone();
+        docs.select_groups(&["docs".to_string()], &[]).unwrap();
+        assert_eq!(files(&docs), [PathBuf::from("docs/index.md")]);
+        assert_eq!(docs.for_component("api").unwrap().targets.len(), 1);
+
+        let mut no_docs = config.clone();
+        no_docs.select_groups(&[], &["docs".to_string()]).unwrap();
+        assert_eq!(
+            files(&no_docs),
+            [PathBuf::from("Cargo.toml"), PathBuf::from("VERSION")]
+        );
+        assert!(no_docs.for_component("api").unwrap().targets.is_empty());
+
+        let mut config = config;
+        assert!(matches!(
+            config.select_groups(&["doc".to_string()], &[]),
+            Err(Error::Usage(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_target_glob() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["b", "a"] {
+            fs::create_dir_all(dir.path().join("crates").join(name)).unwrap();
+            fs::write(dir.path().join("crates").join(name).join("Cargo.toml"), "").unwrap();
+        }
+        let pattern = dir.path().join("crates/*/Cargo.toml");
+        let content = format!(
+            "version = \"1.0.0\"\n[[targets]]\nfile = {:?}\nkey = \"package.version\"\n",
+            pattern.display().to_string()
+        );
+        let config = Config::parse(&content).unwrap();
+        let files: Vec<&Path> = config.targets.iter().map(|t| t.file.as_path()).collect();
+        assert_eq!(
+            files,
+            [
+                dir.path().join("crates/a/Cargo.toml"),
+                dir.path().join("crates/b/Cargo.toml")
+            ]
+        );
+        assert!(config.targets.iter().all(|t| t.key == "package.version"));
+
+        let config = Config::parse(&format!(
+            "exclude = [{:?}]\n{}",
+            dir.path().join("crates/b").display().to_string(),
+            content
+        ))
+        .unwrap();
+        assert_eq!(config.targets.len(), 1);
+        let config = Config::parse(&format!("{}exclude = [\"**/a/*\"]\n", content)).unwrap();
+        assert_eq!(
+            config.targets[0].file,
+            dir.path().join("crates/b/Cargo.toml")
+        );
+        assert!(Config::parse(&format!("{}exclude = [\"crates/**\"]\n", content)).is_ok());
+        assert!(matches!(
+            Config::parse(&format!("{}exclude = [\"**/Cargo.toml\"]\n", content)),
+            Err(Error::ConfigParse(msg)) if msg.contains("matches no files")
+        ));
+
+        let content = content.replace("crates/*", "packages/*");
+        assert!(matches!(
+            Config::parse(&content),
+            Err(Error::ConfigParse(msg)) if msg.contains("matches no files")
+        ));
+    }
+
+    #[test]
+    fn test_parse_target_lockfile() {
+        let content = r#"
+version = "1.2.3"
+
+[[targets]]
+file = "web/package.json"
+key = "version"
+lockfile = true
+"#;
+        let config = Config::parse(content).unwrap();
+        let targets: Vec<(String, &str)> = config
+            .targets
+            .iter()
+            .map(|t| (t.file.display().to_string(), t.key.as_str()))
+            .collect();
+        assert_eq!(
+            targets,
+            [
+                ("web/package.json".to_string(), "version"),
+                ("web/package-lock.json".to_string(), "version"),
+                (
+                    "web/package-lock.json".to_string(),
+                    r#"packages."".version"#
+                ),
+            ]
+        );
+
+        let result = Config::parse(
+            "version = \"1.0.0\"\n[[targets]]\nfile = \"Cargo.toml\"\nkey = \"package.version\"\nlockfile = true\n",
+        );
+        assert!(
+            matches!(result, Err(Error::ConfigParse(msg)) if msg.contains("`lockfile` applies"))
+        );
+    }
+
+    #[test]
+    fn test_parse_citation_date_released() {
+        let content = r#"
+version = "1.2.3"
+
+[[targets]]
+file = "CITATION.cff"
+key = "version"
+
+[[targets]]
+file = "docs/CITATION.cff"
+key = "version"
+extra = { date-released = "2024-01-01" }
+"#;
+        let config = Config::parse(content).unwrap();
+        assert_eq!(config.targets[0].extra["date-released"], "{date}");
+        assert_eq!(config.targets[1].extra["date-released"], "2024-01-01");
+    }
+
+    #[test]
+    fn test_parse_target_without_key() {
+        let result = Config::parse("version = \"1.0.0\"\n[[targets]]\nfile = \"a.json\"\n");
+        assert!(
+            matches!(result, Err(Error::ConfigParse(msg)) if msg.contains("needs `key` or `keys`"))
+        );
+
+        let result = Config::parse(
+            "version = \"1.0.0\"\n[[targets]]\nfile = \"a.json\"\ntemplate = \"v{version}\"\nkeys = { a = \"{version}\" }\n",
+        );
+        assert!(
+            matches!(result, Err(Error::ConfigParse(msg)) if msg.contains("`template` applies to `key`"))
+        );
+    }
+
+    #[test]
+    fn test_parse_target_default_key() {
+        let config =
+            Config::parse("version = \"1.0.0\"\n[[targets]]\nfile = \"VERSION\"\n").unwrap();
+        assert_eq!(config.targets[0].key, "");
+        assert_eq!(config.targets[0].effective_format(), Some(FileFormat::Raw));
+
+        let config = Config::parse(
+            "version = \"1.0.0\"\n[[targets]]\nfile = \"version.txt\"\nformat = \"raw\"\nkey = \"newline\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.targets[0].key, "newline");
+
+        let config =
+            Config::parse("version = \"1.0.0\"\n[[targets]]\nfile = \"openapi.yaml\"\n").unwrap();
+        assert_eq!(config.targets[0].key, "info.version");
+    }
+
+    #[test]
+    fn test_parse_target_extra() {
+        let content = r#"
+version = "1.0.0"
+
+[[targets]]
+file = "metadata.json"
+key = "version"
+
+[targets.extra]
+release_date = "{date}"
+"build.commit" = "{commit}"
+"#;
+        let config = Config::parse(content).unwrap();
+        let extra = &config.targets[0].extra;
+        assert_eq!(extra.len(), 2);
+        assert_eq!(extra["release_date"], "{date}");
+        assert_eq!(extra["build.commit"], "{commit}");
+    }
+
+    #[test]
+    fn test_tag_templates() {
+        let content = r#"
+version = "1.2.3"
+
+[[targets]]
+file = "Cargo.toml"
+key = "package.version"
+
+[git]
+tag_template = "{prefix}{major}.{minor}.{patch}"
+
+[channels.staging]
+tag = "{prefix}{version}+staging"
+
+[channels.hotfix]
+tag = "{prefix}{version}-hotfix.{n}"
+
+[channels.plain]
+"#;
+        let config = Config::parse(content).unwrap();
+        assert_eq!(config.tag_name(), "v1.2.3");
+        assert_eq!(
+            config.channel_tag_name("staging").unwrap(),
+            "v1.2.3+staging"
+        );
+        assert_eq!(
+            config.channel_tag_name("hotfix").unwrap(),
+            "v1.2.3-hotfix.{n}"
+        );
+        assert_eq!(config.channel_tag_name("plain").unwrap(), "v1.2.3");
+        assert!(matches!(
+            config.channel_tag_name("nightly"),
+            Err(Error::UnknownChannel(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_empty_targets() {
+        let content = r#"
+version = "1.0.0"
+"#;
+        let result = Config::parse(content);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_channel_overlay() {
+        let content = r#"
+version = "1.2.3"
+
+[[targets]]
+file = "Cargo.toml"
+key = "package.version"
+
+[[targets]]
+file = "package.json"
+key = "version"
+
+[[targets]]
+file = "crates/a/Cargo.toml"
+key = "package.version"
+
+[[targets]]
+file = "crates/b/Cargo.toml"
+key = "package.version"
+
+[channels.nightly]
+version = "{version}-nightly.{major}"
+targets = ["package.json"]
+
+[channels.crates]
+targets = ["crates/*/Cargo.toml"]
+
+[channels.broken]
+targets = ["missing.toml"]
+"#;
+        let config = Config::parse(content).unwrap();
+        let nightly = config.for_channel("nightly").unwrap();
+        assert_eq!(nightly.version, "1.2.3-nightly.1");
+        assert_eq!(nightly.targets.len(), 1);
+        assert_eq!(nightly.targets[0].file, PathBuf::from("package.json"));
+        assert_eq!(nightly.tag_name(), "v1.2.3-nightly.1");
+        assert_eq!(config.version, "1.2.3");
+        let crates = config.for_channel("crates").unwrap();
+        assert_eq!(crates.targets.len(), 2);
+        assert_eq!(crates.targets[1].file, PathBuf::from("crates/b/Cargo.toml"));
+        assert!(matches!(
+            config.for_channel("broken"),
+            Err(Error::ConfigParse(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_merged() {
+        let base = r#"
+[[targets]]
+file = "Cargo.toml"
+key = "package.version"
+
+[git]
+tag_prefix = "release-"
+remote = "upstream"
+
+[policy]
+forbid_prerelease_on = ["main"]
+"#;
+        let repo = r#"
+version = "2.0.0"
+
+[[targets]]
+file = "package.json"
+key = "version"
+
+[[targets]]
+file = "Cargo.toml"
+key = "package.version"
+format = "toml"
+
+[git]
+tag_prefix = "v"
+"#;
+        let config = Config::parse_merged(&[base.to_string(), repo.to_string()]).unwrap();
+        assert_eq!(config.version, "2.0.0");
+        assert_eq!(config.git.tag_prefix, "v");
+        assert_eq!(config.git.remote, "upstream");
+        assert_eq!(config.policy.forbid_prerelease_on, vec!["main"]);
+        let files: Vec<_> = config.targets.iter().map(|t| t.file.clone()).collect();
+        assert_eq!(
+            files,
+            vec![PathBuf::from("Cargo.toml"), PathBuf::from("package.json")]
+        );
+        assert_eq!(config.targets[0].format, Some(FileFormat::Toml));
+    }
+
+    #[test]
+    fn test_discover() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        fs::create_dir_all(repo.join(".git")).unwrap();
+        fs::create_dir_all(repo.join("crates/a/src")).unwrap();
+        fs::write(dir.path().join(DEFAULT_CONFIG), "").unwrap();
+        assert_eq!(discover(&repo.join("crates/a/src")), None);
+
+        fs::write(repo.join(DEFAULT_CONFIG), "").unwrap();
+        assert_eq!(
+            discover(&repo.join("crates/a/src")),
+            Some(repo.join(DEFAULT_CONFIG))
+        );
+        fs::write(repo.join("crates/a").join(DEFAULT_CONFIG), "").unwrap();
+        assert_eq!(
+            discover(&repo.join("crates/a/src")),
+            Some(repo.join("crates/a").join(DEFAULT_CONFIG))
+        );
+    }
+
+    #[test]
+    fn test_discover_embedded() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join(".git/sub")).unwrap();
+        let pyproject = dir.path().join("pyproject.toml");
+        fs::write(&pyproject, "[project]\nname = \"app\"\n").unwrap();
+        assert_eq!(discover(dir.path()), None);
+
+        fs::write(&pyproject, "[tool.versync]\nversion = \"1.0.0\"\n").unwrap();
+        assert_eq!(discover(dir.path()), Some(pyproject));
+        fs::write(dir.path().join(DEFAULT_CONFIG), "").unwrap();
+        assert_eq!(discover(dir.path()), Some(dir.path().join(DEFAULT_CONFIG)));
+    }
+
+    #[test]
+    fn test_embedded_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pyproject.toml");
+        fs::write(
+            &path,
+            r#"[project]
+name = "app"
 version = "1.0.0"
+
+[tool.versync]
+version = "1.0.0"
+
+[[tool.versync.targets]]
+file = "pyproject.toml"
+key = "project.version"
+
+[tool.versync.git]
+tag_prefix = "release-"
+"#,
+        )
+        .unwrap();
+
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.version, "1.0.0");
+        assert_eq!(config.targets[0].key, "project.version");
+        assert_eq!(config.git.tag_prefix, "release-");
+        assert_eq!(
+            config.version_file(std::slice::from_ref(&path)).unwrap(),
+            path
+        );
+
+        config.write_version(&path, "1.1.0").unwrap();
+        let target = Target {
+            file: PathBuf::from("app/__init__.py"),
+            key: "__version__".to_string(),
+            template: None,
+            scheme: None,
+            group: None,
+            required: true,
+            create: false,
+            format: None,
+            extra: BTreeMap::new(),
+        };
+        Config::add_target(&path, &target).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("[project]\nname = \"app\"\nversion = \"1.0.0\"\n"));
+        assert!(content.contains("[[tool.versync.targets]]\nfile = \"app/__init__.py\""));
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.version, "1.1.0");
+        assert_eq!(config.targets.len(), 2);
+
+        fs::write(&path, "[project]\nname = \"app\"\n").unwrap();
+        assert!(matches!(
+            Config::load(&path),
+            Err(Error::ConfigParse(msg)) if msg.contains("[tool.versync]")
+        ));
+    }
+
+    #[test]
+    fn test_cargo_metadata_config() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join(".git")).unwrap();
+        let path = dir.path().join("Cargo.toml");
+        fs::write(
+            &path,
+            r#"[workspace]
+members = ["crates/*"]
+
+[workspace.package]
+version = "0.3.0"
+
+[workspace.metadata.versync]
+version = "0.3.0"
+
+[[workspace.metadata.versync.targets]]
+file = "Cargo.toml"
+key = "workspace.package.version"
+"#,
+        )
+        .unwrap();
+        assert_eq!(discover(dir.path()), Some(path.clone()));
+
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.targets[0].key, "workspace.package.version");
+        config.write_version(&path, "0.4.0").unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("[workspace.package]\nversion = \"0.3.0\"\n"));
+        assert_eq!(Config::load(&path).unwrap().version, "0.4.0");
+
+        fs::write(&path, "[package]\nname = \"app\"\nversion = \"0.3.0\"\n").unwrap();
+        assert_eq!(discover(dir.path()), None);
+        assert!(matches!(
+            Config::load(&path),
+            Err(Error::ConfigParse(msg))
+                if msg.contains("[package.metadata.versync] or [workspace.metadata.versync]")
+        ));
+    }
+
+    #[test]
+    fn test_package_json_config() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join(".git")).unwrap();
+        let path = dir.path().join("package.json");
+        let content = r#"{
+  "name": "app",
+  "version": "1.0.0",
+  "versync": {
+    "version": "1.0.0",
+    "targets": [{ "file": "package.json", "key": "version" }],
+    "git": { "tag_prefix": "release-" }
+  }
+}
 "#;
-        let result = Config::parse(content);
-        assert!(result.is_err());
+        fs::write(&path, content).unwrap();
+        assert_eq!(discover(dir.path()), Some(path.clone()));
+
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.version, "1.0.0");
+        assert_eq!(config.targets[0].key, "version");
+        assert_eq!(config.git.tag_prefix, "release-");
+
+        // Targets merge with those of other configs like [[targets]]
+        let extracted = read_config(&path).unwrap();
+        assert!(extracted.contains("[[targets]]\n"));
+        let overlay =
+            "[[targets]]\nfile = \"package.json\"\nkey = \"version\"\ntemplate = \"v{version}\"\n";
+        let merged = Config::parse_merged(&[extracted, overlay.to_string()]).unwrap();
+        assert_eq!(merged.targets.len(), 1);
+
+        config.write_version(&path, "1.1.0").unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            content.replace(
+                "\"version\": \"1.0.0\",\n    \"targets\"",
+                "\"version\": \"1.1.0\",\n    \"targets\""
+            )
+        );
+        let target = Config::load(&path).unwrap().targets[0].clone();
+        assert!(matches!(
+            Config::add_target(&path, &target),
+            Err(Error::Usage(_))
+        ));
+
+        fs::write(&path, r#"{"name": "app"}"#).unwrap();
+        assert_eq!(discover(dir.path()), None);
+        assert!(matches!(
+            Config::load(&path),
+            Err(Error::ConfigParse(msg)) if msg.contains("no \"versync\" object")
+        ));
+    }
+
+    #[test]
+    fn test_load_all_from_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("10-base.toml"),
+            "version = \"1.0.0\"\n[[targets]]\nfile = \"a.toml\"\nkey = \"version\"\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("20-repo.toml"), "version = \"1.1.0\"\n").unwrap();
+        fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+        let paths = [dir.path().to_path_buf()];
+        let config = Config::load_all(&paths).unwrap();
+        assert_eq!(config.version, "1.1.0");
+        assert_eq!(
+            config.version_file(&paths).unwrap(),
+            dir.path().join("20-repo.toml")
+        );
+    }
+
+    #[test]
+    fn test_exit_code_overrides() {
+        let content = r#"
+version = "1.0.0"
+
+[[targets]]
+file = "Cargo.toml"
+key = "package.version"
+
+[exit_codes]
+network = 0
+tag_exists = 10
+"#;
+        let config = Config::parse(content).unwrap();
+        assert_eq!(config.exit_code(FailureClass::Network), 0);
+        assert_eq!(
+            config.exit_code_for(&Error::TagExists("v1.0.0".to_string())),
+            10
+        );
+        assert_eq!(config.exit_code(FailureClass::Mismatch), 1);
+        assert!(Config::parse(&content.replace("network", "unknown")).is_err());
+    }
+
+    #[test]
+    fn test_validate_collects_all_issues() {
+        let dir = tempfile::tempdir().unwrap();
+        let good = dir.path().join("good.toml");
+        fs::write(&good, "[package]\nversion = \"1.0.0\"\n").unwrap();
+        let content = format!(
+            r#"
+version = "1.0"
+
+[[targets]]
+file = "{good}"
+key = "package.version"
+
+[[targets]]
+file = "{good}"
+key = "package.version"
+
+[[targets]]
+file = "{good}"
+key = "package.name"
+
+[[targets]]
+file = "{missing}"
+key = "releases[x].version"
+
+[git]
+tag_prefix = "release "
+"#,
+            good = good.display(),
+            missing = dir.path().join("missing.json").display()
+        );
+
+        let issues = Config::parse(&content).unwrap().validate();
+        let locations: Vec<&str> = issues.iter().map(|i| i.location.as_str()).collect();
+        assert_eq!(
+            locations,
+            [
+                "version",
+                "targets[1]",
+                "targets[2]",
+                "targets[3]",
+                "git.tag_prefix"
+            ]
+        );
+        assert!(issues.iter().all(|i| i.severity == Severity::Error));
+        assert!(issues[1].message.starts_with("duplicate of targets[0]"));
+        assert!(issues[3].message.starts_with("Invalid key path"));
+
+        let valid = content
+            .replace("version = \"1.0\"", "version = \"1.0.0\"")
+            .replace("release ", "release-");
+        let config = Config::parse(&valid).unwrap();
+        assert_eq!(config.validate().len(), 3);
+    }
+
+    #[test]
+    fn test_unknown_keys() {
+        let contents = [
+            "version = \"1.0.0\"\nverison = \"1\"\n[[targets]]\nfile = \"a.toml\"\nkey = \"v\"\nfomat = \"toml\"\n[targets.extra]\nanything = \"{date}\"\n".to_string(),
+            "[git]\ntag_prefx = \"v\"\n".to_string(),
+        ];
+        assert_eq!(
+            unknown_keys(&contents).unwrap(),
+            ["verison", "targets.0.fomat", "git.tag_prefx"]
+        );
+    }
+
+    #[test]
+    fn test_check_ref_name() {
+        assert!(check_ref_name("v", true).is_ok());
+        assert!(check_ref_name("", true).is_ok());
+        assert!(check_ref_name("release/", true).is_ok());
+        assert!(check_ref_name("v1.2.3", false).is_ok());
+        assert!(check_ref_name("", false).is_err());
+        assert!(check_ref_name("release/", false).is_err());
+        assert!(check_ref_name("v 1", true).is_err());
+        assert!(check_ref_name("v~", true).is_err());
+        assert!(check_ref_name("-v", true).is_err());
+        assert!(check_ref_name("v..1", false).is_err());
+        assert!(check_ref_name("v1.lock", false).is_err());
+    }
+
+    #[test]
+    fn test_add_target_preserves_formatting() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("version.toml");
+        let original = "# Source of truth\nversion = \"1.0.0\"\n\n[[targets]]\nfile = \"a.toml\" # main\nkey = \"version\"\n\n[git]\ntag_prefix = \"v\"\n";
+        fs::write(&path, original).unwrap();
+
+        let target = Target {
+            file: PathBuf::from("VERSION.txt"),
+            key: "version".to_string(),
+            template: None,
+            scheme: None,
+            group: None,
+            required: true,
+            create: false,
+            format: Some(FileFormat::Json),
+            extra: BTreeMap::new(),
+        };
+        Config::add_target(&path, &target).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        // The new entry is grouped with the existing targets
+        assert_eq!(
+            content,
+            original.replace(
+                "\n[git]",
+                "\n[[targets]]\nfile = \"VERSION.txt\"\nkey = \"version\"\nformat = \"json\"\n\n[git]"
+            )
+        );
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.targets.len(), 2);
+        assert_eq!(config.targets[1].effective_format(), Some(FileFormat::Json));
+    }
+
+    #[test]
+    fn test_components() {
+        let content = r#"
+version = "1.0.0"
+
+[[targets]]
+file = "Cargo.toml"
+key = "package.version"
+
+[components.api]
+version = "2.1.0"
+
+[[components.api.targets]]
+file = "api/package.json"
+key = "version"
+
+[components.cli]
+version = "0.3.0"
+tag_prefix = "cli/v"
+
+[[components.cli.targets]]
+file = "cli/Cargo.toml"
+key = "package.version"
+"#;
+        let config = Config::parse(content).unwrap();
+        let api = config.for_component("api").unwrap();
+        assert_eq!(api.version, "2.1.0");
+        assert_eq!(api.targets[0].file, PathBuf::from("api/package.json"));
+        assert_eq!(api.tag_name(), "api-v2.1.0");
+        assert_eq!(api.version_key(), "components.\"api\".version");
+        assert_eq!(
+            config.for_component("cli").unwrap().tag_name(),
+            "cli/v0.3.0"
+        );
+        assert_eq!(config.tag_name(), "v1.0.0");
+        assert!(matches!(
+            config.for_component("web"),
+            Err(Error::UnknownComponent(_))
+        ));
+
+        let versions: Vec<_> = config
+            .each_component()
+            .into_iter()
+            .map(|c| c.version)
+            .collect();
+        assert_eq!(versions, ["1.0.0", "2.1.0", "0.3.0"]);
+
+        // Without top-level targets, the version is optional
+        let content = content.replace("version = \"1.0.0\"\n", "");
+        let content = content.replace(
+            "[[targets]]\nfile = \"Cargo.toml\"\nkey = \"package.version\"\n",
+            "",
+        );
+        let config = Config::parse(&content).unwrap();
+        assert_eq!(config.each_component().len(), 2);
+        assert!(Config::parse("[components.api]\nversion = \"1.0.0\"\n").is_err());
+    }
+
+    #[test]
+    fn test_override_version() {
+        let mut config = Config::parse(
+            "version = \"1.0.0\"\n[[targets]]\nfile = \"a.toml\"\nkey = \"version\"\n",
+        )
+        .unwrap();
+        config.override_version("1.1.0-rc.1").unwrap();
+        assert_eq!(config.version, "1.1.0-rc.1");
+        config.override_version("refs/tags/v1.2.0").unwrap();
+        assert_eq!(config.version, "1.2.0");
+        assert!(config.override_version("main").is_err());
+        assert_eq!(config.version, "1.2.0");
+    }
+
+    #[test]
+    fn test_git_source() {
+        let config = Config::parse(
+            "source = \"git\"\n\n[[targets]]\nfile = \"a.toml\"\nkey = \"version\"\n",
+        )
+        .unwrap();
+        assert!(matches!(config.source, Source::Git));
+        assert!(config.version.is_empty());
+        assert!(matches!(
+            config.version_file(&[PathBuf::from("version.toml")]),
+            Err(Error::Usage(_))
+        ));
+        assert!(Config::parse(
+            "source = \"git\"\nversion = \"1.0.0\"\n[[targets]]\nfile = \"a.toml\"\nkey = \"version\"\n"
+        )
+        .is_err());
+
+        assert_eq!(
+            described_version("v", Scheme::Semver, "v1.2.3-0-gabc1234").unwrap(),
+            "1.2.3"
+        );
+        assert_eq!(
+            described_version("v", Scheme::Semver, "v1.2.3-rc.1-4-gabc1234").unwrap(),
+            "1.2.3-rc.1+4.gabc1234"
+        );
+        assert_eq!(
+            described_version("release-", Scheme::Semver, "release-2.0.0+b1-12-g0123abc").unwrap(),
+            "2.0.0+b1.12.g0123abc"
+        );
+        assert!(described_version("v", Scheme::Semver, "vnext-1-gabc1234").is_err());
+        assert_eq!(
+            described_version("v", Scheme::Pep440, "v1.2.3rc1-4-gabc1234").unwrap(),
+            "1.2.3-rc.1+4.gabc1234"
+        );
+    }
+
+    #[test]
+    fn test_file_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = dir.path().join("pyproject.toml");
+        fs::write(
+            &manifest,
+            "[project]\nname = \"demo\"\nversion = \"1.4.0rc2\"\n",
+        )
+        .unwrap();
+        let path = dir.path().join("version.toml");
+        fs::write(
+            &path,
+            format!(
+                "[source]\nfile = {:?}\nkey = \"project.version\"\nscheme = \"pep440\"\n\n[[targets]]\nfile = \"package.json\"\nkey = \"version\"\n",
+                manifest.display().to_string()
+            ),
+        )
+        .unwrap();
+
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.version, "1.4.0-rc.2");
+        assert_eq!(
+            config.version_file(std::slice::from_ref(&path)).unwrap(),
+            manifest
+        );
+        config.write_version(&manifest, "1.4.0").unwrap();
+        assert!(fs::read_to_string(&manifest)
+            .unwrap()
+            .contains("version = \"1.4.0\"\n"));
+        assert_eq!(Config::load(&path).unwrap().version, "1.4.0");
+
+        assert!(
+            Config::parse("source = \"svn\"\n[[targets]]\nfile = \"a.toml\"\nkey = \"v\"\n")
+                .is_err()
+        );
+        assert!(Config::parse(
+            "[source]\nfile = \"Cargo.toml\"\n[[targets]]\nfile = \"a.toml\"\nkey = \"v\"\n"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_write_component_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("version.toml");
+        fs::write(
+            &path,
+            "version = \"1.0.0\"\n\n[[targets]]\nfile = \"a.toml\"\nkey = \"version\"\n\n[components.api]\nversion = \"2.0.0\" # api\n\n[[components.api.targets]]\nfile = \"b.toml\"\nkey = \"version\"\n",
+        )
+        .unwrap();
+
+        let paths = [path.clone()];
+        let api = Config::load(&path).unwrap().for_component("api").unwrap();
+        assert_eq!(api.version_file(&paths).unwrap(), path);
+        api.write_version(&path, "2.1.0").unwrap();
+
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.version, "1.0.0");
+        assert_eq!(config.components["api"].version, "2.1.0");
+        assert!(fs::read_to_string(&path)
+            .unwrap()
+            .contains("version = \"2.1.0\" # api\n"));
+    }
+
+    #[test]
+    fn test_config_scheme() {
+        let content = r#"
+version = "1.2.0-RC1"
+scheme = "pep440"
+
+[[targets]]
+file = "pyproject.toml"
+key = "project.version"
+
+[[targets]]
+file = "package.json"
+key = "version"
+scheme = "semver"
+"#;
+        let config = Config::parse(content).unwrap();
+        assert_eq!(config.version, "1.2.0-rc.1");
+        let context = Context::new(&config.version);
+        let [python, node] = &config.targets[..] else {
+            panic!("expected two targets");
+        };
+        assert_eq!(python.expected_value(&context).unwrap(), "1.2.0rc1");
+        assert_eq!(node.expected_value(&context).unwrap(), "1.2.0-rc.1");
+        assert_eq!(
+            config.version_in_scheme(&config.version).unwrap(),
+            "1.2.0rc1"
+        );
+        assert!(config.version_in_scheme("1.3.0-nightly.1").is_err());
+        assert_eq!(config.tag_name(), "v1.2.0rc1");
+
+        let mut config = config;
+        config.override_version("refs/tags/v1.3.0.post2").unwrap();
+        assert_eq!(config.version, "1.3.0+post.2");
+
+        let error = Config::parse(&content.replace("1.2.0-RC1", "1.2.x"))
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.contains("version: Invalid version '1.2.x'"),
+            "{}",
+            error
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("version.toml");
+        fs::write(&path, content).unwrap();
+        let config = Config::load(&path).unwrap();
+        config.write_version(&path, "1.2.0-rc.2").unwrap();
+        assert!(fs::read_to_string(&path)
+            .unwrap()
+            .contains("version = \"1.2.0rc2\""));
+
+        // The source is read in the scheme too
+        let manifest = dir.path().join("pyproject.toml");
+        fs::write(&manifest, "[project]\nversion = \"1.2.0rc1\"\n").unwrap();
+        let source = format!(
+            "source = {{ file = {:?}, key = \"project.version\" }}",
+            manifest.display().to_string()
+        );
+        fs::write(&path, content.replace("version = \"1.2.0-RC1\"", &source)).unwrap();
+        assert_eq!(Config::load(&path).unwrap().version, "1.2.0-rc.1");
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(
+            normalize(Path::new("crates/foo/../../Cargo.toml")),
+            Path::new("Cargo.toml")
+        );
+        assert_eq!(
+            normalize(Path::new("./crates/./foo/Cargo.toml")),
+            Path::new("crates/foo/Cargo.toml")
+        );
+        assert_eq!(
+            normalize(Path::new("crates/../../Cargo.toml")),
+            Path::new("../Cargo.toml")
+        );
+    }
+
+    #[test]
+    fn test_write_version_preserves_comments() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("version.toml");
+        fs::write(
+            &path,
+            "# Source of truth\nversion = \"1.0.0\" # bumped by CI\n\n[[targets]]\nfile = \"a.toml\"\nkey = \"version\"\n",
+        )
+        .unwrap();
+
+        let config = Config::load(&path).unwrap();
+        config.write_version(&path, "1.1.0").unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("# Source of truth\nversion = \"1.1.0\" # bumped by CI\n"));
+        assert_eq!(Config::load(&path).unwrap().version, "1.1.0");
     }
 
     #[test]
@@ -169,6 +3086,107 @@ version = "1.0.0"
             FileFormat::from_path(Path::new("package.json")),
             Some(FileFormat::Json)
         );
-        assert_eq!(FileFormat::from_path(Path::new("README.md")), None);
+        assert_eq!(
+            FileFormat::from_path(Path::new("src/App/App.csproj")),
+            Some(FileFormat::Xml)
+        );
+        assert_eq!(
+            FileFormat::from_path(Path::new("docker/Dockerfile.release")),
+            Some(FileFormat::Dockerfile)
+        );
+        assert_eq!(
+            FileFormat::from_path(Path::new("README.md")),
+            Some(FileFormat::Markdown)
+        );
+        assert_eq!(
+            FileFormat::from_path(Path::new("Cargo.lock")),
+            Some(FileFormat::CargoLock)
+        );
+        assert_eq!(
+            FileFormat::from_path(Path::new("deploy/.env.production")),
+            Some(FileFormat::Dotenv)
+        );
+        assert_eq!(
+            FileFormat::from_path(Path::new("GNUmakefile")),
+            Some(FileFormat::Makefile)
+        );
+        assert_eq!(
+            FileFormat::from_path(Path::new("debian/changelog")),
+            Some(FileFormat::DebianChangelog)
+        );
+        assert_eq!(
+            FileFormat::from_path(Path::new("data/org.example.App.metainfo.xml")),
+            Some(FileFormat::Appstream)
+        );
+        assert_eq!(
+            FileFormat::from_path(Path::new("VERSION")),
+            Some(FileFormat::Raw)
+        );
+        assert_eq!(
+            FileFormat::from_path(Path::new("api/openapi.yaml")),
+            Some(FileFormat::Openapi)
+        );
+        assert_eq!(
+            FileFormat::from_path(Path::new("events.asyncapi.json")),
+            Some(FileFormat::Openapi)
+        );
+        assert_eq!(
+            FileFormat::from_path(Path::new("docker-compose.prod.yml")),
+            Some(FileFormat::Image)
+        );
+        assert_eq!(
+            FileFormat::from_path(Path::new("apps/api/mix.exs")),
+            Some(FileFormat::Mix)
+        );
+        assert_eq!(
+            FileFormat::from_path(Path::new("app/build.gradle.kts")),
+            Some(FileFormat::Gradle)
+        );
+        assert_eq!(
+            FileFormat::from_path(Path::new("installer/Product.wxs")),
+            Some(FileFormat::Wix)
+        );
+        assert_eq!(
+            FileFormat::from_path(Path::new("charts/app/Chart.yaml")),
+            Some(FileFormat::Yaml)
+        );
+        assert_eq!(
+            FileFormat::from_path(Path::new("docker-compose.yml")),
+            Some(FileFormat::Image)
+        );
+        assert_eq!(FileFormat::from_path(Path::new("LICENSE")), None);
+    }
+
+    #[test]
+    fn test_format_sniffing() {
+        assert_eq!(
+            FileFormat::sniff("{\n  \"version\": \"1.2.3\"\n}\n"),
+            Some(FileFormat::Json)
+        );
+        assert_eq!(
+            FileFormat::sniff("{\n  // comment\n  \"version\": \"1.2.3\",\n}\n"),
+            Some(FileFormat::Jsonc)
+        );
+        let pipfile = "# Pipfile\n[[source]]\nurl = \"https://pypi.org/simple\"\n\n[requires]\npython_version = \"3.12\"\n";
+        assert_eq!(FileFormat::sniff(pipfile), Some(FileFormat::Toml));
+        assert_eq!(
+            FileFormat::sniff("version = \"1.2.3\"\n"),
+            Some(FileFormat::Toml)
+        );
+        assert_eq!(
+            FileFormat::sniff("---\nversion: 1.2.3\n"),
+            Some(FileFormat::Yaml)
+        );
+        assert_eq!(FileFormat::sniff("[section]\nkey = some value\n"), None);
+        assert_eq!(FileFormat::sniff("1.2.3\n"), None);
+        assert_eq!(FileFormat::sniff(""), None);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Pipfile");
+        fs::write(&path, pipfile).unwrap();
+        assert_eq!(FileFormat::detect(&path), Some(FileFormat::Toml));
+        let path = dir.path().join("notes.txt");
+        fs::write(&path, pipfile).unwrap();
+        assert_eq!(FileFormat::detect(&path), None);
     }
 }
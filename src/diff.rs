@@ -0,0 +1,54 @@
+use similar::TextDiff;
+
+/// Produce a git-applyable unified diff between two versions of a file
+///
+/// `path` is used for the `a/` and `b/` headers. Returns an empty string when
+/// the contents are identical.
+pub fn unified_diff(path: &str, old: &str, new: &str) -> String {
+    if old == new {
+        return String::new();
+    }
+
+    let path = path.replace('\\', "/");
+    let body = TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("a/{}", path), &format!("b/{}", path))
+        .to_string();
+
+    format!("diff --git a/{0} b/{0}\n{1}", path, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identical_content() {
+        assert_eq!(unified_diff("a.toml", "x\n", "x\n"), "");
+    }
+
+    #[test]
+    fn test_single_line_change() {
+        let old = "[package]\nname = \"x\"\nversion = \"1.0.0\"\n";
+        let new = "[package]\nname = \"x\"\nversion = \"1.1.0\"\n";
+
+        assert_eq!(
+            unified_diff("Cargo.toml", old, new),
+            "diff --git a/Cargo.toml b/Cargo.toml\n\
+             --- a/Cargo.toml\n\
+             +++ b/Cargo.toml\n\
+             @@ -1,3 +1,3 @@\n \
+             [package]\n \
+             name = \"x\"\n\
+             -version = \"1.0.0\"\n\
+             +version = \"1.1.0\"\n"
+        );
+    }
+
+    #[test]
+    fn test_missing_trailing_newline() {
+        let diff = unified_diff("VERSION", "1.0.0", "1.1.0");
+        assert!(diff.contains("\\ No newline at end of file"));
+    }
+}
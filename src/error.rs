@@ -1,3 +1,4 @@
+use serde::Deserialize;
 use std::path::PathBuf;
 use thiserror::Error;
 
@@ -5,10 +6,82 @@ use thiserror::Error;
 pub mod exit_code {
     /// All versions match (check) or operation succeeded (apply/tag)
     pub const SUCCESS: i32 = 0;
-    /// Version mismatch detected (check only)
+    /// Version mismatch detected
     pub const MISMATCH: i32 = 1;
-    /// Execution error (config error, parse failure, etc.)
+    /// Any other execution error
     pub const ERROR: i32 = 2;
+    /// Config file missing or invalid
+    pub const CONFIG: i32 = 3;
+    /// A target file is missing or cannot be parsed
+    pub const PARSE: i32 = 4;
+    /// Git failed or a git precondition (repository, clean tree, branch) is not met
+    pub const GIT: i32 = 5;
+    /// The tag already exists (locally or on the remote)
+    pub const TAG_EXISTS: i32 = 6;
+    /// A network operation failed or timed out
+    pub const NETWORK: i32 = 7;
+    /// A `[policy]` rule is violated
+    pub const POLICY: i32 = 8;
+    /// `compare`: the first version is lower than the second
+    pub const LESS: i32 = 10;
+    /// `compare`: the first version is higher than the second
+    pub const GREATER: i32 = 11;
+}
+
+/// Class of failure, each with its own exit code (remappable in `[exit_codes]`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureClass {
+    Mismatch,
+    Error,
+    Config,
+    Parse,
+    Git,
+    TagExists,
+    Network,
+    Policy,
+}
+
+impl FailureClass {
+    /// All classes, in the order they are documented
+    pub const ALL: [FailureClass; 8] = [
+        FailureClass::Mismatch,
+        FailureClass::Error,
+        FailureClass::Config,
+        FailureClass::Parse,
+        FailureClass::Git,
+        FailureClass::TagExists,
+        FailureClass::Network,
+        FailureClass::Policy,
+    ];
+
+    /// Name of the class as used in `[exit_codes]`
+    pub fn name(self) -> &'static str {
+        match self {
+            FailureClass::Mismatch => "mismatch",
+            FailureClass::Error => "error",
+            FailureClass::Config => "config",
+            FailureClass::Parse => "parse",
+            FailureClass::Git => "git",
+            FailureClass::TagExists => "tag_exists",
+            FailureClass::Network => "network",
+            FailureClass::Policy => "policy",
+        }
+    }
+
+    /// The exit code used unless remapped
+    pub fn default_code(self) -> i32 {
+        match self {
+            FailureClass::Mismatch => exit_code::MISMATCH,
+            FailureClass::Error => exit_code::ERROR,
+            FailureClass::Config => exit_code::CONFIG,
+            FailureClass::Parse => exit_code::PARSE,
+            FailureClass::Git => exit_code::GIT,
+            FailureClass::TagExists => exit_code::TAG_EXISTS,
+            FailureClass::Network => exit_code::NETWORK,
+            FailureClass::Policy => exit_code::POLICY,
+        }
+    }
 }
 
 #[derive(Error, Debug)]
@@ -16,6 +89,12 @@ pub enum Error {
     #[error("Config file not found: {0}")]
     ConfigNotFound(PathBuf),
 
+    #[error("Config file already exists: {0} (pass --force to overwrite)")]
+    ConfigExists(PathBuf),
+
+    #[error("No supported manifests found (looked for {0})")]
+    NoManifestsFound(String),
+
     #[error("Failed to read config file: {0}")]
     ConfigRead(#[from] std::io::Error),
 
@@ -34,9 +113,22 @@ pub enum Error {
     #[error("Value at key '{key}' in '{file}' is not a string")]
     ValueNotString { file: PathBuf, key: String },
 
+    #[error("Key '{key}' matches {count} places in '{file}'; it must match exactly one")]
+    AmbiguousKey {
+        file: PathBuf,
+        key: String,
+        count: usize,
+    },
+
+    #[error("Invalid key path '{key}': {message}")]
+    InvalidKey { key: String, message: String },
+
     #[error("Unknown file format for: {0}")]
     UnknownFormat(PathBuf),
 
+    #[error("Format '{0}' is not registered")]
+    UnregisteredFormat(String),
+
     #[error("Git command failed: {0}")]
     GitCommand(String),
 
@@ -52,8 +144,142 @@ pub enum Error {
     #[error("Tag already exists: {0}")]
     TagExists(String),
 
+    #[error("Tag not found: {0}")]
+    TagNotFound(String),
+
+    #[error("Tag {0} does not point at HEAD")]
+    TagNotAtHead(String),
+
+    #[error("Tag template {0} numbers its tags with {{n}}, and HEAD carries none of them")]
+    UntaggedCounter(String),
+
+    #[error("Network operation '{operation}' timed out after {seconds}s")]
+    NetworkTimeout { operation: String, seconds: u64 },
+
+    #[error("Network operation '{operation}' failed: {message}")]
+    Network { operation: String, message: String },
+
+    #[error("Tag {tag} already exists on remote '{remote}'")]
+    RemoteTagExists { remote: String, tag: String },
+
+    #[error("Release policy violated: {0}")]
+    PolicyViolation(String),
+
+    #[error("Self-update failed: {0}")]
+    SelfUpdate(String),
+
+    #[error("Unknown channel: {0}")]
+    UnknownChannel(String),
+
+    #[error("Unknown component: {0}")]
+    UnknownComponent(String),
+
+    #[error("Unknown prerelease label '{label}' (expected one of: {allowed})")]
+    UnknownPrereleaseLabel { label: String, allowed: String },
+
     #[error("Version mismatch detected, run 'versync check' for details")]
     VersionMismatch,
+
+    #[error("Invalid version '{version}': {reason}")]
+    InvalidVersion { version: String, reason: String },
+
+    #[error("Invalid version range '{range}': {reason}")]
+    InvalidRange { range: String, reason: String },
+
+    #[error("Branch '{branch}' is not consistent with version {version}")]
+    BranchMismatch { branch: String, version: String },
+
+    #[error("Confirmation required: {0} (pass --yes to proceed)")]
+    ConfirmationRequired(String),
+
+    #[error("Interactive input required: {0}")]
+    NotInteractive(String),
+
+    #[error("{0}")]
+    Usage(String),
+
+    #[error("Hook already exists: {0} (pass --force to replace it)")]
+    HookExists(PathBuf),
+
+    #[error("Watch failed: {0}")]
+    Watch(String),
+
+    #[error("Aborted by user")]
+    Aborted,
+
+    #[error("Terminal error: {0}")]
+    Terminal(String),
+}
+
+impl Error {
+    /// The class of failure this error belongs to
+    pub fn class(&self) -> FailureClass {
+        match self {
+            Error::ConfigNotFound(_)
+            | Error::ConfigExists(_)
+            | Error::NoManifestsFound(_)
+            | Error::ConfigParse(_)
+            | Error::InvalidKey { .. }
+            | Error::UnregisteredFormat(_)
+            | Error::UnknownChannel(_)
+            | Error::UnknownComponent(_) => FailureClass::Config,
+            Error::TargetNotFound(_)
+            | Error::TargetParse { .. }
+            | Error::KeyNotFound { .. }
+            | Error::ValueNotString { .. }
+            | Error::AmbiguousKey { .. }
+            | Error::UnknownFormat(_)
+            | Error::InvalidVersion { .. }
+            | Error::InvalidRange { .. }
+            | Error::UnknownPrereleaseLabel { .. } => FailureClass::Parse,
+            Error::GitCommand(_)
+            | Error::NotGitRepository
+            | Error::DirtyWorkingTree
+            | Error::DirtyIndex
+            | Error::TagNotFound(_)
+            | Error::TagNotAtHead(_)
+            | Error::UntaggedCounter(_)
+            | Error::BranchMismatch { .. } => FailureClass::Git,
+            Error::TagExists(_) | Error::RemoteTagExists { .. } => FailureClass::TagExists,
+            Error::Network { .. } | Error::NetworkTimeout { .. } => FailureClass::Network,
+            Error::PolicyViolation(_) => FailureClass::Policy,
+            Error::VersionMismatch => FailureClass::Mismatch,
+            Error::ConfigRead(_)
+            | Error::SelfUpdate(_)
+            | Error::ConfirmationRequired(_)
+            | Error::NotInteractive(_)
+            | Error::Usage(_)
+            | Error::Watch(_)
+            | Error::HookExists(_)
+            | Error::Aborted
+            | Error::Terminal(_) => FailureClass::Error,
+        }
+    }
+
+    /// The default exit code for this error
+    pub fn exit_code(&self) -> i32 {
+        self.class().default_code()
+    }
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_codes_by_class() {
+        assert_eq!(
+            Error::ConfigNotFound(PathBuf::from("version.toml")).exit_code(),
+            exit_code::CONFIG
+        );
+        assert_eq!(Error::DirtyIndex.exit_code(), exit_code::GIT);
+        assert_eq!(
+            Error::TagExists("v1.0.0".to_string()).exit_code(),
+            exit_code::TAG_EXISTS
+        );
+        assert_eq!(Error::VersionMismatch.exit_code(), exit_code::MISMATCH);
+        assert_eq!(Error::Aborted.exit_code(), exit_code::ERROR);
+    }
+}
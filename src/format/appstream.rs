@@ -0,0 +1,287 @@
+use crate::error::{Error, Result};
+use crate::format::pattern::splice;
+use quick_xml::escape::{escape, unescape};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::fs;
+use std::path::Path;
+
+/// Read the version of the latest release from an AppStream metainfo file
+pub fn read_version(path: &Path, key: &str) -> Result<String> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    parse_version(&content, path, key)
+}
+
+/// Add a release for the version to an AppStream metainfo file
+/// Existing releases and the rest of the document are kept exactly
+pub fn write_version(path: &Path, key: &str, version: &str) -> Result<()> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    let updated = update_version(&content, path, key, version)?;
+
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Read the version of the latest release from AppStream metainfo content
+/// (`path` is used for error messages)
+///
+/// The key is the component ID (`<id>`), which the file must declare. The
+/// version is the `version` attribute of the first `<release>` in
+/// `<releases>`, as AppStream lists releases newest first; it is empty if
+/// there are no releases yet.
+pub fn parse_version(content: &str, path: &Path, key: &str) -> Result<String> {
+    Ok(scan(content, path, key)?.version.unwrap_or_default())
+}
+
+/// Return metainfo content with a release for the version on top
+///
+/// Nothing changes if the latest release already has the version. The new
+/// `<release version="..." date="..."/>` is dated today and indented like
+/// the release below it.
+pub fn update_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+    add_release(content, path, key, version, &date)
+}
+
+/// The `<releases>` element of a metainfo file
+struct Releases {
+    /// Version of the first release, if there is one
+    version: Option<String>,
+    /// Where a new release goes: before the first release, or before
+    /// `</releases>` if there is none
+    insert_at: usize,
+    /// Span of a self-closing `<releases/>`
+    empty: Option<std::ops::Range<usize>>,
+}
+
+/// Add a release on top unless the latest release already has the version
+fn add_release(content: &str, path: &Path, key: &str, version: &str, date: &str) -> Result<String> {
+    let releases = scan(content, path, key)?;
+    if releases.version.as_deref() == Some(version) {
+        return Ok(content.to_string());
+    }
+
+    let release = format!(
+        "<release version=\"{}\" date=\"{}\"/>",
+        escape(version),
+        date
+    );
+    let line_start = content[..releases.insert_at]
+        .rfind('\n')
+        .map_or(0, |i| i + 1);
+    let indent: String = content[line_start..]
+        .chars()
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .collect();
+
+    Ok(match (releases.empty, releases.version) {
+        (Some(span), _) => splice(
+            content,
+            span,
+            &format!("<releases>\n{0}  {1}\n{0}</releases>", indent, release),
+        ),
+        (None, Some(_)) => splice(
+            content,
+            releases.insert_at..releases.insert_at,
+            &format!("{}\n{}", release, indent),
+        ),
+        (None, None) => splice(
+            content,
+            releases.insert_at..releases.insert_at,
+            &format!("  {}\n{}", release, indent),
+        ),
+    })
+}
+
+/// Find the component ID and the releases
+fn scan(content: &str, path: &Path, key: &str) -> Result<Releases> {
+    let parse_error = |message: String| Error::TargetParse {
+        file: path.to_path_buf(),
+        message,
+    };
+    let not_found = || Error::KeyNotFound {
+        file: path.to_path_buf(),
+        key: key.to_string(),
+    };
+
+    let mut reader = Reader::from_str(content);
+    let mut stack: Vec<String> = Vec::new();
+    let mut id: Option<String> = None;
+    let mut releases: Option<Releases> = None;
+
+    loop {
+        let before = reader.buffer_position() as usize;
+        let event = reader
+            .read_event()
+            .map_err(|e| parse_error(e.to_string()))?;
+        match event {
+            Event::Start(e) => {
+                let name = local_name(&e);
+                let in_component = stack.len() == 1 && stack[0] == "component";
+                if in_component && name == "id" {
+                    let text = reader
+                        .read_text(e.name())
+                        .map_err(|e| parse_error(e.to_string()))?;
+                    id = Some(text.trim().to_string());
+                    continue;
+                }
+                if in_component && name == "releases" && releases.is_none() {
+                    releases = Some(Releases {
+                        version: None,
+                        insert_at: 0,
+                        empty: None,
+                    });
+                }
+                if name == "release" && is_releases(&stack) {
+                    first_release(&mut releases, &e, before, path)?;
+                }
+                stack.push(name);
+            }
+            Event::Empty(e) => {
+                let name = local_name(&e);
+                let in_component = stack.len() == 1 && stack[0] == "component";
+                if in_component && name == "releases" && releases.is_none() {
+                    releases = Some(Releases {
+                        version: None,
+                        insert_at: before,
+                        empty: Some(before..reader.buffer_position() as usize),
+                    });
+                }
+                if name == "release" && is_releases(&stack) {
+                    first_release(&mut releases, &e, before, path)?;
+                }
+            }
+            Event::End(_) => {
+                if is_releases(&stack) {
+                    if let Some(releases) = releases.as_mut().filter(|r| r.version.is_none()) {
+                        releases.insert_at = before;
+                    }
+                }
+                stack.pop();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if id.as_deref() != Some(key) {
+        return Err(not_found());
+    }
+    releases.ok_or_else(|| parse_error("no <releases> element in the component".to_string()))
+}
+
+/// Record the first `<release>` in `<releases>`
+fn first_release(
+    releases: &mut Option<Releases>,
+    element: &BytesStart,
+    before: usize,
+    path: &Path,
+) -> Result<()> {
+    let Some(releases) = releases.as_mut().filter(|r| r.version.is_none()) else {
+        return Ok(());
+    };
+    let parse_error = |message: String| Error::TargetParse {
+        file: path.to_path_buf(),
+        message,
+    };
+    let version = match element
+        .try_get_attribute("version")
+        .map_err(|e| parse_error(e.to_string()))?
+    {
+        Some(attribute) => unescape(&attribute.value)
+            .map_err(|e| parse_error(e.to_string()))?
+            .into_owned(),
+        None => String::new(),
+    };
+    releases.version = Some(version);
+    releases.insert_at = before;
+    Ok(())
+}
+
+fn local_name(element: &BytesStart) -> String {
+    element.local_name().as_ref().to_string()
+}
+
+/// Whether the innermost open element is the component's `<releases>`
+fn is_releases(stack: &[String]) -> bool {
+    stack.len() == 2 && stack[0] == "component" && stack[1] == "releases"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const METAINFO: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<component type="desktop-application">
+  <id>org.example.App</id>
+  <name>Example</name>
+  <releases>
+    <release version="1.2.3" date="2024-01-15">
+      <description><p>Bug fixes.</p></description>
+    </release>
+    <release version="1.2.2" date="2024-01-01"/>
+  </releases>
+</component>
+"#;
+
+    #[test]
+    fn test_read_latest_release() {
+        let path = Path::new("org.example.App.metainfo.xml");
+        assert_eq!(
+            parse_version(METAINFO, path, "org.example.App").unwrap(),
+            "1.2.3"
+        );
+    }
+
+    #[test]
+    fn test_add_release() {
+        let path = Path::new("org.example.App.metainfo.xml");
+        let updated =
+            add_release(METAINFO, path, "org.example.App", "1.3.0", "2024-02-01").unwrap();
+        assert_eq!(
+            updated,
+            METAINFO.replace(
+                "    <release version=\"1.2.3\"",
+                "    <release version=\"1.3.0\" date=\"2024-02-01\"/>\n    <release version=\"1.2.3\""
+            )
+        );
+        assert_eq!(
+            add_release(METAINFO, path, "org.example.App", "1.2.3", "2024-02-01").unwrap(),
+            METAINFO
+        );
+    }
+
+    #[test]
+    fn test_no_releases_yet() {
+        let path = Path::new("app.metainfo.xml");
+        let content = "<component>\n  <id>app</id>\n  <releases>\n  </releases>\n</component>\n";
+        assert_eq!(parse_version(content, path, "app").unwrap(), "");
+        assert_eq!(
+            add_release(content, path, "app", "1.0.0", "2024-02-01").unwrap(),
+            "<component>\n  <id>app</id>\n  <releases>\n    <release version=\"1.0.0\" date=\"2024-02-01\"/>\n  </releases>\n</component>\n"
+        );
+
+        let content = "<component>\n  <id>app</id>\n  <releases/>\n</component>\n";
+        assert_eq!(
+            add_release(content, path, "app", "1.0.0", "2024-02-01").unwrap(),
+            "<component>\n  <id>app</id>\n  <releases>\n    <release version=\"1.0.0\" date=\"2024-02-01\"/>\n  </releases>\n</component>\n"
+        );
+    }
+
+    #[test]
+    fn test_wrong_id_or_no_releases() {
+        let path = Path::new("app.metainfo.xml");
+        assert!(matches!(
+            parse_version(METAINFO, path, "org.example.Other"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        assert!(matches!(
+            parse_version("<component><id>app</id></component>", path, "app"),
+            Err(Error::TargetParse { .. })
+        ));
+    }
+}
@@ -0,0 +1,166 @@
+use crate::error::{Error, Result};
+use std::fs;
+use std::path::Path;
+use toml_edit::{DocumentMut, Item, Table};
+
+/// Read the version of a workspace package from a Cargo.lock file
+pub fn read_version(path: &Path, key: &str) -> Result<String> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    parse_version(&content, path, key)
+}
+
+/// Write the version of a workspace package to a Cargo.lock file
+/// Preserves comments and formatting
+pub fn write_version(path: &Path, key: &str, version: &str) -> Result<()> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    let updated = update_version(&content, path, key, version)?;
+
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Read the version of a workspace package from Cargo.lock content
+/// (`path` is used for error messages)
+///
+/// The key is the package name. Only `[[package]]` entries without a
+/// `source` are considered, so dependencies of the same name from crates.io
+/// or git are left alone.
+pub fn parse_version(content: &str, path: &Path, key: &str) -> Result<String> {
+    let mut doc = parse_document(content, path)?;
+    let package = find_package(&mut doc, path, key)?;
+    package
+        .get("version")
+        .and_then(Item::as_str)
+        .map(|s| s.to_string())
+        .ok_or_else(|| Error::ValueNotString {
+            file: path.to_path_buf(),
+            key: key.to_string(),
+        })
+}
+
+/// Return Cargo.lock content with the version of the workspace package replaced
+pub fn update_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    let mut doc = parse_document(content, path)?;
+    let package = find_package(&mut doc, path, key)?;
+    let value = package
+        .get_mut("version")
+        .and_then(Item::as_value_mut)
+        .filter(|v| v.is_str())
+        .ok_or_else(|| Error::ValueNotString {
+            file: path.to_path_buf(),
+            key: key.to_string(),
+        })?;
+
+    // Keep the whitespace and trailing comment around the old value
+    let decor = value.decor().clone();
+    *value = toml_edit::Value::from(version);
+    *value.decor_mut() = decor;
+
+    Ok(doc.to_string())
+}
+
+fn parse_document(content: &str, path: &Path) -> Result<DocumentMut> {
+    content
+        .parse()
+        .map_err(|e: toml_edit::TomlError| Error::TargetParse {
+            file: path.to_path_buf(),
+            message: e.to_string(),
+        })
+}
+
+/// Find the single `[[package]]` entry of the workspace package named `key`
+fn find_package<'a>(doc: &'a mut DocumentMut, path: &Path, key: &str) -> Result<&'a mut Table> {
+    let mut matches: Vec<&mut Table> = doc
+        .get_mut("package")
+        .and_then(Item::as_array_of_tables_mut)
+        .map(|packages| {
+            packages
+                .iter_mut()
+                .filter(|p| p.get("name").and_then(Item::as_str) == Some(key))
+                .filter(|p| !p.contains_key("source"))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    match matches.len() {
+        0 => Err(Error::KeyNotFound {
+            file: path.to_path_buf(),
+            key: key.to_string(),
+        }),
+        1 => Ok(matches.remove(0)),
+        count => Err(Error::AmbiguousKey {
+            file: path.to_path_buf(),
+            key: key.to_string(),
+            count,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CARGO_LOCK: &str = r#"# This file is automatically @generated by Cargo.
+# It is not intended for manual editing.
+version = 4
+
+[[package]]
+name = "app"
+version = "1.2.3"
+dependencies = [
+ "serde",
+]
+
+[[package]]
+name = "serde"
+version = "1.0.200"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "ddc6f9cc94d67c0e21aaf7eda3a010fd3af78ebf6e096aa6e2e13c79749cce4f"
+
+[[package]]
+name = "app-macros"
+version = "1.2.3"
+"#;
+
+    #[test]
+    fn test_read_workspace_package() {
+        let path = Path::new("Cargo.lock");
+        assert_eq!(parse_version(CARGO_LOCK, path, "app").unwrap(), "1.2.3");
+        assert_eq!(
+            parse_version(CARGO_LOCK, path, "app-macros").unwrap(),
+            "1.2.3"
+        );
+    }
+
+    #[test]
+    fn test_update_preserves_document() {
+        let path = Path::new("Cargo.lock");
+        let updated = update_version(CARGO_LOCK, path, "app", "1.3.0").unwrap();
+        assert_eq!(
+            updated,
+            CARGO_LOCK.replacen("version = \"1.2.3\"", "version = \"1.3.0\"", 1)
+        );
+    }
+
+    #[test]
+    fn test_registry_packages_are_skipped() {
+        let path = Path::new("Cargo.lock");
+        assert!(matches!(
+            parse_version(CARGO_LOCK, path, "serde"),
+            Err(Error::KeyNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_ambiguous_package() {
+        let content = "[[package]]\nname = \"a\"\nversion = \"1.0.0\"\n\n[[package]]\nname = \"a\"\nversion = \"2.0.0\"\n";
+        assert!(matches!(
+            parse_version(content, Path::new("Cargo.lock"), "a"),
+            Err(Error::AmbiguousKey { count: 2, .. })
+        ));
+    }
+}
@@ -0,0 +1,129 @@
+use crate::error::{Error, Result};
+use crate::format::pattern::{find_unique, splice, unquote};
+use regex::Regex;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+/// Read the version value from a CITATION.cff file
+pub fn read_version(path: &Path, key: &str) -> Result<String> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    parse_version(&content, path, key)
+}
+
+/// Write the version value to a CITATION.cff file
+/// Only the value is replaced; quotes, comments and other lines are kept
+pub fn write_version(path: &Path, key: &str, version: &str) -> Result<()> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    let updated = update_version(&content, path, key, version)?;
+
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Read the version value from CITATION.cff content (`path` is used for
+/// error messages)
+///
+/// The key is a top-level field such as `version` or `date-released`, with a
+/// plain, single-quoted or double-quoted scalar value on the same line.
+/// Nested fields (e.g. under `preferred-citation`) are not considered, and
+/// the field must occur exactly once.
+pub fn parse_version(content: &str, path: &Path, key: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(content[span].to_string())
+}
+
+/// Return CITATION.cff content with the value of the field replaced
+pub fn update_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(splice(content, span, version))
+}
+
+/// Locate the value of the top-level field, inside its quotes if it has any
+fn find_value(content: &str, path: &Path, key: &str) -> Result<Range<usize>> {
+    let pattern = Regex::new(&format!(
+        r#"(?m)^{}:[ \t]*(?P<value>"[^"\n]*"|'[^'\n]*'|[^\s"'#|>&*!%@`\[{{-][^\n#]*?)[ \t]*(?:#.*)?$"#,
+        regex::escape(key)
+    ))
+    .map_err(|e| Error::TargetParse {
+        file: path.to_path_buf(),
+        message: e.to_string(),
+    })?;
+
+    match find_unique(content, path, key, &pattern) {
+        Ok(span) => Ok(unquote(content, span)),
+        // The field exists, but holds a list, a mapping or nothing
+        Err(Error::KeyNotFound { .. })
+            if content
+                .lines()
+                .any(|line| line.strip_prefix(key).is_some_and(|r| r.starts_with(':'))) =>
+        {
+            Err(Error::ValueNotString {
+                file: path.to_path_buf(),
+                key: key.to_string(),
+            })
+        }
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CITATION: &str = r#"cff-version: 1.2.0
+message: "If you use this software, please cite it as below."
+title: example
+version: 1.2.3 # bumped by versync
+date-released: '2024-01-15'
+authors:
+  - family-names: Doe
+    given-names: Jane
+preferred-citation:
+  type: software
+  version: 0.1.0
+"#;
+
+    #[test]
+    fn test_read_top_level_fields() {
+        let path = Path::new("CITATION.cff");
+        assert_eq!(parse_version(CITATION, path, "version").unwrap(), "1.2.3");
+        assert_eq!(
+            parse_version(CITATION, path, "date-released").unwrap(),
+            "2024-01-15"
+        );
+    }
+
+    #[test]
+    fn test_update_preserves_file() {
+        let path = Path::new("CITATION.cff");
+        let updated = update_version(CITATION, path, "version", "2.0.0").unwrap();
+        assert_eq!(
+            updated,
+            CITATION.replace("version: 1.2.3 #", "version: 2.0.0 #")
+        );
+        let updated = update_version(CITATION, path, "date-released", "2024-02-01").unwrap();
+        assert!(updated.contains("date-released: '2024-02-01'\n"));
+    }
+
+    #[test]
+    fn test_key_not_found_or_not_scalar() {
+        let path = Path::new("CITATION.cff");
+        assert!(matches!(
+            parse_version(CITATION, path, "doi"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        assert!(matches!(
+            parse_version(CITATION, path, "type"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        assert!(matches!(
+            parse_version(CITATION, path, "authors"),
+            Err(Error::ValueNotString { .. })
+        ));
+    }
+}
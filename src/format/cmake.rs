@@ -0,0 +1,243 @@
+use crate::error::{Error, Result};
+use crate::format::pattern::{splice, unquote};
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+/// Read the version value from a CMake file
+pub fn read_version(path: &Path, key: &str) -> Result<String> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    parse_version(&content, path, key)
+}
+
+/// Write the version value to a CMake file
+/// Only the argument is replaced; the rest of the file is kept exactly
+pub fn write_version(path: &Path, key: &str, version: &str) -> Result<()> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    let updated = update_version(&content, path, key, version)?;
+
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Read the version value from CMake content (`path` is used for error messages)
+///
+/// The key is a command and a keyword argument, e.g. `project.VERSION` for
+/// `project(example VERSION 1.2.3 LANGUAGES CXX)`; the argument after the
+/// keyword is the value. Since `set(NAME value)` has the same shape,
+/// `set.EXAMPLE_VERSION` works too. Command names are matched
+/// case-insensitively, keywords exactly. The keyword must occur exactly once
+/// across the invocations of the command.
+pub fn parse_version(content: &str, path: &Path, key: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(content[span].to_string())
+}
+
+/// Return CMake content with the argument after the keyword replaced
+pub fn update_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(splice(content, span, version))
+}
+
+/// A command invocation with the spans of its arguments
+struct Invocation<'a> {
+    name: &'a str,
+    args: Vec<Range<usize>>,
+}
+
+/// Locate the argument following the keyword, without quotes
+fn find_value(content: &str, path: &Path, key: &str) -> Result<Range<usize>> {
+    let Some((command, keyword)) = key.split_once('.') else {
+        return Err(Error::TargetParse {
+            file: path.to_path_buf(),
+            message: format!(
+                "invalid key '{}': expected a command and a keyword, e.g. 'project.VERSION'",
+                key
+            ),
+        });
+    };
+
+    let spans: Vec<Range<usize>> = invocations(content, path)?
+        .into_iter()
+        .filter(|invocation| invocation.name.eq_ignore_ascii_case(command))
+        .flat_map(|invocation| {
+            invocation
+                .args
+                .windows(2)
+                .filter(|pair| &content[pair[0].clone()] == keyword)
+                .map(|pair| pair[1].clone())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    match spans.as_slice() {
+        [span] => Ok(unquote(content, span.clone())),
+        [] => Err(Error::KeyNotFound {
+            file: path.to_path_buf(),
+            key: key.to_string(),
+        }),
+        _ => Err(Error::AmbiguousKey {
+            file: path.to_path_buf(),
+            key: key.to_string(),
+            count: spans.len(),
+        }),
+    }
+}
+
+/// Split CMake content into command invocations, skipping comments
+fn invocations<'a>(content: &'a str, path: &Path) -> Result<Vec<Invocation<'a>>> {
+    let bytes = content.as_bytes();
+    let mut result = Vec::new();
+    let mut current: Option<Invocation> = None;
+    let mut depth = 0;
+    // Last word seen outside of an invocation, a candidate command name
+    let mut word: Option<Range<usize>> = None;
+
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'#' => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'(' => {
+                if let Some(invocation) = current.as_mut() {
+                    depth += 1;
+                    invocation.args.push(i..i + 1);
+                } else if let Some(name) = word.take() {
+                    current = Some(Invocation {
+                        name: &content[name],
+                        args: Vec::new(),
+                    });
+                    depth = 1;
+                }
+                i += 1;
+            }
+            b')' => {
+                if current.is_some() {
+                    depth -= 1;
+                    if depth == 0 {
+                        result.extend(current.take());
+                    } else if let Some(invocation) = current.as_mut() {
+                        invocation.args.push(i..i + 1);
+                    }
+                }
+                i += 1;
+            }
+            b'"' => {
+                let start = i;
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+                if i >= bytes.len() {
+                    return Err(Error::TargetParse {
+                        file: path.to_path_buf(),
+                        message: "unterminated quoted argument".to_string(),
+                    });
+                }
+                i += 1;
+                if let Some(invocation) = current.as_mut() {
+                    invocation.args.push(start..i);
+                }
+            }
+            b if b.is_ascii_whitespace() => i += 1,
+            _ => {
+                let start = i;
+                while i < bytes.len()
+                    && !bytes[i].is_ascii_whitespace()
+                    && !matches!(bytes[i], b'(' | b')' | b'#' | b'"')
+                {
+                    i += 1;
+                }
+                match current.as_mut() {
+                    Some(invocation) => invocation.args.push(start..i),
+                    None => word = Some(start..i),
+                }
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CMAKE_LISTS: &str = r#"cmake_minimum_required(VERSION 3.20)
+# project(old VERSION 0.0.1)
+project(example
+  VERSION 1.2.3   # managed by versync
+  DESCRIPTION "An (example) project"
+  LANGUAGES CXX)
+
+set(EXAMPLE_API_VERSION "1.2")
+if((WIN32))
+  message(STATUS "VERSION 9.9.9")
+endif()
+"#;
+
+    #[test]
+    fn test_read_keyword_arguments() {
+        let path = Path::new("CMakeLists.txt");
+        assert_eq!(
+            parse_version(CMAKE_LISTS, path, "project.VERSION").unwrap(),
+            "1.2.3"
+        );
+        assert_eq!(
+            parse_version(CMAKE_LISTS, path, "PROJECT.VERSION").unwrap(),
+            "1.2.3"
+        );
+        assert_eq!(
+            parse_version(CMAKE_LISTS, path, "set.EXAMPLE_API_VERSION").unwrap(),
+            "1.2"
+        );
+        assert_eq!(
+            parse_version(CMAKE_LISTS, path, "cmake_minimum_required.VERSION").unwrap(),
+            "3.20"
+        );
+    }
+
+    #[test]
+    fn test_update_preserves_file() {
+        let path = Path::new("CMakeLists.txt");
+        let updated = update_version(CMAKE_LISTS, path, "project.VERSION", "2.0.0").unwrap();
+        assert_eq!(
+            updated,
+            CMAKE_LISTS.replace("VERSION 1.2.3 ", "VERSION 2.0.0 ")
+        );
+        let updated = update_version(CMAKE_LISTS, path, "set.EXAMPLE_API_VERSION", "2.0").unwrap();
+        assert!(updated.contains("set(EXAMPLE_API_VERSION \"2.0\")"));
+    }
+
+    #[test]
+    fn test_invalid_missing_and_ambiguous() {
+        let path = Path::new("CMakeLists.txt");
+        assert!(matches!(
+            parse_version(CMAKE_LISTS, path, "VERSION"),
+            Err(Error::TargetParse { .. })
+        ));
+        assert!(matches!(
+            parse_version(CMAKE_LISTS, path, "project.SOVERSION"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        assert!(matches!(
+            parse_version(
+                "project(a VERSION 1)\nproject(b VERSION 2)\n",
+                path,
+                "project.VERSION"
+            ),
+            Err(Error::AmbiguousKey { count: 2, .. })
+        ));
+        assert!(matches!(
+            parse_version("set(A \"1)\n", path, "set.A"),
+            Err(Error::TargetParse { .. })
+        ));
+    }
+}
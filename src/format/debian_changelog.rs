@@ -0,0 +1,209 @@
+use crate::error::{Error, Result};
+use regex::Regex;
+use std::fs;
+use std::path::Path;
+
+/// Read the version of the top entry of a Debian changelog
+pub fn read_version(path: &Path, key: &str) -> Result<String> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    parse_version(&content, path, key)
+}
+
+/// Prepend an entry for the version to a Debian changelog
+/// Existing entries are kept exactly
+pub fn write_version(path: &Path, key: &str, version: &str) -> Result<()> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    let updated = update_version(&content, path, key, version)?;
+
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Read the version of the top entry from Debian changelog content (`path`
+/// is used for error messages)
+///
+/// The key is the source package name, which the top entry must be for
+/// (`example (1.2.3-1) unstable; urgency=medium`). The version is the full
+/// Debian version, including any revision, so targets usually set a
+/// template such as `{version}-1`.
+pub fn parse_version(content: &str, path: &Path, key: &str) -> Result<String> {
+    Ok(top_entry(content, path, key)?.version)
+}
+
+/// Return Debian changelog content with an entry for the version on top
+///
+/// Nothing changes if the top entry already has the version. The new entry
+/// copies the distribution and urgency of the previous top entry. The
+/// maintainer is taken from `DEBFULLNAME` and `DEBEMAIL`, as for `dch`, or
+/// else from the previous top entry; the timestamp is the current time.
+pub fn update_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    let maintainer = match (std::env::var("DEBFULLNAME"), std::env::var("DEBEMAIL")) {
+        (Ok(name), Ok(email)) if !name.is_empty() && !email.is_empty() => {
+            Some(format!("{} <{}>", name, email))
+        }
+        (_, Ok(email)) if email.contains('<') => Some(email),
+        _ => None,
+    };
+    let date = chrono::Local::now()
+        .format("%a, %d %b %Y %H:%M:%S %z")
+        .to_string();
+
+    prepend_entry(content, path, key, version, maintainer, &date)
+}
+
+/// The header and trailer of the top changelog entry
+struct Entry {
+    version: String,
+    /// Everything after the version, e.g. `unstable; urgency=medium`
+    distribution: String,
+    maintainer: String,
+}
+
+/// Parse the top entry, which must be for the package
+fn top_entry(content: &str, path: &Path, key: &str) -> Result<Entry> {
+    let parse_error = |message: &str| Error::TargetParse {
+        file: path.to_path_buf(),
+        message: message.to_string(),
+    };
+    let header = Regex::new(r"^(?P<package>\S+) \((?P<version>[^)\s]+)\) (?P<rest>[^;\n]+;.*)$")
+        .map_err(|e| parse_error(&e.to_string()))?;
+    let trailer =
+        Regex::new(r"^ -- (?P<maintainer>.+?)  \S.*$").map_err(|e| parse_error(&e.to_string()))?;
+
+    let mut lines = content.lines().skip_while(|line| line.trim().is_empty());
+    let Some(first) = lines.next() else {
+        return Err(Error::KeyNotFound {
+            file: path.to_path_buf(),
+            key: key.to_string(),
+        });
+    };
+    let caps = header
+        .captures(first)
+        .ok_or_else(|| parse_error("the first line is not an entry header"))?;
+    if &caps["package"] != key {
+        return Err(Error::KeyNotFound {
+            file: path.to_path_buf(),
+            key: key.to_string(),
+        });
+    }
+    let maintainer = lines
+        .find_map(|line| trailer.captures(line))
+        .ok_or_else(|| parse_error("the top entry has no ` -- maintainer  date` line"))?;
+
+    Ok(Entry {
+        version: caps["version"].to_string(),
+        distribution: caps["rest"].to_string(),
+        maintainer: maintainer["maintainer"].to_string(),
+    })
+}
+
+/// Prepend an entry unless the top entry already has the version
+fn prepend_entry(
+    content: &str,
+    path: &Path,
+    key: &str,
+    version: &str,
+    maintainer: Option<String>,
+    date: &str,
+) -> Result<String> {
+    let top = top_entry(content, path, key)?;
+    if top.version == version {
+        return Ok(content.to_string());
+    }
+
+    Ok(format!(
+        "{} ({}) {}\n\n  * New upstream release.\n\n -- {}  {}\n\n{}",
+        key,
+        version,
+        top.distribution,
+        maintainer.unwrap_or(top.maintainer),
+        date,
+        content.trim_start_matches('\n')
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHANGELOG: &str = "\
+example (1.2.3-1) unstable; urgency=medium
+
+  * New upstream release.
+  * Fix the build on armhf.
+
+ -- Jane Doe <jane@example.org>  Mon, 15 Jan 2024 10:00:00 +0000
+
+example (1.2.2-1) unstable; urgency=low
+
+  * Initial release.
+
+ -- Jane Doe <jane@example.org>  Mon, 01 Jan 2024 10:00:00 +0000
+";
+
+    const DATE: &str = "Thu, 01 Feb 2024 12:30:00 +0100";
+
+    #[test]
+    fn test_read_top_entry() {
+        let path = Path::new("debian/changelog");
+        assert_eq!(
+            parse_version(CHANGELOG, path, "example").unwrap(),
+            "1.2.3-1"
+        );
+    }
+
+    #[test]
+    fn test_prepend_entry() {
+        let path = Path::new("debian/changelog");
+        let updated = prepend_entry(CHANGELOG, path, "example", "1.3.0-1", None, DATE).unwrap();
+        let expected = format!(
+            "example (1.3.0-1) unstable; urgency=medium\n\n  * New upstream release.\n\n -- Jane Doe <jane@example.org>  {}\n\n{}",
+            DATE, CHANGELOG
+        );
+        assert_eq!(updated, expected);
+        assert_eq!(parse_version(&updated, path, "example").unwrap(), "1.3.0-1");
+
+        let maintainer = Some("Release Bot <bot@example.org>".to_string());
+        let updated =
+            prepend_entry(CHANGELOG, path, "example", "1.3.0-1", maintainer, DATE).unwrap();
+        assert!(updated.contains(" -- Release Bot <bot@example.org>  Thu, 01 Feb 2024"));
+    }
+
+    #[test]
+    fn test_same_version_is_unchanged() {
+        let path = Path::new("debian/changelog");
+        assert_eq!(
+            prepend_entry(CHANGELOG, path, "example", "1.2.3-1", None, DATE).unwrap(),
+            CHANGELOG
+        );
+    }
+
+    #[test]
+    fn test_wrong_package_or_invalid() {
+        let path = Path::new("debian/changelog");
+        assert!(matches!(
+            parse_version(CHANGELOG, path, "other"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        assert!(matches!(
+            parse_version("", path, "example"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        assert!(matches!(
+            parse_version("Version 1.2.3\n", path, "example"),
+            Err(Error::TargetParse { .. })
+        ));
+        assert!(matches!(
+            parse_version(
+                "example (1.0-1) unstable; urgency=low\n\n  * x\n",
+                path,
+                "example"
+            ),
+            Err(Error::TargetParse { .. })
+        ));
+    }
+}
@@ -0,0 +1,148 @@
+use crate::error::{Error, Result};
+use crate::format::pattern::{find_unique, splice, unquote};
+use regex::Regex;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+/// Instructions whose `name=value` pairs can hold the version
+const INSTRUCTIONS: &[&str] = &["ARG", "ENV", "LABEL"];
+
+/// Read the version value from a Dockerfile
+pub fn read_version(path: &Path, key: &str) -> Result<String> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    parse_version(&content, path, key)
+}
+
+/// Write the version value to a Dockerfile
+/// Only the value is replaced; the rest of the file is kept exactly
+pub fn write_version(path: &Path, key: &str, version: &str) -> Result<()> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    let updated = update_version(&content, path, key, version)?;
+
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Read the version value from a Dockerfile (`path` is used for error messages)
+///
+/// The key is an instruction and a name, e.g. `ARG VERSION` for
+/// `ARG VERSION=1.2.3` or `LABEL org.opencontainers.image.version` for
+/// `LABEL org.opencontainers.image.version="1.2.3"`. `ARG`, `ENV` and
+/// `LABEL` are supported, with the `name=value` form; a LABEL may set several
+/// labels and continue over lines. The pair must occur exactly once.
+pub fn parse_version(content: &str, path: &Path, key: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(content[span].to_string())
+}
+
+/// Return the Dockerfile with the value of the pair replaced
+pub fn update_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(splice(content, span, version))
+}
+
+/// Locate the value of the `name=value` pair, without quotes
+fn find_value(content: &str, path: &Path, key: &str) -> Result<Range<usize>> {
+    let invalid_key = || Error::TargetParse {
+        file: path.to_path_buf(),
+        message: format!(
+            "invalid key '{}': expected an instruction and a name, e.g. 'ARG VERSION'",
+            key
+        ),
+    };
+    let (instruction, name) = key.split_once(' ').ok_or_else(invalid_key)?;
+    let instruction = instruction.to_uppercase();
+    if !INSTRUCTIONS.contains(&instruction.as_str()) || name.trim().is_empty() {
+        return Err(invalid_key());
+    }
+
+    let pattern = Regex::new(&format!(
+        r#"(?m)^[ \t]*(?i:{})(?:\\\n|[^\n])*?[ \t]{}=(?P<value>"[^"\n]*"|'[^'\n]*'|[^\s"']+)"#,
+        instruction,
+        regex::escape(name.trim())
+    ))
+    .map_err(|e| Error::TargetParse {
+        file: path.to_path_buf(),
+        message: e.to_string(),
+    })?;
+
+    let span = find_unique(content, path, key, &pattern)?;
+    Ok(unquote(content, span))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCKERFILE: &str = r#"# syntax=docker/dockerfile:1
+ARG VERSION=1.2.3
+FROM rust:1.80 AS build
+ARG VERSION
+
+FROM debian:bookworm-slim
+LABEL org.opencontainers.image.title="example" \
+      org.opencontainers.image.version="1.2.3"
+env APP_VERSION='1.2.3'
+"#;
+
+    #[test]
+    fn test_read_pairs() {
+        let path = Path::new("Dockerfile");
+        assert_eq!(
+            parse_version(DOCKERFILE, path, "ARG VERSION").unwrap(),
+            "1.2.3"
+        );
+        assert_eq!(
+            parse_version(DOCKERFILE, path, "LABEL org.opencontainers.image.version").unwrap(),
+            "1.2.3"
+        );
+        assert_eq!(
+            parse_version(DOCKERFILE, path, "ENV APP_VERSION").unwrap(),
+            "1.2.3"
+        );
+    }
+
+    #[test]
+    fn test_update_preserves_file() {
+        let path = Path::new("Dockerfile");
+        let updated = update_version(
+            DOCKERFILE,
+            path,
+            "LABEL org.opencontainers.image.version",
+            "2.0.0",
+        )
+        .unwrap();
+        assert_eq!(
+            updated,
+            DOCKERFILE.replace("image.version=\"1.2.3\"", "image.version=\"2.0.0\"")
+        );
+
+        let updated = update_version(DOCKERFILE, path, "ARG VERSION", "2.0.0").unwrap();
+        assert_eq!(
+            updated,
+            DOCKERFILE.replace("ARG VERSION=1.2.3", "ARG VERSION=2.0.0")
+        );
+    }
+
+    #[test]
+    fn test_invalid_and_missing_keys() {
+        let path = Path::new("Dockerfile");
+        assert!(matches!(
+            parse_version(DOCKERFILE, path, "VERSION"),
+            Err(Error::TargetParse { .. })
+        ));
+        assert!(matches!(
+            parse_version(DOCKERFILE, path, "RUN VERSION"),
+            Err(Error::TargetParse { .. })
+        ));
+        assert!(matches!(
+            parse_version(DOCKERFILE, path, "ARG RELEASE"),
+            Err(Error::KeyNotFound { .. })
+        ));
+    }
+}
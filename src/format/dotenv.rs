@@ -0,0 +1,115 @@
+use crate::error::{Error, Result};
+use crate::format::pattern::{find_unique, splice, unquote};
+use regex::Regex;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+/// Read the version value from a dotenv file
+pub fn read_version(path: &Path, key: &str) -> Result<String> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    parse_version(&content, path, key)
+}
+
+/// Write the version value to a dotenv file
+/// Only the value is replaced; quotes, comments and other lines are kept
+pub fn write_version(path: &Path, key: &str, version: &str) -> Result<()> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    let updated = update_version(&content, path, key, version)?;
+
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Read the version value from dotenv content (`path` is used for error messages)
+///
+/// The key is the variable name (`APP_VERSION=1.2.3`, optionally prefixed
+/// with `export`). The value may be unquoted, single-quoted or
+/// double-quoted; an unquoted value ends at whitespace or a `#` comment. The
+/// variable must be assigned exactly once.
+pub fn parse_version(content: &str, path: &Path, key: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(content[span].to_string())
+}
+
+/// Return dotenv content with the value of the variable replaced
+pub fn update_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(splice(content, span, version))
+}
+
+/// Locate the value of the variable, inside its quotes if it has any
+fn find_value(content: &str, path: &Path, key: &str) -> Result<Range<usize>> {
+    let pattern = Regex::new(&format!(
+        r#"(?m)^[ \t]*(?:export[ \t]+)?{}[ \t]*=[ \t]*(?P<value>"[^"\n]*"|'[^'\n]*'|[^\s"'#][^\s#]*|)"#,
+        regex::escape(key)
+    ))
+    .map_err(|e| Error::TargetParse {
+        file: path.to_path_buf(),
+        message: e.to_string(),
+    })?;
+
+    let span = find_unique(content, path, key, &pattern)?;
+    Ok(unquote(content, span))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENV: &str = r#"# Deployment settings
+APP_NAME=example
+APP_VERSION=1.2.3 # bumped by versync
+export IMAGE_TAG="v1.2.3"
+  BUILD_LABEL='1.2.3'
+# APP_VERSION=0.0.0
+"#;
+
+    #[test]
+    fn test_read_quote_styles() {
+        let path = Path::new(".env");
+        assert_eq!(parse_version(ENV, path, "APP_VERSION").unwrap(), "1.2.3");
+        assert_eq!(parse_version(ENV, path, "IMAGE_TAG").unwrap(), "v1.2.3");
+        assert_eq!(parse_version(ENV, path, "BUILD_LABEL").unwrap(), "1.2.3");
+    }
+
+    #[test]
+    fn test_update_preserves_quotes_and_comments() {
+        let path = Path::new(".env");
+        let updated = update_version(ENV, path, "APP_VERSION", "2.0.0").unwrap();
+        assert_eq!(
+            updated,
+            ENV.replace("APP_VERSION=1.2.3 # bumped", "APP_VERSION=2.0.0 # bumped")
+        );
+        let updated = update_version(ENV, path, "IMAGE_TAG", "v2.0.0").unwrap();
+        assert!(updated.contains("export IMAGE_TAG=\"v2.0.0\"\n"));
+    }
+
+    #[test]
+    fn test_empty_value() {
+        let path = Path::new(".env");
+        let content = "APP_VERSION=\nOTHER=1\n";
+        assert_eq!(parse_version(content, path, "APP_VERSION").unwrap(), "");
+        assert_eq!(
+            update_version(content, path, "APP_VERSION", "1.0.0").unwrap(),
+            "APP_VERSION=1.0.0\nOTHER=1\n"
+        );
+    }
+
+    #[test]
+    fn test_key_not_found_or_ambiguous() {
+        let path = Path::new(".env");
+        assert!(matches!(
+            parse_version(ENV, path, "VERSION"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        assert!(matches!(
+            parse_version("V=1\nV=2\n", path, "V"),
+            Err(Error::AmbiguousKey { count: 2, .. })
+        ));
+    }
+}
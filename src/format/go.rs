@@ -0,0 +1,124 @@
+use crate::error::{Error, Result};
+use crate::format::pattern::{find_unique, splice};
+use regex::Regex;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+/// Read the version value from Go source
+pub fn read_version(path: &Path, key: &str) -> Result<String> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    parse_version(&content, path, key)
+}
+
+/// Write the version value to Go source
+/// Only the string literal is replaced; the rest of the file is kept exactly
+pub fn write_version(path: &Path, key: &str, version: &str) -> Result<()> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    let updated = update_version(&content, path, key, version)?;
+
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Read the version value from Go source (`path` is used for error messages)
+///
+/// The key is the name of a constant or variable initialized with a string
+/// literal: `const Version = "1.2.3"`, `var version string = "1.2.3"`, or
+/// `Version = "1.2.3"` inside a `const (...)` or `var (...)` group.
+/// Interpreted (`"..."`) and raw (`` `...` ``) strings are supported. The
+/// name must be assigned a string exactly once in the file.
+pub fn parse_version(content: &str, path: &Path, key: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(content[span].to_string())
+}
+
+/// Return Go source with the string of the constant or variable replaced
+pub fn update_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(splice(content, span, version))
+}
+
+/// Locate the contents of the string literal assigned to the name
+fn find_value(content: &str, path: &Path, key: &str) -> Result<Range<usize>> {
+    let pattern = Regex::new(&format!(
+        r#"(?m)^[ \t]*(?:(?:const|var)[ \t]+)?{}(?:[ \t]+string)?[ \t]*=[ \t]*(?P<value>"[^"\\\n]*"|`[^`]*`)"#,
+        regex::escape(key)
+    ))
+    .map_err(|e| Error::TargetParse {
+        file: path.to_path_buf(),
+        message: e.to_string(),
+    })?;
+
+    let span = find_unique(content, path, key, &pattern)?;
+    Ok(span.start + 1..span.end - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VERSION_GO: &str = r#"// Package version reports the build version.
+package version
+
+import "fmt"
+
+// Version is the release version.
+const Version = "1.2.3"
+
+var (
+	Commit    = "unknown"
+	BuildDate string = `2024-01-01`
+)
+
+func String() string {
+	return fmt.Sprintf("%s (%s)", Version, Commit)
+}
+"#;
+
+    #[test]
+    fn test_read_declarations() {
+        let path = Path::new("version.go");
+        assert_eq!(parse_version(VERSION_GO, path, "Version").unwrap(), "1.2.3");
+        assert_eq!(
+            parse_version(VERSION_GO, path, "Commit").unwrap(),
+            "unknown"
+        );
+        assert_eq!(
+            parse_version(VERSION_GO, path, "BuildDate").unwrap(),
+            "2024-01-01"
+        );
+    }
+
+    #[test]
+    fn test_update_preserves_source() {
+        let path = Path::new("version.go");
+        let updated = update_version(VERSION_GO, path, "Version", "2.0.0").unwrap();
+        assert_eq!(
+            updated,
+            VERSION_GO.replace("Version = \"1.2.3\"", "Version = \"2.0.0\"")
+        );
+    }
+
+    #[test]
+    fn test_key_not_found_or_ambiguous() {
+        let path = Path::new("version.go");
+        assert!(matches!(
+            parse_version(VERSION_GO, path, "version"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        assert!(matches!(
+            parse_version("var v = 1\n", path, "v"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        let content = "var v = \"1.0\"\n\nfunc init() {\n\tv = \"2.0\"\n}\n";
+        assert!(matches!(
+            parse_version(content, path, "v"),
+            Err(Error::AmbiguousKey { count: 2, .. })
+        ));
+    }
+}
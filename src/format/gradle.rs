@@ -0,0 +1,237 @@
+use crate::error::{Error, Result};
+use crate::format::pattern::splice;
+use regex::Regex;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+/// Read the version value from a Gradle build script
+pub fn read_version(path: &Path, key: &str) -> Result<String> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    parse_version(&content, path, key)
+}
+
+/// Write the version value to a Gradle build script
+/// Only the string literal is replaced; the rest of the script is kept exactly
+pub fn write_version(path: &Path, key: &str, version: &str) -> Result<()> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    let updated = update_version(&content, path, key, version)?;
+
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Read the version value from a Gradle build script in the Groovy or Kotlin
+/// DSL (`path` is used for error messages)
+///
+/// The key is a property assigned a string at the top level of the script,
+/// e.g. `version` for `version = "1.2.3"` or `version '1.2.3'`. Blocks are
+/// entered by naming them, so `allprojects.version` is the property inside
+/// `allprojects { ... }`. Assignments in other blocks, such as dependency
+/// and plugin versions, are never considered.
+pub fn parse_version(content: &str, path: &Path, key: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(content[span].to_string())
+}
+
+/// Return the build script with the string of the property replaced
+pub fn update_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(splice(content, span, version))
+}
+
+/// Locate the contents of the string assigned to the property
+fn find_value(content: &str, path: &Path, key: &str) -> Result<Range<usize>> {
+    let (blocks, property) = key.rsplit_once('.').unwrap_or(("", key));
+    let blocks: Vec<&str> = blocks.split('.').filter(|b| !b.is_empty()).collect();
+    let pattern = Regex::new(&format!(
+        r#"^[ \t]*{}(?:[ \t]*=[ \t]*|[ \t]+)(?P<value>"[^"\\\n$]*"|'[^'\\\n]*')[ \t]*(?:;[ \t]*)?(?://.*)?\r?$"#,
+        regex::escape(property)
+    ))
+    .map_err(|e| Error::TargetParse {
+        file: path.to_path_buf(),
+        message: e.to_string(),
+    })?;
+
+    let spans: Vec<Range<usize>> = lines_in_blocks(content)
+        .into_iter()
+        .filter(|(_, stack)| *stack == blocks)
+        .filter_map(|(line, _)| {
+            let value = pattern.captures(&content[line.clone()])?.name("value")?;
+            Some(line.start + value.start() + 1..line.start + value.end() - 1)
+        })
+        .collect();
+
+    match spans.as_slice() {
+        [span] => Ok(span.clone()),
+        [] => Err(Error::KeyNotFound {
+            file: path.to_path_buf(),
+            key: key.to_string(),
+        }),
+        _ => Err(Error::AmbiguousKey {
+            file: path.to_path_buf(),
+            key: key.to_string(),
+            count: spans.len(),
+        }),
+    }
+}
+
+/// Each line with the names of the blocks it is in
+///
+/// A block is named by the identifier before its `{`; strings and comments
+/// are skipped. Lines that start inside a string or comment are left out.
+fn lines_in_blocks(content: &str) -> Vec<(Range<usize>, Vec<&str>)> {
+    let bytes = content.as_bytes();
+    let mut lines = Vec::new();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut line_start = 0;
+    let mut in_code = true;
+    let mut i = 0;
+
+    while i <= bytes.len() {
+        if i == bytes.len() || bytes[i] == b'\n' {
+            if in_code {
+                lines.push((line_start..i, stack.clone()));
+            }
+            line_start = i + 1;
+            in_code = true;
+            i += 1;
+            continue;
+        }
+        let rest = &content[i..];
+        let skip_to = |end: &str, from: usize| {
+            rest[from..]
+                .find(end)
+                .map_or(bytes.len(), |j| i + from + j + end.len())
+        };
+        let next = if rest.starts_with("//") {
+            rest.find('\n').map_or(bytes.len(), |j| i + j)
+        } else if rest.starts_with("/*") {
+            skip_to("*/", 2)
+        } else if rest.starts_with("\"\"\"") {
+            skip_to("\"\"\"", 3)
+        } else if bytes[i] == b'"' || bytes[i] == b'\'' {
+            let quote = bytes[i];
+            let mut j = i + 1;
+            while j < bytes.len() && bytes[j] != quote && bytes[j] != b'\n' {
+                j += if bytes[j] == b'\\' { 2 } else { 1 };
+            }
+            (j + 1).min(bytes.len())
+        } else {
+            match bytes[i] {
+                b'{' => {
+                    let before = content[..i].trim_end();
+                    let name_start = before
+                        .rfind(|c: char| !(c.is_alphanumeric() || c == '_'))
+                        .map_or(0, |j| j + 1);
+                    stack.push(&before[name_start..]);
+                }
+                b'}' => {
+                    stack.pop();
+                }
+                _ => {}
+            }
+            i + rest.chars().next().map_or(1, char::len_utf8)
+        };
+        // A multi-line string or comment hides the lines it spans
+        if content[i..next.min(bytes.len())].contains('\n') {
+            if in_code {
+                lines.push((line_start..i, stack.clone()));
+            }
+            line_start = content[..next].rfind('\n').map_or(0, |j| j + 1);
+            in_code = false;
+        }
+        i = next;
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GROOVY: &str = r#"plugins {
+    id 'java'
+    id 'org.springframework.boot' version '3.2.0'
+}
+
+group = 'com.example'
+description = 'Ünïcode'
+version = '1.2.3' // bumped by versync
+
+/* version = '0.0.0' */
+dependencies {
+    implementation 'com.google.guava:guava:33.0.0-jre'
+    constraints {
+        implementation('org.slf4j:slf4j-api') { version { strictly '2.0.9' } }
+    }
+}
+
+allprojects {
+    version "1.2.3"
+}
+"#;
+
+    const KOTLIN: &str = r#"plugins {
+    kotlin("jvm") version "1.9.22"
+}
+
+group = "com.example"
+version = "1.2.3"
+
+dependencies {
+    implementation(kotlin("stdlib"))
+}
+"#;
+
+    #[test]
+    fn test_read_top_level_property() {
+        let path = Path::new("build.gradle");
+        assert_eq!(parse_version(GROOVY, path, "version").unwrap(), "1.2.3");
+        assert_eq!(parse_version(GROOVY, path, "group").unwrap(), "com.example");
+        assert_eq!(
+            parse_version(GROOVY, path, "description").unwrap(),
+            "Ünïcode"
+        );
+        assert_eq!(
+            parse_version(GROOVY, path, "allprojects.version").unwrap(),
+            "1.2.3"
+        );
+        let path = Path::new("build.gradle.kts");
+        assert_eq!(parse_version(KOTLIN, path, "version").unwrap(), "1.2.3");
+    }
+
+    #[test]
+    fn test_update_ignores_plugins_and_dependencies() {
+        let path = Path::new("build.gradle.kts");
+        let updated = update_version(KOTLIN, path, "version", "2.0.0").unwrap();
+        assert_eq!(
+            updated,
+            KOTLIN.replace("version = \"1.2.3\"", "version = \"2.0.0\"")
+        );
+
+        let path = Path::new("build.gradle");
+        let updated = update_version(GROOVY, path, "version", "2.0.0").unwrap();
+        assert_eq!(
+            updated,
+            GROOVY.replace("version = '1.2.3'", "version = '2.0.0'")
+        );
+    }
+
+    #[test]
+    fn test_key_not_found() {
+        let path = Path::new("build.gradle");
+        assert!(matches!(
+            parse_version(GROOVY, path, "sourceCompatibility"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        assert!(matches!(
+            parse_version(GROOVY, path, "subprojects.version"),
+            Err(Error::KeyNotFound { .. })
+        ));
+    }
+}
@@ -0,0 +1,459 @@
+use crate::error::{Error, Result};
+use crate::format::pattern::splice;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+/// Read the version value from an HCL file
+pub fn read_version(path: &Path, key: &str) -> Result<String> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    parse_version(&content, path, key)
+}
+
+/// Write the version value to an HCL file
+/// Only the string is replaced; the rest of the file is kept exactly
+pub fn write_version(path: &Path, key: &str, version: &str) -> Result<()> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    let updated = update_version(&content, path, key, version)?;
+
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Read the version value from HCL content (`path` is used for error messages)
+///
+/// The key is the block type, the block labels and the attribute name joined
+/// with dots, e.g. `variable.app_version.default` for
+/// `variable "app_version" { default = "1.2.3" }`, `module.vpc.version` or
+/// `locals.version`; a top-level attribute (as in `.tfvars` files) is just
+/// its name. The attribute must be a string literal without interpolation
+/// and must occur exactly once.
+pub fn parse_version(content: &str, path: &Path, key: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(content[span].replace("\\\"", "\"").replace("\\\\", "\\"))
+}
+
+/// Return HCL content with the string at the key replaced
+pub fn update_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    let escaped = version.replace('\\', "\\\\").replace('"', "\\\"");
+    Ok(splice(content, span, &escaped))
+}
+
+/// Locate the contents of the string literal assigned at the key
+fn find_value(content: &str, path: &Path, key: &str) -> Result<Range<usize>> {
+    let segments: Vec<&str> = key.split('.').collect();
+    if segments.iter().any(|s| s.is_empty()) {
+        return Err(Error::InvalidKey {
+            key: key.to_string(),
+            message: "expected block type, labels and attribute separated by dots".to_string(),
+        });
+    }
+
+    let mut scanner = Scanner {
+        content,
+        bytes: content.as_bytes(),
+        pos: 0,
+        path,
+        key: &segments,
+        matches: Vec::new(),
+    };
+    scanner.body(&mut Vec::new())?;
+    if scanner.pos < content.len() {
+        return Err(scanner.error("unexpected '}'"));
+    }
+
+    match scanner.matches.as_slice() {
+        [Some(span)] => Ok(span.clone()),
+        [None] => Err(Error::ValueNotString {
+            file: path.to_path_buf(),
+            key: key.to_string(),
+        }),
+        [] => Err(Error::KeyNotFound {
+            file: path.to_path_buf(),
+            key: key.to_string(),
+        }),
+        matches => Err(Error::AmbiguousKey {
+            file: path.to_path_buf(),
+            key: key.to_string(),
+            count: matches.len(),
+        }),
+    }
+}
+
+/// A cursor over HCL content collecting the attributes at the key
+struct Scanner<'a> {
+    content: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+    path: &'a Path,
+    key: &'a [&'a str],
+    /// Each attribute at the key: the span of its string, or `None` if the
+    /// value is not a plain string literal
+    matches: Vec<Option<Range<usize>>>,
+}
+
+impl<'a> Scanner<'a> {
+    /// Scan the attributes and blocks of a body, up to its closing brace
+    fn body(&mut self, prefix: &mut Vec<&'a str>) -> Result<()> {
+        loop {
+            self.skip_trivia()?;
+            match self.peek() {
+                None | Some(b'}') => return Ok(()),
+                _ => {}
+            }
+
+            let name = self.ident()?;
+            self.skip_inline()?;
+            if self.peek() == Some(b'=') && self.bytes.get(self.pos + 1) != Some(&b'=') {
+                self.pos += 1;
+                self.skip_inline()?;
+                prefix.push(name);
+                let matched = prefix.as_slice() == self.key;
+                prefix.pop();
+                let value = self.attribute_value()?;
+                if matched {
+                    self.matches.push(value);
+                }
+                continue;
+            }
+
+            // A block: labels, then the body in braces
+            let depth = prefix.len();
+            prefix.push(name);
+            loop {
+                match self.peek() {
+                    Some(b'"') => {
+                        let (span, plain) = self.string()?;
+                        if !plain {
+                            return Err(self.error("block labels cannot be templates"));
+                        }
+                        prefix.push(&self.content[span.start + 1..span.end - 1]);
+                    }
+                    Some(b'{') => break,
+                    _ => {
+                        let label = self.ident()?;
+                        prefix.push(label);
+                    }
+                }
+                self.skip_inline()?;
+            }
+            self.pos += 1;
+            self.body(prefix)?;
+            if self.peek() != Some(b'}') {
+                return Err(self.error("unclosed block"));
+            }
+            self.pos += 1;
+            prefix.truncate(depth);
+        }
+    }
+
+    /// Scan an attribute value up to the end of its line
+    ///
+    /// Returns the span of the string contents if the value is a single
+    /// string literal without interpolation.
+    fn attribute_value(&mut self) -> Result<Option<Range<usize>>> {
+        let start = self.pos;
+        let mut literal = None;
+        if self.peek() == Some(b'"') {
+            let (span, plain) = self.string()?;
+            self.skip_inline()?;
+            if plain && matches!(self.peek(), None | Some(b'\n' | b'\r' | b'}')) {
+                literal = Some(span.start + 1..span.end - 1);
+            }
+        }
+        if literal.is_none() {
+            self.pos = start;
+            self.expression()?;
+        }
+        Ok(literal)
+    }
+
+    /// Skip an expression, which ends at a newline outside of brackets
+    fn expression(&mut self) -> Result<()> {
+        let mut depth = 0usize;
+        loop {
+            self.skip_inline()?;
+            match self.peek() {
+                None => return Ok(()),
+                Some(b'\n') if depth == 0 => return Ok(()),
+                Some(b'}') if depth == 0 => return Ok(()),
+                Some(b'\n') => self.pos += 1,
+                Some(b'(' | b'[' | b'{') => {
+                    depth += 1;
+                    self.pos += 1;
+                }
+                Some(b')' | b']' | b'}') => {
+                    depth = depth.saturating_sub(1);
+                    self.pos += 1;
+                }
+                Some(b'"') => {
+                    self.string()?;
+                }
+                Some(b'<') if self.content[self.pos..].starts_with("<<") => self.heredoc()?,
+                Some(_) => self.pos += 1,
+            }
+        }
+    }
+
+    /// Skip a heredoc (`<<EOT` or `<<-EOT`) through its closing marker
+    fn heredoc(&mut self) -> Result<()> {
+        let rest = &self.content[self.pos + 2..];
+        let rest = rest.strip_prefix('-').unwrap_or(rest);
+        let marker: String = rest
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+            .collect();
+        if marker.is_empty() {
+            // A `<<` operator rather than a heredoc
+            self.pos += 2;
+            return Ok(());
+        }
+
+        let Some(newline) = self.content[self.pos..].find('\n') else {
+            return Err(self.error("unterminated heredoc"));
+        };
+        self.pos += newline + 1;
+        loop {
+            let line_end = self.content[self.pos..]
+                .find('\n')
+                .map_or(self.content.len(), |i| self.pos + i);
+            let line = &self.content[self.pos..line_end];
+            if line.trim() == marker {
+                self.pos = line_end;
+                return Ok(());
+            }
+            if line_end == self.content.len() {
+                return Err(self.error("unterminated heredoc"));
+            }
+            self.pos = line_end + 1;
+        }
+    }
+
+    /// Skip a quoted string, returning its span including the quotes and
+    /// whether it is free of `${...}` and `%{...}` templates
+    fn string(&mut self) -> Result<(Range<usize>, bool)> {
+        let start = self.pos;
+        let mut plain = true;
+        self.pos += 1;
+        loop {
+            match self.peek() {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok((start..self.pos, plain));
+                }
+                Some(b'\\') => self.pos += 2,
+                Some(b'$' | b'%') if self.bytes.get(self.pos + 1) == Some(&b'{') => {
+                    plain = false;
+                    self.pos += 2;
+                    self.template()?;
+                }
+                Some(b'\n') | None => {
+                    self.pos = start;
+                    return Err(self.error("unterminated string"));
+                }
+                Some(_) => self.pos += 1,
+            }
+        }
+    }
+
+    /// Skip the inside of a template interpolation through its closing brace
+    fn template(&mut self) -> Result<()> {
+        let mut depth = 0usize;
+        loop {
+            match self.peek() {
+                Some(b'"') => {
+                    self.string()?;
+                }
+                Some(b'{') => {
+                    depth += 1;
+                    self.pos += 1;
+                }
+                Some(b'}') if depth == 0 => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                Some(b'}') => {
+                    depth -= 1;
+                    self.pos += 1;
+                }
+                Some(_) => self.pos += 1,
+                None => return Err(self.error("unterminated interpolation")),
+            }
+        }
+    }
+
+    /// Read an identifier
+    fn ident(&mut self) -> Result<&'a str> {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if !(c.is_ascii_alphanumeric() || c == b'_' || c == b'-') {
+                break;
+            }
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return Err(self.error("expected an identifier"));
+        }
+        Ok(&self.content[start..self.pos])
+    }
+
+    /// Skip whitespace, newlines and comments
+    fn skip_trivia(&mut self) -> Result<()> {
+        loop {
+            self.skip_inline()?;
+            match self.peek() {
+                Some(b'\n' | b'\r') => self.pos += 1,
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    /// Skip spaces and comments, but not the end of the line
+    fn skip_inline(&mut self) -> Result<()> {
+        loop {
+            match (self.peek(), self.bytes.get(self.pos + 1)) {
+                (Some(b' ' | b'\t'), _) => self.pos += 1,
+                (Some(b'#'), _) | (Some(b'/'), Some(b'/')) => {
+                    while self.peek().is_some_and(|c| c != b'\n') {
+                        self.pos += 1;
+                    }
+                }
+                (Some(b'/'), Some(b'*')) => match self.content[self.pos + 2..].find("*/") {
+                    Some(end) => self.pos += end + 4,
+                    None => return Err(self.error("unterminated comment")),
+                },
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn error(&self, message: &str) -> Error {
+        let line = self.content[..self.pos.min(self.content.len())]
+            .matches('\n')
+            .count()
+            + 1;
+        Error::TargetParse {
+            file: self.path.to_path_buf(),
+            message: format!("{} at line {}", message, line),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VARIABLES_TF: &str = r#"# Application release
+variable "app_version" {
+  type        = string
+  default     = "1.2.3" # bumped by versync
+  description = "Version of the ${var.name} image"
+}
+
+locals {
+  version = "1.2.3"
+  tags = {
+    version = "ignored"
+  }
+  script = <<-EOT
+    version = "0.0.0"
+  EOT
+}
+
+module "vpc" {
+  source  = "terraform-aws-modules/vpc/aws"
+  version = "5.1.0"
+}
+"#;
+
+    #[test]
+    fn test_read_block_attributes() {
+        let path = Path::new("variables.tf");
+        assert_eq!(
+            parse_version(VARIABLES_TF, path, "variable.app_version.default").unwrap(),
+            "1.2.3"
+        );
+        assert_eq!(
+            parse_version(VARIABLES_TF, path, "locals.version").unwrap(),
+            "1.2.3"
+        );
+        assert_eq!(
+            parse_version(VARIABLES_TF, path, "module.vpc.version").unwrap(),
+            "5.1.0"
+        );
+    }
+
+    #[test]
+    fn test_update_preserves_file() {
+        let path = Path::new("variables.tf");
+        let updated =
+            update_version(VARIABLES_TF, path, "variable.app_version.default", "2.0.0").unwrap();
+        assert_eq!(
+            updated,
+            VARIABLES_TF.replace("\"1.2.3\" # bumped", "\"2.0.0\" # bumped")
+        );
+    }
+
+    #[test]
+    fn test_top_level_attribute() {
+        let path = Path::new("prod.tfvars");
+        let content = "region = \"eu-west-1\"\napp_version = \"1.0.0\"\n";
+        assert_eq!(
+            parse_version(content, path, "app_version").unwrap(),
+            "1.0.0"
+        );
+        assert_eq!(
+            update_version(content, path, "app_version", "1.1.0").unwrap(),
+            "region = \"eu-west-1\"\napp_version = \"1.1.0\"\n"
+        );
+    }
+
+    #[test]
+    fn test_not_a_string() {
+        let path = Path::new("main.tf");
+        for content in [
+            "locals {\n  version = var.version\n}\n",
+            "locals {\n  version = \"v${var.version}\"\n}\n",
+            "locals {\n  version = \"1.0\" == \"1.0\"\n}\n",
+        ] {
+            assert!(
+                matches!(
+                    parse_version(content, path, "locals.version"),
+                    Err(Error::ValueNotString { .. })
+                ),
+                "{}",
+                content
+            );
+        }
+    }
+
+    #[test]
+    fn test_key_not_found_or_ambiguous() {
+        let path = Path::new("main.tf");
+        assert!(matches!(
+            parse_version(VARIABLES_TF, path, "locals.tags"),
+            Err(Error::ValueNotString { .. })
+        ));
+        assert!(matches!(
+            parse_version(VARIABLES_TF, path, "variable.other.default"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        assert!(matches!(
+            parse_version("a = \"1\"\na = \"2\"\n", path, "a"),
+            Err(Error::AmbiguousKey { count: 2, .. })
+        ));
+        assert!(matches!(
+            parse_version("locals {\n  a = \"1\"\n", path, "locals.a"),
+            Err(Error::TargetParse { .. })
+        ));
+    }
+}
@@ -0,0 +1,156 @@
+use crate::error::{Error, Result};
+use crate::format::pattern::splice;
+use regex::Regex;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+/// Read the image tag from a Compose file or Kubernetes manifest
+pub fn read_version(path: &Path, key: &str) -> Result<String> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    parse_version(&content, path, key)
+}
+
+/// Write the image tag to a Compose file or Kubernetes manifest
+/// Only the tags are replaced; the rest of the file is kept exactly
+pub fn write_version(path: &Path, key: &str, version: &str) -> Result<()> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    let updated = update_version(&content, path, key, version)?;
+
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Read the image tag from YAML content (`path` is used for error messages)
+///
+/// The key is the image name without the tag, e.g. `ghcr.io/acme/app`, and
+/// selects the `image: ghcr.io/acme/app:1.2.3` entries to consider; images
+/// with other names are never touched, and neither are images pinned by
+/// digest. The image may be used several times. If the tags disagree, all of
+/// them are returned, separated by `, `, so the target shows as out of date;
+/// an image without a tag reads as empty.
+pub fn parse_version(content: &str, path: &Path, key: &str) -> Result<String> {
+    let mut tags: Vec<&str> = Vec::new();
+    for tag in find_tags(content, path, key)? {
+        if !tags.contains(&&content[tag.clone()]) {
+            tags.push(&content[tag]);
+        }
+    }
+    Ok(tags.join(", "))
+}
+
+/// Return content with the tag of every matching image replaced
+pub fn update_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    let tags = find_tags(content, path, key)?;
+    Ok(tags
+        .into_iter()
+        .rev()
+        .fold(content.to_string(), |updated, tag| {
+            // An image without a tag gets one
+            if tag.is_empty() {
+                splice(&updated, tag, &format!(":{}", version))
+            } else {
+                splice(&updated, tag, version)
+            }
+        }))
+}
+
+/// Locate the tag of each `image:` entry for the image, or the end of the
+/// name if it has no tag
+fn find_tags(content: &str, path: &Path, key: &str) -> Result<Vec<Range<usize>>> {
+    let pattern = Regex::new(&format!(
+        r#"(?m)^[ \t]*(?:-[ \t]+)?["']?image["']?[ \t]*:[ \t]*["']?(?P<name>{})(?P<tag>:[\w][\w.-]*)?["']?[ \t]*(?:#.*)?\r?$"#,
+        regex::escape(key)
+    ))
+    .map_err(|e| Error::TargetParse {
+        file: path.to_path_buf(),
+        message: e.to_string(),
+    })?;
+
+    let tags: Vec<Range<usize>> = pattern
+        .captures_iter(content)
+        .filter_map(|caps| match caps.name("tag") {
+            Some(tag) => Some(tag.start() + 1..tag.end()),
+            // Right after the name of an image without a tag
+            None => caps.name("name").map(|name| name.end()..name.end()),
+        })
+        .collect();
+
+    if tags.is_empty() {
+        return Err(Error::KeyNotFound {
+            file: path.to_path_buf(),
+            key: key.to_string(),
+        });
+    }
+    Ok(tags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMPOSE: &str = "\
+services:
+  app:
+    image: ghcr.io/acme/app:1.2.3
+  worker:
+    image: \"ghcr.io/acme/app:1.2.3\" # same image
+  db:
+    image: postgres:16
+  pinned:
+    image: ghcr.io/acme/app:1.0.0@sha256:0123456789abcdef
+  other:
+    image: ghcr.io/acme/app-sidecar:0.1.0
+";
+
+    #[test]
+    fn test_read_tags() {
+        let path = Path::new("compose.yaml");
+        assert_eq!(
+            parse_version(COMPOSE, path, "ghcr.io/acme/app").unwrap(),
+            "1.2.3"
+        );
+        assert_eq!(parse_version(COMPOSE, path, "postgres").unwrap(), "16");
+        assert!(matches!(
+            parse_version(COMPOSE, path, "acme/app"),
+            Err(Error::KeyNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_update_only_matching_images() {
+        let path = Path::new("compose.yaml");
+        let updated = update_version(COMPOSE, path, "ghcr.io/acme/app", "2.0.0").unwrap();
+        assert_eq!(
+            updated,
+            COMPOSE
+                .replace("app:1.2.3\n", "app:2.0.0\n")
+                .replace("app:1.2.3\"", "app:2.0.0\"")
+        );
+    }
+
+    #[test]
+    fn test_kubernetes_manifest() {
+        let path = Path::new("deployment.yaml");
+        let content = "\
+spec:
+  template:
+    spec:
+      containers:
+        - name: app
+          image: registry.example.org:5000/app:1.2.3
+        - image: registry.example.org:5000/app
+          name: migrate
+";
+        let key = "registry.example.org:5000/app";
+        assert_eq!(parse_version(content, path, key).unwrap(), "1.2.3, ");
+        let updated = update_version(content, path, key, "1.3.0").unwrap();
+        assert!(updated.contains("image: registry.example.org:5000/app:1.3.0\n          name"));
+        assert!(updated.contains("- image: registry.example.org:5000/app:1.3.0\n"));
+        assert_eq!(parse_version(&updated, path, key).unwrap(), "1.3.0");
+    }
+}
@@ -0,0 +1,176 @@
+use crate::error::{Error, Result};
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+/// Read the version value from an INI file at the specified key path
+pub fn read_version(path: &Path, key: &str) -> Result<String> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    parse_version(&content, path, key)
+}
+
+/// Write the version value to an INI file at the specified key path
+/// Only the value is replaced; comments and other lines are kept
+pub fn write_version(path: &Path, key: &str, version: &str) -> Result<()> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    let updated = update_version(&content, path, key, version)?;
+
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Read the version value from INI content (`path` is used for error messages)
+///
+/// The key is `section.key` (e.g. `metadata.version`); the part after the
+/// last dot is the key, so section names may contain dots. A key without a
+/// dot refers to the entries before the first section. Values may be
+/// written as `key = value` or `key: value`, optionally quoted.
+pub fn parse_version(content: &str, path: &Path, key: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(content[span].to_string())
+}
+
+/// Return INI content with the value at the key path replaced
+pub fn update_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(format!(
+        "{}{}{}",
+        &content[..span.start],
+        version,
+        &content[span.end..]
+    ))
+}
+
+/// Locate the byte range of the value at the key path, without quotes
+fn find_value(content: &str, path: &Path, key: &str) -> Result<Range<usize>> {
+    let (section, name) = match key.rsplit_once('.') {
+        Some((section, name)) => (Some(section), name),
+        None => (None, key),
+    };
+
+    let mut current: Option<&str> = None;
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with(['#', ';']) {
+            continue;
+        }
+        if let Some(header) = trimmed.strip_prefix('[') {
+            let Some(header) = header.strip_suffix(']') else {
+                return Err(Error::TargetParse {
+                    file: path.to_path_buf(),
+                    message: format!("invalid section header: {}", trimmed),
+                });
+            };
+            current = Some(header.trim());
+            continue;
+        }
+        if current != section {
+            continue;
+        }
+
+        let Some(separator) = line.find(['=', ':']) else {
+            continue;
+        };
+        if line[..separator].trim() != name {
+            continue;
+        }
+
+        let value = &line[separator + 1..];
+        let value_start = start + separator + 1 + (value.len() - value.trim_start().len());
+        let value = value.trim();
+        let span = value_start..value_start + value.len();
+        return Ok(unquote(value, span));
+    }
+
+    Err(Error::KeyNotFound {
+        file: path.to_path_buf(),
+        key: key.to_string(),
+    })
+}
+
+/// Narrow a value span to the inside of matching quotes, if any
+fn unquote(value: &str, span: Range<usize>) -> Range<usize> {
+    let quoted = value.len() >= 2
+        && ((value.starts_with('"') && value.ends_with('"'))
+            || (value.starts_with('\'') && value.ends_with('\'')));
+    if quoted {
+        span.start + 1..span.end - 1
+    } else {
+        span
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SETUP_CFG: &str = "\
+# Package metadata
+[metadata]
+name = example
+version = 1.2.3
+; maintained by versync
+
+[options]
+version: 0.0.0
+";
+
+    #[test]
+    fn test_read_section_key() {
+        let path = Path::new("setup.cfg");
+        assert_eq!(
+            parse_version(SETUP_CFG, path, "metadata.version").unwrap(),
+            "1.2.3"
+        );
+        assert_eq!(
+            parse_version(SETUP_CFG, path, "options.version").unwrap(),
+            "0.0.0"
+        );
+    }
+
+    #[test]
+    fn test_update_preserves_other_lines() {
+        let path = Path::new("setup.cfg");
+        let updated = update_version(SETUP_CFG, path, "metadata.version", "2.0.0").unwrap();
+        assert_eq!(
+            updated,
+            SETUP_CFG.replace("version = 1.2.3", "version = 2.0.0")
+        );
+    }
+
+    #[test]
+    fn test_global_and_quoted_values() {
+        let path = Path::new("app.ini");
+        let content = "version = \"1.0.0\"\n[tool.bump]\ncurrent='1.0.0' \n";
+        assert_eq!(parse_version(content, path, "version").unwrap(), "1.0.0");
+        assert_eq!(
+            update_version(content, path, "tool.bump.current", "1.1.0").unwrap(),
+            "version = \"1.0.0\"\n[tool.bump]\ncurrent='1.1.0' \n"
+        );
+    }
+
+    #[test]
+    fn test_key_not_found() {
+        let path = Path::new("setup.cfg");
+        assert!(matches!(
+            parse_version(SETUP_CFG, path, "version"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        assert!(matches!(
+            parse_version(SETUP_CFG, path, "metadata.author"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        assert!(matches!(
+            parse_version("[metadata\nversion = 1", path, "metadata.version"),
+            Err(Error::TargetParse { .. })
+        ));
+    }
+}
@@ -1,4 +1,5 @@
 use crate::error::{Error, Result};
+use crate::format::keypath::{self, Segment};
 use serde_json::Value;
 use std::fs;
 use std::path::Path;
@@ -8,12 +9,7 @@ pub fn read_version(path: &Path, key: &str) -> Result<String> {
     let content =
         fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
 
-    let json: Value = serde_json::from_str(&content).map_err(|e| Error::TargetParse {
-        file: path.to_path_buf(),
-        message: e.to_string(),
-    })?;
-
-    get_value(&json, path, key)
+    parse_version(&content, path, key)
 }
 
 /// Write the version value to a JSON file at the specified key path
@@ -22,30 +18,85 @@ pub fn write_version(path: &Path, key: &str, version: &str) -> Result<()> {
     let content =
         fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
 
-    let mut json: Value = serde_json::from_str(&content).map_err(|e| Error::TargetParse {
+    let updated = update_version(&content, path, key, version)?;
+
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Read the version value from JSON content (`path` is used for error messages)
+pub fn parse_version(content: &str, path: &Path, key: &str) -> Result<String> {
+    let json = parse_document(content, path)?;
+    get_value(&json, path, key)
+}
+
+/// Return JSON content with the version at the key path replaced
+///
+/// The output is pretty printed with 2-space indentation and a trailing newline.
+pub fn update_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    let mut json = parse_document(content, path)?;
+    set_value(&mut json, path, key, version)?;
+    render(&json, path)
+}
+
+/// Return JSON content with the version set at the key path, creating the
+/// key and any missing objects along it
+///
+/// Array elements are never created. The output is pretty printed like
+/// [`update_version`].
+pub fn insert_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    let mut json = parse_document(content, path)?;
+    let not_found = || Error::KeyNotFound {
         file: path.to_path_buf(),
-        message: e.to_string(),
-    })?;
+        key: key.to_string(),
+    };
+
+    let segments = keypath::parse(key)?;
+    let (last, parents) = segments.split_last().ok_or_else(not_found)?;
+    let mut target = &mut json;
+    for segment in parents {
+        if child_mut(target, segment).is_none() {
+            target
+                .as_object_mut()
+                .ok_or_else(not_found)?
+                .insert(segment.key(), Value::Object(Default::default()));
+        }
+        target = child_mut(target, segment).ok_or_else(not_found)?;
+    }
+    if child_mut(target, last).is_none() {
+        target
+            .as_object_mut()
+            .ok_or_else(not_found)?
+            .insert(last.key(), Value::String(version.to_string()));
+    }
 
     set_value(&mut json, path, key, version)?;
+    render(&json, path)
+}
 
-    // Pretty print with 2-space indentation and trailing newline
-    let output = serde_json::to_string_pretty(&json).map_err(|e| Error::TargetParse {
+/// Pretty print with 2-space indentation and a trailing newline
+fn render(json: &Value, path: &Path) -> Result<String> {
+    let output = serde_json::to_string_pretty(json).map_err(|e| Error::TargetParse {
         file: path.to_path_buf(),
         message: e.to_string(),
     })?;
 
-    fs::write(path, format!("{}\n", output))?;
-    Ok(())
+    Ok(format!("{}\n", output))
+}
+
+fn parse_document(content: &str, path: &Path) -> Result<Value> {
+    serde_json::from_str(content).map_err(|e| Error::TargetParse {
+        file: path.to_path_buf(),
+        message: e.to_string(),
+    })
 }
 
 /// Get a string value from a JSON value at the specified key path
 fn get_value(json: &Value, path: &Path, key: &str) -> Result<String> {
-    let keys: Vec<&str> = key.split('.').collect();
     let mut current = json;
 
-    for k in &keys {
-        current = current.get(k).ok_or_else(|| Error::KeyNotFound {
+    for segment in keypath::parse(key)? {
+        current = child(current, &segment).ok_or_else(|| Error::KeyNotFound {
             file: path.to_path_buf(),
             key: key.to_string(),
         })?;
@@ -62,28 +113,15 @@ fn get_value(json: &Value, path: &Path, key: &str) -> Result<String> {
 
 /// Set a string value in a JSON value at the specified key path
 fn set_value(json: &mut Value, path: &Path, key: &str, version: &str) -> Result<()> {
-    let keys: Vec<&str> = key.split('.').collect();
-    let mut current = json;
+    let mut target = json;
 
-    for k in &keys[..keys.len() - 1] {
-        current = current.get_mut(k).ok_or_else(|| Error::KeyNotFound {
+    for segment in keypath::parse(key)? {
+        target = child_mut(target, &segment).ok_or_else(|| Error::KeyNotFound {
             file: path.to_path_buf(),
             key: key.to_string(),
         })?;
     }
 
-    let last_key = keys.last().ok_or_else(|| Error::KeyNotFound {
-        file: path.to_path_buf(),
-        key: key.to_string(),
-    })?;
-
-    let target = current
-        .get_mut(last_key)
-        .ok_or_else(|| Error::KeyNotFound {
-            file: path.to_path_buf(),
-            key: key.to_string(),
-        })?;
-
     // Ensure it's currently a string before replacing
     if !target.is_string() {
         return Err(Error::ValueNotString {
@@ -96,6 +134,22 @@ fn set_value(json: &mut Value, path: &Path, key: &str, version: &str) -> Result<
     Ok(())
 }
 
+/// Get the value at one segment of a key path
+fn child<'a>(value: &'a Value, segment: &Segment) -> Option<&'a Value> {
+    match (segment, value) {
+        (Segment::Index(index), Value::Array(items)) => items.get(*index),
+        _ => value.get(segment.key()),
+    }
+}
+
+/// Get the value at one segment of a key path, for modification
+fn child_mut<'a>(value: &'a mut Value, segment: &Segment) -> Option<&'a mut Value> {
+    match (segment, value) {
+        (Segment::Index(index), Value::Array(items)) => items.get_mut(*index),
+        (_, value) => value.get_mut(segment.key()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -129,6 +183,59 @@ mod tests {
         assert_eq!(result, "2.0.0");
     }
 
+    #[test]
+    fn test_empty_key_segment() {
+        let lock = r#"{"name":"app","version":"1.0.0","packages":{"":{"version":"1.0.0"}}}"#;
+        let path = Path::new("package-lock.json");
+        assert_eq!(
+            parse_version(lock, path, r#"packages."".version"#).unwrap(),
+            "1.0.0"
+        );
+        let updated = update_version(lock, path, r#"packages."".version"#, "1.1.0").unwrap();
+        assert_eq!(
+            parse_version(&updated, path, r#"packages."".version"#).unwrap(),
+            "1.1.0"
+        );
+    }
+
+    #[test]
+    fn test_json_pointer() {
+        let content = r#"{"packages": {"": {"version": "1.0.0"}}, "a/b": {"c.d": "2.0.0"}}"#;
+        let path = Path::new("package-lock.json");
+        assert_eq!(
+            parse_version(content, path, "/packages//version").unwrap(),
+            "1.0.0"
+        );
+        assert_eq!(parse_version(content, path, "/a~1b/c.d").unwrap(), "2.0.0");
+        let updated = update_version(content, path, "/a~1b/c.d", "2.1.0").unwrap();
+        assert_eq!(parse_version(&updated, path, "/a~1b/c.d").unwrap(), "2.1.0");
+    }
+
+    #[test]
+    fn test_array_index() {
+        let content =
+            r#"{"releases": [{"version": "1.0.0"}, {"version": "2.0.0"}], "0": {"v": "x"}}"#;
+        let path = Path::new("releases.json");
+        assert_eq!(
+            parse_version(content, path, "releases[1].version").unwrap(),
+            "2.0.0"
+        );
+        assert_eq!(
+            parse_version(content, path, "releases.0.version").unwrap(),
+            "1.0.0"
+        );
+        assert_eq!(parse_version(content, path, "0.v").unwrap(), "x");
+        let updated = update_version(content, path, "releases[0].version", "1.0.1").unwrap();
+        assert_eq!(
+            parse_version(&updated, path, "releases[0].version").unwrap(),
+            "1.0.1"
+        );
+        assert!(matches!(
+            parse_version(content, path, "releases[2].version"),
+            Err(Error::KeyNotFound { .. })
+        ));
+    }
+
     #[test]
     fn test_write_pretty_prints() {
         let mut file = NamedTempFile::with_suffix(".json").unwrap();
@@ -150,4 +257,28 @@ mod tests {
         let result = read_version(file.path(), "nonexistent");
         assert!(matches!(result, Err(Error::KeyNotFound { .. })));
     }
+
+    #[test]
+    fn test_insert_version() {
+        let path = Path::new("package.json");
+        let updated = insert_version(r#"{"name": "app"}"#, path, "version", "1.0.0").unwrap();
+        assert_eq!(
+            updated,
+            "{\n  \"name\": \"app\",\n  \"version\": \"1.0.0\"\n}\n"
+        );
+
+        let updated = insert_version("{}", path, "config.app.version", "1.0.0").unwrap();
+        assert_eq!(
+            parse_version(&updated, path, "config.app.version").unwrap(),
+            "1.0.0"
+        );
+        assert!(matches!(
+            insert_version(r#"{"releases": []}"#, path, "releases[0].version", "1.0.0"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        assert!(matches!(
+            insert_version(r#"{"version": 1}"#, path, "version", "1.0.0"),
+            Err(Error::ValueNotString { .. })
+        ));
+    }
 }
@@ -0,0 +1,455 @@
+use crate::error::{Error, Result};
+use crate::format::keypath::{self, Segment};
+use crate::format::pattern::splice;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+/// Read the version value from a JSON-with-comments file at the specified key path
+pub fn read_version(path: &Path, key: &str) -> Result<String> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    parse_version(&content, path, key)
+}
+
+/// Write the version value to a JSON-with-comments file at the specified key path
+/// Only the string is replaced; comments, trailing commas and formatting are kept
+pub fn write_version(path: &Path, key: &str, version: &str) -> Result<()> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    let updated = update_version(&content, path, key, version)?;
+
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Read the version value from JSONC or JSON5 content (`path` is used for
+/// error messages)
+///
+/// Besides plain JSON this accepts `//` and `/* */` comments, trailing
+/// commas, unquoted object keys and single-quoted strings. The key is a
+/// dot-separated path as for JSON; if an object repeats a key, the first
+/// occurrence is used.
+pub fn parse_version(content: &str, path: &Path, key: &str) -> Result<String> {
+    let (span, quote) = find_string(content, path, key)?;
+    unescape(&content[span], quote).ok_or_else(|| Error::TargetParse {
+        file: path.to_path_buf(),
+        message: format!("invalid escape in the string at '{}'", key),
+    })
+}
+
+/// Return JSONC or JSON5 content with the string at the key path replaced
+pub fn update_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    let (span, quote) = find_string(content, path, key)?;
+    Ok(splice(content, span, &escape(version, quote)))
+}
+
+/// Locate the contents of the string at the key path and its quote character
+fn find_string(content: &str, path: &Path, key: &str) -> Result<(Range<usize>, u8)> {
+    let segments = keypath::parse(key)?;
+    let mut scanner = Scanner {
+        content,
+        bytes: content.as_bytes(),
+        pos: 0,
+        path,
+    };
+
+    scanner.skip_trivia()?;
+    let found = scanner.find(&segments)?;
+    scanner.skip_trivia()?;
+    if scanner.pos < content.len() {
+        return Err(scanner.error("unexpected content after the document"));
+    }
+
+    match found {
+        Found::String(span, quote) => Ok((span, quote)),
+        Found::Other => Err(Error::ValueNotString {
+            file: path.to_path_buf(),
+            key: key.to_string(),
+        }),
+        Found::Missing => Err(Error::KeyNotFound {
+            file: path.to_path_buf(),
+            key: key.to_string(),
+        }),
+    }
+}
+
+/// Result of looking up a key path
+enum Found {
+    /// A string, as the span of its contents and its quote character
+    String(Range<usize>, u8),
+    /// A value that is not a string
+    Other,
+    /// No value at the key path
+    Missing,
+}
+
+/// A cursor over JSONC content
+struct Scanner<'a> {
+    content: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+    path: &'a Path,
+}
+
+impl Scanner<'_> {
+    /// Find the value at the key path, starting at a value
+    ///
+    /// The whole document is still scanned once the value is found, so
+    /// malformed content is reported instead of being edited.
+    fn find(&mut self, segments: &[Segment]) -> Result<Found> {
+        let Some((first, rest)) = segments.split_first() else {
+            return match self.peek() {
+                Some(quote @ (b'"' | b'\'')) => {
+                    let span = self.string()?;
+                    Ok(Found::String(span.start + 1..span.end - 1, quote))
+                }
+                _ => {
+                    self.value()?;
+                    Ok(Found::Other)
+                }
+            };
+        };
+
+        let close = match (self.peek(), first) {
+            (Some(b'{'), _) => b'}',
+            (Some(b'['), Segment::Index(_)) => b']',
+            _ => {
+                self.value()?;
+                return Ok(Found::Missing);
+            }
+        };
+
+        let mut found = Found::Missing;
+        let mut index = 0;
+        self.pos += 1;
+        loop {
+            self.skip_trivia()?;
+            if self.peek() == Some(close) {
+                self.pos += 1;
+                return Ok(found);
+            }
+
+            let matched = if close == b'}' {
+                let name = self.member_name()?;
+                self.skip_trivia()?;
+                self.expect(b':')?;
+                self.skip_trivia()?;
+                name == first.key()
+            } else {
+                *first == Segment::Index(index)
+            };
+            if matches!(found, Found::Missing) && matched {
+                found = self.find(rest)?;
+            } else {
+                self.value()?;
+            }
+            index += 1;
+
+            self.skip_trivia()?;
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(c) if c == close => {}
+                _ => return Err(self.error("expected ',' or a closing bracket")),
+            }
+        }
+    }
+
+    /// Skip over any value
+    fn value(&mut self) -> Result<()> {
+        match self.peek() {
+            Some(open @ (b'{' | b'[')) => {
+                let close = if open == b'{' { b'}' } else { b']' };
+                self.pos += 1;
+                loop {
+                    self.skip_trivia()?;
+                    if self.peek() == Some(close) {
+                        self.pos += 1;
+                        return Ok(());
+                    }
+                    if open == b'{' {
+                        self.member_name()?;
+                        self.skip_trivia()?;
+                        self.expect(b':')?;
+                        self.skip_trivia()?;
+                    }
+                    self.value()?;
+                    self.skip_trivia()?;
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(c) if c == close => {}
+                        _ => return Err(self.error("expected ',' or a closing bracket")),
+                    }
+                }
+            }
+            Some(b'"' | b'\'') => self.string().map(|_| ()),
+            Some(_) => {
+                // Numbers, true, false, null and other JSON5 literals
+                let start = self.pos;
+                while let Some(c) = self.peek() {
+                    if c.is_ascii_whitespace() || b",:]}/".contains(&c) {
+                        break;
+                    }
+                    self.pos += 1;
+                }
+                if self.pos == start {
+                    return Err(self.error("expected a value"));
+                }
+                Ok(())
+            }
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    /// Read an object member name, quoted or a bare identifier
+    fn member_name(&mut self) -> Result<String> {
+        match self.peek() {
+            Some(quote @ (b'"' | b'\'')) => {
+                let span = self.string()?;
+                unescape(&self.content[span.start + 1..span.end - 1], quote)
+                    .ok_or_else(|| self.error("invalid escape in member name"))
+            }
+            _ => {
+                let start = self.pos;
+                while let Some(c) = self.peek() {
+                    if !(c.is_ascii_alphanumeric() || c == b'_' || c == b'$') {
+                        break;
+                    }
+                    self.pos += 1;
+                }
+                if self.pos == start {
+                    return Err(self.error("expected a member name"));
+                }
+                Ok(self.content[start..self.pos].to_string())
+            }
+        }
+    }
+
+    /// Skip a string literal, returning its span including the quotes
+    fn string(&mut self) -> Result<Range<usize>> {
+        let start = self.pos;
+        let quote = self.bytes[start];
+        self.pos += 1;
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            match c {
+                b'\\' => self.pos += 1,
+                b'\n' => break,
+                c if c == quote => return Ok(start..self.pos),
+                _ => {}
+            }
+        }
+        self.pos = start;
+        Err(self.error("unterminated string"))
+    }
+
+    /// Skip whitespace and comments
+    fn skip_trivia(&mut self) -> Result<()> {
+        loop {
+            match (self.peek(), self.bytes.get(self.pos + 1)) {
+                (Some(c), _) if c.is_ascii_whitespace() => self.pos += 1,
+                (Some(b'/'), Some(b'/')) => {
+                    while self.peek().is_some_and(|c| c != b'\n') {
+                        self.pos += 1;
+                    }
+                }
+                (Some(b'/'), Some(b'*')) => match self.content[self.pos + 2..].find("*/") {
+                    Some(end) => self.pos += end + 4,
+                    None => return Err(self.error("unterminated comment")),
+                },
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn expect(&mut self, c: u8) -> Result<()> {
+        if self.peek() != Some(c) {
+            return Err(self.error(&format!("expected '{}'", c as char)));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn error(&self, message: &str) -> Error {
+        let line = self.content[..self.pos.min(self.content.len())]
+            .matches('\n')
+            .count()
+            + 1;
+        Error::TargetParse {
+            file: self.path.to_path_buf(),
+            message: format!("{} at line {}", message, line),
+        }
+    }
+}
+
+/// Decode the escapes in the contents of a string literal
+fn unescape(raw: &str, quote: u8) -> Option<String> {
+    let mut out = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => out.push('\n'),
+            't' => out.push('\t'),
+            'r' => out.push('\r'),
+            'b' => out.push('\u{8}'),
+            'f' => out.push('\u{c}'),
+            'u' => {
+                let hex: String = chars.by_ref().take(4).collect();
+                out.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+            }
+            c @ ('"' | '\'' | '\\' | '/') => out.push(c),
+            c if c as u32 == quote as u32 => out.push(c),
+            _ => return None,
+        }
+    }
+    Some(out)
+}
+
+/// Escape a value for a string literal with the given quote character
+fn escape(value: &str, quote: u8) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c if c as u32 == quote as u32 => {
+                out.push('\\');
+                out.push(c);
+            }
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DENO_JSONC: &str = r#"// Deno configuration
+{
+  "name": "@example/app",
+  /* the published version */
+  "version": "1.2.3", // bumped by versync
+  "tasks": {
+    "dev": "deno run --watch main.ts", // "version": "0.0.0"
+  },
+  "exclude": ["dist/", ],
+}
+"#;
+
+    #[test]
+    fn test_read_with_comments_and_trailing_commas() {
+        let path = Path::new("deno.jsonc");
+        assert_eq!(parse_version(DENO_JSONC, path, "version").unwrap(), "1.2.3");
+        assert_eq!(
+            parse_version(DENO_JSONC, path, "tasks.dev").unwrap(),
+            "deno run --watch main.ts"
+        );
+    }
+
+    #[test]
+    fn test_update_preserves_comments() {
+        let path = Path::new("deno.jsonc");
+        let updated = update_version(DENO_JSONC, path, "version", "2.0.0").unwrap();
+        assert_eq!(
+            updated,
+            DENO_JSONC.replace("\"version\": \"1.2.3\"", "\"version\": \"2.0.0\"")
+        );
+    }
+
+    #[test]
+    fn test_json5_keys_and_quotes() {
+        let path = Path::new("config.json5");
+        let content = "{\n  app: { version: '1.0.0', build: 7, },\n}\n";
+        assert_eq!(
+            parse_version(content, path, "app.version").unwrap(),
+            "1.0.0"
+        );
+        assert_eq!(
+            update_version(content, path, "app.version", "it's").unwrap(),
+            "{\n  app: { version: 'it\\'s', build: 7, },\n}\n"
+        );
+        assert!(matches!(
+            parse_version(content, path, "app.build"),
+            Err(Error::ValueNotString { .. })
+        ));
+    }
+
+    #[test]
+    fn test_array_index() {
+        let path = Path::new("a.jsonc");
+        let content =
+            "{\"releases\": [\n  {\"version\": \"1.0.0\"}, // old\n  {\"version\": \"2.0.0\"},\n]}";
+        assert_eq!(
+            parse_version(content, path, "releases[1].version").unwrap(),
+            "2.0.0"
+        );
+        assert_eq!(
+            update_version(content, path, "releases.0.version", "1.0.1").unwrap(),
+            content.replace("1.0.0", "1.0.1")
+        );
+        assert!(matches!(
+            parse_version(content, path, "releases[2].version"),
+            Err(Error::KeyNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_escapes() {
+        let path = Path::new("a.jsonc");
+        let content = r#"{"v": "1.0\"xA"}"#;
+        assert_eq!(parse_version(content, path, "v").unwrap(), "1.0\"xA");
+        assert_eq!(
+            update_version(content, path, "v", "2\"0").unwrap(),
+            r#"{"v": "2\"0"}"#
+        );
+    }
+
+    #[test]
+    fn test_key_not_found() {
+        let path = Path::new("a.jsonc");
+        assert!(matches!(
+            parse_version(DENO_JSONC, path, "tasks.build"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        assert!(matches!(
+            parse_version(DENO_JSONC, path, "version.major"),
+            Err(Error::KeyNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_content() {
+        let path = Path::new("a.jsonc");
+        for content in [
+            "{\"v\": \"1.0\"",
+            "{\"v\" \"1.0\"}",
+            "{/* \"v\": \"1\"}",
+            "{\"v\": \"1.0}",
+            "{} {}",
+        ] {
+            assert!(
+                matches!(
+                    parse_version(content, path, "v"),
+                    Err(Error::TargetParse { .. })
+                ),
+                "{}",
+                content
+            );
+        }
+    }
+}
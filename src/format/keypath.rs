@@ -0,0 +1,240 @@
+use crate::error::{Error, Result};
+
+/// A segment of a key path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    /// A key of a table or object
+    Key(String),
+    /// An index into an array (`[0]`, or a bare number such as `.0.`)
+    ///
+    /// A bare number applied to a table or object is looked up as a key.
+    Index(usize),
+}
+
+impl Segment {
+    /// The key to look up when the segment is applied to a table or object
+    pub fn key(&self) -> String {
+        match self {
+            Segment::Key(key) => key.clone(),
+            Segment::Index(index) => index.to_string(),
+        }
+    }
+}
+
+/// Parse a dot-separated key path used by the TOML and JSON formats
+///
+/// Segments are separated by `.` and may be followed by array indices in
+/// brackets, so `releases[0].version` and `releases.0.version` address the
+/// same value. A key containing `.` or `[` is written in double quotes
+/// (`"my.app".version`, with `\"` and `\\` escapes), in single quotes (taken
+/// literally) or with a backslash before the character (`my\.app.version`).
+/// A quoted or escaped segment is always a key, never an index.
+///
+/// A key starting with `/` is an RFC 6901 JSON Pointer instead
+/// (`/packages//version`), see [`parse_pointer`].
+pub fn parse(key: &str) -> Result<Vec<Segment>> {
+    let invalid = |message: &str| Error::InvalidKey {
+        key: key.to_string(),
+        message: message.to_string(),
+    };
+    if key.starts_with('/') {
+        return parse_pointer(key);
+    }
+
+    let mut segments = Vec::new();
+    let mut chars = key.chars().peekable();
+    loop {
+        let mut name = String::new();
+        // Quoted or escaped characters make the segment a key
+        let mut literal = false;
+        while let Some(&c) = chars.peek() {
+            match c {
+                '.' | '[' => break,
+                '"' | '\'' => {
+                    chars.next();
+                    literal = true;
+                    loop {
+                        match chars.next() {
+                            Some(q) if q == c => break,
+                            Some('\\') if c == '"' => {
+                                name.push(chars.next().ok_or_else(|| invalid("unclosed quote"))?)
+                            }
+                            Some(other) => name.push(other),
+                            None => return Err(invalid("unclosed quote")),
+                        }
+                    }
+                }
+                '\\' => {
+                    chars.next();
+                    literal = true;
+                    name.push(chars.next().ok_or_else(|| invalid("trailing `\\`"))?);
+                }
+                _ => {
+                    chars.next();
+                    name.push(c);
+                }
+            }
+        }
+
+        if literal || !name.is_empty() || chars.peek() != Some(&'[') {
+            segments.push(match parse_index(&name) {
+                Some(index) if !literal && (name == "0" || !name.starts_with('0')) => {
+                    Segment::Index(index)
+                }
+                _ => Segment::Key(name),
+            });
+        }
+
+        while chars.peek() == Some(&'[') {
+            chars.next();
+            let mut index = String::new();
+            loop {
+                match chars.next() {
+                    Some(']') => break,
+                    Some(c) => index.push(c),
+                    None => return Err(invalid("unclosed `[`")),
+                }
+            }
+            let index = parse_index(&index)
+                .ok_or_else(|| invalid("array index must be a non-negative integer"))?;
+            segments.push(Segment::Index(index));
+        }
+
+        match chars.next() {
+            None => return Ok(segments),
+            Some('.') => {}
+            Some(_) => return Err(invalid("expected `.` or `[` after `]`")),
+        }
+    }
+}
+
+/// Parse an RFC 6901 JSON Pointer such as `/packages//version`
+///
+/// Each `/` starts a segment, which may be empty and may contain dots;
+/// `~1` stands for `/` and `~0` for `~`. A segment of digits without a
+/// leading zero indexes into an array, and is looked up as a key when
+/// applied to a table or object, as in dot-separated paths.
+fn parse_pointer(key: &str) -> Result<Vec<Segment>> {
+    let invalid = |message: &str| Error::InvalidKey {
+        key: key.to_string(),
+        message: message.to_string(),
+    };
+    let Some(pointer) = key.strip_prefix('/') else {
+        return Err(invalid("a JSON Pointer starts with `/`"));
+    };
+
+    pointer
+        .split('/')
+        .map(|token| {
+            let mut name = String::new();
+            let mut chars = token.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    '~' => match chars.next() {
+                        Some('0') => name.push('~'),
+                        Some('1') => name.push('/'),
+                        _ => return Err(invalid("`~` must be followed by `0` or `1`")),
+                    },
+                    _ => name.push(c),
+                }
+            }
+            Ok(match parse_index(&name) {
+                Some(index) if name == "0" || !name.starts_with('0') => Segment::Index(index),
+                _ => Segment::Key(name),
+            })
+        })
+        .collect()
+}
+
+/// Parse a string of ASCII digits as an index
+fn parse_index(s: &str) -> Option<usize> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(name: &str) -> Segment {
+        Segment::Key(name.to_string())
+    }
+
+    #[test]
+    fn test_parse_keys() {
+        assert_eq!(
+            parse("package.version").unwrap(),
+            [key("package"), key("version")]
+        );
+        assert_eq!(
+            parse("packages..version").unwrap(),
+            [key("packages"), key(""), key("version")]
+        );
+    }
+
+    #[test]
+    fn test_parse_indices() {
+        let expected = [key("releases"), Segment::Index(0), key("version")];
+        assert_eq!(parse("releases[0].version").unwrap(), expected);
+        assert_eq!(parse("releases.0.version").unwrap(), expected);
+        assert_eq!(
+            parse("matrix[1][2]").unwrap(),
+            [key("matrix"), Segment::Index(1), Segment::Index(2)]
+        );
+        assert_eq!(parse("[3]").unwrap(), [Segment::Index(3)]);
+        assert_eq!(parse("v.01.+1").unwrap(), [key("v"), key("01"), key("+1")]);
+    }
+
+    #[test]
+    fn test_parse_escaped_dots() {
+        let expected = [key("my.app"), key("version")];
+        assert_eq!(parse(r#""my.app".version"#).unwrap(), expected);
+        assert_eq!(parse("'my.app'.version").unwrap(), expected);
+        assert_eq!(parse(r"my\.app.version").unwrap(), expected);
+        assert_eq!(
+            parse(r#"packages."".version"#).unwrap(),
+            [key("packages"), key(""), key("version")]
+        );
+        assert_eq!(
+            parse(r#""a\"b\\c"[0]"#).unwrap(),
+            [key(r#"a"b\c"#), Segment::Index(0)]
+        );
+        assert_eq!(parse(r"'a\b'").unwrap(), [key(r"a\b")]);
+        assert_eq!(parse(r#""0".v"#).unwrap(), [key("0"), key("v")]);
+    }
+
+    #[test]
+    fn test_parse_pointer() {
+        assert_eq!(
+            parse("/packages//version").unwrap(),
+            [key("packages"), key(""), key("version")]
+        );
+        assert_eq!(
+            parse("/releases/0/version").unwrap(),
+            [key("releases"), Segment::Index(0), key("version")]
+        );
+        assert_eq!(
+            parse("/a~1b/c.d/~0e/01").unwrap(),
+            [key("a/b"), key("c.d"), key("~e"), key("01")]
+        );
+        assert_eq!(parse("/").unwrap(), [key("")]);
+        for pointer in ["/a~", "/a~2"] {
+            assert!(matches!(parse(pointer), Err(Error::InvalidKey { .. })));
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for key in [
+            "a[", "a[1", "a[x]", "a[-1]", "a[+1]", "a[0]b", "a[]", "\"a.b", "'a", "a\\",
+        ] {
+            assert!(
+                matches!(parse(key), Err(Error::InvalidKey { .. })),
+                "{}",
+                key
+            );
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use crate::error::{Error, Result};
+use crate::format::pattern::{find_unique, splice, unquote};
+use regex::Regex;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+/// Read the version value from a Makefile
+pub fn read_version(path: &Path, key: &str) -> Result<String> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    parse_version(&content, path, key)
+}
+
+/// Write the version value to a Makefile
+/// Only the value is replaced; the rest of the file is kept exactly
+pub fn write_version(path: &Path, key: &str, version: &str) -> Result<()> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    let updated = update_version(&content, path, key, version)?;
+
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Read the version value from a Makefile (`path` is used for error messages)
+///
+/// The key is the variable name, assigned with `=`, `:=`, `::=`, `:::=` or
+/// `?=` and optionally prefixed with `export` or `override`. The value runs
+/// to the end of the line or a `#` comment, without surrounding whitespace
+/// (and inside quotes, if it is quoted). Values referring to other variables
+/// or functions (`$(...)`) are not matched, and the variable must be
+/// assigned exactly once.
+pub fn parse_version(content: &str, path: &Path, key: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(content[span].to_string())
+}
+
+/// Return the Makefile with the value of the variable replaced
+pub fn update_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(splice(content, span, version))
+}
+
+/// Locate the value of the variable, inside its quotes if it has any
+fn find_value(content: &str, path: &Path, key: &str) -> Result<Range<usize>> {
+    // Lines starting with a tab are recipe lines, not assignments
+    let pattern = Regex::new(&format!(
+        r"(?m)^ *(?:(?:export|override)[ \t]+)*{}[ \t]*(?::{{1,3}}=|\?=|=)[ \t]*(?P<value>[^\s#$\\](?:[^#$\\\n]*[^\s#$\\])?|)[ \t]*(?:#.*)?$",
+        regex::escape(key)
+    ))
+    .map_err(|e| Error::TargetParse {
+        file: path.to_path_buf(),
+        message: e.to_string(),
+    })?;
+
+    let span = find_unique(content, path, key, &pattern)?;
+    Ok(unquote(content, span))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAKEFILE: &str = "\
+# Release settings
+VERSION := 1.2.3 # bumped by versync
+export IMAGE_TAG ?= v1.2.3
+NAME = \"example\"
+COMMIT := $(shell git rev-parse HEAD)
+
+build:
+\tVERSION=0.0.0 ./build.sh $(VERSION)
+";
+
+    #[test]
+    fn test_read_assignments() {
+        let path = Path::new("Makefile");
+        assert_eq!(parse_version(MAKEFILE, path, "VERSION").unwrap(), "1.2.3");
+        assert_eq!(
+            parse_version(MAKEFILE, path, "IMAGE_TAG").unwrap(),
+            "v1.2.3"
+        );
+        assert_eq!(parse_version(MAKEFILE, path, "NAME").unwrap(), "example");
+    }
+
+    #[test]
+    fn test_update_preserves_file() {
+        let path = Path::new("Makefile");
+        let updated = update_version(MAKEFILE, path, "VERSION", "2.0.0").unwrap();
+        assert_eq!(
+            updated,
+            MAKEFILE.replace("VERSION := 1.2.3 #", "VERSION := 2.0.0 #")
+        );
+    }
+
+    #[test]
+    fn test_empty_value() {
+        let path = Path::new("Makefile");
+        assert_eq!(parse_version("V ::=\n", path, "V").unwrap(), "");
+        assert_eq!(
+            update_version("V ::=\n", path, "V", "1.0.0").unwrap(),
+            "V ::=1.0.0\n"
+        );
+    }
+
+    #[test]
+    fn test_references_are_not_matched() {
+        let path = Path::new("Makefile");
+        assert!(matches!(
+            parse_version(MAKEFILE, path, "COMMIT"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        assert!(matches!(
+            parse_version("V := 1\nV += 2\nV ?= 3\n", path, "V"),
+            Err(Error::AmbiguousKey { count: 2, .. })
+        ));
+    }
+}
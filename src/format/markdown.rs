@@ -0,0 +1,171 @@
+use crate::error::{Error, Result};
+use crate::format::pattern::splice;
+use regex::Regex;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+/// Placeholder marking the version in a Markdown key
+const PLACEHOLDER: &str = "{version}";
+
+/// A version: dotted numbers with optional prerelease and build metadata
+const VERSION: &str = r"[0-9]+(?:\.[0-9]+)*(?:-[0-9A-Za-z.-]+)?(?:\+[0-9A-Za-z.-]+)?";
+
+/// Read the version value from a Markdown file
+pub fn read_version(path: &Path, key: &str) -> Result<String> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    parse_version(&content, path, key)
+}
+
+/// Write the version value to a Markdown file
+/// Only the versions in the matching snippets are replaced
+pub fn write_version(path: &Path, key: &str, version: &str) -> Result<()> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    let updated = update_version(&content, path, key, version)?;
+
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Read the version value from Markdown content (`path` is used for error messages)
+///
+/// The key is a snippet of the text with `{version}` where the version
+/// appears, e.g. `pip install example=={version}` or
+/// `img.shields.io/badge/version-{version}-blue`. Every occurrence of the
+/// snippet is a place to update; when they disagree, the distinct versions
+/// are returned comma-separated so the target shows up as a mismatch.
+pub fn parse_version(content: &str, path: &Path, key: &str) -> Result<String> {
+    let mut values: Vec<&str> = Vec::new();
+    for span in find_values(content, path, key)? {
+        let value = &content[span];
+        if !values.contains(&value) {
+            values.push(value);
+        }
+    }
+    Ok(values.join(", "))
+}
+
+/// Return Markdown content with the version in every matching snippet replaced
+pub fn update_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    let spans = find_values(content, path, key)?;
+    Ok(spans
+        .into_iter()
+        .rev()
+        .fold(content.to_string(), |content, span| {
+            splice(&content, span, version)
+        }))
+}
+
+/// Locate the versions in all occurrences of the snippet
+fn find_values(content: &str, path: &Path, key: &str) -> Result<Vec<Range<usize>>> {
+    let Some((before, after)) = key
+        .split_once(PLACEHOLDER)
+        .filter(|(_, after)| !after.contains(PLACEHOLDER))
+    else {
+        return Err(Error::TargetParse {
+            file: path.to_path_buf(),
+            message: format!("key '{}' must contain {} exactly once", key, PLACEHOLDER),
+        });
+    };
+
+    let pattern = Regex::new(&format!(
+        "{}(?P<value>{}){}",
+        regex::escape(before),
+        VERSION,
+        regex::escape(after)
+    ))
+    .map_err(|e| Error::TargetParse {
+        file: path.to_path_buf(),
+        message: e.to_string(),
+    })?;
+
+    let spans: Vec<Range<usize>> = pattern
+        .captures_iter(content)
+        .filter_map(|caps| caps.name("value"))
+        .map(|m| m.range())
+        .collect();
+    if spans.is_empty() {
+        return Err(Error::KeyNotFound {
+            file: path.to_path_buf(),
+            key: key.to_string(),
+        });
+    }
+    Ok(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const README: &str = "\
+# example
+
+![version](https://img.shields.io/badge/version-1.2.3-blue)
+
+```bash
+pip install example==1.2.3
+```
+
+Pin it in requirements.txt: `example==1.2.3`.
+";
+
+    #[test]
+    fn test_read_snippets() {
+        let path = Path::new("README.md");
+        assert_eq!(
+            parse_version(README, path, "badge/version-{version}-blue").unwrap(),
+            "1.2.3"
+        );
+        assert_eq!(
+            parse_version(README, path, "example=={version}").unwrap(),
+            "1.2.3"
+        );
+    }
+
+    #[test]
+    fn test_update_every_occurrence() {
+        let path = Path::new("README.md");
+        let updated = update_version(README, path, "example=={version}", "2.0.0-rc.1").unwrap();
+        assert_eq!(
+            updated,
+            README.replace("example==1.2.3", "example==2.0.0-rc.1")
+        );
+
+        let updated = update_version(README, path, "version-{version}-blue", "2.0.0").unwrap();
+        assert_eq!(
+            updated,
+            README.replace("version-1.2.3-blue", "version-2.0.0-blue")
+        );
+    }
+
+    #[test]
+    fn test_disagreeing_occurrences() {
+        let path = Path::new("README.md");
+        let stale = README.replacen("example==1.2.3", "example==1.1.0", 1);
+        assert_eq!(
+            parse_version(&stale, path, "example=={version}").unwrap(),
+            "1.1.0, 1.2.3"
+        );
+    }
+
+    #[test]
+    fn test_invalid_and_missing_keys() {
+        let path = Path::new("README.md");
+        assert!(matches!(
+            parse_version(README, path, "example"),
+            Err(Error::TargetParse { .. })
+        ));
+        assert!(matches!(
+            parse_version(README, path, "{version}-{version}"),
+            Err(Error::TargetParse { .. })
+        ));
+        assert!(matches!(
+            parse_version(README, path, "npm install example@{version}"),
+            Err(Error::KeyNotFound { .. })
+        ));
+    }
+}
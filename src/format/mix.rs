@@ -0,0 +1,176 @@
+use crate::error::{Error, Result};
+use crate::format::pattern::{find_unique, splice};
+use regex::Regex;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+/// Read the version value from an Elixir mix.exs file
+pub fn read_version(path: &Path, key: &str) -> Result<String> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    parse_version(&content, path, key)
+}
+
+/// Write the version value to an Elixir mix.exs file
+/// Only the string is replaced; the rest of the file is kept exactly
+pub fn write_version(path: &Path, key: &str, version: &str) -> Result<()> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    let updated = update_version(&content, path, key, version)?;
+
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Read the version value from mix.exs content (`path` is used for error
+/// messages)
+///
+/// The key is an entry of the keyword list returned by `project/0`, e.g.
+/// `version` for `version: "1.2.3"`. Keywords elsewhere, such as dependency
+/// requirements in `deps/0`, are never considered. If the entry refers to a
+/// module attribute (`version: @version`), the attribute's string
+/// (`@version "1.2.3"`) is used instead.
+pub fn parse_version(content: &str, path: &Path, key: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(content[span].to_string())
+}
+
+/// Return mix.exs content with the string of the entry replaced
+pub fn update_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(splice(content, span, version))
+}
+
+/// Locate the contents of the string for the entry
+fn find_value(content: &str, path: &Path, key: &str) -> Result<Range<usize>> {
+    let regex = |pattern: &str| {
+        Regex::new(pattern).map_err(|e| Error::TargetParse {
+            file: path.to_path_buf(),
+            message: e.to_string(),
+        })
+    };
+
+    let project = project_body(content).ok_or_else(|| Error::TargetParse {
+        file: path.to_path_buf(),
+        message: "no `def project` in the file".to_string(),
+    })?;
+    let entry = regex(&format!(
+        r#"(?:^|[\[,\s]){}:[ \t]*(?P<value>"[^"\\\n]*"|@\w+)"#,
+        regex::escape(key)
+    ))?;
+    let span = find_unique(&content[project.clone()], path, key, &entry)?;
+    let span = project.start + span.start..project.start + span.end;
+
+    let value = &content[span.clone()];
+    let Some(attribute) = value.strip_prefix('@') else {
+        return Ok(span.start + 1..span.end - 1);
+    };
+    let definition = regex(&format!(
+        r#"(?m)^[ \t]*@{}[ \t]+(?P<value>"[^"\\\n]*")"#,
+        regex::escape(attribute)
+    ))?;
+    let span = find_unique(content, path, value, &definition)?;
+    Ok(span.start + 1..span.end - 1)
+}
+
+/// The lines of the `project/0` function, up to the next line indented no
+/// deeper than its `def` (its `end`, or the next function)
+fn project_body(content: &str) -> Option<Range<usize>> {
+    let mut start = None;
+    let mut def_indent = 0;
+    let mut offset = 0;
+
+    for line in content.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+
+        match start {
+            None => {
+                let is_project = trimmed.strip_prefix("def project").is_some_and(|rest| {
+                    rest.is_empty() || rest.starts_with([' ', ',', '(', '\n', '\r'])
+                });
+                if is_project {
+                    start = Some(line_start);
+                    def_indent = indent;
+                }
+            }
+            Some(start) if !trimmed.trim().is_empty() && indent <= def_indent => {
+                return Some(start..line_start);
+            }
+            Some(_) => {}
+        }
+    }
+    start.map(|start| start..content.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIX: &str = r#"defmodule Example.MixProject do
+  use Mix.Project
+
+  def project do
+    [
+      app: :example,
+      version: "1.2.3",
+      elixir: "~> 1.15",
+      deps: deps()
+    ]
+  end
+
+  defp deps do
+    [
+      {:jason, version: "~> 1.4"}
+    ]
+  end
+end
+"#;
+
+    #[test]
+    fn test_read_project_entry() {
+        let path = Path::new("mix.exs");
+        assert_eq!(parse_version(MIX, path, "version").unwrap(), "1.2.3");
+        assert_eq!(parse_version(MIX, path, "elixir").unwrap(), "~> 1.15");
+    }
+
+    #[test]
+    fn test_update_ignores_deps() {
+        let path = Path::new("mix.exs");
+        let updated = update_version(MIX, path, "version", "2.0.0").unwrap();
+        assert_eq!(
+            updated,
+            MIX.replace("version: \"1.2.3\"", "version: \"2.0.0\"")
+        );
+        assert!(updated.contains("{:jason, version: \"~> 1.4\"}"));
+    }
+
+    #[test]
+    fn test_module_attribute() {
+        let path = Path::new("mix.exs");
+        let content = "defmodule Example.MixProject do\n  use Mix.Project\n\n  @version \"1.2.3\"\n\n  def project, do: [app: :example, version: @version]\nend\n";
+        assert_eq!(parse_version(content, path, "version").unwrap(), "1.2.3");
+        assert_eq!(
+            update_version(content, path, "version", "1.3.0").unwrap(),
+            content.replace("@version \"1.2.3\"", "@version \"1.3.0\"")
+        );
+    }
+
+    #[test]
+    fn test_key_not_found() {
+        let path = Path::new("mix.exs");
+        assert!(matches!(
+            parse_version(MIX, path, "description"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        assert!(matches!(
+            parse_version("defmodule X do\nend\n", path, "version"),
+            Err(Error::TargetParse { .. })
+        ));
+    }
+}
@@ -1,22 +1,97 @@
+pub mod appstream;
+pub mod cargo_lock;
+pub mod cff;
+pub mod cmake;
+pub mod debian_changelog;
+pub mod dockerfile;
+pub mod dotenv;
+pub mod go;
+pub mod gradle;
+pub mod hcl;
+pub mod image;
+pub mod ini;
 pub mod json;
+pub mod jsonc;
+mod keypath;
+pub mod makefile;
+pub mod markdown;
+pub mod mix;
+pub mod openapi;
+mod pattern;
+pub mod plist;
+pub mod properties;
+pub mod python;
+pub mod raw;
+mod registry;
+pub mod rpm_spec;
+pub mod rust;
+pub mod shell;
 pub mod toml;
+pub mod wix;
+pub mod xml;
+pub mod yaml;
 
 use crate::config::FileFormat;
 use crate::error::Result;
 use std::path::Path;
 
+pub(crate) use registry::name_for_extension;
+pub use registry::{lookup, register, VersionFormat};
+
+/// Check the syntax of a key path without reading the file
+///
+/// Only the formats with dot-separated key paths (TOML, JSON, JSONC and
+/// plist) can reject a key up front; for the others any key is accepted here.
+pub fn check_key(key: &str, format: FileFormat) -> Result<()> {
+    registry::get(format)?.check_key(key)
+}
+
 /// Read the version value from a file at the specified key path
 pub fn read_version(path: &Path, key: &str, format: FileFormat) -> Result<String> {
-    match format {
-        FileFormat::Toml => toml::read_version(path, key),
-        FileFormat::Json => json::read_version(path, key),
-    }
+    registry::get(format)?.read_version(path, key)
 }
 
 /// Write the version value to a file at the specified key path
 pub fn write_version(path: &Path, key: &str, version: &str, format: FileFormat) -> Result<()> {
+    registry::get(format)?.write_version(path, key, version)
+}
+
+/// Read the version value from in-memory content (`path` is used for error messages)
+pub fn parse_version(content: &str, path: &Path, key: &str, format: FileFormat) -> Result<String> {
+    registry::get(format)?.parse_version(content, path, key)
+}
+
+/// Return in-memory content with the version at the key path replaced
+pub fn update_version(
+    content: &str,
+    path: &Path,
+    key: &str,
+    version: &str,
+    format: FileFormat,
+) -> Result<String> {
+    registry::get(format)?.update_version(content, path, key, version)
+}
+
+/// Whether [`insert_version`] can create missing keys in the format
+pub fn can_create(format: FileFormat) -> bool {
+    matches!(format, FileFormat::Toml | FileFormat::Json)
+}
+
+/// Return in-memory content with the version set at the key path, creating
+/// the key if it is missing
+///
+/// Formats that can't create keys (see [`can_create`]) only replace an
+/// existing value, like [`update_version`].
+pub fn insert_version(
+    content: &str,
+    path: &Path,
+    key: &str,
+    version: &str,
+    format: FileFormat,
+) -> Result<String> {
     match format {
-        FileFormat::Toml => toml::write_version(path, key, version),
-        FileFormat::Json => json::write_version(path, key, version),
+        FileFormat::Toml => toml::insert_version(content, path, key, version),
+        FileFormat::Json => json::insert_version(content, path, key, version),
+        _ => update_version(content, path, key, version, format),
     }
 }
@@ -0,0 +1,109 @@
+use crate::error::{Error, Result};
+use crate::format::jsonc;
+use crate::format::yaml;
+use std::fs;
+use std::path::Path;
+
+/// Read the version value from an OpenAPI or AsyncAPI spec
+pub fn read_version(path: &Path, key: &str) -> Result<String> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    parse_version(&content, path, key)
+}
+
+/// Write the version value to an OpenAPI or AsyncAPI spec
+/// Only the value is replaced; comments and formatting are kept
+pub fn write_version(path: &Path, key: &str, version: &str) -> Result<()> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    let updated = update_version(&content, path, key, version)?;
+
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Read the version value from spec content (`path` is used for error
+/// messages)
+///
+/// The key is a dot-separated path, usually `info.version`. JSON specs are
+/// read as JSONC; YAML specs may hold several documents, and every document
+/// with the key counts. If they disagree, all their values are returned,
+/// separated by `, `, so the target shows as out of date.
+pub fn parse_version(content: &str, path: &Path, key: &str) -> Result<String> {
+    if is_json(content) {
+        return jsonc::parse_version(content, path, key);
+    }
+
+    yaml::parse_version(content, path, key)
+}
+
+/// Return spec content with the value replaced in every document
+pub fn update_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    if is_json(content) {
+        return jsonc::update_version(content, path, key, version);
+    }
+
+    yaml::update_version(content, path, key, version)
+}
+
+fn is_json(content: &str) -> bool {
+    content.trim_start().starts_with('{')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = "\
+openapi: 3.1.0
+info:
+  title: Example API
+  version: 1.2.3
+paths: {}
+";
+
+    #[test]
+    fn test_read_yaml_and_json() {
+        let path = Path::new("openapi.yaml");
+        assert_eq!(parse_version(SPEC, path, "info.version").unwrap(), "1.2.3");
+        let json = "{\n  \"openapi\": \"3.1.0\",\n  \"info\": { \"version\": \"1.2.3\" }\n}\n";
+        let path = Path::new("openapi.json");
+        assert_eq!(parse_version(json, path, "info.version").unwrap(), "1.2.3");
+        assert_eq!(
+            update_version(json, path, "info.version", "2.0.0").unwrap(),
+            json.replace("1.2.3", "2.0.0")
+        );
+    }
+
+    #[test]
+    fn test_update_preserves_spec() {
+        let path = Path::new("openapi.yaml");
+        assert_eq!(
+            update_version(SPEC, path, "info.version", "2.0.0").unwrap(),
+            SPEC.replace("version: 1.2.3", "version: 2.0.0")
+        );
+    }
+
+    #[test]
+    fn test_multiple_documents() {
+        let path = Path::new("asyncapi.yaml");
+        let content = "asyncapi: 3.0.0\ninfo:\n  version: '1.2.3'\n---\nasyncapi: 3.0.0\ninfo:\n  version: '1.2.2'\n";
+        assert_eq!(
+            parse_version(content, path, "info.version").unwrap(),
+            "1.2.3, 1.2.2"
+        );
+        let updated = update_version(content, path, "info.version", "1.3.0").unwrap();
+        assert_eq!(
+            updated,
+            content
+                .replace("'1.2.3'", "'1.3.0'")
+                .replace("'1.2.2'", "'1.3.0'")
+        );
+        assert_eq!(
+            parse_version(&updated, path, "info.version").unwrap(),
+            "1.3.0"
+        );
+    }
+}
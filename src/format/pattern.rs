@@ -0,0 +1,86 @@
+use crate::error::{Error, Result};
+use regex::Regex;
+use std::ops::Range;
+use std::path::Path;
+
+/// Locate the `value` group of the only match of a pattern
+///
+/// Source files have no structure to address a key by, so formats such as
+/// Python and Rust sources find the version with a pattern built from the
+/// key. No match is `KeyNotFound`; several matches are `AmbiguousKey`, since
+/// updating an arbitrary one would leave the others stale.
+pub fn find_unique(content: &str, path: &Path, key: &str, pattern: &Regex) -> Result<Range<usize>> {
+    let spans: Vec<Range<usize>> = pattern
+        .captures_iter(content)
+        .filter_map(|caps| caps.name("value"))
+        .map(|m| m.range())
+        .collect();
+
+    match spans.as_slice() {
+        [span] => Ok(span.clone()),
+        [] => Err(Error::KeyNotFound {
+            file: path.to_path_buf(),
+            key: key.to_string(),
+        }),
+        _ => Err(Error::AmbiguousKey {
+            file: path.to_path_buf(),
+            key: key.to_string(),
+            count: spans.len(),
+        }),
+    }
+}
+
+/// Replace a byte range of the content
+pub fn splice(content: &str, span: Range<usize>, value: &str) -> String {
+    format!(
+        "{}{}{}",
+        &content[..span.start],
+        value,
+        &content[span.end..]
+    )
+}
+
+/// Narrow the span of a quoted string literal to its contents
+pub fn unquote(content: &str, span: Range<usize>) -> Range<usize> {
+    let value = &content[span.clone()];
+    let quoted = value.len() >= 2
+        && ['"', '\'']
+            .iter()
+            .any(|q| value.starts_with(*q) && value.ends_with(*q));
+    if quoted {
+        span.start + 1..span.end - 1
+    } else {
+        span
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_unique() {
+        let path = Path::new("a.txt");
+        let pattern = Regex::new(r"(?m)^v=(?P<value>\S+)$").unwrap();
+        assert_eq!(
+            find_unique("x\nv=1.0\n", path, "v", &pattern).unwrap(),
+            4..7
+        );
+        assert!(matches!(
+            find_unique("x\n", path, "v", &pattern),
+            Err(Error::KeyNotFound { .. })
+        ));
+        assert!(matches!(
+            find_unique("v=1\nv=2\n", path, "v", &pattern),
+            Err(Error::AmbiguousKey { count: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_splice_and_unquote() {
+        assert_eq!(splice("v=1.0;", 2..5, "2.0"), "v=2.0;");
+        assert_eq!(unquote("v='1.0'", 2..7), 3..6);
+        assert_eq!(unquote("v=1.0", 2..5), 2..5);
+        assert_eq!(unquote("v=\"1.0'", 2..7), 2..7);
+    }
+}
@@ -0,0 +1,291 @@
+use crate::error::{Error, Result};
+use crate::format::keypath::{self, Segment};
+use crate::format::pattern::splice;
+use quick_xml::escape::{escape, unescape};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+/// Read the version value from a property list at the specified key path
+pub fn read_version(path: &Path, key: &str) -> Result<String> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    parse_version(&content, path, key)
+}
+
+/// Write the version value to a property list at the specified key path
+/// Only the string is replaced; everything else is kept byte for byte
+pub fn write_version(path: &Path, key: &str, version: &str) -> Result<()> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    let updated = update_version(&content, path, key, version)?;
+
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Read the version value from XML property list content (`path` is used for
+/// error messages)
+///
+/// The key is a dot-separated path of dictionary keys and array indices from
+/// the root dictionary, e.g. `CFBundleShortVersionString`; keys containing
+/// dots are quoted as for JSON. The value must be a `<string>`.
+pub fn parse_version(content: &str, path: &Path, key: &str) -> Result<String> {
+    match find_string(content, path, key)? {
+        Found::Text(span) => unescape(&content[span])
+            .map(|text| text.into_owned())
+            .map_err(|e| Error::TargetParse {
+                file: path.to_path_buf(),
+                message: e.to_string(),
+            }),
+        Found::Empty(_) => Ok(String::new()),
+    }
+}
+
+/// Return property list content with the string at the key path replaced
+pub fn update_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    Ok(match find_string(content, path, key)? {
+        Found::Text(span) => splice(content, span, &escape(version)),
+        Found::Empty(span) => splice(
+            content,
+            span,
+            &format!("<string>{}</string>", escape(version)),
+        ),
+    })
+}
+
+/// Location of a `<string>` value
+enum Found {
+    /// The span of the text of `<string>...</string>`
+    Text(Range<usize>),
+    /// The span of a self-closing `<string/>`
+    Empty(Range<usize>),
+}
+
+/// An open `<dict>` or `<array>`
+enum Frame {
+    /// A dictionary and the last `<key>` read in it
+    Dict { key: Option<String>, nested: bool },
+    /// An array and the index of its next value
+    Array { next: usize, nested: bool },
+}
+
+/// Locate the `<string>` at the key path
+fn find_string(content: &str, path: &Path, key: &str) -> Result<Found> {
+    let target = keypath::parse(key)?;
+    let parse_error = |e: quick_xml::Error| Error::TargetParse {
+        file: path.to_path_buf(),
+        message: e.to_string(),
+    };
+    let not_string = || Error::ValueNotString {
+        file: path.to_path_buf(),
+        key: key.to_string(),
+    };
+
+    let mut reader = Reader::from_str(content);
+    let mut frames: Vec<Frame> = Vec::new();
+    // Key path of the innermost open container
+    let mut location: Vec<Segment> = Vec::new();
+
+    loop {
+        let before = reader.buffer_position() as usize;
+        let (element, empty) = match reader.read_event().map_err(parse_error)? {
+            Event::Start(e) => (e, false),
+            Event::Empty(e) => (e, true),
+            Event::End(e) => {
+                if matches!(e.local_name().as_ref(), "dict" | "array") {
+                    let nested = match frames.pop() {
+                        Some(Frame::Dict { nested, .. } | Frame::Array { nested, .. }) => nested,
+                        None => false,
+                    };
+                    if nested {
+                        location.pop();
+                    }
+                }
+                continue;
+            }
+            Event::Eof => break,
+            _ => continue,
+        };
+
+        let name = element.local_name().as_ref().to_string();
+        if name == "plist" {
+            continue;
+        }
+        if name == "key" {
+            let text = if empty {
+                String::new()
+            } else {
+                let span = reader.read_to_end(element.name()).map_err(parse_error)?;
+                let raw = &content[span.start as usize..span.end as usize];
+                unescape(raw)
+                    .map_err(|e| Error::TargetParse {
+                        file: path.to_path_buf(),
+                        message: e.to_string(),
+                    })?
+                    .into_owned()
+            };
+            match frames.last_mut() {
+                Some(Frame::Dict { key, .. }) => *key = Some(text),
+                _ => {
+                    return Err(Error::TargetParse {
+                        file: path.to_path_buf(),
+                        message: "<key> outside of a <dict>".to_string(),
+                    })
+                }
+            }
+            continue;
+        }
+
+        // A value: its segment in the enclosing container
+        let segment = match frames.last_mut() {
+            Some(Frame::Dict { key, .. }) => key.take().map(Segment::Key),
+            Some(Frame::Array { next, .. }) => {
+                *next += 1;
+                Some(Segment::Index(*next - 1))
+            }
+            None => None,
+        };
+        let at_target = segment.as_ref().is_some_and(|segment| {
+            location.len() + 1 == target.len()
+                && location
+                    .iter()
+                    .chain(std::iter::once(segment))
+                    .zip(&target)
+                    .all(|(at, wanted)| matches_segment(at, wanted))
+        });
+
+        match (name.as_str(), empty) {
+            ("string", true) if at_target => {
+                return Ok(Found::Empty(before..reader.buffer_position() as usize))
+            }
+            ("string", false) if at_target => {
+                let span = reader.read_to_end(element.name()).map_err(parse_error)?;
+                return Ok(Found::Text(span.start as usize..span.end as usize));
+            }
+            _ if at_target => return Err(not_string()),
+            ("dict" | "array", false) => {
+                let nested = segment.is_some();
+                if let Some(segment) = segment {
+                    location.push(segment);
+                }
+                frames.push(if name == "dict" {
+                    Frame::Dict { key: None, nested }
+                } else {
+                    Frame::Array { next: 0, nested }
+                });
+            }
+            (_, false) => {
+                reader.read_to_end(element.name()).map_err(parse_error)?;
+            }
+            (_, true) => {}
+        }
+    }
+
+    Err(Error::KeyNotFound {
+        file: path.to_path_buf(),
+        key: key.to_string(),
+    })
+}
+
+/// Whether a position in the document matches a segment of the key path
+fn matches_segment(at: &Segment, wanted: &Segment) -> bool {
+    match (at, wanted) {
+        (Segment::Key(at), Segment::Key(wanted)) => at == wanted,
+        (Segment::Index(at), Segment::Index(wanted)) => at == wanted,
+        (Segment::Key(at), Segment::Index(_)) => *at == wanted.key(),
+        (Segment::Index(_), Segment::Key(_)) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INFO_PLIST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>CFBundleName</key>
+	<string>Example</string>
+	<key>CFBundleShortVersionString</key>
+	<string>1.2.3</string>
+	<key>CFBundleVersion</key>
+	<string>42</string>
+	<key>CFBundleURLTypes</key>
+	<array>
+		<dict>
+			<key>CFBundleURLName</key>
+			<string>com.example.app</string>
+		</dict>
+	</array>
+	<key>LSRequiresIPhoneOS</key>
+	<true/>
+</dict>
+</plist>
+"#;
+
+    #[test]
+    fn test_read_keys() {
+        let path = Path::new("Info.plist");
+        assert_eq!(
+            parse_version(INFO_PLIST, path, "CFBundleShortVersionString").unwrap(),
+            "1.2.3"
+        );
+        assert_eq!(
+            parse_version(INFO_PLIST, path, "CFBundleVersion").unwrap(),
+            "42"
+        );
+        assert_eq!(
+            parse_version(INFO_PLIST, path, "CFBundleURLTypes[0].CFBundleURLName").unwrap(),
+            "com.example.app"
+        );
+    }
+
+    #[test]
+    fn test_update_preserves_document() {
+        let path = Path::new("Info.plist");
+        let updated =
+            update_version(INFO_PLIST, path, "CFBundleShortVersionString", "2.0.0").unwrap();
+        assert_eq!(
+            updated,
+            INFO_PLIST.replace("<string>1.2.3</string>", "<string>2.0.0</string>")
+        );
+    }
+
+    #[test]
+    fn test_empty_string() {
+        let path = Path::new("Info.plist");
+        let content = "<plist><dict><key>V</key><string/></dict></plist>";
+        assert_eq!(parse_version(content, path, "V").unwrap(), "");
+        assert_eq!(
+            update_version(content, path, "V", "1.0").unwrap(),
+            "<plist><dict><key>V</key><string>1.0</string></dict></plist>"
+        );
+    }
+
+    #[test]
+    fn test_not_a_string_or_missing() {
+        let path = Path::new("Info.plist");
+        assert!(matches!(
+            parse_version(INFO_PLIST, path, "LSRequiresIPhoneOS"),
+            Err(Error::ValueNotString { .. })
+        ));
+        assert!(matches!(
+            parse_version(INFO_PLIST, path, "CFBundleURLTypes"),
+            Err(Error::ValueNotString { .. })
+        ));
+        assert!(matches!(
+            parse_version(INFO_PLIST, path, "CFBundleIdentifier"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        assert!(matches!(
+            parse_version(INFO_PLIST, path, "CFBundleName.x"),
+            Err(Error::KeyNotFound { .. })
+        ));
+    }
+}
@@ -0,0 +1,174 @@
+use crate::error::{Error, Result};
+use crate::format::pattern::splice;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+/// Read the version value from a properties file
+pub fn read_version(path: &Path, key: &str) -> Result<String> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    parse_version(&content, path, key)
+}
+
+/// Write the version value to a properties file
+/// Only the value is replaced; every other line is kept exactly
+pub fn write_version(path: &Path, key: &str, version: &str) -> Result<()> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    let updated = update_version(&content, path, key, version)?;
+
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Read the version value from properties content (`path` is used for error messages)
+///
+/// The key is the full property name (e.g. `version` or `app.version`);
+/// `key=value`, `key: value` and `key value` are all accepted. As in Java,
+/// the last definition of a key wins.
+pub fn parse_version(content: &str, path: &Path, key: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(content[span].to_string())
+}
+
+/// Return properties content with the value of the key replaced
+pub fn update_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(splice(content, span, version))
+}
+
+/// Locate the value of the last definition of the key
+fn find_value(content: &str, path: &Path, key: &str) -> Result<Range<usize>> {
+    let mut found = None;
+    let mut offset = 0;
+    let mut continued = false;
+    for line in content.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+
+        let line = line.trim_end_matches(['\n', '\r']);
+        // Lines continuing the previous value are part of it, not entries
+        let continuation = continued;
+        continued = ends_with_continuation(line);
+        if continuation {
+            continue;
+        }
+
+        let entry = line.trim_start();
+        if entry.is_empty() || entry.starts_with(['#', '!']) {
+            continue;
+        }
+        let entry_start = start + line.len() - entry.len();
+
+        let key_end = key_length(entry);
+        if entry[..key_end] != *key {
+            continue;
+        }
+
+        let rest = &entry[key_end..];
+        let after_space = rest.trim_start_matches([' ', '\t', '\x0c']);
+        let after_separator = after_space
+            .strip_prefix(['=', ':'])
+            .unwrap_or(after_space)
+            .trim_start_matches([' ', '\t', '\x0c']);
+        let value_start = entry_start + entry.len() - after_separator.len();
+        let value = after_separator.trim_end();
+        found = Some(value_start..value_start + value.len());
+    }
+
+    found.ok_or_else(|| Error::KeyNotFound {
+        file: path.to_path_buf(),
+        key: key.to_string(),
+    })
+}
+
+/// Length of the key at the start of an entry, up to the first unescaped
+/// separator (`=`, `:` or whitespace)
+fn key_length(entry: &str) -> usize {
+    let mut escaped = false;
+    for (i, c) in entry.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if matches!(c, '=' | ':' | ' ' | '\t' | '\x0c') {
+            return i;
+        }
+    }
+    entry.len()
+}
+
+/// Whether a line ends with an odd number of backslashes
+fn ends_with_continuation(line: &str) -> bool {
+    line.chars().rev().take_while(|c| *c == '\\').count() % 2 == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRADLE_PROPERTIES: &str = "\
+# Project settings
+org.gradle.jvmargs=-Xmx2g \\
+    -Dversion=0.0.0
+group = com.example
+version=1.2.3
+app.version: 1.2.3
+! legacy
+legacy.version 0.9.0
+";
+
+    #[test]
+    fn test_read_separators() {
+        let path = Path::new("gradle.properties");
+        assert_eq!(
+            parse_version(GRADLE_PROPERTIES, path, "version").unwrap(),
+            "1.2.3"
+        );
+        assert_eq!(
+            parse_version(GRADLE_PROPERTIES, path, "app.version").unwrap(),
+            "1.2.3"
+        );
+        assert_eq!(
+            parse_version(GRADLE_PROPERTIES, path, "legacy.version").unwrap(),
+            "0.9.0"
+        );
+    }
+
+    #[test]
+    fn test_update_preserves_other_lines() {
+        let path = Path::new("gradle.properties");
+        let updated = update_version(GRADLE_PROPERTIES, path, "version", "2.0.0").unwrap();
+        assert_eq!(
+            updated,
+            GRADLE_PROPERTIES.replace("\nversion=1.2.3", "\nversion=2.0.0")
+        );
+    }
+
+    #[test]
+    fn test_last_definition_wins() {
+        let path = Path::new("a.properties");
+        let content = "version=1.0.0\nversion=1.1.0\r\n";
+        assert_eq!(parse_version(content, path, "version").unwrap(), "1.1.0");
+        assert_eq!(
+            update_version(content, path, "version", "2.0.0").unwrap(),
+            "version=1.0.0\nversion=2.0.0\r\n"
+        );
+    }
+
+    #[test]
+    fn test_key_not_found() {
+        let path = Path::new("gradle.properties");
+        assert!(matches!(
+            parse_version(GRADLE_PROPERTIES, path, "-Dversion"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        assert!(matches!(
+            parse_version(GRADLE_PROPERTIES, path, "ver"),
+            Err(Error::KeyNotFound { .. })
+        ));
+    }
+}
@@ -0,0 +1,130 @@
+use crate::error::{Error, Result};
+use crate::format::pattern::{find_unique, splice, unquote};
+use regex::Regex;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+/// Read the version value from a Python module
+pub fn read_version(path: &Path, key: &str) -> Result<String> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    parse_version(&content, path, key)
+}
+
+/// Write the version value to a Python module
+/// Only the string literal is replaced; the rest of the file is kept exactly
+pub fn write_version(path: &Path, key: &str, version: &str) -> Result<()> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    let updated = update_version(&content, path, key, version)?;
+
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Read the version value from Python source (`path` is used for error messages)
+///
+/// The key is the name of a module-level variable assigned a string literal,
+/// typically `__version__` (`__version__ = "1.2.3"`, single or double quotes,
+/// optionally annotated as `__version__: str = ...`). It must be assigned
+/// exactly once.
+pub fn parse_version(content: &str, path: &Path, key: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(content[span].to_string())
+}
+
+/// Return Python source with the string assigned to the variable replaced
+pub fn update_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(splice(content, span, version))
+}
+
+/// Locate the contents of the string literal assigned to the variable
+fn find_value(content: &str, path: &Path, key: &str) -> Result<Range<usize>> {
+    let pattern = Regex::new(&format!(
+        r#"(?m)^{}[ \t]*(?::[^=\n]*)?=[ \t]*(?P<value>"[^"\n]*"|'[^'\n]*')"#,
+        regex::escape(key)
+    ))
+    .map_err(|e| Error::TargetParse {
+        file: path.to_path_buf(),
+        message: e.to_string(),
+    })?;
+
+    let span = find_unique(content, path, key, &pattern)?;
+    Ok(unquote(content, span))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INIT_PY: &str = r#""""Example package."""
+
+from ._core import main
+
+__all__ = ["main"]
+__version__ = "1.2.3"
+
+
+def version() -> str:
+    __version__ = "shadowed"
+    return __version__
+"#;
+
+    #[test]
+    fn test_read_version() {
+        let path = Path::new("__init__.py");
+        assert_eq!(
+            parse_version(INIT_PY, path, "__version__").unwrap(),
+            "1.2.3"
+        );
+    }
+
+    #[test]
+    fn test_update_preserves_file() {
+        let path = Path::new("__init__.py");
+        let updated = update_version(INIT_PY, path, "__version__", "2.0.0").unwrap();
+        assert_eq!(
+            updated,
+            INIT_PY.replace("__version__ = \"1.2.3\"", "__version__ = \"2.0.0\"")
+        );
+    }
+
+    #[test]
+    fn test_single_quotes_and_annotation() {
+        let path = Path::new("_version.py");
+        let content = "__version__: str='0.1.0'  # managed by versync\n";
+        assert_eq!(
+            parse_version(content, path, "__version__").unwrap(),
+            "0.1.0"
+        );
+        assert_eq!(
+            update_version(content, path, "__version__", "0.2.0").unwrap(),
+            "__version__: str='0.2.0'  # managed by versync\n"
+        );
+    }
+
+    #[test]
+    fn test_missing_and_ambiguous() {
+        let path = Path::new("_version.py");
+        assert!(matches!(
+            parse_version("VERSION = '1.0'\n", path, "__version__"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        assert!(matches!(
+            parse_version("__version__ = version('pkg')\n", path, "__version__"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        assert!(matches!(
+            parse_version(
+                "__version__ = '1'\n__version__ = '2'\n",
+                path,
+                "__version__"
+            ),
+            Err(Error::AmbiguousKey { count: 2, .. })
+        ));
+    }
+}
@@ -0,0 +1,115 @@
+use crate::error::{Error, Result};
+use std::fs;
+use std::path::Path;
+
+/// Read the version from a file holding nothing but the version
+pub fn read_version(path: &Path, key: &str) -> Result<String> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    parse_version(&content, path, key)
+}
+
+/// Write the version to a file holding nothing but the version
+/// The trailing newline follows the policy in the key
+pub fn write_version(path: &Path, key: &str, version: &str) -> Result<()> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    let updated = update_version(&content, path, key, version)?;
+
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Read the version from raw content: the whole content, without
+/// surrounding whitespace (`path` is used for error messages)
+pub fn parse_version(content: &str, path: &Path, key: &str) -> Result<String> {
+    check_key(key)?;
+    let version = content.trim();
+    if version.contains('\n') {
+        return Err(Error::TargetParse {
+            file: path.to_path_buf(),
+            message: "expected the version alone, found several lines".to_string(),
+        });
+    }
+    Ok(version.to_string())
+}
+
+/// Return the version as the new content
+///
+/// The key sets the trailing newline: empty (the default) keeps the file's
+/// convention, `\n`, `\r\n` or none, and adds `\n` to an empty file;
+/// `newline` always ends the file with `\n` and `no-newline` never does.
+pub fn update_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    parse_version(content, path, key)?;
+    let newline = match key {
+        "newline" => "\n",
+        "no-newline" => "",
+        _ if content.is_empty() => "\n",
+        _ if content.ends_with("\r\n") => "\r\n",
+        _ if content.ends_with('\n') => "\n",
+        _ => "",
+    };
+    Ok(format!("{}{}", version, newline))
+}
+
+/// Check that the key is a trailing-newline policy
+pub fn check_key(key: &str) -> Result<()> {
+    match key {
+        "" | "newline" | "no-newline" => Ok(()),
+        _ => Err(Error::InvalidKey {
+            key: key.to_string(),
+            message: "raw targets take no key, or `newline` or `no-newline`".to_string(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_trims() {
+        let path = Path::new("VERSION");
+        assert_eq!(parse_version("1.2.3\n", path, "").unwrap(), "1.2.3");
+        assert_eq!(parse_version("  1.2.3\r\n", path, "").unwrap(), "1.2.3");
+        assert_eq!(parse_version("", path, "").unwrap(), "");
+        assert!(matches!(
+            parse_version("1.2.3\n1.2.4\n", path, ""),
+            Err(Error::TargetParse { .. })
+        ));
+    }
+
+    #[test]
+    fn test_update_preserves_newline() {
+        let path = Path::new("VERSION");
+        assert_eq!(
+            update_version("1.2.3\n", path, "", "2.0.0").unwrap(),
+            "2.0.0\n"
+        );
+        assert_eq!(
+            update_version("1.2.3\r\n", path, "", "2.0.0").unwrap(),
+            "2.0.0\r\n"
+        );
+        assert_eq!(update_version("1.2.3", path, "", "2.0.0").unwrap(), "2.0.0");
+        assert_eq!(update_version("", path, "", "2.0.0").unwrap(), "2.0.0\n");
+    }
+
+    #[test]
+    fn test_newline_policy() {
+        let path = Path::new("VERSION");
+        assert_eq!(
+            update_version("1.2.3", path, "newline", "2.0.0").unwrap(),
+            "2.0.0\n"
+        );
+        assert_eq!(
+            update_version("1.2.3\n", path, "no-newline", "2.0.0").unwrap(),
+            "2.0.0"
+        );
+        assert!(matches!(
+            parse_version("1.2.3\n", path, "version"),
+            Err(Error::InvalidKey { .. })
+        ));
+    }
+}
@@ -0,0 +1,271 @@
+use super::*;
+use crate::error::{Error, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// A file format versync can read and write versions in
+///
+/// Implement `parse_version` and `update_version` on in-memory content and
+/// register the format with [`register`]; targets can then select it with
+/// `format = "<name>"`, or by extension if it declares any. The file-based
+/// methods read and write the whole file and rarely need overriding.
+pub trait VersionFormat: Send + Sync {
+    /// The name used in the config (`format = "..."`)
+    fn name(&self) -> &'static str;
+
+    /// File extensions (without the dot) the format is inferred from
+    ///
+    /// Built-in formats take precedence for the extensions they claim.
+    fn extensions(&self) -> &[&'static str] {
+        &[]
+    }
+
+    /// Check the syntax of a key without reading a file
+    fn check_key(&self, _key: &str) -> Result<()> {
+        Ok(())
+    }
+
+    /// Read the version value from in-memory content (`path` is used for
+    /// error messages)
+    fn parse_version(&self, content: &str, path: &Path, key: &str) -> Result<String>;
+
+    /// Return in-memory content with the version at the key replaced
+    fn update_version(
+        &self,
+        content: &str,
+        path: &Path,
+        key: &str,
+        version: &str,
+    ) -> Result<String>;
+
+    /// Read the version value from a file
+    fn read_version(&self, path: &Path, key: &str) -> Result<String> {
+        let content =
+            fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+        self.parse_version(&content, path, key)
+    }
+
+    /// Write the version value to a file
+    fn write_version(&self, path: &Path, key: &str, version: &str) -> Result<()> {
+        let content =
+            fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+        let updated = self.update_version(&content, path, key, version)?;
+        fs::write(path, updated)?;
+        Ok(())
+    }
+}
+
+type ParseFn = fn(&str, &Path, &str) -> Result<String>;
+type UpdateFn = fn(&str, &Path, &str, &str) -> Result<String>;
+type ReadFn = fn(&Path, &str) -> Result<String>;
+type WriteFn = fn(&Path, &str, &str) -> Result<()>;
+
+/// A built-in format, backed by the functions of its module
+struct Builtin {
+    format: FileFormat,
+    read: ReadFn,
+    write: WriteFn,
+    parse: ParseFn,
+    update: UpdateFn,
+}
+
+impl VersionFormat for Builtin {
+    fn name(&self) -> &'static str {
+        self.format.name()
+    }
+
+    fn check_key(&self, key: &str) -> Result<()> {
+        match self.format {
+            FileFormat::Toml
+            | FileFormat::Json
+            | FileFormat::Jsonc
+            | FileFormat::Plist
+            | FileFormat::Openapi
+            | FileFormat::Yaml => keypath::parse(key).map(|_| ()),
+            FileFormat::Raw => raw::check_key(key),
+            _ => Ok(()),
+        }
+    }
+
+    fn parse_version(&self, content: &str, path: &Path, key: &str) -> Result<String> {
+        (self.parse)(content, path, key)
+    }
+
+    fn update_version(
+        &self,
+        content: &str,
+        path: &Path,
+        key: &str,
+        version: &str,
+    ) -> Result<String> {
+        (self.update)(content, path, key, version)
+    }
+
+    fn read_version(&self, path: &Path, key: &str) -> Result<String> {
+        (self.read)(path, key)
+    }
+
+    fn write_version(&self, path: &Path, key: &str, version: &str) -> Result<()> {
+        (self.write)(path, key, version)
+    }
+}
+
+macro_rules! builtin {
+    ($format:expr, $module:ident) => {
+        Builtin {
+            format: $format,
+            read: $module::read_version,
+            write: $module::write_version,
+            parse: $module::parse_version,
+            update: $module::update_version,
+        }
+    };
+}
+
+fn builtins() -> Vec<Builtin> {
+    vec![
+        builtin!(FileFormat::Toml, toml),
+        builtin!(FileFormat::Json, json),
+        builtin!(FileFormat::Xml, xml),
+        builtin!(FileFormat::Ini, ini),
+        builtin!(FileFormat::Python, python),
+        builtin!(FileFormat::Rust, rust),
+        builtin!(FileFormat::Dockerfile, dockerfile),
+        builtin!(FileFormat::Markdown, markdown),
+        builtin!(FileFormat::Properties, properties),
+        builtin!(FileFormat::Cmake, cmake),
+        builtin!(FileFormat::CargoLock, cargo_lock),
+        builtin!(FileFormat::Dotenv, dotenv),
+        builtin!(FileFormat::Jsonc, jsonc),
+        builtin!(FileFormat::Hcl, hcl),
+        builtin!(FileFormat::Shell, shell),
+        builtin!(FileFormat::Makefile, makefile),
+        builtin!(FileFormat::Plist, plist),
+        builtin!(FileFormat::Go, go),
+        builtin!(FileFormat::Cff, cff),
+        builtin!(FileFormat::DebianChangelog, debian_changelog),
+        builtin!(FileFormat::RpmSpec, rpm_spec),
+        builtin!(FileFormat::Appstream, appstream),
+        builtin!(FileFormat::Raw, raw),
+        builtin!(FileFormat::Openapi, openapi),
+        builtin!(FileFormat::Image, image),
+        builtin!(FileFormat::Mix, mix),
+        builtin!(FileFormat::Gradle, gradle),
+        builtin!(FileFormat::Wix, wix),
+        builtin!(FileFormat::Yaml, yaml),
+    ]
+}
+
+/// Formats by name, starting with the built-in ones
+fn registry() -> &'static RwLock<HashMap<&'static str, Arc<dyn VersionFormat>>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<&'static str, Arc<dyn VersionFormat>>>> =
+        OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let formats = builtins()
+            .into_iter()
+            .map(|b| (b.name(), Arc::new(b) as Arc<dyn VersionFormat>))
+            .collect();
+        RwLock::new(formats)
+    })
+}
+
+/// Register a format, replacing any format with the same name
+///
+/// Registering under a built-in name (e.g. `toml`) overrides the built-in
+/// format. Formats must be registered before the config is loaded.
+pub fn register(format: impl VersionFormat + 'static) {
+    let mut formats = registry().write().unwrap_or_else(|e| e.into_inner());
+    formats.insert(format.name(), Arc::new(format));
+}
+
+/// Look up the format registered under the name
+pub fn lookup(name: &str) -> Option<Arc<dyn VersionFormat>> {
+    let formats = registry().read().unwrap_or_else(|e| e.into_inner());
+    formats.get(name).cloned()
+}
+
+/// Name of the registered format claiming the extension, if any
+pub(crate) fn name_for_extension(extension: &str) -> Option<&'static str> {
+    let formats = registry().read().unwrap_or_else(|e| e.into_inner());
+    let mut names: Vec<&'static str> = formats
+        .values()
+        .filter(|f| f.extensions().contains(&extension))
+        .map(|f| f.name())
+        .collect();
+    // Deterministic if several formats claim the extension
+    names.sort_unstable();
+    names.first().copied()
+}
+
+/// The implementation of a format
+pub(crate) fn get(format: FileFormat) -> Result<Arc<dyn VersionFormat>> {
+    lookup(format.name()).ok_or_else(|| Error::UnregisteredFormat(format.name().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Stores the version as the whole content, ignoring the key
+    struct Plain;
+
+    impl VersionFormat for Plain {
+        fn name(&self) -> &'static str {
+            "test-plain"
+        }
+
+        fn extensions(&self) -> &[&'static str] {
+            &["test-plain", "toml"]
+        }
+
+        fn parse_version(&self, content: &str, _path: &Path, _key: &str) -> Result<String> {
+            Ok(content.trim().to_string())
+        }
+
+        fn update_version(
+            &self,
+            _content: &str,
+            _path: &Path,
+            _key: &str,
+            version: &str,
+        ) -> Result<String> {
+            Ok(format!("{}\n", version))
+        }
+    }
+
+    #[test]
+    fn test_register_custom_format() {
+        register(Plain);
+        let format = FileFormat::from_name("test-plain").unwrap();
+        assert_eq!(format, FileFormat::Custom("test-plain"));
+        assert_eq!(
+            FileFormat::from_path(Path::new("VERSION.test-plain")),
+            Some(format)
+        );
+        // Built-in formats keep their extensions
+        assert_eq!(
+            FileFormat::from_path(Path::new("Cargo.toml")),
+            Some(FileFormat::Toml)
+        );
+
+        let path = Path::new("VERSION.test-plain");
+        assert_eq!(parse_version("1.2.3\n", path, "", format).unwrap(), "1.2.3");
+        assert_eq!(
+            update_version("1.2.3\n", path, "", "2.0.0", format).unwrap(),
+            "2.0.0\n"
+        );
+    }
+
+    #[test]
+    fn test_builtins_and_unregistered() {
+        assert_eq!(lookup("cargo-lock").unwrap().name(), "cargo-lock");
+        assert!(lookup("toml").unwrap().check_key("a[").is_err());
+        assert!(FileFormat::from_name("no-such-format").is_err());
+        assert!(matches!(
+            parse_version("", Path::new("x"), "", FileFormat::Custom("no-such-format")),
+            Err(Error::UnregisteredFormat(_))
+        ));
+    }
+}
@@ -0,0 +1,115 @@
+use crate::error::{Error, Result};
+use crate::format::pattern::{find_unique, splice};
+use regex::Regex;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+/// Read the version value from an RPM spec file
+pub fn read_version(path: &Path, key: &str) -> Result<String> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    parse_version(&content, path, key)
+}
+
+/// Write the version value to an RPM spec file
+/// Only the value is replaced; the rest of the spec is kept exactly
+pub fn write_version(path: &Path, key: &str, version: &str) -> Result<()> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    let updated = update_version(&content, path, key, version)?;
+
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Read the version value from RPM spec content (`path` is used for error
+/// messages)
+///
+/// The key is a preamble tag such as `Version` or `Release`, matched
+/// case-insensitively as RPM does (`Version:    1.2.3`). The tag must occur
+/// exactly once before `%changelog`, which is never searched or modified.
+pub fn parse_version(content: &str, path: &Path, key: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(content[span].to_string())
+}
+
+/// Return RPM spec content with the value of the tag replaced
+pub fn update_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(splice(content, span, version))
+}
+
+/// Locate the value of the tag, without surrounding whitespace
+fn find_value(content: &str, path: &Path, key: &str) -> Result<Range<usize>> {
+    let pattern = Regex::new(&format!(
+        r"(?mi)^{}[ \t]*:[ \t]*(?P<value>\S(?:[^\n]*\S)?)[ \t]*$",
+        regex::escape(key)
+    ))
+    .map_err(|e| Error::TargetParse {
+        file: path.to_path_buf(),
+        message: e.to_string(),
+    })?;
+
+    let changelog = Regex::new(r"(?m)^%changelog\b").map_err(|e| Error::TargetParse {
+        file: path.to_path_buf(),
+        message: e.to_string(),
+    })?;
+    let end = changelog.find(content).map_or(content.len(), |m| m.start());
+
+    find_unique(&content[..end], path, key, &pattern)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = "\
+Name:           example
+Version:        1.2.3
+Release:        3%{?dist}
+Summary:        An example package
+
+%description
+Example.
+
+%changelog
+* Mon Jan 15 2024 Jane Doe <jane@example.org> - 1.2.3-3
+- Version: bumped
+";
+
+    #[test]
+    fn test_read_tags() {
+        let path = Path::new("example.spec");
+        assert_eq!(parse_version(SPEC, path, "Version").unwrap(), "1.2.3");
+        assert_eq!(parse_version(SPEC, path, "release").unwrap(), "3%{?dist}");
+    }
+
+    #[test]
+    fn test_update_preserves_changelog() {
+        let path = Path::new("example.spec");
+        let updated = update_version(SPEC, path, "Version", "2.0.0").unwrap();
+        assert_eq!(
+            updated,
+            SPEC.replace("Version:        1.2.3", "Version:        2.0.0")
+        );
+        let updated = update_version(&updated, path, "Release", "1%{?dist}").unwrap();
+        assert!(updated.contains("Release:        1%{?dist}\n"));
+        assert!(updated.ends_with("- 1.2.3-3\n- Version: bumped\n"));
+    }
+
+    #[test]
+    fn test_key_not_found_or_ambiguous() {
+        let path = Path::new("example.spec");
+        assert!(matches!(
+            parse_version(SPEC, path, "Epoch"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        assert!(matches!(
+            parse_version("Version: 1\n%package devel\nVersion: 2\n", path, "Version"),
+            Err(Error::AmbiguousKey { count: 2, .. })
+        ));
+    }
+}
@@ -0,0 +1,110 @@
+use crate::error::{Error, Result};
+use crate::format::pattern::{find_unique, splice, unquote};
+use regex::Regex;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+/// Read the version value from a Rust source file
+pub fn read_version(path: &Path, key: &str) -> Result<String> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    parse_version(&content, path, key)
+}
+
+/// Write the version value to a Rust source file
+/// Only the string literal is replaced; the rest of the file is kept exactly
+pub fn write_version(path: &Path, key: &str, version: &str) -> Result<()> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    let updated = update_version(&content, path, key, version)?;
+
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Read the version value from Rust source (`path` is used for error messages)
+///
+/// The key is the name of a `&str` constant or static, e.g. `VERSION` for
+/// `pub const VERSION: &str = "1.2.3";` (any visibility, `&'static str`
+/// allowed). Exactly one such item must exist in the file.
+pub fn parse_version(content: &str, path: &Path, key: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(content[span].to_string())
+}
+
+/// Return Rust source with the string of the constant replaced
+pub fn update_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(splice(content, span, version))
+}
+
+/// Locate the contents of the string literal of the constant
+fn find_value(content: &str, path: &Path, key: &str) -> Result<Range<usize>> {
+    let pattern = Regex::new(&format!(
+        r#"(?m)^[ \t]*(?:pub(?:\([^)\n]*\))?[ \t]+)?(?:const|static)[ \t]+{}[ \t]*:[ \t]*&[ \t]*(?:'static[ \t]+)?str[ \t]*=[ \t]*(?P<value>"[^"\n]*")[ \t]*;"#,
+        regex::escape(key)
+    ))
+    .map_err(|e| Error::TargetParse {
+        file: path.to_path_buf(),
+        message: e.to_string(),
+    })?;
+
+    let span = find_unique(content, path, key, &pattern)?;
+    Ok(unquote(content, span))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VERSION_RS: &str = r#"//! Build information
+
+/// The released version
+pub const VERSION: &str = "1.2.3";
+
+pub(crate) static NAME: &'static str = "example";
+
+mod inner {
+    const VERSION_SUFFIX: &str = "";
+}
+"#;
+
+    #[test]
+    fn test_read_constants() {
+        let path = Path::new("src/version.rs");
+        assert_eq!(parse_version(VERSION_RS, path, "VERSION").unwrap(), "1.2.3");
+        assert_eq!(parse_version(VERSION_RS, path, "NAME").unwrap(), "example");
+        assert_eq!(
+            parse_version(VERSION_RS, path, "VERSION_SUFFIX").unwrap(),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_update_preserves_file() {
+        let path = Path::new("src/version.rs");
+        let updated = update_version(VERSION_RS, path, "VERSION", "2.0.0").unwrap();
+        assert_eq!(
+            updated,
+            VERSION_RS.replace("VERSION: &str = \"1.2.3\"", "VERSION: &str = \"2.0.0\"")
+        );
+    }
+
+    #[test]
+    fn test_exactly_one_match() {
+        let path = Path::new("src/lib.rs");
+        assert!(matches!(
+            parse_version("const VERSION: u32 = 1;\n", path, "VERSION"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        let twice =
+            "#[cfg(a)]\nconst VERSION: &str = \"1\";\n#[cfg(b)]\nconst VERSION: &str = \"2\";\n";
+        assert!(matches!(
+            parse_version(twice, path, "VERSION"),
+            Err(Error::AmbiguousKey { count: 2, .. })
+        ));
+    }
+}
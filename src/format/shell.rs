@@ -0,0 +1,129 @@
+use crate::error::{Error, Result};
+use crate::format::pattern::{find_unique, splice, unquote};
+use regex::Regex;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+/// Read the version value from a shell script
+pub fn read_version(path: &Path, key: &str) -> Result<String> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    parse_version(&content, path, key)
+}
+
+/// Write the version value to a shell script
+/// Only the value is replaced; the rest of the script is kept exactly
+pub fn write_version(path: &Path, key: &str, version: &str) -> Result<()> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    let updated = update_version(&content, path, key, version)?;
+
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Read the version value from a shell script (`path` is used for error messages)
+///
+/// The key is the variable name. Assignments start a line (after
+/// indentation), may be prefixed with `export`, `readonly`, `local` or
+/// `declare`, and have no spaces around `=`. The value is a literal:
+/// unquoted, single-quoted or double-quoted without `$` expansions, so
+/// `VERSION="${VERSION:-1.2.3}"` is not a match. The variable must be
+/// assigned exactly once.
+pub fn parse_version(content: &str, path: &Path, key: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(content[span].to_string())
+}
+
+/// Return the shell script with the value of the variable replaced
+pub fn update_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(splice(content, span, version))
+}
+
+/// Locate the value of the variable, inside its quotes if it has any
+fn find_value(content: &str, path: &Path, key: &str) -> Result<Range<usize>> {
+    let pattern = Regex::new(&format!(
+        r#"(?m)^[ \t]*(?:(?:export|readonly|local|declare(?:[ \t]+-\w+)*)[ \t]+)?{}=(?P<value>"[^"$`\\\n]*"|'[^'\n]*'|[^\s"'$`\\;&|#()<>]*)(?:[ \t;&|)#]|$)"#,
+        regex::escape(key)
+    ))
+    .map_err(|e| Error::TargetParse {
+        file: path.to_path_buf(),
+        message: e.to_string(),
+    })?;
+
+    let span = find_unique(content, path, key, &pattern)?;
+    Ok(unquote(content, span))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INSTALL_SH: &str = r#"#!/usr/bin/env bash
+set -euo pipefail
+
+# Release to install
+VERSION="1.2.3"
+readonly CHANNEL='stable'
+export BUILD=42; echo "building"
+
+install() {
+  local target="${PREFIX:-/usr/local}/bin"
+  echo "installing ${VERSION} to ${target}"
+}
+"#;
+
+    #[test]
+    fn test_read_assignments() {
+        let path = Path::new("install.sh");
+        assert_eq!(parse_version(INSTALL_SH, path, "VERSION").unwrap(), "1.2.3");
+        assert_eq!(
+            parse_version(INSTALL_SH, path, "CHANNEL").unwrap(),
+            "stable"
+        );
+        assert_eq!(parse_version(INSTALL_SH, path, "BUILD").unwrap(), "42");
+    }
+
+    #[test]
+    fn test_update_preserves_script() {
+        let path = Path::new("install.sh");
+        let updated = update_version(INSTALL_SH, path, "VERSION", "2.0.0").unwrap();
+        assert_eq!(
+            updated,
+            INSTALL_SH.replace("VERSION=\"1.2.3\"", "VERSION=\"2.0.0\"")
+        );
+        let updated = update_version(INSTALL_SH, path, "BUILD", "43").unwrap();
+        assert!(updated.contains("export BUILD=43; echo"));
+    }
+
+    #[test]
+    fn test_expansions_are_not_matched() {
+        let path = Path::new("install.sh");
+        assert!(matches!(
+            parse_version(INSTALL_SH, path, "target"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        assert!(matches!(
+            parse_version("V=$(cat VERSION)\n", path, "V"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        assert!(matches!(
+            parse_version("V = 1.0\n", path, "V"),
+            Err(Error::KeyNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_ambiguous() {
+        let path = Path::new("build.sh");
+        let content = "if [ -z \"$CI\" ]; then\n  V=1.0.0\nelse\n  V=1.0.0-dev\nfi\n";
+        assert!(matches!(
+            parse_version(content, path, "V"),
+            Err(Error::AmbiguousKey { count: 2, .. })
+        ));
+    }
+}
@@ -1,22 +1,15 @@
 use crate::error::{Error, Result};
+use crate::format::keypath::{self, Segment};
 use std::fs;
 use std::path::Path;
-use toml_edit::{DocumentMut, Item};
+use toml_edit::{DocumentMut, InlineTable, Item, Table};
 
 /// Read the version value from a TOML file at the specified key path
 pub fn read_version(path: &Path, key: &str) -> Result<String> {
     let content =
         fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
 
-    let doc: DocumentMut =
-        content
-            .parse()
-            .map_err(|e: toml_edit::TomlError| Error::TargetParse {
-                file: path.to_path_buf(),
-                message: e.to_string(),
-            })?;
-
-    get_value(&doc, path, key)
+    parse_version(&content, path, key)
 }
 
 /// Write the version value to a TOML file at the specified key path
@@ -25,27 +18,83 @@ pub fn write_version(path: &Path, key: &str, version: &str) -> Result<()> {
     let content =
         fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
 
-    let mut doc: DocumentMut =
-        content
-            .parse()
-            .map_err(|e: toml_edit::TomlError| Error::TargetParse {
-                file: path.to_path_buf(),
-                message: e.to_string(),
-            })?;
+    let updated = update_version(&content, path, key, version)?;
 
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Read the version value from TOML content (`path` is used for error messages)
+pub fn parse_version(content: &str, path: &Path, key: &str) -> Result<String> {
+    let doc = parse_document(content, path)?;
+    get_value(&doc, path, key)
+}
+
+/// Return TOML content with the version at the key path replaced
+pub fn update_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    let mut doc = parse_document(content, path)?;
     set_value(&mut doc, path, key, version)?;
+    Ok(doc.to_string())
+}
 
-    fs::write(path, doc.to_string())?;
-    Ok(())
+/// Return TOML content with the version set at the key path, creating the
+/// key and any missing tables along it
+///
+/// Missing tables under a `[table]` become `[table.sub]` headers, missing
+/// ones under an inline table become inline tables. Array elements are never
+/// created.
+pub fn insert_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    let mut doc = parse_document(content, path)?;
+    let not_found = || Error::KeyNotFound {
+        file: path.to_path_buf(),
+        key: key.to_string(),
+    };
+
+    let segments = keypath::parse(key)?;
+    let (last, parents) = segments.split_last().ok_or_else(not_found)?;
+    let mut target: &mut Item = doc.as_item_mut();
+    for segment in parents {
+        if child_mut(target, segment).is_none() {
+            let item = if target.is_table() {
+                let mut table = Table::new();
+                table.set_implicit(true);
+                Item::Table(table)
+            } else {
+                Item::Value(InlineTable::new().into())
+            };
+            target
+                .as_table_like_mut()
+                .ok_or_else(not_found)?
+                .insert(&segment.key(), item);
+        }
+        target = child_mut(target, segment).ok_or_else(not_found)?;
+    }
+    if child_mut(target, last).is_none() {
+        target
+            .as_table_like_mut()
+            .ok_or_else(not_found)?
+            .insert(&last.key(), toml_edit::value(version));
+    }
+
+    set_value(&mut doc, path, key, version)?;
+    Ok(doc.to_string())
+}
+
+fn parse_document(content: &str, path: &Path) -> Result<DocumentMut> {
+    content
+        .parse()
+        .map_err(|e: toml_edit::TomlError| Error::TargetParse {
+            file: path.to_path_buf(),
+            message: e.to_string(),
+        })
 }
 
 /// Get a string value from a TOML document at the specified key path
 fn get_value(doc: &DocumentMut, path: &Path, key: &str) -> Result<String> {
-    let keys: Vec<&str> = key.split('.').collect();
     let mut current: &Item = doc.as_item();
 
-    for k in &keys {
-        current = current.get(k).ok_or_else(|| Error::KeyNotFound {
+    for segment in keypath::parse(key)? {
+        current = child(current, &segment).ok_or_else(|| Error::KeyNotFound {
             file: path.to_path_buf(),
             key: key.to_string(),
         })?;
@@ -62,28 +111,15 @@ fn get_value(doc: &DocumentMut, path: &Path, key: &str) -> Result<String> {
 
 /// Set a string value in a TOML document at the specified key path
 fn set_value(doc: &mut DocumentMut, path: &Path, key: &str, version: &str) -> Result<()> {
-    let keys: Vec<&str> = key.split('.').collect();
-    let mut current: &mut Item = doc.as_item_mut();
+    let mut target: &mut Item = doc.as_item_mut();
 
-    for k in &keys[..keys.len() - 1] {
-        current = current.get_mut(k).ok_or_else(|| Error::KeyNotFound {
+    for segment in keypath::parse(key)? {
+        target = child_mut(target, &segment).ok_or_else(|| Error::KeyNotFound {
             file: path.to_path_buf(),
             key: key.to_string(),
         })?;
     }
 
-    let last_key = keys.last().ok_or_else(|| Error::KeyNotFound {
-        file: path.to_path_buf(),
-        key: key.to_string(),
-    })?;
-
-    let target = current
-        .get_mut(last_key)
-        .ok_or_else(|| Error::KeyNotFound {
-            file: path.to_path_buf(),
-            key: key.to_string(),
-        })?;
-
     // Ensure it's currently a string before replacing
     if !target.is_str() {
         return Err(Error::ValueNotString {
@@ -92,10 +128,32 @@ fn set_value(doc: &mut DocumentMut, path: &Path, key: &str, version: &str) -> Re
         });
     }
 
+    // Keep the whitespace and trailing comment around the old value
+    let decor = target.as_value().map(|v| v.decor().clone());
     *target = toml_edit::value(version);
+    if let (Some(decor), Some(value)) = (decor, target.as_value_mut()) {
+        *value.decor_mut() = decor;
+    }
     Ok(())
 }
 
+/// Get the item at one segment of a key path
+fn child<'a>(item: &'a Item, segment: &Segment) -> Option<&'a Item> {
+    match segment {
+        Segment::Key(key) => item.get(key),
+        Segment::Index(index) => item.get(*index).or_else(|| item.get(segment.key())),
+    }
+}
+
+/// Get the item at one segment of a key path, for modification
+fn child_mut<'a>(item: &'a mut Item, segment: &Segment) -> Option<&'a mut Item> {
+    let is_array = item.is_array() || item.is_array_of_tables();
+    match segment {
+        Segment::Index(index) if is_array => item.get_mut(*index),
+        _ => item.as_table_like_mut()?.get_mut(&segment.key()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -165,6 +223,49 @@ version = "1.0.0"  # version number
         assert!(content.contains("\"2.0.0\""));
     }
 
+    #[test]
+    fn test_update_preserves_value_decor() {
+        let content = "version=\"1.0.0\"  # keep me\n";
+        let updated = update_version(content, Path::new("a.toml"), "version", "2.0.0").unwrap();
+        assert_eq!(updated, "version=\"2.0.0\"  # keep me\n");
+    }
+
+    #[test]
+    fn test_array_index() {
+        let content = r#"[tool.poetry]
+packages = [{ include = "app", version = "1.0.0" }]
+
+[[releases]]
+version = "0.9.0"
+
+[[releases]]
+version = "1.0.0" # current
+"#;
+        let path = Path::new("pyproject.toml");
+        assert_eq!(
+            parse_version(content, path, "tool.poetry.packages.0.version").unwrap(),
+            "1.0.0"
+        );
+        assert_eq!(
+            parse_version(content, path, "releases[1].version").unwrap(),
+            "1.0.0"
+        );
+        let updated = update_version(content, path, "releases[1].version", "1.1.0").unwrap();
+        assert!(updated.contains("version = \"1.1.0\" # current\n"));
+        assert!(matches!(
+            parse_version(content, path, "releases[2].version"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        assert!(matches!(
+            update_version(content, path, "tool.missing.version", "1.1.0"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        assert!(matches!(
+            parse_version(content, path, "releases[x].version"),
+            Err(Error::InvalidKey { .. })
+        ));
+    }
+
     #[test]
     fn test_key_not_found() {
         let mut file = NamedTempFile::new().unwrap();
@@ -173,4 +274,41 @@ version = "1.0.0"  # version number
         let result = read_version(file.path(), "nonexistent");
         assert!(matches!(result, Err(Error::KeyNotFound { .. })));
     }
+
+    #[test]
+    fn test_insert_version() {
+        let path = Path::new("pyproject.toml");
+        let content = "[project]\nname = \"app\"\n";
+        let updated = insert_version(content, path, "project.version", "1.0.0").unwrap();
+        assert_eq!(updated, "[project]\nname = \"app\"\nversion = \"1.0.0\"\n");
+
+        let updated = insert_version(content, path, "tool.app.meta.version", "1.0.0").unwrap();
+        assert!(updated.ends_with("[tool.app.meta]\nversion = \"1.0.0\"\n"));
+        assert_eq!(
+            parse_version(&updated, path, "tool.app.meta.version").unwrap(),
+            "1.0.0"
+        );
+
+        let content = "app = { name = \"app\" }\n";
+        let updated = insert_version(content, path, "app.meta.version", "1.0.0").unwrap();
+        assert_eq!(
+            parse_version(&updated, path, "app.meta.version").unwrap(),
+            "1.0.0"
+        );
+
+        // An existing key is only replaced
+        let content = "version = \"0.9.0\" # current\n";
+        assert_eq!(
+            insert_version(content, path, "version", "1.0.0").unwrap(),
+            "version = \"1.0.0\" # current\n"
+        );
+        assert!(matches!(
+            insert_version("releases = []\n", path, "releases[0].version", "1.0.0"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        assert!(matches!(
+            insert_version("name = \"app\"\n", path, "name.version", "1.0.0"),
+            Err(Error::KeyNotFound { .. })
+        ));
+    }
 }
@@ -0,0 +1,161 @@
+use crate::error::{Error, Result};
+use crate::format::pattern::{find_unique, splice};
+use regex::Regex;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+/// Read the version value from a WiX source file
+pub fn read_version(path: &Path, key: &str) -> Result<String> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    parse_version(&content, path, key)
+}
+
+/// Write the version value to a WiX source file
+/// Only the attribute value is replaced; the rest of the file is kept exactly
+pub fn write_version(path: &Path, key: &str, version: &str) -> Result<()> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    let updated = update_version(&content, path, key, version)?;
+
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Read the version value from WiX source (`path` is used for error messages)
+///
+/// The key is an attribute of the `<Product>` (WiX 3) or `<Package>` (WiX 4
+/// and later) element, usually `Version`; it must be set on exactly one of
+/// them. If the attribute refers to a preprocessor variable
+/// (`Version="$(var.ProductVersion)"`), the value of its
+/// `<?define ProductVersion = "1.2.3" ?>` is used instead. Comments are
+/// ignored.
+pub fn parse_version(content: &str, path: &Path, key: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(content[span].to_string())
+}
+
+/// Return WiX source with the value of the attribute replaced
+pub fn update_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    let span = find_value(content, path, key)?;
+    Ok(splice(content, span, version))
+}
+
+/// Locate the attribute value, or the value of the variable it refers to
+fn find_value(content: &str, path: &Path, key: &str) -> Result<Range<usize>> {
+    let regex = |pattern: &str| {
+        Regex::new(pattern).map_err(|e| Error::TargetParse {
+            file: path.to_path_buf(),
+            message: e.to_string(),
+        })
+    };
+
+    // Blank out comments, keeping offsets, so commented-out elements are
+    // never matched
+    let comment = regex(r"(?s)<!--.*?-->")?;
+    let masked = comment.replace_all(content, |caps: &regex::Captures| " ".repeat(caps[0].len()));
+
+    let attribute = regex(&format!(
+        r#"<(?:\w+:)?(?:Product|Package)\b[^>]*?\s{}\s*=\s*(?:"(?P<dq>[^"]*)"|'(?P<sq>[^']*)')"#,
+        regex::escape(key)
+    ))?;
+    let spans: Vec<Range<usize>> = attribute
+        .captures_iter(&masked)
+        .filter_map(|caps| caps.name("dq").or_else(|| caps.name("sq")))
+        .map(|m| m.range())
+        .collect();
+    let span = match spans.as_slice() {
+        [span] => span.clone(),
+        [] => {
+            return Err(Error::KeyNotFound {
+                file: path.to_path_buf(),
+                key: key.to_string(),
+            })
+        }
+        _ => {
+            return Err(Error::AmbiguousKey {
+                file: path.to_path_buf(),
+                key: key.to_string(),
+                count: spans.len(),
+            })
+        }
+    };
+
+    let variable = regex(r"^\$\((?:var\.)?(?P<name>[\w.]+)\)$")?;
+    let Some(caps) = variable.captures(&content[span.clone()]) else {
+        return Ok(span);
+    };
+    let name = &caps["name"];
+    let define = regex(&format!(
+        r#"<\?define\s+{}\s*=\s*"(?P<value>[^"]*)"\s*\?>"#,
+        regex::escape(name)
+    ))?;
+    find_unique(&masked, path, name, &define)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIX3: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Wix xmlns="http://schemas.microsoft.com/wix/2006/wi">
+  <!-- <Product Version="0.0.1"> -->
+  <Product Id="*" Name="Example" Language="1033" Version="1.2.3"
+           Manufacturer="Example Inc." UpgradeCode="12345678-1234-1234-1234-123456789012">
+    <Package InstallerVersion="500" Compressed="yes" InstallScope="perMachine" />
+  </Product>
+</Wix>
+"#;
+
+    #[test]
+    fn test_read_product_version() {
+        let path = Path::new("Product.wxs");
+        assert_eq!(parse_version(WIX3, path, "Version").unwrap(), "1.2.3");
+        assert_eq!(parse_version(WIX3, path, "Name").unwrap(), "Example");
+    }
+
+    #[test]
+    fn test_update_package_version() {
+        let path = Path::new("Package.wxs");
+        let content = "<Wix xmlns=\"http://wixtoolset.org/schemas/v4/wxs\">\n  <Package Name=\"Example\" Version='1.2.3' Manufacturer=\"Example Inc.\">\n  </Package>\n</Wix>\n";
+        assert_eq!(parse_version(content, path, "Version").unwrap(), "1.2.3");
+        assert_eq!(
+            update_version(content, path, "Version", "2.0.0").unwrap(),
+            content.replace("'1.2.3'", "'2.0.0'")
+        );
+
+        let updated = update_version(WIX3, path, "Version", "2.0.0").unwrap();
+        assert_eq!(
+            updated,
+            WIX3.replace("Version=\"1.2.3\"", "Version=\"2.0.0\"")
+        );
+    }
+
+    #[test]
+    fn test_preprocessor_variable() {
+        let path = Path::new("Product.wxs");
+        let content = "<?define ProductVersion = \"1.2.3\" ?>\n<Wix>\n  <Product Version=\"$(var.ProductVersion)\" />\n</Wix>\n";
+        assert_eq!(parse_version(content, path, "Version").unwrap(), "1.2.3");
+        assert_eq!(
+            update_version(content, path, "Version", "1.3.0").unwrap(),
+            content.replace("= \"1.2.3\"", "= \"1.3.0\"")
+        );
+    }
+
+    #[test]
+    fn test_key_not_found() {
+        let path = Path::new("Product.wxs");
+        assert!(matches!(
+            parse_version(WIX3, path, "Codepage"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        let content = "<Wix><Product Version=\"$(var.Missing)\" /></Wix>";
+        assert!(matches!(
+            parse_version(content, path, "Version"),
+            Err(Error::KeyNotFound { .. })
+        ));
+    }
+}
@@ -0,0 +1,237 @@
+use crate::error::{Error, Result};
+use quick_xml::escape::{escape, unescape};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+/// Read the version value from an XML file at the specified element path
+pub fn read_version(path: &Path, key: &str) -> Result<String> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    parse_version(&content, path, key)
+}
+
+/// Write the version value to an XML file at the specified element path
+/// Only the element text is replaced; everything else is kept byte for byte
+pub fn write_version(path: &Path, key: &str, version: &str) -> Result<()> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    let updated = update_version(&content, path, key, version)?;
+
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Read the version value from XML content (`path` is used for error messages)
+///
+/// The key is a `/`-separated element path from the root element, matched by
+/// local name so namespace prefixes can be left out (e.g.
+/// `Project/PropertyGroup/Version` or `project/version`). The first element
+/// in document order matching the path is used.
+pub fn parse_version(content: &str, path: &Path, key: &str) -> Result<String> {
+    let span = find_text(content, path, key)?;
+    let raw = &content[span];
+    unescape(raw.trim())
+        .map(|text| text.into_owned())
+        .map_err(|e| Error::TargetParse {
+            file: path.to_path_buf(),
+            message: e.to_string(),
+        })
+}
+
+/// Return XML content with the text of the element at the path replaced
+///
+/// Whitespace around the text inside the element is kept.
+pub fn update_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    let span = find_text(content, path, key)?;
+    let raw = &content[span.clone()];
+    let leading = &raw[..raw.len() - raw.trim_start().len()];
+    let trailing = &raw[raw.trim_end().len()..];
+
+    Ok(format!(
+        "{}{}{}{}{}",
+        &content[..span.start],
+        leading,
+        escape(version),
+        trailing,
+        &content[span.end..]
+    ))
+}
+
+/// Locate the byte range of the content of the element at the path
+///
+/// Fails with `ValueNotString` when the element is self-closing or has child
+/// elements, since it then has no text to replace.
+fn find_text(content: &str, path: &Path, key: &str) -> Result<Range<usize>> {
+    let names: Vec<&str> = key.split('/').filter(|n| !n.is_empty()).collect();
+    let parse_error = |e: quick_xml::Error| Error::TargetParse {
+        file: path.to_path_buf(),
+        message: e.to_string(),
+    };
+    let not_string = || Error::ValueNotString {
+        file: path.to_path_buf(),
+        key: key.to_string(),
+    };
+
+    let mut reader = Reader::from_str(content);
+    let mut stack: Vec<String> = Vec::new();
+    // Start of the content of the matching element, once found
+    let mut start: Option<usize> = None;
+
+    loop {
+        let before = reader.buffer_position() as usize;
+        match reader.read_event().map_err(parse_error)? {
+            Event::Start(e) => {
+                if start.is_some() {
+                    return Err(not_string());
+                }
+                stack.push(e.local_name().as_ref().to_string());
+                if stack == names {
+                    start = Some(reader.buffer_position() as usize);
+                }
+            }
+            Event::Empty(e) => {
+                if start.is_some() {
+                    return Err(not_string());
+                }
+                stack.push(e.local_name().as_ref().to_string());
+                if stack == names {
+                    return Err(not_string());
+                }
+                stack.pop();
+            }
+            Event::End(_) => {
+                if let Some(start) = start {
+                    return Ok(start..before);
+                }
+                stack.pop();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if let Some(name) = stack.last() {
+        return Err(Error::TargetParse {
+            file: path.to_path_buf(),
+            message: format!("unclosed element <{}>", name),
+        });
+    }
+    Err(Error::KeyNotFound {
+        file: path.to_path_buf(),
+        key: key.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CSPROJ: &str = r#"<Project Sdk="Microsoft.NET.Sdk">
+
+  <PropertyGroup>
+    <TargetFramework>net8.0</TargetFramework>
+  </PropertyGroup>
+
+  <PropertyGroup Label="Packaging">
+    <!-- bumped by versync -->
+    <Version>1.2.3</Version>
+  </PropertyGroup>
+
+</Project>
+"#;
+
+    #[test]
+    fn test_read_element_path() {
+        let path = Path::new("app.csproj");
+        assert_eq!(
+            parse_version(CSPROJ, path, "Project/PropertyGroup/Version").unwrap(),
+            "1.2.3"
+        );
+        assert_eq!(
+            parse_version(CSPROJ, path, "Project/PropertyGroup/TargetFramework").unwrap(),
+            "net8.0"
+        );
+    }
+
+    #[test]
+    fn test_update_preserves_document() {
+        let path = Path::new("app.csproj");
+        let updated =
+            update_version(CSPROJ, path, "Project/PropertyGroup/Version", "2.0.0").unwrap();
+        assert_eq!(
+            updated,
+            CSPROJ.replace("<Version>1.2.3</Version>", "<Version>2.0.0</Version>")
+        );
+    }
+
+    #[test]
+    fn test_namespaces_and_whitespace() {
+        let pom = r#"<?xml version="1.0" encoding="UTF-8"?>
+<m:project xmlns:m="http://maven.apache.org/POM/4.0.0">
+  <m:artifactId>demo</m:artifactId>
+  <m:version>
+    0.9.0
+  </m:version>
+</m:project>
+"#;
+        let path = Path::new("pom.xml");
+        assert_eq!(
+            parse_version(pom, path, "project/version").unwrap(),
+            "0.9.0"
+        );
+        let updated = update_version(pom, path, "project/version", "1.0.0").unwrap();
+        assert!(updated.contains("<m:version>\n    1.0.0\n  </m:version>"));
+    }
+
+    #[test]
+    fn test_escaping() {
+        let xml = "<a><v>1.0&amp;x</v></a>";
+        let path = Path::new("a.xml");
+        assert_eq!(parse_version(xml, path, "a/v").unwrap(), "1.0&x");
+        assert_eq!(
+            update_version(xml, path, "a/v", "2<3").unwrap(),
+            "<a><v>2&lt;3</v></a>"
+        );
+    }
+
+    #[test]
+    fn test_empty_element() {
+        let path = Path::new("a.xml");
+        assert_eq!(parse_version("<a><v></v></a>", path, "a/v").unwrap(), "");
+        assert_eq!(
+            update_version("<a><v></v></a>", path, "a/v", "1.0.0").unwrap(),
+            "<a><v>1.0.0</v></a>"
+        );
+    }
+
+    #[test]
+    fn test_not_a_string() {
+        let path = Path::new("a.xml");
+        assert!(matches!(
+            parse_version("<a><v/></a>", path, "a/v"),
+            Err(Error::ValueNotString { .. })
+        ));
+        assert!(matches!(
+            parse_version("<a><v><b>1</b></v></a>", path, "a/v"),
+            Err(Error::ValueNotString { .. })
+        ));
+    }
+
+    #[test]
+    fn test_key_not_found() {
+        let path = Path::new("a.xml");
+        assert!(matches!(
+            parse_version(CSPROJ, path, "Project/Version"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        assert!(matches!(
+            parse_version("<a><v>", path, "a/v"),
+            Err(Error::TargetParse { .. })
+        ));
+    }
+}
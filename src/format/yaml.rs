@@ -0,0 +1,265 @@
+use crate::error::{Error, Result};
+use crate::format::keypath;
+use crate::format::pattern::{splice, unquote};
+use regex::Regex;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+/// Read the version value from a YAML file
+pub fn read_version(path: &Path, key: &str) -> Result<String> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    parse_version(&content, path, key)
+}
+
+/// Write the version value to a YAML file
+/// Only the value is replaced; comments and formatting are kept
+pub fn write_version(path: &Path, key: &str, version: &str) -> Result<()> {
+    let content =
+        fs::read_to_string(path).map_err(|_| Error::TargetNotFound(path.to_path_buf()))?;
+
+    let updated = update_version(&content, path, key, version)?;
+
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Read the version value from YAML content (`path` is used for error
+/// messages)
+///
+/// The key is a dot-separated path such as `appVersion` or `image.tag`
+/// (see [`find_values`]). Every document with the key counts; if they
+/// disagree, all their values are returned, separated by `, `, so the
+/// target shows as out of date.
+pub fn parse_version(content: &str, path: &Path, key: &str) -> Result<String> {
+    let mut values: Vec<&str> = Vec::new();
+    for span in find_values(content, path, key)? {
+        if !values.contains(&&content[span.clone()]) {
+            values.push(&content[span]);
+        }
+    }
+    Ok(values.join(", "))
+}
+
+/// Return YAML content with the value replaced in every document
+pub fn update_version(content: &str, path: &Path, key: &str, version: &str) -> Result<String> {
+    let spans = find_values(content, path, key)?;
+    Ok(spans
+        .into_iter()
+        .rev()
+        .fold(content.to_string(), |updated, span| {
+            splice(&updated, span, version)
+        }))
+}
+
+/// Locate the scalar at a dot-separated key path in each document of a
+/// block-style YAML stream
+///
+/// Documents are separated by `---` lines. Only nested mappings are
+/// followed: a path never leads into a sequence, and flow collections
+/// (`{...}`, `[...]`) and block scalars (`|`, `>`) are skipped. The returned
+/// spans are inside the quotes of quoted scalars and exclude comments, one
+/// per document that has the key, in document order.
+pub fn find_values(content: &str, path: &Path, key: &str) -> Result<Vec<Range<usize>>> {
+    let segments: Vec<String> = keypath::parse(key)?.iter().map(|s| s.key()).collect();
+    let entry = Regex::new(
+        r#"^(?:"(?P<dq>(?:[^"\\]|\\.)*)"|'(?P<sq>(?:[^']|'')*)'|(?P<plain>[^\s#:'"{}\[\],&*!|>%@`-][^#]*?))[ \t]*:(?:[ \t]+(?P<value>.*))?$"#,
+    )
+    .map_err(|e| Error::TargetParse {
+        file: path.to_path_buf(),
+        message: e.to_string(),
+    })?;
+
+    let mut spans = Vec::new();
+    // Keys of the enclosing mappings with their indentation; `None` for a
+    // sequence item
+    let mut frames: Vec<(usize, Option<String>)> = Vec::new();
+    let mut in_document: Option<Range<usize>> = None;
+    let mut block_scalar: Option<usize> = None;
+    let mut offset = 0;
+
+    for line in content.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let line = line.trim_end_matches(['\n', '\r']);
+        let indent = line.len() - line.trim_start_matches(' ').len();
+        let rest = &line[indent..];
+
+        if let Some(block_indent) = block_scalar {
+            if rest.is_empty() || indent > block_indent {
+                continue;
+            }
+            block_scalar = None;
+        }
+        if line == "---" || line.starts_with("--- ") || line == "..." {
+            if let Some(found) = in_document.take() {
+                spans.push(found);
+            }
+            frames.clear();
+            continue;
+        }
+        if rest.is_empty() || rest.starts_with('#') {
+            continue;
+        }
+
+        let mut indent = indent;
+        let mut rest = rest;
+        frames.retain(|(i, _)| *i < indent);
+        while rest == "-" || rest.starts_with("- ") {
+            frames.push((indent, None));
+            let item = rest[1..].trim_start_matches(' ');
+            indent += rest.len() - item.len();
+            rest = item;
+        }
+
+        let Some(caps) = entry.captures(rest) else {
+            continue;
+        };
+        let name = if let Some(m) = caps.name("dq") {
+            m.as_str().replace("\\\"", "\"").replace("\\\\", "\\")
+        } else if let Some(m) = caps.name("sq") {
+            m.as_str().replace("''", "'")
+        } else {
+            caps["plain"].to_string()
+        };
+        let value = caps.name("value").map(|m| {
+            let base = start + (line.len() - rest.len()) + m.start();
+            (base, m.as_str())
+        });
+
+        let depth = frames.len();
+        let on_path = depth < segments.len()
+            && frames
+                .iter()
+                .zip(&segments)
+                .all(|((_, k), s)| k.as_deref() == Some(s.as_str()))
+            && segments[depth] == name;
+
+        match value.map(|(base, v)| (base, strip_comment(v))) {
+            Some((base, v)) if on_path && depth + 1 == segments.len() => {
+                if v.is_empty() || v.starts_with(['|', '>', '{', '[', '&', '*', '!']) {
+                    return Err(Error::ValueNotString {
+                        file: path.to_path_buf(),
+                        key: key.to_string(),
+                    });
+                }
+                if in_document.is_some() {
+                    return Err(Error::AmbiguousKey {
+                        file: path.to_path_buf(),
+                        key: key.to_string(),
+                        count: 2,
+                    });
+                }
+                in_document = Some(unquote(content, base..base + v.len()));
+            }
+            Some((_, v)) if v.starts_with(['|', '>']) => block_scalar = Some(indent),
+            Some((_, v)) if !v.is_empty() => {}
+            _ if on_path && depth + 1 == segments.len() => {
+                return Err(Error::ValueNotString {
+                    file: path.to_path_buf(),
+                    key: key.to_string(),
+                });
+            }
+            _ => frames.push((indent, Some(name))),
+        }
+    }
+    if let Some(found) = in_document {
+        spans.push(found);
+    }
+
+    if spans.is_empty() {
+        return Err(Error::KeyNotFound {
+            file: path.to_path_buf(),
+            key: key.to_string(),
+        });
+    }
+    Ok(spans)
+}
+
+/// The value without a trailing comment or whitespace
+fn strip_comment(value: &str) -> &str {
+    let value = value.trim_end();
+    let end = match value.chars().next() {
+        Some(quote @ ('"' | '\'')) => value[1..].find(quote).map(|i| i + 2),
+        _ => None,
+    };
+    match end {
+        Some(end) => &value[..end],
+        None => value.find(" #").map_or(value, |i| value[..i].trim_end()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(content: &str, key: &str) -> Result<Vec<String>> {
+        let spans = find_values(content, Path::new("spec.yaml"), key)?;
+        Ok(spans.into_iter().map(|s| content[s].to_string()).collect())
+    }
+
+    #[test]
+    fn test_nested_keys() {
+        let content = "\
+openapi: 3.1.0
+info:
+  title: Example # the name
+  description: |
+    version: 0.0.1
+  version: '1.2.3' # bumped
+servers:
+  - url: https://example.org
+    version: 9.9.9
+";
+        assert_eq!(values(content, "info.version").unwrap(), ["1.2.3"]);
+        assert_eq!(values(content, "info.title").unwrap(), ["Example"]);
+        assert_eq!(values(content, "openapi").unwrap(), ["3.1.0"]);
+        assert!(matches!(
+            values(content, "servers.version"),
+            Err(Error::KeyNotFound { .. })
+        ));
+        assert!(matches!(
+            values(content, "info"),
+            Err(Error::ValueNotString { .. })
+        ));
+    }
+
+    #[test]
+    fn test_multiple_documents() {
+        let content = "\
+---
+info:
+  version: \"1.0.0\"
+---
+kind: Other
+...
+---
+info:
+  version: 1.0.1
+";
+        assert_eq!(values(content, "info.version").unwrap(), ["1.0.0", "1.0.1"]);
+    }
+
+    #[test]
+    fn test_update_version() {
+        let content = "\
+apiVersion: v2
+name: app
+version: 1.2.3 # chart
+appVersion: \"v1.2.3\"
+";
+        let path = Path::new("Chart.yaml");
+        assert_eq!(
+            parse_version(content, path, "appVersion").unwrap(),
+            "v1.2.3"
+        );
+        let updated = update_version(content, path, "appVersion", "v1.3.0").unwrap();
+        let updated = update_version(&updated, path, "version", "1.3.0").unwrap();
+        assert_eq!(
+            updated,
+            "apiVersion: v2\nname: app\nversion: 1.3.0 # chart\nappVersion: \"v1.3.0\"\n"
+        );
+    }
+}
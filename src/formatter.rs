@@ -0,0 +1,302 @@
+use crate::error::{Error, Result};
+use toml_edit::{Decor, DocumentMut, Item, Key, RawString, Table};
+
+/// Canonical order of the top-level configuration entries
+const ORDER: &[&str] = &[
+    "version",
+    "scheme",
+    "source",
+    "exclude",
+    "targets",
+    "git",
+    "network",
+    "channels",
+    "policy",
+    "prerelease",
+    "release",
+    "exit_codes",
+    "components",
+];
+
+/// Canonical order of the keys inside a `[[targets]]` entry
+const TARGET_ORDER: &[&str] = &[
+    "file", "key", "template", "scheme", "keys", "group", "required", "create", "format",
+    "lockfile", "exclude",
+];
+
+/// Format a `version.toml` file
+///
+/// Top-level entries are put in the order of the documentation (`version`,
+/// `[[targets]]`, `[git]`, ...), targets are sorted by file and key, keys use
+/// the bare style where possible and spacing is normalized to `key = value`
+/// with exactly one blank line before each table. Comments are kept; values
+/// are written as they are.
+pub fn format_config(content: &str) -> Result<String> {
+    let mut doc: DocumentMut = content
+        .parse()
+        .map_err(|e: toml_edit::TomlError| Error::ConfigParse(e.to_string()))?;
+
+    let root = doc.as_table_mut();
+    root.sort_values_by(|a, _, b, _| rank(ORDER, a).cmp(&rank(ORDER, b)));
+    if let Some(targets) = root
+        .get_mut("targets")
+        .and_then(Item::as_array_of_tables_mut)
+    {
+        let mut tables: Vec<Table> = targets.iter().cloned().collect();
+        tables.sort_by_key(|t| (string(t, "file"), string(t, "key")));
+        targets.clear();
+        for mut table in tables {
+            table.sort_values_by(|a, _, b, _| rank(TARGET_ORDER, a).cmp(&rank(TARGET_ORDER, b)));
+            targets.push(table);
+        }
+    }
+
+    let mut state = State {
+        position: 1,
+        blank: has_values(root),
+    };
+    format_table(root, &mut state);
+
+    let trailing = comments(doc.trailing().as_str().unwrap_or_default());
+    doc.set_trailing(if trailing.is_empty() {
+        String::new()
+    } else {
+        format!("\n{}", trailing)
+    });
+
+    Ok(doc.to_string())
+}
+
+/// Progress while formatting the tables of a document
+struct State {
+    /// Next table position in the document
+    position: usize,
+    /// Whether the next table header needs a blank line before it
+    blank: bool,
+}
+
+/// Format the key-value pairs of a table and its sub-tables, in order
+fn format_table(table: &mut Table, state: &mut State) {
+    for (mut key, item) in table.iter_mut() {
+        let prefix = comments(prefix(key.leaf_decor()));
+        let suffix = comment(key.leaf_decor().suffix());
+        key.fmt();
+        match item {
+            Item::Table(sub) if sub.is_dotted() => {
+                format_values(sub);
+                key.leaf_decor_mut().set_prefix(prefix);
+            }
+            Item::Table(sub) => {
+                key.leaf_decor_mut().set_suffix(suffix);
+                format_header(sub, state);
+                format_table(sub, state);
+            }
+            Item::ArrayOfTables(tables) => {
+                for sub in tables.iter_mut() {
+                    format_header(sub, state);
+                    format_table(sub, state);
+                }
+            }
+            Item::Value(value) => {
+                key.leaf_decor_mut().set_prefix(prefix);
+                key.leaf_decor_mut().set_suffix(" ");
+                let suffix = comment(value.decor().suffix());
+                value.decor_mut().set_prefix(" ");
+                value.decor_mut().set_suffix(suffix);
+            }
+            Item::None => {}
+        }
+    }
+}
+
+/// Format the key-value pairs of a dotted table (`git.remote = "origin"`)
+fn format_values(table: &mut Table) {
+    for (mut key, item) in table.iter_mut() {
+        key.fmt();
+        match item {
+            Item::Table(sub) => format_values(sub),
+            Item::Value(value) => {
+                let suffix = comment(value.decor().suffix());
+                value.decor_mut().set_prefix(" ");
+                value.decor_mut().set_suffix(suffix);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Position a table header and normalize the lines before and after it
+fn format_header(table: &mut Table, state: &mut State) {
+    table.set_position(state.position);
+    state.position += 1;
+    if table.is_implicit() {
+        return;
+    }
+
+    let lines = comments(prefix(table.decor()));
+    let suffix = comment(table.decor().suffix());
+    let blank = if state.blank { "\n" } else { "" };
+    let decor = table.decor_mut();
+    decor.set_prefix(format!("{}{}", blank, lines));
+    decor.set_suffix(suffix);
+    state.blank = true;
+}
+
+/// Whether a table has key-value pairs of its own, as opposed to sub-tables
+fn has_values(table: &Table) -> bool {
+    table.iter().any(|(_, item)| match item {
+        Item::Value(_) => true,
+        Item::Table(sub) => sub.is_dotted(),
+        _ => false,
+    })
+}
+
+/// Index of a key in a canonical order, unknown keys last
+fn rank(order: &[&str], key: &Key) -> usize {
+    order
+        .iter()
+        .position(|k| *k == key.get())
+        .unwrap_or(order.len())
+}
+
+/// String value of a key, or an empty string
+fn string(table: &Table, key: &str) -> String {
+    table
+        .get(key)
+        .and_then(Item::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn prefix(decor: &Decor) -> &str {
+    decor
+        .prefix()
+        .and_then(RawString::as_str)
+        .unwrap_or_default()
+}
+
+/// The comment lines of a decor prefix, each ending with a newline
+fn comments(raw: &str) -> String {
+    raw.lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with('#'))
+        .map(|line| format!("{}\n", line))
+        .collect()
+}
+
+/// A trailing comment as ` # comment`, or an empty string
+fn comment(suffix: Option<&RawString>) -> String {
+    suffix
+        .and_then(RawString::as_str)
+        .map(str::trim)
+        .filter(|s| s.starts_with('#'))
+        .map(|s| format!(" {}", s))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_orders_tables_and_targets() {
+        let input = r#"[git]
+tag_prefix="v"
+
+[[targets]]
+key = "version"
+file = "package.json"
+[[targets]]
+file = "Cargo.toml"
+key = "package.version"
+version = "1.2.3"
+"#;
+        let expected = r#"[[targets]]
+file = "Cargo.toml"
+key = "package.version"
+version = "1.2.3"
+
+[[targets]]
+file = "package.json"
+key = "version"
+
+[git]
+tag_prefix = "v"
+"#;
+        // `version` belongs to the last target in the input
+        assert_eq!(format_config(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_format_version_first_and_spacing() {
+        let input = "[git]\n  tag_prefix   =   \"v\"\n\n\n\nversion_x = 1\n";
+        let formatted = format_config(&format!("version = \"1.0.0\"\n{}", input)).unwrap();
+        assert_eq!(
+            formatted,
+            "version = \"1.0.0\"\n\n[git]\ntag_prefix = \"v\"\nversion_x = 1\n"
+        );
+    }
+
+    #[test]
+    fn test_format_keeps_comments() {
+        let input = r#"# versync configuration
+version   = "1.0.0"    # bumped by CI
+
+
+# The Rust crate
+[[targets]]   # main
+file = "Cargo.toml"
+  # nested key
+key = "package.version"
+# end
+"#;
+        let expected = r#"# versync configuration
+version = "1.0.0" # bumped by CI
+
+# The Rust crate
+[[targets]] # main
+file = "Cargo.toml"
+# nested key
+key = "package.version"
+
+# end
+"#;
+        assert_eq!(format_config(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_format_quoted_keys_and_channels() {
+        let input = r#"version = "1.0.0"
+[channels."beta"]
+tag = "v{version}-beta"
+[[targets]]
+"file" = "a.toml"
+key = "version"
+"#;
+        let expected = r#"version = "1.0.0"
+
+[[targets]]
+file = "a.toml"
+key = "version"
+
+[channels.beta]
+tag = "v{version}-beta"
+"#;
+        assert_eq!(format_config(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_format_is_idempotent() {
+        let input = "version='1'\n[release]\ncommit=true\n[[targets]]\nfile='a'\nkey='v'\n";
+        let once = format_config(input).unwrap();
+        assert_eq!(format_config(&once).unwrap(), once);
+    }
+
+    #[test]
+    fn test_format_invalid_toml() {
+        assert!(matches!(
+            format_config("version = "),
+            Err(Error::ConfigParse(_))
+        ));
+    }
+}
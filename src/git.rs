@@ -1,6 +1,111 @@
+use crate::cache::Cache;
+use crate::config::NetworkConfig;
 use crate::error::{Error, Result};
+use crate::network;
+use crate::scheme::Scheme;
+use crate::version::Version;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
+/// A commit in the history
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commit {
+    pub sha: String,
+    /// Full commit message (subject and body)
+    pub message: String,
+}
+
+/// The installed git version (`git version 2.x`), or `None` if git cannot run
+pub fn version() -> Option<String> {
+    let output = Command::new("git").arg("--version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// The directory git runs hooks from (honours `core.hooksPath`)
+pub fn hooks_dir() -> Result<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--git-path", "hooks"])
+        .output()
+        .map_err(|e| Error::GitCommand(format!("Failed to execute git rev-parse: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::GitCommand(format!(
+            "Failed to locate the hooks directory: {}",
+            stderr.trim()
+        )));
+    }
+
+    Ok(PathBuf::from(
+        String::from_utf8_lossy(&output.stdout).trim(),
+    ))
+}
+
+/// The working directory relative to the top of the working tree (empty at
+/// the top)
+pub fn prefix() -> Result<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--show-prefix"])
+        .output()
+        .map_err(|e| Error::GitCommand(format!("Failed to execute git rev-parse: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::GitCommand(format!(
+            "Failed to locate the working directory in the repository: {}",
+            stderr.trim()
+        )));
+    }
+
+    Ok(PathBuf::from(
+        String::from_utf8_lossy(&output.stdout).trim(),
+    ))
+}
+
+/// The top directory of the working tree
+pub fn toplevel() -> Result<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--show-toplevel"])
+        .output()
+        .map_err(|e| Error::GitCommand(format!("Failed to execute git rev-parse: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::GitCommand(format!(
+            "Failed to locate the top of the working tree: {}",
+            stderr.trim()
+        )));
+    }
+
+    Ok(PathBuf::from(
+        String::from_utf8_lossy(&output.stdout).trim(),
+    ))
+}
+
+/// Check if a remote name is configured in the repository
+pub fn remote_exists(remote: &str) -> Result<bool> {
+    let output = Command::new("git")
+        .args(["remote", "get-url", remote])
+        .output()
+        .map_err(|e| Error::GitCommand(format!("Failed to execute git remote: {}", e)))?;
+
+    Ok(output.status.success())
+}
+
+/// A tag whose name is the tag prefix followed by a semantic version
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaggedRelease {
+    pub tag: String,
+    pub version: Version,
+    /// The commit the tag points at
+    pub commit: String,
+    /// Date of the tag (annotated) or of its commit (lightweight), `YYYY-MM-DD`
+    pub date: String,
+}
+
 /// Check if we're inside a git repository
 pub fn is_inside_work_tree() -> Result<bool> {
     let output = Command::new("git")
@@ -47,6 +152,311 @@ pub fn tag_exists(tag: &str) -> Result<bool> {
     Ok(!stdout.trim().is_empty())
 }
 
+/// Get the name of the current branch (`None` when HEAD is detached)
+pub fn current_branch() -> Result<Option<String>> {
+    let output = Command::new("git")
+        .args(["symbolic-ref", "--short", "-q", "HEAD"])
+        .output()
+        .map_err(|e| Error::GitCommand(format!("Failed to execute git symbolic-ref: {}", e)))?;
+
+    if !output.status.success() {
+        return Ok(None);
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(Some(stdout.trim().to_string()))
+}
+
+/// Resolve a revision (e.g. `HEAD` or a tag name) to a commit SHA
+pub fn resolve_commit(rev: &str) -> Result<Option<String>> {
+    let output = Command::new("git")
+        .args([
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{}^{{commit}}", rev),
+        ])
+        .output()
+        .map_err(|e| Error::GitCommand(format!("Failed to execute git rev-parse: {}", e)))?;
+
+    if !output.status.success() {
+        return Ok(None);
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(Some(stdout.trim().to_string()))
+}
+
+/// Describe a revision relative to the nearest tag (e.g. `v1.2.2-3-gabc1234`)
+///
+/// Returns `None` when no tag is reachable from the revision.
+pub fn describe(rev: &str) -> Result<Option<String>> {
+    let output = Command::new("git")
+        .args(["describe", "--tags", rev])
+        .output()
+        .map_err(|e| Error::GitCommand(format!("Failed to execute git describe: {}", e)))?;
+
+    if !output.status.success() {
+        return Ok(None);
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(Some(stdout.trim().to_string()))
+}
+
+/// Describe HEAD relative to the nearest tag with the prefix, always in the
+/// long form (`v1.2.2-0-gabc1234` on the tag itself)
+///
+/// Returns `None` when no such tag is reachable from HEAD.
+pub fn describe_long(prefix: &str) -> Result<Option<String>> {
+    let pattern = format!("{}*", prefix);
+    let output = Command::new("git")
+        .args(["describe", "--tags", "--long", "--match", &pattern, "HEAD"])
+        .output()
+        .map_err(|e| Error::GitCommand(format!("Failed to execute git describe: {}", e)))?;
+
+    if !output.status.success() {
+        return Ok(None);
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(Some(stdout.trim().to_string()))
+}
+
+/// A path relative to the current directory (or absolute) as a path from the
+/// root of the repository, the form `rev:path` takes
+fn repository_path(path: &Path) -> Result<String> {
+    let absolute = crate::config::normalize(&std::env::current_dir()?.join(path));
+    // Resolve symlinks in the directory, as git does for the top level
+    let absolute = match (absolute.parent(), absolute.file_name()) {
+        (Some(dir), Some(name)) => {
+            std::fs::canonicalize(dir).map_or_else(|_| absolute.clone(), |dir| dir.join(name))
+        }
+        _ => absolute,
+    };
+    let relative = absolute
+        .strip_prefix(std::fs::canonicalize(toplevel()?)?)
+        .map_err(|_| Error::GitCommand(format!("{} is outside the repository", path.display())))?;
+    let components: Vec<_> = relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect();
+    Ok(components.join("/"))
+}
+
+/// Read a file's content at a revision (`path` is relative to the current
+/// directory, or absolute)
+pub fn show_file(rev: &str, path: &Path) -> Result<String> {
+    let spec = format!("{}:{}", rev, repository_path(path)?);
+    let output = Command::new("git")
+        .args(["show", &spec])
+        .output()
+        .map_err(|e| Error::GitCommand(format!("Failed to execute git show: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::GitCommand(format!(
+            "Failed to read {}: {}",
+            spec,
+            stderr.trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// List tags starting with the given prefix, newest version first
+pub fn list_tags(prefix: &str) -> Result<Vec<String>> {
+    let pattern = format!("{}*", prefix);
+    let output = Command::new("git")
+        .args(["tag", "--list", &pattern, "--sort=-v:refname"])
+        .output()
+        .map_err(|e| Error::GitCommand(format!("Failed to execute git tag --list: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(Error::GitCommand("git tag --list failed".to_string()));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout.lines().map(|line| line.to_string()).collect())
+}
+
+/// List the commits reachable from HEAD but not from `since` (all commits
+/// if `None`), newest first
+pub fn commits_since(since: Option<&str>) -> Result<Vec<Commit>> {
+    let range = match since {
+        Some(since) => format!("{}..HEAD", since),
+        None => "HEAD".to_string(),
+    };
+    let output = Command::new("git")
+        .args(["log", "--format=%H%x1f%B%x1e", &range, "--"])
+        .output()
+        .map_err(|e| Error::GitCommand(format!("Failed to execute git log: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::GitCommand(format!(
+            "Failed to read history: {}",
+            stderr.trim()
+        )));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout
+        .split('\x1e')
+        .filter_map(|record| {
+            let (sha, message) = record.trim_start_matches('\n').split_once('\x1f')?;
+            Some(Commit {
+                sha: sha.to_string(),
+                message: message.trim().to_string(),
+            })
+        })
+        .collect())
+}
+
+/// List the tags pointing at a revision
+pub fn tags_at(rev: &str) -> Result<Vec<String>> {
+    let output = Command::new("git")
+        .args(["tag", "--points-at", rev, "--sort=-v:refname"])
+        .output()
+        .map_err(|e| Error::GitCommand(format!("Failed to execute git tag --points-at: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(Error::GitCommand("git tag --points-at failed".to_string()));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout.lines().map(|line| line.to_string()).collect())
+}
+
+/// Check if a tag exists on a remote (network access via `git ls-remote`)
+pub fn remote_tag_exists(network: &NetworkConfig, remote: &str, tag: &str) -> Result<bool> {
+    Ok(remote_tags(network, remote)?.iter().any(|t| t == tag))
+}
+
+/// List the tags on a remote
+///
+/// The listing is cached on disk for `network.cache_ttl` seconds, keyed by
+/// the remote's URL, so repeated invocations do not query the remote again.
+pub fn remote_tags(network: &NetworkConfig, remote: &str) -> Result<Vec<String>> {
+    let key = remote_tags_key(remote)?;
+    let listing = Cache::new(network).get_or_fetch(&key, || {
+        let mut command = Command::new("git");
+        command
+            .args(["ls-remote", "--tags", "--refs", remote])
+            .env("GIT_TERMINAL_PROMPT", "0");
+        let output = network::command_output(network, "git ls-remote", &mut command)?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::GitCommand(format!(
+                "git ls-remote {} failed: {}",
+                remote,
+                stderr.trim()
+            )));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    })?;
+
+    Ok(listing
+        .lines()
+        .filter_map(|line| line.split_once("refs/tags/"))
+        .map(|(_, tag)| tag.to_string())
+        .collect())
+}
+
+/// Cache key for the tag listing of a remote
+fn remote_tags_key(remote: &str) -> Result<String> {
+    Ok(format!("ls-remote-tags {}", remote_url(remote)?))
+}
+
+/// Resolve a remote name (or URL) to its URL
+///
+/// A relative path (`../remote.git`) is made absolute against the top of
+/// the working tree, as git resolves it, so that it names one repository
+/// wherever it is used.
+fn remote_url(remote: &str) -> Result<String> {
+    let output = Command::new("git")
+        .args(["ls-remote", "--get-url", remote])
+        .output()
+        .map_err(|e| Error::GitCommand(format!("Failed to execute git ls-remote: {}", e)))?;
+    let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+    // `host:path` is an scp-style URL unless a `/` comes before the colon
+    let scp_like = url
+        .split_once(':')
+        .is_some_and(|(host, _)| !host.contains('/'));
+    if url.contains("://") || scp_like || Path::new(&url).is_absolute() {
+        return Ok(url);
+    }
+    let path = toplevel()?.join(&url);
+    Ok(path.canonicalize().unwrap_or(path).display().to_string())
+}
+
+/// The version a tag with the given prefix names, written in `scheme`
+fn tag_version(tag: &str, prefix: &str, scheme: Scheme) -> Option<Version> {
+    let version = scheme.to_semver(tag.strip_prefix(prefix)?).ok()?;
+    Version::parse(&version).ok()
+}
+
+/// List tags with the given prefix whose remainder is a version in `scheme`,
+/// sorted by version (oldest first)
+pub fn tagged_versions(prefix: &str, scheme: Scheme) -> Result<Vec<(String, Version)>> {
+    let mut versions: Vec<(String, Version)> = list_tags(prefix)?
+        .into_iter()
+        .filter_map(|tag| {
+            let version = tag_version(&tag, prefix, scheme)?;
+            Some((tag, version))
+        })
+        .collect();
+    versions.sort_by(|a, b| scheme.compare(&a.1, &b.1).then_with(|| a.1.cmp(&b.1)));
+    Ok(versions)
+}
+
+/// List release tags with the given prefix and a version in `scheme`, with
+/// their commit and date, sorted by version (oldest first)
+pub fn tagged_releases(prefix: &str, scheme: Scheme) -> Result<Vec<TaggedRelease>> {
+    let output = Command::new("git")
+        .args([
+            "for-each-ref",
+            "refs/tags",
+            "--format=%(refname:strip=2)%1f%(*objectname)%1f%(objectname)%1f%(creatordate:short)",
+        ])
+        .output()
+        .map_err(|e| Error::GitCommand(format!("Failed to execute git for-each-ref: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::GitCommand(format!(
+            "Failed to list tags: {}",
+            stderr.trim()
+        )));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut releases: Vec<TaggedRelease> = stdout
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\x1f');
+            let tag = fields.next()?;
+            let peeled = fields.next()?;
+            let object = fields.next()?;
+            let date = fields.next()?;
+            let version = tag_version(tag, prefix, scheme)?;
+            Some(TaggedRelease {
+                tag: tag.to_string(),
+                version,
+                // Annotated tags point at a tag object; use the commit behind it
+                commit: if peeled.is_empty() { object } else { peeled }.to_string(),
+                date: date.to_string(),
+            })
+        })
+        .collect();
+    releases.sort_by(|a, b| a.version.cmp(&b.version));
+    Ok(releases)
+}
+
 /// Create an annotated tag
 pub fn create_annotated_tag(tag: &str, message: &str) -> Result<()> {
     let output = Command::new("git")
@@ -65,6 +475,117 @@ pub fn create_annotated_tag(tag: &str, message: &str) -> Result<()> {
     Ok(())
 }
 
+/// Create an annotated tag, replacing any existing tag with the same name
+pub fn force_annotated_tag(tag: &str, message: &str) -> Result<()> {
+    let output = Command::new("git")
+        .args(["tag", "-f", "-a", tag, "-m", message])
+        .output()
+        .map_err(|e| Error::GitCommand(format!("Failed to execute git tag -f: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::GitCommand(format!(
+            "Failed to replace tag: {}",
+            stderr.trim()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Delete a local tag
+pub fn delete_tag(tag: &str) -> Result<()> {
+    let output = Command::new("git")
+        .args(["tag", "-d", tag])
+        .output()
+        .map_err(|e| Error::GitCommand(format!("Failed to execute git tag -d: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::GitCommand(format!(
+            "Failed to delete tag: {}",
+            stderr.trim()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Paths with uncommitted changes (staged or not) below the current
+/// directory, relative to it
+pub fn changed_paths() -> Result<Vec<PathBuf>> {
+    let output = Command::new("git")
+        .args(["diff", "HEAD", "--name-only", "--relative"])
+        .output()
+        .map_err(|e| Error::GitCommand(format!("Failed to execute git diff: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::GitCommand(format!(
+            "Failed to list changes: {}",
+            stderr.trim()
+        )));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout.lines().map(PathBuf::from).collect())
+}
+
+/// Stage the given paths and commit them
+pub fn commit_paths(paths: &[PathBuf], message: &str) -> Result<()> {
+    let output = Command::new("git")
+        .arg("add")
+        .arg("--")
+        .args(paths)
+        .output()
+        .map_err(|e| Error::GitCommand(format!("Failed to execute git add: {}", e)))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::GitCommand(format!(
+            "Failed to stage files: {}",
+            stderr.trim()
+        )));
+    }
+
+    let output = Command::new("git")
+        .args(["commit", "-m", message, "--"])
+        .args(paths)
+        .output()
+        .map_err(|e| Error::GitCommand(format!("Failed to execute git commit: {}", e)))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::GitCommand(format!(
+            "Failed to commit: {}",
+            stderr.trim()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Push refs to a remote (network access via `git push`)
+pub fn push(network: &NetworkConfig, remote: &str, refspecs: &[String]) -> Result<()> {
+    let mut command = Command::new("git");
+    command
+        .args(["push", remote])
+        .args(refspecs)
+        .env("GIT_TERMINAL_PROMPT", "0");
+    let output = network::command_output(network, "git push", &mut command)?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::GitCommand(format!(
+            "git push {} failed: {}",
+            remote,
+            stderr.trim()
+        )));
+    }
+
+    // The push may have created or deleted tags on the remote
+    Cache::new(network).invalidate(&remote_tags_key(remote)?);
+    Ok(())
+}
+
 /// Ensure we're in a git repository
 pub fn ensure_git_repository() -> Result<()> {
     if !is_inside_work_tree()? {
@@ -91,3 +612,18 @@ pub fn ensure_tag_not_exists(tag: &str) -> Result<()> {
     }
     Ok(())
 }
+
+/// Ensure a tag doesn't already exist on a remote
+pub fn ensure_remote_tag_not_exists(
+    network: &NetworkConfig,
+    remote: &str,
+    tag: &str,
+) -> Result<()> {
+    if remote_tag_exists(network, remote, tag)? {
+        return Err(Error::RemoteTagExists {
+            remote: remote.to_string(),
+            tag: tag.to_string(),
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,239 @@
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Whether a path contains glob wildcards (`*`, `?` or `[...]`)
+///
+/// A `[` is only a wildcard when a `]` closes it in the same component, so
+/// `notes[draft.md` is a plain file name.
+pub fn is_glob(path: &Path) -> bool {
+    path.to_str().is_some_and(|p| {
+        p.contains(['*', '?'])
+            || p.split(['/', '\\']).any(|component| {
+                component
+                    .find('[')
+                    .is_some_and(|i| component[i + 1..].contains(']'))
+            })
+    })
+}
+
+/// Find the files matching a glob pattern, sorted by path
+///
+/// `*` matches any part of a name and `?` one character, `[abc]` and
+/// `[!abc]` a character in or not in the set (a `[` without a closing `]` is
+/// literal); none of them match `/`, nor a
+/// leading `.` unless the pattern has one. `**` as a whole component matches
+/// any number of directories, including none. Relative patterns are resolved
+/// against the working directory and yield relative paths.
+pub fn expand(pattern: &Path) -> Vec<PathBuf> {
+    let pattern = pattern.to_string_lossy().replace('\\', "/");
+    let (base, rest) = match pattern.strip_prefix('/') {
+        Some(rest) => (PathBuf::from("/"), rest),
+        None => (PathBuf::new(), pattern.as_str()),
+    };
+    let components: Vec<&str> = rest.split('/').filter(|c| !c.is_empty()).collect();
+
+    let mut files = Vec::new();
+    walk(&base, &components, &mut files);
+    files.sort();
+    files.dedup();
+    files
+}
+
+fn walk(dir: &Path, components: &[&str], files: &mut Vec<PathBuf>) {
+    let Some((component, rest)) = components.split_first() else {
+        if dir.is_file() {
+            files.push(dir.to_path_buf());
+        }
+        return;
+    };
+
+    if *component == "**" {
+        walk(dir, rest, files);
+        for entry in entries(dir) {
+            if entry.is_dir() && !is_hidden(&entry) {
+                walk(&entry, components, files);
+            }
+        }
+    } else if !is_glob(Path::new(component)) {
+        walk(&dir.join(component), rest, files);
+    } else if let Some(regex) = component_regex(component) {
+        for entry in entries(dir) {
+            let name = entry
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default();
+            if regex.is_match(name) && (component.starts_with('.') || !name.starts_with('.')) {
+                walk(&entry, rest, files);
+            }
+        }
+    }
+}
+
+/// Entries of a directory, joined to it (a relative empty path is `.`)
+fn entries(dir: &Path) -> Vec<PathBuf> {
+    let read = if dir.as_os_str().is_empty() {
+        fs::read_dir(".")
+    } else {
+        fs::read_dir(dir)
+    };
+    read.map(|entries| {
+        entries
+            .filter_map(|e| e.ok())
+            .map(|e| dir.join(e.file_name()))
+            .collect()
+    })
+    .unwrap_or_default()
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.starts_with('.'))
+}
+
+/// Whether a path matches a glob pattern, or lies in a directory that does
+///
+/// Wildcards follow [`expand`], except that they also match hidden names;
+/// `crates/experimental` and `crates/experimental/**` both match every file
+/// in that directory. A leading `./` is ignored on both sides.
+pub fn matches(pattern: &str, path: &Path) -> bool {
+    let pattern = pattern.replace('\\', "/");
+    let pattern: Vec<&str> = pattern
+        .split('/')
+        .filter(|c| !c.is_empty() && *c != ".")
+        .collect();
+    let path = path.to_string_lossy().replace('\\', "/");
+    let path: Vec<&str> = path
+        .split('/')
+        .filter(|c| !c.is_empty() && *c != ".")
+        .collect();
+    match_components(&pattern, &path)
+}
+
+fn match_components(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        // Everything inside a matching directory matches too
+        None => true,
+        Some((&"**", rest)) => (0..=path.len()).any(|i| match_components(rest, &path[i..])),
+        Some((component, rest)) => path.split_first().is_some_and(|(name, path)| {
+            let matched = if is_glob(Path::new(component)) {
+                component_regex(component).is_some_and(|r| r.is_match(name))
+            } else {
+                component == name
+            };
+            matched && match_components(rest, path)
+        }),
+    }
+}
+
+/// Translate one path component of a glob into an anchored regex
+fn component_regex(component: &str) -> Option<Regex> {
+    let mut pattern = String::from("^");
+    let mut chars = component.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => pattern.push_str(".*"),
+            '?' => pattern.push('.'),
+            // Without a closing `]`, the `[` is literal and the rest is
+            // read as usual
+            '[' if chars.clone().any(|c| c == ']') => {
+                let mut class = String::new();
+                if chars.peek() == Some(&'!') {
+                    chars.next();
+                    class.push('^');
+                }
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                    if matches!(c, '\\' | '[' | '^' | '&' | '~') {
+                        class.push('\\');
+                    }
+                    class.push(c);
+                }
+                pattern.push_str(&format!("[{}]", class));
+            }
+            _ => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+    Regex::new(&pattern).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn touch(dir: &Path, path: &str) {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "").unwrap();
+    }
+
+    #[test]
+    fn test_expand_sorted() {
+        let dir = tempfile::tempdir().unwrap();
+        for path in [
+            "crates/b/Cargo.toml",
+            "crates/a/Cargo.toml",
+            "crates/a/src/lib.rs",
+            "crates/.hidden/Cargo.toml",
+            "crates/c/nested/Cargo.toml",
+            "Cargo.toml",
+        ] {
+            touch(dir.path(), path);
+        }
+
+        let found = expand(&dir.path().join("crates/*/Cargo.toml"));
+        assert_eq!(
+            found,
+            [
+                dir.path().join("crates/a/Cargo.toml"),
+                dir.path().join("crates/b/Cargo.toml")
+            ]
+        );
+
+        let found = expand(&dir.path().join("**/Cargo.toml"));
+        assert_eq!(found.len(), 4);
+        assert_eq!(found[0], dir.path().join("Cargo.toml"));
+
+        assert_eq!(
+            expand(&dir.path().join("crates/[!a]/Cargo.toml")),
+            [dir.path().join("crates/b/Cargo.toml")]
+        );
+        assert!(expand(&dir.path().join("packages/*/package.json")).is_empty());
+
+        touch(dir.path(), "notes[draft.md");
+        assert_eq!(
+            expand(&dir.path().join("notes[draft.md")),
+            [dir.path().join("notes[draft.md")]
+        );
+    }
+
+    #[test]
+    fn test_matches() {
+        let path = Path::new("crates/experimental/sub/Cargo.toml");
+        assert!(matches("crates/experimental/**", path));
+        assert!(matches("crates/experimental", path));
+        assert!(matches("./crates/exp*", path));
+        assert!(matches("**/sub/*.toml", path));
+        assert!(!matches("crates/stable/**", path));
+        assert!(!matches("crates/experimental/other", path));
+        assert!(matches("**/.cache", Path::new("a/.cache/x.json")));
+    }
+
+    #[test]
+    fn test_component_regex() {
+        let regex = component_regex("v?.[0-9]*").unwrap();
+        assert!(regex.is_match("v1.2-beta"));
+        assert!(!regex.is_match("v1.x"));
+        assert!(component_regex("a[b").unwrap().is_match("a[b"));
+        assert!(is_glob(Path::new("crates/*/Cargo.toml")));
+        assert!(is_glob(Path::new("crates/[ab]/Cargo.toml")));
+        assert!(!is_glob(Path::new("Cargo.toml")));
+        assert!(!is_glob(Path::new("notes[draft/v]1.md")));
+        assert!(matches("notes[draft.md", Path::new("notes[draft.md")));
+        assert!(matches("notes[draft*", Path::new("notes[draft.md")));
+    }
+}
@@ -0,0 +1,123 @@
+use std::path::{Path, PathBuf};
+
+/// Marker line identifying hooks written by versync; only hooks carrying it
+/// are replaced or removed
+pub const MARKER: &str = "# Installed by versync";
+
+/// Git hooks versync can install
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hook {
+    /// Runs `versync check` before every commit
+    PreCommit,
+    /// Verifies the release tag before it is pushed
+    PrePush,
+}
+
+const PRE_COMMIT: &str = r#"#!/bin/sh
+{marker} (pre-commit); remove with `versync hooks uninstall`
+# Refuse to commit when the versions are out of sync.
+exec {versync} check
+"#;
+
+const PRE_PUSH: &str = r#"#!/bin/sh
+{marker} (pre-push); remove with `versync hooks uninstall`
+# Refuse to push when the versions are out of sync, or when the tag for the
+# current version is pushed but does not point at HEAD.
+{versync} check || exit 1
+# A tag template with a {n} counter names the tag at HEAD, if any (empty
+# otherwise), so other counters are tags of their own and may point elsewhere.
+tag=$({versync} show --tag) || exit 1
+while read -r local_ref local_sha remote_ref remote_sha; do
+    if [ -n "$tag" ] && [ "$local_ref" = "refs/tags/$tag" ]; then
+        tagged=$(git rev-parse "$local_ref^{commit}") || exit 1
+        if [ "$tagged" != "$(git rev-parse HEAD)" ]; then
+            echo "versync: tag $tag does not point at HEAD" >&2
+            exit 1
+        fi
+    fi
+done
+"#;
+
+impl Hook {
+    /// All hooks, in installation order
+    pub const ALL: [Hook; 2] = [Hook::PreCommit, Hook::PrePush];
+
+    /// The hook's file name in the hooks directory
+    pub fn name(self) -> &'static str {
+        match self {
+            Hook::PreCommit => "pre-commit",
+            Hook::PrePush => "pre-push",
+        }
+    }
+
+    /// Render the hook script for a versync invocation (see [`command`])
+    pub fn script(self, versync: &str) -> String {
+        let template = match self {
+            Hook::PreCommit => PRE_COMMIT,
+            Hook::PrePush => PRE_PUSH,
+        };
+        crate::template::render(template, |name| match name {
+            "marker" => Some(MARKER.to_string()),
+            "versync" => Some(versync.to_string()),
+            _ => None,
+        })
+    }
+}
+
+/// Whether a hook script was written by versync
+pub fn is_managed(script: &str) -> bool {
+    script.lines().any(|line| line.starts_with(MARKER))
+}
+
+/// The shell command hooks use to run versync
+///
+/// Config paths are passed on unless they are the default; hooks run from
+/// the repository root, so relative paths are taken from there.
+pub fn command(config_paths: &[PathBuf]) -> String {
+    let mut command = "versync".to_string();
+    if config_paths != [Path::new("version.toml")] {
+        for path in config_paths {
+            command.push_str(" --config ");
+            command.push_str(&shell_quote(&path.display().to_string()));
+        }
+    }
+    command
+}
+
+/// Quote a word for POSIX shells if it needs it
+fn shell_quote(word: &str) -> String {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "-_./=+:@%".contains(c);
+    if !word.is_empty() && word.chars().all(safe) {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script() {
+        let script = Hook::PreCommit.script("versync --config ci.toml");
+        assert!(script.starts_with("#!/bin/sh\n# Installed by versync (pre-commit)"));
+        assert!(script.contains("exec versync --config ci.toml check\n"));
+        assert!(is_managed(&script));
+        assert!(!is_managed("#!/bin/sh\nnpm test\n"));
+
+        let script = Hook::PrePush.script("versync");
+        assert!(script.contains("tag=$(versync show --tag) || exit 1\n"));
+        assert!(script.contains(r#"git rev-parse "$local_ref^{commit}""#));
+    }
+
+    #[test]
+    fn test_command() {
+        assert_eq!(command(&[PathBuf::from("version.toml")]), "versync");
+        assert_eq!(
+            command(&[PathBuf::from("base.toml"), PathBuf::from("my config.toml")]),
+            "versync --config base.toml --config 'my config.toml'"
+        );
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+    }
+}
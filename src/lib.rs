@@ -1,8 +1,28 @@
+pub mod branch;
+pub mod cache;
+pub mod changelog;
+pub mod ci;
 pub mod commands;
+pub mod commits;
 pub mod config;
+pub mod diff;
 pub mod error;
 pub mod format;
+pub mod formatter;
 pub mod git;
+pub mod glob;
+pub mod hooks;
+pub mod network;
+pub mod policy;
+pub mod prompt;
+pub mod range;
+pub mod schema;
+pub mod scheme;
+pub mod table;
+pub mod template;
+pub mod term;
+pub mod update;
+pub mod version;
 
 pub use config::Config;
 pub use error::{Error, Result};
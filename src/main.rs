@@ -1,10 +1,26 @@
-use clap::{Parser, Subcommand};
-use std::path::PathBuf;
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use std::cmp::Ordering;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 
+use versync::ci::{self, Ci};
 use versync::commands;
-use versync::config::Config;
-use versync::error::exit_code;
+use versync::commands::adopt::AdoptOptions;
+use versync::commands::bump::{BumpLevel, BumpOptions};
+use versync::commands::check::CheckOptions;
+use versync::commands::export::ExportFormat;
+use versync::commands::graduate::GraduateOptions;
+use versync::commands::hooks::InstallOptions;
+use versync::commands::release::ReleaseOptions;
+use versync::commands::self_update::SelfUpdateOptions;
+use versync::commands::tag::TagOptions;
+use versync::commands::untag::UntagOptions;
+use versync::config::{self, Config, FileFormat, NetworkConfig};
+use versync::error::{exit_code, Error, FailureClass};
+use versync::scheme::Scheme;
+use versync::term::{self, ColorChoice, Term};
 
 #[derive(Parser)]
 #[command(name = "versync")]
@@ -14,9 +30,22 @@ struct Cli {
     #[command(subcommand)]
     command: Commands,
 
-    /// Path to the configuration file
-    #[arg(long, default_value = "version.toml", global = true)]
-    config: PathBuf,
+    /// Path to a configuration file or directory; repeat to merge several
+    /// (later ones take precedence) [default: version.toml, or the versync
+    /// table of pyproject.toml, Cargo.toml or package.json, also searched for
+    /// in parent directories up to the git root]
+    #[arg(long, global = true)]
+    config: Vec<PathBuf>,
+
+    /// Work on a component defined in [components] (default: the top-level
+    /// version; check and apply also cover every component)
+    #[arg(long, global = true)]
+    component: Option<String>,
+
+    /// Use this version instead of the configured one for this run (a tag
+    /// name such as v1.2.3 works too; default: $VERSYNC_VERSION)
+    #[arg(long, value_name = "VERSION", global = true)]
+    version_override: Option<String>,
 
     /// Suppress output
     #[arg(long, global = true)]
@@ -25,67 +54,963 @@ struct Cli {
     /// Enable verbose output
     #[arg(long, global = true)]
     verbose: bool,
+
+    /// Answer yes to all confirmation prompts
+    #[arg(short, long, global = true)]
+    yes: bool,
+
+    /// When to use colors
+    #[arg(long, value_enum, default_value_t = ColorChoice::Auto, global = true)]
+    color: ColorChoice,
+
+    /// Use ASCII instead of unicode glyphs
+    #[arg(long, global = true)]
+    ascii: bool,
+
+    /// Disable all network access (remote checks are skipped)
+    #[arg(long, global = true)]
+    offline: bool,
+
+    /// Ignore cached responses of remote lookups
+    #[arg(long, global = true)]
+    refresh: bool,
+
+    /// CI mode: no prompts, colors or tables, structured errors, and
+    /// warnings fail (detected from CI environment variables, minus the
+    /// warnings)
+    #[arg(long, global = true)]
+    ci: bool,
+
+    /// Disable CI mode even when a CI environment is detected
+    #[arg(long, global = true, conflicts_with = "ci")]
+    no_ci: bool,
 }
 
 #[derive(Subcommand)]
 enum Commands {
+    /// Create a config from the manifests found in the current directory
+    Init {
+        /// Overwrite an existing config file
+        #[arg(long)]
+        force: bool,
+    },
     /// Check if all version numbers match the source of truth
-    Check,
+    Check {
+        /// Also require a clean tree and the release tag at HEAD (for CI on tagged builds)
+        #[arg(long)]
+        frozen: bool,
+        /// Interactively adopt, overwrite or skip each mismatching target
+        #[arg(long, conflicts_with_all = ["frozen", "channel"])]
+        resolve: bool,
+        /// Re-run the check whenever the config or a target file changes
+        #[arg(long, conflicts_with = "resolve")]
+        watch: bool,
+        /// Rewrite mismatching targets to the source-of-truth version
+        #[arg(long, conflicts_with_all = ["resolve", "watch"])]
+        fix: bool,
+        /// Check the version derived for a release channel defined in [channels]
+        #[arg(long)]
+        channel: Option<String>,
+        /// Only check the targets in this group (repeatable)
+        #[arg(long = "group", value_name = "GROUP")]
+        groups: Vec<String>,
+        /// Skip the targets in this group (repeatable)
+        #[arg(long = "exclude-group", value_name = "GROUP")]
+        exclude_groups: Vec<String>,
+    },
+    /// Report the version, target state and git state in one place
+    Status,
     /// Apply the version from source of truth to all target files
-    Apply,
+    Apply {
+        /// Print the changes as a unified diff instead of writing them
+        #[arg(long)]
+        dry_run: bool,
+        /// Apply the version derived for a release channel defined in [channels]
+        #[arg(long)]
+        channel: Option<String>,
+        /// Only apply to the targets in this group (repeatable)
+        #[arg(long = "group", value_name = "GROUP")]
+        groups: Vec<String>,
+        /// Skip the targets in this group (repeatable)
+        #[arg(long = "exclude-group", value_name = "GROUP")]
+        exclude_groups: Vec<String>,
+    },
+    /// Increment the version in the config file
+    Bump {
+        /// Component to increment
+        #[arg(value_enum)]
+        level: BumpLevel,
+        /// Start a prerelease with this label (default: first of [prerelease] labels),
+        /// or switch label when bumping the prerelease
+        #[arg(long, value_name = "LABEL", num_args = 0..=1)]
+        pre: Option<Option<String>>,
+        /// Also apply the new version to all targets
+        #[arg(long)]
+        apply: bool,
+    },
+    /// Suggest the next version from conventional commits since the latest tag
+    Next {
+        /// Write the suggested version to the config file
+        #[arg(long)]
+        apply: bool,
+    },
+    /// Generate the changelog section for the current version from git history
+    Changelog {
+        /// Prepend the section to a changelog file instead of printing it
+        #[arg(long, value_name = "FILE", num_args = 0..=1, default_missing_value = "CHANGELOG.md")]
+        write: Option<PathBuf>,
+    },
+    /// Set an explicit version in the config file
+    Set {
+        /// New version (must be valid semver)
+        version: String,
+        /// Also apply the new version to all targets
+        #[arg(long)]
+        apply: bool,
+    },
+    /// Promote a prerelease to the stable version (1.4.0-rc.3 -> 1.4.0)
+    Graduate {
+        /// Also apply the stable version to all targets
+        #[arg(long)]
+        apply: bool,
+        /// Apply, commit and tag the stable version (like `release --no-push`)
+        #[arg(long)]
+        tag: bool,
+    },
+    /// Take the version stored in a target file as the new source of truth
+    Adopt {
+        /// Target file to read the version from
+        file: PathBuf,
+        /// Key of the target, when the file has several targets
+        #[arg(long)]
+        key: Option<String>,
+        /// Also apply the adopted version to all targets
+        #[arg(long)]
+        apply: bool,
+    },
+    /// Manage the targets in the config file
+    Target {
+        #[command(subcommand)]
+        action: TargetAction,
+    },
+    /// List every target with its format and the version stored in it
+    List,
+    /// List the released versions from git tags, newest first
+    History,
+    /// Print the current version (bare, for scripts)
+    #[command(alias = "current")]
+    Show {
+        /// Print the tag name for the version instead
+        #[arg(long)]
+        tag: bool,
+        /// Show the version derived for a release channel defined in [channels]
+        #[arg(long)]
+        channel: Option<String>,
+    },
+    /// Print the version for CI as plain text, JSON, env or GitHub output lines
+    Export {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = ExportFormat::Plain)]
+        format: ExportFormat,
+        /// Export the version derived for a release channel defined in [channels]
+        #[arg(long)]
+        channel: Option<String>,
+    },
+    /// Show the changes apply would make as a unified diff
+    Diff {
+        /// Emit a plain patch suitable for `git apply`
+        #[arg(long)]
+        patch: bool,
+        /// Diff the version derived for a release channel defined in [channels]
+        #[arg(long)]
+        channel: Option<String>,
+    },
     /// Create a git tag based on the current version
-    Tag,
+    Tag {
+        /// Replace the tag if it already exists
+        #[arg(long)]
+        force: bool,
+        /// Check every precondition and print the tag without creating it
+        #[arg(long)]
+        dry_run: bool,
+        /// Use the changelog section for the version as the tag message
+        #[arg(long)]
+        changelog: bool,
+        /// Push the tag to the remote (git.remote) after creating it
+        #[arg(long)]
+        push: bool,
+        /// Create the tag for a release channel defined in [channels]
+        #[arg(long)]
+        channel: Option<String>,
+    },
+    /// Push the tag for the current version, which must point at HEAD
+    Push {
+        /// Remote to push to (default: git.remote)
+        #[arg(long)]
+        remote: Option<String>,
+    },
+    /// Fail unless HEAD carries the tag for the current version
+    VerifyTag {
+        /// Verify the tag of a release channel defined in [channels]
+        #[arg(long)]
+        channel: Option<String>,
+    },
+    /// Delete the tag for the current (or given) version
+    Untag {
+        /// Version whose tag to delete (default: the configured version)
+        version: Option<String>,
+        /// Also delete the tag on the remote (git.remote)
+        #[arg(long)]
+        remote: bool,
+    },
+    /// Apply, commit, tag and push a release
+    Release {
+        /// Do not commit the updated target files
+        #[arg(long)]
+        no_commit: bool,
+        /// Do not create the tag
+        #[arg(long)]
+        no_tag: bool,
+        /// Do not push the commit and tag
+        #[arg(long)]
+        no_push: bool,
+    },
+    /// Compare the version with the one configured at another git ref
+    CompareRef {
+        /// Git ref to compare against (e.g. origin/main)
+        reference: String,
+        /// Also compare each target file at that ref
+        #[arg(long)]
+        targets: bool,
+    },
+    /// Set the version in content read from stdin and write it to stdout
+    Filter {
+        /// Format of the input
+        #[arg(long, value_parser = FileFormat::from_name)]
+        format: FileFormat,
+        /// Dot-separated key path of the version field
+        #[arg(long)]
+        key: String,
+        /// Version to set
+        #[arg(long)]
+        set: String,
+    },
+    /// Update versync to the latest release from GitHub
+    SelfUpdate {
+        /// Only check whether a newer release is available (exits 1 if so)
+        #[arg(long)]
+        check: bool,
+    },
+    /// Work with the configuration file
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Install or remove git hooks that run versync
+    Hooks {
+        #[command(subcommand)]
+        action: HooksAction,
+    },
+    /// Diagnose the config, target files and git environment
+    Doctor,
+    /// Normalize the config file: table and target order, key style and spacing
+    Fmt {
+        /// Only report files that are not formatted (exits 1 if any)
+        #[arg(long)]
+        check: bool,
+    },
+    /// Compare two versions (exit 0 if equal, 10 if lower, 11 if higher),
+    /// or check a version against a range with --satisfies
+    Compare {
+        /// Version to compare
+        a: String,
+        /// Version to compare against
+        #[arg(required_unless_present = "satisfies")]
+        b: Option<String>,
+        /// Check that the version satisfies a range such as ">=1.2, <2" or "^1.4"
+        #[arg(long, value_name = "RANGE", conflicts_with = "b")]
+        satisfies: Option<String>,
+        /// Order the versions as this scheme does (pep440 puts a
+        /// post-release such as 1.2.0+post.1 after its release)
+        #[arg(long, value_parser = Scheme::from_name, default_value = "semver")]
+        scheme: Scheme,
+    },
+    /// Print a shell completion script to stdout
+    Completions {
+        /// Shell to generate completions for
+        shell: Shell,
+    },
+    /// Open an interactive status dashboard
+    #[cfg(feature = "tui")]
+    Ui,
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Report every problem in the config: unknown keys, missing files,
+    /// duplicate targets, unresolvable keys and invalid tag names
+    Validate,
+    /// Print the JSON Schema of the config file, for editors and CI
+    Schema,
+}
+
+#[derive(Subcommand)]
+enum TargetAction {
+    /// Append a [[targets]] entry; the key must resolve in the file
+    Add {
+        /// Target file, relative to the working directory
+        file: PathBuf,
+        /// Dot-separated key path of the version (e.g. "package.version")
+        #[arg(long)]
+        key: String,
+        /// File format (inferred from the extension if omitted)
+        #[arg(long, value_parser = FileFormat::from_name)]
+        format: Option<FileFormat>,
+    },
+}
+
+#[derive(Subcommand)]
+enum HooksAction {
+    /// Install a pre-commit hook running `versync check`
+    Install {
+        /// Also install a pre-push hook verifying the release tag
+        #[arg(long)]
+        pre_push: bool,
+        /// Replace existing hooks that were not installed by versync
+        #[arg(long)]
+        force: bool,
+    },
+    /// Remove the hooks installed by versync
+    Uninstall,
 }
 
 fn main() -> ExitCode {
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
+    let ci = Ci::detect(cli.ci, cli.no_ci);
+    ci::init(ci);
+    let color = match cli.color {
+        ColorChoice::Auto if ci.enabled => ColorChoice::Never,
+        choice => choice,
+    };
+    term::init(Term::detect(color, cli.ascii || ci.enabled));
+
+    // Without --config, a version.toml (or a config embedded in pyproject.toml,
+    // Cargo.toml or package.json) in this or a parent directory is used, and
+    // target paths are relative to it
+    if cli.config.is_empty() {
+        let mut default = PathBuf::from(config::DEFAULT_CONFIG);
+        if !default.exists() && !matches!(cli.command, Commands::Init { .. }) {
+            let cwd = std::env::current_dir().ok();
+            let found = cwd.as_deref().and_then(config::discover);
+            if let (Some(cwd), Some(found)) = (cwd, found) {
+                let dir = found.parent().unwrap_or(Path::new(""));
+                let name = found.file_name().unwrap_or_default();
+                let working_dir = cwd.strip_prefix(dir).unwrap_or(Path::new(""));
+                config::init_working_dir(working_dir.to_path_buf());
+                if cli.verbose && !cli.quiet {
+                    eprintln!("Found config in {}", found.display());
+                }
+                default = config::root().join(name);
+            }
+        }
+        cli.config = vec![default];
+    }
+
+    // Commands that do not use the configuration
+    let standalone = match &cli.command {
+        Commands::Init { force } => {
+            Some(commands::init(&cli.config[0], *force, cli.quiet).map(|()| true))
+        }
+        Commands::Filter { format, key, set } => {
+            Some(commands::filter(*format, key, set).map(|()| true))
+        }
+        Commands::SelfUpdate { check } => {
+            // The config is optional here, but its [network] settings apply;
+            // a config that doesn't load must not stand in the way of an update
+            let mut network = if cli.config.iter().any(|path| path.exists()) {
+                match Config::load_all(&cli.config) {
+                    Ok(config) => config.network,
+                    Err(e) => {
+                        if !cli.quiet {
+                            eprintln!("Ignoring the config's [network] settings: {}", e);
+                        }
+                        NetworkConfig::default()
+                    }
+                }
+            } else {
+                NetworkConfig::default()
+            };
+            network.offline |= cli.offline;
+            network.refresh |= cli.refresh;
+            let options = SelfUpdateOptions {
+                check: *check,
+                yes: cli.yes,
+            };
+            Some(commands::self_update(&network, &options, cli.quiet))
+        }
+        Commands::Config {
+            action: ConfigAction::Validate,
+        } => Some(commands::config::validate(
+            &cli.config,
+            ci.strict,
+            cli.quiet,
+        )),
+        Commands::Config {
+            action: ConfigAction::Schema,
+        } => Some(commands::config::schema().map(|()| true)),
+        Commands::Hooks { action } => Some(
+            match action {
+                HooksAction::Install { pre_push, force } => {
+                    let options = InstallOptions {
+                        pre_push: *pre_push,
+                        force: *force,
+                    };
+                    commands::hooks::install(&cli.config, &options, cli.quiet)
+                }
+                HooksAction::Uninstall => commands::hooks::uninstall(cli.quiet),
+            }
+            .map(|()| true),
+        ),
+        Commands::Compare {
+            a,
+            b: Some(b),
+            scheme,
+            ..
+        } => {
+            return match commands::compare(a, b, *scheme, cli.quiet) {
+                Ok(Ordering::Equal) => ExitCode::from(exit_code::SUCCESS as u8),
+                Ok(Ordering::Less) => ExitCode::from(exit_code::LESS as u8),
+                Ok(Ordering::Greater) => ExitCode::from(exit_code::GREATER as u8),
+                Err(e) => {
+                    if !cli.quiet {
+                        eprintln!("{}", ci.error_line(&e));
+                    }
+                    ExitCode::from(e.exit_code() as u8)
+                }
+            };
+        }
+        Commands::Compare {
+            a,
+            satisfies: Some(range),
+            ..
+        } => Some(commands::compare::satisfies(a, range, cli.quiet)),
+        Commands::Compare { .. } => unreachable!("clap requires a version or --satisfies"),
+        Commands::Doctor => Some(Ok(commands::doctor(&cli.config, ci.strict, cli.quiet))),
+        Commands::Fmt { check } => Some(commands::fmt(&cli.config, *check, cli.quiet)),
+        Commands::Completions { shell } => {
+            clap_complete::generate(*shell, &mut Cli::command(), "versync", &mut io::stdout());
+            Some(Ok(true))
+        }
+        _ => None,
+    };
+    if let Some(result) = standalone {
+        return match result {
+            Ok(true) => ExitCode::from(exit_code::SUCCESS as u8),
+            Ok(false) => ExitCode::from(exit_code::MISMATCH as u8),
+            Err(e) => {
+                if !cli.quiet {
+                    eprintln!("{}", ci.error_line(&e));
+                }
+                ExitCode::from(e.exit_code() as u8)
+            }
+        };
+    }
 
     // Load configuration
-    let config = match Config::load(&cli.config) {
+    let mut config = match Config::load_all(&cli.config) {
         Ok(config) => config,
         Err(e) => {
             if !cli.quiet {
-                eprintln!("Error: {}", e);
+                eprintln!("{}", ci.error_line(&e));
             }
-            return ExitCode::from(exit_code::ERROR as u8);
+            return ExitCode::from(e.exit_code() as u8);
         }
     };
 
+    if cli.offline {
+        config.network.offline = true;
+    }
+    if cli.refresh {
+        config.network.refresh = true;
+    }
+
+    // Components, like channels, work on a derived configuration
+    if let Some(component) = &cli.component {
+        config = match config.for_component(component) {
+            Ok(config) => config,
+            Err(e) => {
+                if !cli.quiet {
+                    eprintln!("{}", ci.error_line(&e));
+                }
+                return ExitCode::from(config.exit_code_for(&e));
+            }
+        };
+    }
+
+    // A version injected by CI replaces the configured one for this run
+    let version_override = match &cli.version_override {
+        Some(version) => Some((version.clone(), "--version-override")),
+        None => std::env::var("VERSYNC_VERSION")
+            .ok()
+            .filter(|version| !version.is_empty())
+            .map(|version| (version, "VERSYNC_VERSION")),
+    };
+    if let Some((version, origin)) = &version_override {
+        // These derive the new version from the current one and write it to
+        // the config, which would persist the override
+        let command = match &cli.command {
+            Commands::Bump { .. } => Some("bump"),
+            Commands::Graduate { .. } => Some("graduate"),
+            _ => None,
+        };
+        let result = match command {
+            Some(command) => Err(Error::Usage(format!(
+                "{} writes the configured version and cannot run with a version override (unset {})",
+                command, origin
+            ))),
+            None => config.override_version(version),
+        };
+        if let Err(e) = result {
+            if !cli.quiet {
+                eprintln!("{}", ci.error_line(&e));
+            }
+            return ExitCode::from(config.exit_code_for(&e));
+        }
+        if !cli.quiet {
+            eprintln!(
+                "Version override active: {} (from {})",
+                config.version, origin
+            );
+        }
+    }
+
+    // Release channels work on a derived configuration
+    let channel = match &cli.command {
+        Commands::Check { channel, .. }
+        | Commands::Apply { channel, .. }
+        | Commands::Diff { channel, .. }
+        | Commands::Show { channel, .. }
+        | Commands::Export { channel, .. }
+        | Commands::Tag { channel, .. }
+        | Commands::VerifyTag { channel } => channel.as_deref(),
+        _ => None,
+    };
+    if let Some(channel) = channel {
+        config = match config.for_channel(channel) {
+            Ok(config) => config,
+            Err(e) => {
+                if !cli.quiet {
+                    eprintln!("{}", ci.error_line(&e));
+                }
+                return ExitCode::from(config.exit_code_for(&e));
+            }
+        };
+    }
+
+    // Check and apply can be narrowed to groups of targets
+    let (groups, exclude_groups) = match &cli.command {
+        Commands::Check {
+            groups,
+            exclude_groups,
+            ..
+        }
+        | Commands::Apply {
+            groups,
+            exclude_groups,
+            ..
+        } => (groups.clone(), exclude_groups.clone()),
+        _ => Default::default(),
+    };
+    if let Err(e) = config.select_groups(&groups, &exclude_groups) {
+        if !cli.quiet {
+            eprintln!("{}", ci.error_line(&e));
+        }
+        return ExitCode::from(config.exit_code_for(&e));
+    }
+
+    // Check and apply cover every component unless one is chosen; other
+    // commands need a version to work on
+    let configs = match &cli.command {
+        Commands::Check {
+            resolve: false,
+            watch: false,
+            ..
+        }
+        | Commands::Apply { .. }
+            if cli.component.is_none() && channel.is_none() =>
+        {
+            config.each_component()
+        }
+        _ => vec![config.clone()],
+    };
+    if configs.iter().any(|config| config.version.is_empty()) {
+        let names: Vec<&str> = config.components.keys().map(String::as_str).collect();
+        let e = Error::Usage(format!(
+            "The config has no top-level version, pass --component (one of: {})",
+            names.join(", ")
+        ));
+        if !cli.quiet {
+            eprintln!("{}", ci.error_line(&e));
+        }
+        return ExitCode::from(config.exit_code_for(&e));
+    }
+
     if cli.verbose && !cli.quiet {
-        eprintln!("Using config: {}", cli.config.display());
+        let paths: Vec<_> = cli.config.iter().map(|p| p.display().to_string()).collect();
+        eprintln!("Using config: {}", paths.join(", "));
+        if let Some(component) = &config.component {
+            eprintln!("Component: {}", component);
+        }
         eprintln!("Version: {}", config.version);
         eprintln!("Targets: {}", config.targets.len());
     }
 
     // Execute command
     match cli.command {
-        Commands::Check => match commands::check(&config, cli.quiet) {
+        Commands::Check {
+            frozen,
+            resolve,
+            watch,
+            fix,
+            channel,
+            ..
+        } => {
+            let options = CheckOptions { frozen, fix };
+            if watch {
+                let load = || {
+                    let mut config = Config::load_all(&cli.config)?;
+                    config.network.offline |= cli.offline;
+                    config.network.refresh |= cli.refresh;
+                    if let Some(component) = &cli.component {
+                        config = config.for_component(component)?;
+                    }
+                    if let Some((version, _)) = &version_override {
+                        config.override_version(version)?;
+                    }
+                    config.select_groups(&groups, &exclude_groups)?;
+                    match &channel {
+                        Some(channel) => config.for_channel(channel),
+                        None => Ok(config),
+                    }
+                };
+                let Err(e) = commands::watch(&cli.config, load, &options, cli.quiet);
+                if !cli.quiet {
+                    eprintln!("{}", ci.error_line(&e));
+                }
+                return ExitCode::from(config.exit_code_for(&e));
+            }
+            let result = if resolve {
+                config
+                    .version_file(&cli.config)
+                    .and_then(|path| commands::resolve(&config, &path, cli.quiet))
+            } else {
+                configs.iter().try_fold(true, |all_ok, config| {
+                    print_component(config, configs.len(), cli.quiet);
+                    Ok(commands::check(config, &options, cli.quiet)? && all_ok)
+                })
+            };
+            match result {
+                Ok(true) => ExitCode::from(exit_code::SUCCESS as u8),
+                Ok(false) => ExitCode::from(config.exit_code(FailureClass::Mismatch)),
+                Err(e) => {
+                    if !cli.quiet {
+                        eprintln!("{}", ci.error_line(&e));
+                    }
+                    ExitCode::from(config.exit_code_for(&e))
+                }
+            }
+        }
+        Commands::Status => match commands::status(&config, cli.quiet) {
             Ok(true) => ExitCode::from(exit_code::SUCCESS as u8),
-            Ok(false) => ExitCode::from(exit_code::MISMATCH as u8),
+            Ok(false) => ExitCode::from(config.exit_code(FailureClass::Mismatch)),
+            Err(e) => {
+                if !cli.quiet {
+                    eprintln!("{}", ci.error_line(&e));
+                }
+                ExitCode::from(config.exit_code_for(&e))
+            }
+        },
+        Commands::Apply { dry_run, .. } => {
+            let result = configs.iter().try_for_each(|config| {
+                print_component(config, configs.len(), cli.quiet);
+                if dry_run {
+                    commands::diff::apply_dry_run(config, cli.quiet).map(|_| ())
+                } else {
+                    commands::apply(config, cli.quiet)
+                }
+            });
+            match result {
+                Ok(()) => ExitCode::from(exit_code::SUCCESS as u8),
+                Err(e) => {
+                    if !cli.quiet {
+                        eprintln!("{}", ci.error_line(&e));
+                    }
+                    ExitCode::from(config.exit_code_for(&e))
+                }
+            }
+        }
+        Commands::Bump { level, pre, apply } => {
+            let options = BumpOptions { level, pre, apply };
+            match config
+                .version_file(&cli.config)
+                .and_then(|path| commands::bump(&config, &path, &options, cli.quiet))
+            {
+                Ok(_) => ExitCode::from(exit_code::SUCCESS as u8),
+                Err(e) => {
+                    if !cli.quiet {
+                        eprintln!("{}", ci.error_line(&e));
+                    }
+                    ExitCode::from(config.exit_code_for(&e))
+                }
+            }
+        }
+        Commands::Next { apply } => {
+            let result = if apply {
+                config
+                    .version_file(&cli.config)
+                    .and_then(|path| commands::next(&config, Some(&path), cli.quiet))
+            } else {
+                commands::next(&config, None, cli.quiet)
+            };
+            match result {
+                Ok(_) => ExitCode::from(exit_code::SUCCESS as u8),
+                Err(e) => {
+                    if !cli.quiet {
+                        eprintln!("{}", ci.error_line(&e));
+                    }
+                    ExitCode::from(config.exit_code_for(&e))
+                }
+            }
+        }
+        Commands::Changelog { write } => {
+            match commands::changelog(&config, write.as_deref(), cli.quiet) {
+                Ok(()) => ExitCode::from(exit_code::SUCCESS as u8),
+                Err(e) => {
+                    if !cli.quiet {
+                        eprintln!("{}", ci.error_line(&e));
+                    }
+                    ExitCode::from(config.exit_code_for(&e))
+                }
+            }
+        }
+        Commands::Set { version, apply } => match config
+            .version_file(&cli.config)
+            .and_then(|path| commands::set(&config, &path, &version, apply, cli.quiet))
+        {
+            Ok(_) => ExitCode::from(exit_code::SUCCESS as u8),
             Err(e) => {
                 if !cli.quiet {
-                    eprintln!("Error: {}", e);
+                    eprintln!("{}", ci.error_line(&e));
                 }
-                ExitCode::from(exit_code::ERROR as u8)
+                ExitCode::from(config.exit_code_for(&e))
             }
         },
-        Commands::Apply => match commands::apply(&config, cli.quiet) {
+        Commands::Graduate { apply, tag } => {
+            let options = GraduateOptions {
+                apply,
+                tag,
+                yes: cli.yes,
+            };
+            match commands::graduate(&config, &cli.config, &options, cli.quiet) {
+                Ok(_) => ExitCode::from(exit_code::SUCCESS as u8),
+                Err(e) => {
+                    if !cli.quiet {
+                        eprintln!("{}", ci.error_line(&e));
+                    }
+                    ExitCode::from(config.exit_code_for(&e))
+                }
+            }
+        }
+        Commands::Adopt { file, key, apply } => {
+            let options = AdoptOptions { key, apply };
+            match config
+                .version_file(&cli.config)
+                .and_then(|path| commands::adopt(&config, &path, &file, &options, cli.quiet))
+            {
+                Ok(_) => ExitCode::from(exit_code::SUCCESS as u8),
+                Err(e) => {
+                    if !cli.quiet {
+                        eprintln!("{}", ci.error_line(&e));
+                    }
+                    ExitCode::from(config.exit_code_for(&e))
+                }
+            }
+        }
+        Commands::Target {
+            action: TargetAction::Add { file, key, format },
+        } => match config
+            .version_file(&cli.config)
+            .and_then(|path| commands::target::add(&config, &path, &file, &key, format, cli.quiet))
+        {
             Ok(()) => ExitCode::from(exit_code::SUCCESS as u8),
             Err(e) => {
                 if !cli.quiet {
-                    eprintln!("Error: {}", e);
+                    eprintln!("{}", ci.error_line(&e));
                 }
-                ExitCode::from(exit_code::ERROR as u8)
+                ExitCode::from(config.exit_code_for(&e))
             }
         },
-        Commands::Tag => match commands::tag(&config, cli.quiet) {
+        Commands::List => {
+            commands::list(&config, cli.quiet);
+            ExitCode::from(exit_code::SUCCESS as u8)
+        }
+        Commands::History => match commands::history(&config, cli.quiet) {
             Ok(()) => ExitCode::from(exit_code::SUCCESS as u8),
             Err(e) => {
                 if !cli.quiet {
-                    eprintln!("Error: {}", e);
+                    eprintln!("{}", ci.error_line(&e));
                 }
-                ExitCode::from(exit_code::ERROR as u8)
+                ExitCode::from(config.exit_code_for(&e))
             }
         },
+        Commands::Show { tag, .. } => match commands::show(&config, tag, cli.quiet) {
+            Ok(()) => ExitCode::from(exit_code::SUCCESS as u8),
+            Err(e) => {
+                if !cli.quiet {
+                    eprintln!("{}", ci.error_line(&e));
+                }
+                ExitCode::from(config.exit_code_for(&e))
+            }
+        },
+        Commands::Export { format, .. } => match commands::export(&config, format, cli.quiet) {
+            Ok(()) => ExitCode::from(exit_code::SUCCESS as u8),
+            Err(e) => {
+                if !cli.quiet {
+                    eprintln!("{}", ci.error_line(&e));
+                }
+                ExitCode::from(config.exit_code_for(&e))
+            }
+        },
+        Commands::Diff { patch, .. } => match commands::diff(&config, patch, cli.quiet) {
+            Ok(_) => ExitCode::from(exit_code::SUCCESS as u8),
+            Err(e) => {
+                if !cli.quiet {
+                    eprintln!("{}", ci.error_line(&e));
+                }
+                ExitCode::from(config.exit_code_for(&e))
+            }
+        },
+        Commands::Tag {
+            force,
+            dry_run,
+            changelog,
+            push,
+            ..
+        } => {
+            let options = TagOptions {
+                force,
+                yes: cli.yes,
+                dry_run,
+                changelog,
+                push,
+            };
+            match commands::tag(&config, &options, cli.quiet) {
+                Ok(_) => ExitCode::from(exit_code::SUCCESS as u8),
+                Err(e) => {
+                    if !cli.quiet {
+                        eprintln!("{}", ci.error_line(&e));
+                    }
+                    ExitCode::from(config.exit_code_for(&e))
+                }
+            }
+        }
+        Commands::Push { remote } => match commands::push(&config, remote.as_deref(), cli.quiet) {
+            Ok(()) => ExitCode::from(exit_code::SUCCESS as u8),
+            Err(e) => {
+                if !cli.quiet {
+                    eprintln!("{}", ci.error_line(&e));
+                }
+                ExitCode::from(config.exit_code_for(&e))
+            }
+        },
+        Commands::VerifyTag { .. } => match commands::verify_tag(&config, cli.quiet) {
+            Ok(_) => ExitCode::from(exit_code::SUCCESS as u8),
+            Err(e) => {
+                if !cli.quiet {
+                    eprintln!("{}", ci.error_line(&e));
+                }
+                ExitCode::from(config.exit_code_for(&e))
+            }
+        },
+        Commands::Untag { version, remote } => {
+            let options = UntagOptions {
+                version,
+                remote,
+                yes: cli.yes,
+            };
+            match commands::untag(&config, &options, cli.quiet) {
+                Ok(()) => ExitCode::from(exit_code::SUCCESS as u8),
+                Err(e) => {
+                    if !cli.quiet {
+                        eprintln!("{}", ci.error_line(&e));
+                    }
+                    ExitCode::from(config.exit_code_for(&e))
+                }
+            }
+        }
+        Commands::Release {
+            no_commit,
+            no_tag,
+            no_push,
+        } => {
+            let options = ReleaseOptions {
+                no_commit,
+                no_tag,
+                no_push,
+                yes: cli.yes,
+            };
+            match commands::release(&config, &cli.config, &options, cli.quiet) {
+                Ok(()) => ExitCode::from(exit_code::SUCCESS as u8),
+                Err(e) => {
+                    if !cli.quiet {
+                        eprintln!("{}", ci.error_line(&e));
+                    }
+                    ExitCode::from(config.exit_code_for(&e))
+                }
+            }
+        }
+        Commands::CompareRef { reference, targets } => {
+            match commands::compare_ref(&config, &cli.config, &reference, targets, cli.quiet) {
+                Ok(Ordering::Less) => ExitCode::from(config.exit_code(FailureClass::Mismatch)),
+                Ok(_) => ExitCode::from(exit_code::SUCCESS as u8),
+                Err(e) => {
+                    if !cli.quiet {
+                        eprintln!("{}", ci.error_line(&e));
+                    }
+                    ExitCode::from(config.exit_code_for(&e))
+                }
+            }
+        }
+        Commands::Init { .. }
+        | Commands::Filter { .. }
+        | Commands::SelfUpdate { .. }
+        | Commands::Config { .. }
+        | Commands::Hooks { .. }
+        | Commands::Compare { .. }
+        | Commands::Doctor
+        | Commands::Fmt { .. }
+        | Commands::Completions { .. } => {
+            unreachable!("handled before loading the configuration")
+        }
+        #[cfg(feature = "tui")]
+        Commands::Ui => match commands::ui(&cli.config, &config) {
+            Ok(()) => ExitCode::from(exit_code::SUCCESS as u8),
+            Err(e) => {
+                if !cli.quiet {
+                    eprintln!("{}", ci.error_line(&e));
+                }
+                ExitCode::from(config.exit_code_for(&e))
+            }
+        },
+    }
+}
+
+/// Head the output for a component when a command covers several
+fn print_component(config: &Config, count: usize, quiet: bool) {
+    if count > 1 && !quiet {
+        match &config.component {
+            Some(name) => println!("COMPONENT {} ({})", name, config.version),
+            None => println!("VERSION {}", config.version),
+        }
     }
 }
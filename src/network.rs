@@ -0,0 +1,263 @@
+use crate::config::NetworkConfig;
+use crate::error::{Error, Result};
+use std::io::Read;
+use std::process::{Command, Output, Stdio};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Outcome of a step that needs network access
+#[derive(Debug, PartialEq, Eq)]
+pub enum Online<T> {
+    /// The step ran and produced a value
+    Ran(T),
+    /// The step was skipped because offline mode is enabled
+    Skipped,
+}
+
+/// Run a network-touching step unless offline mode is enabled
+///
+/// Offline runs never attempt the step, so air-gapped environments get an
+/// explicit `Skipped` instead of a hang or a connection error.
+pub fn run<T>(network: &NetworkConfig, step: impl FnOnce() -> Result<T>) -> Result<Online<T>> {
+    if network.offline {
+        return Ok(Online::Skipped);
+    }
+    step().map(Online::Ran)
+}
+
+/// The line printed for a step skipped in offline mode
+pub fn skipped(what: &str) -> String {
+    format!("SKIPPED {}: offline", what)
+}
+
+/// Stderr fragments that indicate a transient connectivity problem rather
+/// than a real failure of the operation
+const NETWORK_FAILURES: &[&str] = &[
+    "could not resolve host",
+    "could not resolve proxy",
+    "connection refused",
+    "connection timed out",
+    "connection reset",
+    "network is unreachable",
+    "operation timed out",
+    "failed to connect",
+    "temporary failure in name resolution",
+    "the remote end hung up unexpectedly",
+    "tls connection was non-properly terminated",
+];
+
+/// Whether a command's stderr describes a network failure
+pub fn is_network_failure(stderr: &str) -> bool {
+    let stderr = stderr.to_lowercase();
+    NETWORK_FAILURES.iter().any(|f| stderr.contains(f))
+}
+
+/// Whether an error is worth retrying
+fn is_retryable(error: &Error) -> bool {
+    matches!(error, Error::Network { .. } | Error::NetworkTimeout { .. })
+}
+
+/// Delay before the given retry (0-based), doubling each time
+fn backoff(network: &NetworkConfig, retry: u32) -> Duration {
+    Duration::from_millis(network.retry_delay.saturating_mul(1 << retry.min(16)))
+}
+
+/// Run a remote command with the configured proxy, timeout and retries
+///
+/// Timeouts and network failures (classified from stderr) are retried with
+/// exponential backoff and surface as `NetworkTimeout`/`Network` errors. Any
+/// other outcome, including a non-zero exit, is returned for the caller to
+/// interpret.
+pub fn command_output(
+    network: &NetworkConfig,
+    operation: &str,
+    command: &mut Command,
+) -> Result<Output> {
+    if let Some(proxy) = &network.proxy {
+        for var in ["HTTPS_PROXY", "HTTP_PROXY", "https_proxy", "http_proxy"] {
+            command.env(var, proxy);
+        }
+    }
+
+    let mut retry = 0;
+    loop {
+        let result = output_with_timeout(network, operation, command).and_then(|output| {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if !output.status.success() && is_network_failure(&stderr) {
+                Err(Error::Network {
+                    operation: operation.to_string(),
+                    message: stderr.trim().to_string(),
+                })
+            } else {
+                Ok(output)
+            }
+        });
+
+        match result {
+            Err(e) if is_retryable(&e) && retry < network.retries => {
+                thread::sleep(backoff(network, retry));
+                retry += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Fetch a URL with curl, honouring the proxy, timeout and retry settings
+///
+/// HTTP errors (status 400 and above) fail the request.
+pub fn fetch(network: &NetworkConfig, url: &str, headers: &[String]) -> Result<Vec<u8>> {
+    let mut command = Command::new("curl");
+    command.args([
+        "--silent",
+        "--show-error",
+        "--fail",
+        "--location",
+        "--proto",
+        "=https",
+    ]);
+    for header in headers {
+        command.args(["--header", header]);
+    }
+    command.arg(url);
+
+    let output = command_output(network, "curl", &mut command)?;
+    if !output.status.success() {
+        return Err(Error::Network {
+            operation: format!("GET {}", url),
+            message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(output.stdout)
+}
+
+/// Run a command, killing it if it exceeds the configured timeout
+fn output_with_timeout(
+    network: &NetworkConfig,
+    operation: &str,
+    command: &mut Command,
+) -> Result<Output> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| Error::GitCommand(format!("Failed to execute {}: {}", operation, e)))?;
+
+    // Drain the pipes on threads so a chatty child cannot block on a full pipe
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let deadline = Instant::now() + Duration::from_secs(network.timeout);
+    let status = loop {
+        match child.try_wait()? {
+            Some(status) => break status,
+            None if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(Error::NetworkTimeout {
+                    operation: operation.to_string(),
+                    seconds: network.timeout,
+                });
+            }
+            None => thread::sleep(Duration::from_millis(20)),
+        }
+    };
+
+    Ok(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+/// Read a child pipe to the end on a background thread
+fn drain<R: Read + Send + 'static>(pipe: Option<R>) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buffer);
+        }
+        buffer
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offline_skips_step() {
+        let network = NetworkConfig {
+            offline: true,
+            ..Default::default()
+        };
+        let result = run(&network, || -> Result<()> { panic!("must not run") }).unwrap();
+        assert_eq!(result, Online::Skipped);
+    }
+
+    #[test]
+    fn test_online_runs_step() {
+        let network = NetworkConfig::default();
+        assert_eq!(run(&network, || Ok(42)).unwrap(), Online::Ran(42));
+    }
+
+    #[test]
+    fn test_network_failure_classification() {
+        assert!(is_network_failure(
+            "fatal: unable to access 'https://x/': Could not resolve host: x"
+        ));
+        assert!(is_network_failure(
+            "ssh: connect to host x port 22: Connection refused"
+        ));
+        assert!(!is_network_failure(
+            "fatal: 'origin' does not appear to be a git repository"
+        ));
+        assert!(!is_network_failure(
+            "! [rejected] v1.0.0 -> v1.0.0 (already exists)"
+        ));
+    }
+
+    #[test]
+    fn test_backoff_doubles() {
+        let network = NetworkConfig {
+            retry_delay: 100,
+            ..Default::default()
+        };
+        assert_eq!(backoff(&network, 0), Duration::from_millis(100));
+        assert_eq!(backoff(&network, 1), Duration::from_millis(200));
+        assert_eq!(backoff(&network, 3), Duration::from_millis(800));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_timeout_kills_command() {
+        let network = NetworkConfig {
+            timeout: 1,
+            retries: 0,
+            ..Default::default()
+        };
+        let mut command = Command::new("sleep");
+        command.arg("10");
+
+        let started = Instant::now();
+        let result = command_output(&network, "sleep", &mut command);
+        assert!(matches!(
+            result,
+            Err(Error::NetworkTimeout { seconds: 1, .. })
+        ));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_output_success() {
+        let network = NetworkConfig::default();
+        let mut command = Command::new("echo");
+        command.arg("hello");
+
+        let output = command_output(&network, "echo", &mut command).unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "hello\n");
+    }
+}
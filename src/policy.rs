@@ -0,0 +1,301 @@
+use crate::config::{Config, PolicyConfig};
+use crate::error::{Error, Result};
+use crate::format;
+use crate::git;
+use crate::scheme::Scheme;
+use crate::version::{Level, Version};
+use regex::Regex;
+
+/// A violated `[policy]` rule
+#[derive(Debug, PartialEq, Eq)]
+pub struct Violation {
+    /// The config key of the rule
+    pub rule: &'static str,
+    pub message: String,
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "POLICY {}: {}", self.rule, self.message)
+    }
+}
+
+/// Repository facts the git-dependent rules are evaluated against
+#[derive(Debug, Default)]
+pub struct RepoState {
+    /// Current branch (`None` when detached or outside a repository)
+    pub branch: Option<String>,
+    /// Latest version among existing tags
+    pub latest_tag: Option<Version>,
+}
+
+impl RepoState {
+    /// Read the repository state, or an empty state outside a git repository
+    pub fn read(config: &Config) -> Result<Self> {
+        if !git::is_inside_work_tree()? {
+            return Ok(Self::default());
+        }
+        Ok(Self {
+            branch: git::current_branch()?,
+            latest_tag: git::tagged_versions(&config.git.tag_prefix, config.effective_scheme())?
+                .pop()
+                .map(|(_, version)| version),
+        })
+    }
+}
+
+fn compile(rule: &str, pattern: &str) -> Result<Regex> {
+    Regex::new(pattern).map_err(|e| Error::ConfigParse(format!("Invalid policy.{}: {}", rule, e)))
+}
+
+/// Evaluate all policy rules for a version and tag prefix
+pub fn evaluate(
+    policy: &PolicyConfig,
+    version: &str,
+    tag_prefix: &str,
+    repo: &RepoState,
+) -> Result<Vec<Violation>> {
+    let mut violations = Vec::new();
+
+    if let Some(pattern) = &policy.version_pattern {
+        if !compile("version_pattern", pattern)?.is_match(version) {
+            violations.push(Violation {
+                rule: "version_pattern",
+                message: format!("version {} does not match '{}'", version, pattern),
+            });
+        }
+    }
+
+    if let Some(pattern) = &policy.tag_prefix_pattern {
+        if !compile("tag_prefix_pattern", pattern)?.is_match(tag_prefix) {
+            violations.push(Violation {
+                rule: "tag_prefix_pattern",
+                message: format!("tag prefix '{}' does not match '{}'", tag_prefix, pattern),
+            });
+        }
+    }
+
+    let needs_semver = !policy.forbid_prerelease_on.is_empty() || policy.max_jump.is_some();
+    if !needs_semver {
+        return Ok(violations);
+    }
+    let parsed = Version::parse(version)?;
+
+    if let Some(branch) = &repo.branch {
+        if parsed.is_prerelease() && policy.forbid_prerelease_on.contains(branch) {
+            violations.push(Violation {
+                rule: "forbid_prerelease_on",
+                message: format!("prerelease {} is not allowed on {}", version, branch),
+            });
+        }
+    }
+
+    if let (Some(level), Some(latest)) = (policy.max_jump, &repo.latest_tag) {
+        if exceeds_jump(latest, &parsed, level) {
+            violations.push(Violation {
+                rule: "max_jump",
+                message: format!(
+                    "{} is more than a {} bump from the latest tag {}",
+                    version, level, latest
+                ),
+            });
+        }
+    }
+
+    Ok(violations)
+}
+
+/// Whether `version` is beyond a single `level` bump of `latest`
+fn exceeds_jump(latest: &Version, version: &Version, level: Level) -> bool {
+    version.core() > latest.core().bump(level)
+}
+
+/// Evaluate the policy of a configuration against the current repository
+pub fn check(config: &Config) -> Result<Vec<Violation>> {
+    let repo = RepoState::read(config)?;
+    evaluate(
+        &config.policy,
+        &config.version,
+        &config.git.tag_prefix,
+        &repo,
+    )
+}
+
+/// Fail with `PolicyViolation` if any rule is violated
+pub fn enforce(config: &Config) -> Result<()> {
+    let violations = check(config)?;
+    if violations.is_empty() {
+        return Ok(());
+    }
+
+    let messages: Vec<String> = violations.iter().map(|v| v.message.clone()).collect();
+    Err(Error::PolicyViolation(messages.join("; ")))
+}
+
+/// The `allow_downgrade` rule: replacing the version `from` (held by
+/// `what`) with a lower `to`, in the ordering of `scheme`
+///
+/// Values that aren't semver are not compared.
+pub fn downgrade(
+    policy: &PolicyConfig,
+    scheme: Scheme,
+    what: &str,
+    from: &str,
+    to: &str,
+) -> Option<Violation> {
+    if policy.allow_downgrade {
+        return None;
+    }
+    let (Ok(old), Ok(new)) = (Version::parse(from), Version::parse(to)) else {
+        return None;
+    };
+    scheme.compare(&new, &old).is_lt().then(|| Violation {
+        rule: "allow_downgrade",
+        message: format!("{} holds {}, which {} would downgrade", what, from, to),
+    })
+}
+
+/// Fail with `PolicyViolation` if the version would go from `from` down to `to`
+pub fn enforce_downgrade(config: &Config, what: &str, from: &str, to: &str) -> Result<()> {
+    match downgrade(&config.policy, config.effective_scheme(), what, from, to) {
+        Some(violation) => Err(Error::PolicyViolation(violation.message)),
+        None => Ok(()),
+    }
+}
+
+/// Fail with `PolicyViolation` if applying the version would lower the
+/// version a target holds
+///
+/// Targets that can't be read are left to apply to report.
+pub fn enforce_targets(config: &Config) -> Result<()> {
+    if config.policy.allow_downgrade {
+        return Ok(());
+    }
+
+    let mut messages = Vec::new();
+    for target in &config.targets {
+        let Some(format) = target.effective_format() else {
+            continue;
+        };
+        let Ok(value) = format::read_version(&target.file, &target.key, format) else {
+            continue;
+        };
+        let Some(current) = target.version_from_value(&value) else {
+            continue;
+        };
+        let what = format!("{} {}", target.file.display(), target.key);
+        let scheme = target.scheme.unwrap_or(config.effective_scheme());
+        if let Some(violation) = downgrade(&config.policy, scheme, &what, &current, &config.version)
+        {
+            messages.push(violation.message);
+        }
+    }
+
+    if messages.is_empty() {
+        return Ok(());
+    }
+    Err(Error::PolicyViolation(messages.join("; ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(violations: Vec<Violation>) -> Vec<&'static str> {
+        violations.into_iter().map(|v| v.rule).collect()
+    }
+
+    #[test]
+    fn test_empty_policy() {
+        let policy = PolicyConfig::default();
+        let repo = RepoState::default();
+        assert!(evaluate(&policy, "anything", "", &repo).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_patterns() {
+        let policy = PolicyConfig {
+            version_pattern: Some(r"^\d+\.\d+\.\d+$".to_string()),
+            tag_prefix_pattern: Some("^v$".to_string()),
+            ..Default::default()
+        };
+        let repo = RepoState::default();
+
+        assert!(evaluate(&policy, "1.2.3", "v", &repo).unwrap().is_empty());
+        assert_eq!(
+            rules(evaluate(&policy, "1.2.3-rc.1", "release-", &repo).unwrap()),
+            vec!["version_pattern", "tag_prefix_pattern"]
+        );
+    }
+
+    #[test]
+    fn test_forbid_prerelease_on_branch() {
+        let policy = PolicyConfig {
+            forbid_prerelease_on: vec!["main".to_string()],
+            ..Default::default()
+        };
+        let on = |branch: &str| RepoState {
+            branch: Some(branch.to_string()),
+            latest_tag: None,
+        };
+
+        assert_eq!(
+            rules(evaluate(&policy, "1.0.0-rc.1", "v", &on("main")).unwrap()),
+            vec!["forbid_prerelease_on"]
+        );
+        assert!(evaluate(&policy, "1.0.0", "v", &on("main"))
+            .unwrap()
+            .is_empty());
+        assert!(evaluate(&policy, "1.0.0-rc.1", "v", &on("develop"))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_max_jump() {
+        let latest = Version::parse("1.2.3").unwrap();
+        let check =
+            |version: &str, level| exceeds_jump(&latest, &Version::parse(version).unwrap(), level);
+
+        assert!(!check("1.2.4", Level::Patch));
+        assert!(check("1.2.5", Level::Patch));
+        assert!(check("1.3.0", Level::Patch));
+        assert!(!check("1.3.0", Level::Minor));
+        assert!(!check("1.3.0-rc.1", Level::Minor));
+        assert!(check("1.4.0", Level::Minor));
+        assert!(check("2.0.0", Level::Minor));
+        assert!(!check("2.0.0", Level::Major));
+        assert!(check("3.0.0", Level::Major));
+        assert!(!check("1.0.0", Level::Patch));
+    }
+
+    #[test]
+    fn test_downgrade() {
+        let mut policy = PolicyConfig::default();
+        assert!(downgrade(&policy, Scheme::Semver, "version", "1.3.0", "1.2.0").is_none());
+
+        policy.allow_downgrade = false;
+        assert_eq!(
+            downgrade(
+                &policy,
+                Scheme::Semver,
+                "Cargo.toml package.version",
+                "1.3.0",
+                "1.2.0"
+            ),
+            Some(Violation {
+                rule: "allow_downgrade",
+                message: "Cargo.toml package.version holds 1.3.0, which 1.2.0 would downgrade"
+                    .to_string(),
+            })
+        );
+        assert!(downgrade(&policy, Scheme::Semver, "version", "1.2.0", "1.2.0").is_none());
+        assert!(downgrade(&policy, Scheme::Semver, "version", "1.2.0-rc.1", "1.2.0").is_none());
+        assert!(downgrade(&policy, Scheme::Semver, "version", "1.2.0", "1.2.0-rc.1").is_some());
+        // Build metadata doesn't take part in ordering
+        assert!(downgrade(&policy, Scheme::Semver, "version", "1.2.0+5", "1.2.0").is_none());
+        assert!(downgrade(&policy, Scheme::Semver, "version", "unreleased", "1.2.0").is_none());
+        // Except for a PEP 440 post-release
+        assert!(downgrade(&policy, Scheme::Pep440, "version", "1.2.0+post.1", "1.2.0").is_some());
+    }
+}
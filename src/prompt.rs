@@ -0,0 +1,112 @@
+use crate::ci;
+use crate::error::{Error, Result};
+use std::io::{self, BufRead, IsTerminal, Write};
+
+/// Ask the user to confirm a destructive operation
+///
+/// Returns immediately when `assume_yes` is set (`--yes`). Otherwise the
+/// question is only asked when stdin is a terminal and CI mode is off;
+/// non-interactive runs fail with `ConfirmationRequired` instead of silently
+/// proceeding.
+pub fn confirm(message: &str, assume_yes: bool) -> Result<()> {
+    if assume_yes {
+        return Ok(());
+    }
+
+    if !interactive() {
+        return Err(Error::ConfirmationRequired(message.to_string()));
+    }
+
+    eprint!("{} [y/N] ", message);
+    io::stderr().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+
+    if is_yes(&answer) {
+        Ok(())
+    } else {
+        Err(Error::Aborted)
+    }
+}
+
+/// Ask the user to pick one of several choices, identified by a key letter
+///
+/// Asks again until a valid key is entered. Fails with `NotInteractive`
+/// when stdin is not a terminal or in CI mode.
+pub fn choose(message: &str, choices: &[(char, &str)]) -> Result<char> {
+    if !interactive() {
+        return Err(Error::NotInteractive(message.to_string()));
+    }
+
+    eprintln!("{}", message);
+    for (key, label) in choices {
+        eprintln!("  [{}] {}", key, label);
+    }
+
+    loop {
+        eprint!("Choice: ");
+        io::stderr().flush()?;
+
+        let mut answer = String::new();
+        if io::stdin().lock().read_line(&mut answer)? == 0 {
+            return Err(Error::Aborted);
+        }
+        if let Some(key) = parse_choice(&answer, choices) {
+            return Ok(key);
+        }
+    }
+}
+
+/// Whether prompts can be shown
+fn interactive() -> bool {
+    io::stdin().is_terminal() && !ci::get().enabled
+}
+
+/// Match an answer against the choice keys (case-insensitive)
+fn parse_choice(answer: &str, choices: &[(char, &str)]) -> Option<char> {
+    let mut chars = answer.trim().chars();
+    let first = chars.next()?.to_ascii_lowercase();
+    if chars.next().is_some() {
+        return None;
+    }
+    choices
+        .iter()
+        .map(|(key, _)| *key)
+        .find(|key| *key == first)
+}
+
+/// Interpret a prompt answer, defaulting to "no"
+fn is_yes(answer: &str) -> bool {
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_yes() {
+        assert!(is_yes("y\n"));
+        assert!(is_yes("YES"));
+        assert!(is_yes("  yes  "));
+        assert!(!is_yes(""));
+        assert!(!is_yes("n"));
+        assert!(!is_yes("yep"));
+    }
+
+    #[test]
+    fn test_parse_choice() {
+        let choices = [('a', "adopt"), ('s', "skip")];
+        assert_eq!(parse_choice("a\n", &choices), Some('a'));
+        assert_eq!(parse_choice(" S ", &choices), Some('s'));
+        assert_eq!(parse_choice("x", &choices), None);
+        assert_eq!(parse_choice("as", &choices), None);
+        assert_eq!(parse_choice("", &choices), None);
+    }
+
+    #[test]
+    fn test_assume_yes_skips_prompt() {
+        assert!(confirm("Delete everything?", true).is_ok());
+    }
+}
@@ -0,0 +1,313 @@
+use crate::error::{Error, Result};
+use crate::version::{Identifier, Version};
+use std::cmp::Ordering;
+
+/// A comparison operator in a version range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+/// A single bound such as `>=1.2.0`
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Comparator {
+    op: Op,
+    version: Version,
+}
+
+impl Comparator {
+    fn matches(&self, version: &Version) -> bool {
+        let ordering = version.precedence(&self.version);
+        match self.op {
+            Op::Eq => ordering == Ordering::Equal,
+            Op::Gt => ordering == Ordering::Greater,
+            Op::Ge => ordering != Ordering::Less,
+            Op::Lt => ordering == Ordering::Less,
+            Op::Le => ordering != Ordering::Greater,
+        }
+    }
+}
+
+/// A version requirement such as `>=1.2, <2` or `^1.4 || ~2.0.1`
+///
+/// Comparators within a set are separated by commas or whitespace and must
+/// all match; sets are separated by `||` and any of them may match.
+/// Supported forms:
+///
+/// - `=1.2.3`, `>1.2.3`, `>=1.2.3`, `<1.2.3`, `<=1.2.3`
+/// - `1.2.3` (exact), `1.2` / `1.2.x` (any patch), `1` / `1.x`, `*`
+/// - `^1.2.3`: compatible versions, `>=1.2.3, <2.0.0` (`^0.2.3` is `<0.3.0`)
+/// - `~1.2.3`: patch updates, `>=1.2.3, <1.3.0`
+///
+/// Like npm and Cargo, a prerelease only satisfies the range if one of the
+/// comparators has a prerelease of the same `MAJOR.MINOR.PATCH`, so `>=1.0`
+/// does not match `2.0.0-rc.1`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Range {
+    sets: Vec<Vec<Comparator>>,
+}
+
+fn invalid(range: &str, reason: impl Into<String>) -> Error {
+    Error::InvalidRange {
+        range: range.to_string(),
+        reason: reason.into(),
+    }
+}
+
+/// A possibly partial version (`1`, `1.2`, `1.x`, `1.2.3-rc.1`)
+#[derive(Debug)]
+struct Partial {
+    major: Option<u64>,
+    minor: Option<u64>,
+    patch: Option<u64>,
+    pre: Vec<Identifier>,
+}
+
+impl Partial {
+    fn parse(s: &str, range: &str) -> Result<Self> {
+        if s.contains(['-', '+']) {
+            let version = Version::parse(s)?;
+            return Ok(Self {
+                major: Some(version.major),
+                minor: Some(version.minor),
+                patch: Some(version.patch),
+                pre: version.pre,
+            });
+        }
+
+        let mut parts = [None; 3];
+        let mut wildcard = false;
+        let components: Vec<&str> = s.split('.').collect();
+        if components.len() > 3 {
+            return Err(invalid(range, format!("'{}' has too many components", s)));
+        }
+        for (i, component) in components.iter().enumerate() {
+            if matches!(*component, "x" | "X" | "*") {
+                wildcard = true;
+            } else if wildcard {
+                return Err(invalid(
+                    range,
+                    format!("'{}' has a number after a wildcard", s),
+                ));
+            } else {
+                parts[i] = Some(
+                    component
+                        .parse()
+                        .map_err(|_| invalid(range, format!("'{}' is not a version", s)))?,
+                );
+            }
+        }
+
+        Ok(Self {
+            major: parts[0],
+            minor: parts[1],
+            patch: parts[2],
+            pre: Vec::new(),
+        })
+    }
+
+    /// The lowest version matching the partial (`1.2` -> `1.2.0`)
+    fn floor(&self) -> Version {
+        let mut version = Version::new(
+            self.major.unwrap_or(0),
+            self.minor.unwrap_or(0),
+            self.patch.unwrap_or(0),
+        );
+        version.pre = self.pre.clone();
+        version
+    }
+
+    /// The first version above every version matching the partial
+    /// (`1.2` -> `1.3.0`); `None` for a full version or `*`
+    fn ceiling(&self) -> Option<Version> {
+        match (self.major, self.minor, self.patch) {
+            (Some(major), None, _) => Some(Version::new(major + 1, 0, 0)),
+            (Some(major), Some(minor), None) => Some(Version::new(major, minor + 1, 0)),
+            _ => None,
+        }
+    }
+}
+
+fn bound(op: Op, version: Version) -> Comparator {
+    Comparator { op, version }
+}
+
+/// Expand one comparator token (`^1.2`, `>=1.0.0`, `1.x`) into bounds
+fn desugar(token: &str, range: &str) -> Result<Vec<Comparator>> {
+    let (op, rest) = ["<=", ">=", "<", ">", "=", "^", "~"]
+        .iter()
+        .find_map(|op| token.strip_prefix(op).map(|rest| (*op, rest.trim())))
+        .unwrap_or(("", token));
+    if rest.is_empty() {
+        return Err(invalid(range, format!("'{}' is missing a version", token)));
+    }
+    let partial = Partial::parse(rest, range)?;
+    let Some(major) = partial.major else {
+        // `*`: everything, or nothing for strict bounds
+        return Ok(match op {
+            ">" | "<" => vec![bound(Op::Lt, Version::new(0, 0, 0))],
+            _ => Vec::new(),
+        });
+    };
+    let floor = partial.floor();
+
+    Ok(match op {
+        "" | "=" => match partial.ceiling() {
+            Some(ceiling) => vec![bound(Op::Ge, floor), bound(Op::Lt, ceiling)],
+            None => vec![bound(Op::Eq, floor)],
+        },
+        ">" => match partial.ceiling() {
+            Some(ceiling) => vec![bound(Op::Ge, ceiling)],
+            None => vec![bound(Op::Gt, floor)],
+        },
+        ">=" => vec![bound(Op::Ge, floor)],
+        "<" => vec![bound(Op::Lt, floor)],
+        "<=" => match partial.ceiling() {
+            Some(ceiling) => vec![bound(Op::Lt, ceiling)],
+            None => vec![bound(Op::Le, floor)],
+        },
+        "~" => {
+            let ceiling = match partial.minor {
+                Some(minor) => Version::new(major, minor + 1, 0),
+                None => Version::new(major + 1, 0, 0),
+            };
+            vec![bound(Op::Ge, floor), bound(Op::Lt, ceiling)]
+        }
+        "^" => {
+            let ceiling = match (partial.minor, partial.patch) {
+                _ if major > 0 => Version::new(major + 1, 0, 0),
+                (None, _) => Version::new(1, 0, 0),
+                (Some(0), Some(patch)) => Version::new(0, 0, patch + 1),
+                (Some(minor), _) => Version::new(0, minor + 1, 0),
+            };
+            vec![bound(Op::Ge, floor), bound(Op::Lt, ceiling)]
+        }
+        _ => unreachable!("operators are matched above"),
+    })
+}
+
+impl Range {
+    /// Parse a range expression
+    pub fn parse(s: &str) -> Result<Self> {
+        let mut sets = Vec::new();
+        for set in s.split("||") {
+            // Tokens are separated by commas or whitespace; a bare operator
+            // (`>= 1.2`) is joined with the following token
+            let mut tokens: Vec<String> = Vec::new();
+            let mut pending_op: Option<&str> = None;
+            for word in set.split([',', ' ', '\t']).filter(|w| !w.is_empty()) {
+                if matches!(word, "<=" | ">=" | "<" | ">" | "=" | "^" | "~") {
+                    if pending_op.is_some() {
+                        return Err(invalid(s, format!("'{}' is missing a version", word)));
+                    }
+                    pending_op = Some(word);
+                    continue;
+                }
+                tokens.push(format!("{}{}", pending_op.take().unwrap_or(""), word));
+            }
+            if let Some(op) = pending_op {
+                return Err(invalid(s, format!("'{}' is missing a version", op)));
+            }
+            if tokens.is_empty() {
+                return Err(invalid(s, "empty requirement"));
+            }
+
+            let mut comparators = Vec::new();
+            for token in &tokens {
+                comparators.extend(desugar(token, s)?);
+            }
+            sets.push(comparators);
+        }
+        Ok(Self { sets })
+    }
+
+    /// Whether a version satisfies the range
+    pub fn satisfies(&self, version: &Version) -> bool {
+        self.sets.iter().any(|set| {
+            set.iter().all(|comparator| comparator.matches(version))
+                && (!version.is_prerelease()
+                    || set.iter().any(|comparator| {
+                        comparator.version.is_prerelease()
+                            && comparator.version.core() == version.core()
+                    }))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn satisfies(range: &str, version: &str) -> bool {
+        Range::parse(range)
+            .unwrap()
+            .satisfies(&Version::parse(version).unwrap())
+    }
+
+    #[test]
+    fn test_comparators() {
+        assert!(satisfies(">=1.2.0, <2", "1.9.9"));
+        assert!(!satisfies(">=1.2.0, <2", "2.0.0"));
+        assert!(satisfies(">= 1.2 < 2", "1.2.0"));
+        assert!(satisfies("=1.2.3", "1.2.3"));
+        assert!(satisfies("1.2.3", "1.2.3+build"));
+        assert!(!satisfies("1.2.3", "1.2.4"));
+        assert!(satisfies(">1.2", "1.3.0"));
+        assert!(!satisfies(">1.2", "1.2.9"));
+        assert!(satisfies("<=1.2", "1.2.9"));
+        assert!(!satisfies("<1.2", "1.2.0"));
+    }
+
+    #[test]
+    fn test_partial_and_wildcards() {
+        assert!(satisfies("1.2", "1.2.7"));
+        assert!(satisfies("1.x", "1.9.0"));
+        assert!(!satisfies("1.x", "2.0.0"));
+        assert!(satisfies("*", "0.0.1"));
+        assert!(satisfies("1.2.*", "1.2.0"));
+    }
+
+    #[test]
+    fn test_caret_and_tilde() {
+        assert!(satisfies("^1.2.3", "1.9.0"));
+        assert!(!satisfies("^1.2.3", "2.0.0"));
+        assert!(!satisfies("^1.2.3", "1.2.2"));
+        assert!(satisfies("^0.2.3", "0.2.9"));
+        assert!(!satisfies("^0.2.3", "0.3.0"));
+        assert!(!satisfies("^0.0.3", "0.0.4"));
+        assert!(satisfies("^0.0", "0.0.9"));
+        assert!(satisfies("~1.2.3", "1.2.9"));
+        assert!(!satisfies("~1.2.3", "1.3.0"));
+        assert!(satisfies("~1", "1.9.0"));
+    }
+
+    #[test]
+    fn test_alternatives() {
+        assert!(satisfies("^1.4 || ~2.0.1", "2.0.5"));
+        assert!(satisfies("^1.4 || ~2.0.1", "1.5.0"));
+        assert!(!satisfies("^1.4 || ~2.0.1", "2.1.0"));
+    }
+
+    #[test]
+    fn test_prereleases() {
+        assert!(!satisfies(">=1.0", "2.0.0-rc.1"));
+        assert!(satisfies(">=2.0.0-rc.1", "2.0.0-rc.2"));
+        assert!(!satisfies(">=2.0.0-rc.1", "2.1.0-rc.1"));
+        assert!(satisfies(">=2.0.0-rc.1", "2.1.0"));
+    }
+
+    #[test]
+    fn test_invalid() {
+        for range in ["", ">=", "1.x.2", "1.2.3.4", "abc", ">= >= 1"] {
+            assert!(
+                matches!(Range::parse(range), Err(Error::InvalidRange { .. })),
+                "{}",
+                range
+            );
+        }
+    }
+}
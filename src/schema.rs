@@ -0,0 +1,343 @@
+use crate::config::FileFormat;
+use crate::error::FailureClass;
+use crate::scheme::Scheme;
+use serde_json::{json, Value};
+
+/// JSON Schema (draft 2020-12) of the configuration file
+///
+/// Keys versync doesn't know are rejected (`additionalProperties: false`),
+/// so editors flag typos that versync itself only warns about.
+pub fn config_schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "versync configuration",
+        "description": "Configuration of versync (version.toml)",
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "version": {
+                "description": "The authoritative version string",
+                "type": "string",
+            },
+            "scheme": {
+                "description": "Notation the version is written in; the default for targets too",
+                "enum": Scheme::ALL.map(Scheme::name),
+            },
+            "source": {
+                "description": "Where the version comes from (default: the `version` field)",
+                "anyOf": [
+                    { "enum": ["config", "git"] },
+                    { "$ref": "#/$defs/target" },
+                ],
+            },
+            "exclude": patterns("Patterns of files that wildcard targets skip (e.g. `vendor/**`)"),
+            "targets": targets(),
+            "git": {
+                "description": "Git configuration",
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "tag_prefix": string("Prefix for git tags (default: \"v\")"),
+                    "tag_template": string("Template for tag names (default: \"{prefix}{version}\")"),
+                    "branch_pattern": string("Regex that versioned branches must match, with version placeholders"),
+                    "remote": string("Remote used for remote tag operations (default: \"origin\")"),
+                    "check_remote": boolean("Refuse to tag when the tag already exists on the remote"),
+                },
+            },
+            "network": {
+                "description": "Network configuration",
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "offline": boolean("Skip every network-touching feature"),
+                    "timeout": integer("Timeout for a single remote operation, in seconds (default: 30)"),
+                    "retries": integer("Retries after a network failure (default: 2)"),
+                    "retry_delay": integer("Delay before the first retry in milliseconds, doubled on each retry (default: 500)"),
+                    "proxy": string("Proxy URL for remote operations"),
+                    "cache_ttl": integer("How long responses of remote lookups are cached, in seconds (default: 300, 0 disables)"),
+                },
+            },
+            "channels": {
+                "description": "Named release channels",
+                "type": "object",
+                "additionalProperties": {
+                    "type": "object",
+                    "additionalProperties": false,
+                    "properties": {
+                        "tag": string("Tag template for this channel (e.g. \"{prefix}{version}+staging\")"),
+                        "version": string("Version template deriving the channel version (e.g. \"{version}-nightly.{date}\")"),
+                        "targets": {
+                            "description": "Target files the channel applies to, as paths or globs (all targets if empty)",
+                            "type": "array",
+                            "items": { "type": "string" },
+                        },
+                    },
+                },
+            },
+            "policy": {
+                "description": "Release policy rules",
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "version_pattern": string("Regex the version must match"),
+                    "tag_prefix_pattern": string("Regex the tag prefix must match"),
+                    "forbid_prerelease_on": {
+                        "description": "Branches on which prerelease versions must not be tagged",
+                        "type": "array",
+                        "items": { "type": "string" },
+                    },
+                    "max_jump": {
+                        "description": "Largest increment allowed over the latest tagged version",
+                        "enum": ["major", "minor", "patch"],
+                    },
+                    "allow_downgrade": boolean("Whether apply, bump and set may lower a version (default: true)"),
+                },
+            },
+            "prerelease": {
+                "description": "Prerelease settings",
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "labels": {
+                        "description": "Prerelease labels accepted by `bump --pre`; the first is the default",
+                        "type": "array",
+                        "items": { "type": "string" },
+                    },
+                },
+            },
+            "release": {
+                "description": "Release command settings",
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "commit_message": string("Commit message template (default: \"Release {version}\")"),
+                },
+            },
+            "exit_codes": {
+                "description": "Exit code overrides per failure class (e.g. `network = 0`)",
+                "type": "object",
+                "propertyNames": { "enum": FailureClass::ALL.map(FailureClass::name) },
+                "additionalProperties": { "type": "integer", "minimum": 0, "maximum": 255 },
+            },
+            "components": {
+                "description": "Independently versioned components, each with its own targets",
+                "type": "object",
+                "additionalProperties": {
+                    "type": "object",
+                    "additionalProperties": false,
+                    "required": ["version", "targets"],
+                    "properties": {
+                        "version": string("The authoritative version string of the component"),
+                        "tag_prefix": string("Prefix for the component's git tags (default: \"<name>-\" and git.tag_prefix)"),
+                        "exclude": patterns("Patterns of files that the component's wildcard targets skip"),
+                        "targets": targets(),
+                    },
+                },
+            },
+        },
+        "$defs": {
+            "target": {
+                "description": "A target file containing a version field",
+                "type": "object",
+                "additionalProperties": false,
+                "required": ["file"],
+                "properties": {
+                    "file": string("Path to the file, or a glob such as `crates/*/Cargo.toml`"),
+                    "key": string("Key path of the version (e.g. \"project.version\")"),
+                    "keys": {
+                        "description": "Several key paths, each with its value template",
+                        "type": "object",
+                        "additionalProperties": { "type": "string" },
+                    },
+                    "template": string("Template for the value of the key (default: \"{version}\")"),
+                    "scheme": {
+                        "description": "Notation the version is written in",
+                        "enum": Scheme::ALL.map(Scheme::name),
+                    },
+                    "format": {
+                        "description": "File format (inferred from the file name if omitted)",
+                        "enum": FileFormat::BUILTIN.map(FileFormat::name),
+                    },
+                    "group": string("Group for selecting targets with --group (e.g. \"docs\")"),
+                    "required": boolean("Whether a missing file is an error (default: true)"),
+                    "create": boolean("Whether apply inserts the key when it is missing"),
+                    "extra": {
+                        "description": "Additional keys set from templates whenever the version is applied",
+                        "type": "object",
+                        "additionalProperties": { "type": "string" },
+                    },
+                    "lockfile": boolean("Also update the package-lock.json next to a package.json"),
+                    "exclude": patterns("Patterns of files a glob `file` skips"),
+                },
+            },
+        },
+    })
+}
+
+fn targets() -> Value {
+    json!({
+        "description": "Target files to keep in sync",
+        "type": "array",
+        "items": { "$ref": "#/$defs/target" },
+    })
+}
+
+fn patterns(description: &str) -> Value {
+    json!({
+        "description": description,
+        "type": "array",
+        "items": { "type": "string" },
+    })
+}
+
+fn string(description: &str) -> Value {
+    json!({ "description": description, "type": "string" })
+}
+
+fn boolean(description: &str) -> Value {
+    json!({ "description": description, "type": "boolean" })
+}
+
+fn integer(description: &str) -> Value {
+    json!({ "description": description, "type": "integer", "minimum": 0 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::unknown_keys;
+    use toml_edit::{DocumentMut, Item};
+
+    /// Follow a `$ref` into `$defs`
+    fn resolve<'a>(root: &'a Value, schema: &'a Value) -> &'a Value {
+        match schema["$ref"].as_str() {
+            Some(reference) => {
+                let name = reference.trim_start_matches("#/$defs/");
+                &root["$defs"][name]
+            }
+            None => schema,
+        }
+    }
+
+    /// Keys of a TOML item the schema doesn't describe
+    fn undescribed(root: &Value, schema: &Value, item: &Item, path: &str, found: &mut Vec<String>) {
+        let schema = resolve(root, schema);
+        if let Some(tables) = item.as_array_of_tables() {
+            for table in tables.iter() {
+                let item = Item::Table(table.clone());
+                undescribed(root, &schema["items"], &item, path, found);
+            }
+            return;
+        }
+        let Some(table) = item.as_table_like() else {
+            return;
+        };
+        for (key, item) in table.iter() {
+            let path = format!("{}{}", path, key);
+            let child = match &schema["properties"][key] {
+                Value::Null => match &schema["additionalProperties"] {
+                    Value::Object(_) => &schema["additionalProperties"],
+                    _ => {
+                        found.push(path);
+                        continue;
+                    }
+                },
+                child => child,
+            };
+            let child = match &child["anyOf"] {
+                Value::Array(options) => options.last().unwrap(),
+                _ => child,
+            };
+            undescribed(root, child, item, &format!("{}.", path), found);
+        }
+    }
+
+    #[test]
+    fn test_schema_describes_config() {
+        let content = r#"
+version = "1.2.0"
+scheme = "semver"
+exclude = ["vendor/**"]
+
+[[targets]]
+file = "Cargo.toml"
+key = "package.version"
+template = "{version}"
+scheme = "semver"
+format = "toml"
+group = "manifests"
+required = true
+create = false
+extra = { date = "{date}" }
+
+[[targets]]
+file = "package.json"
+keys = { version = "{version}" }
+lockfile = true
+
+[git]
+tag_prefix = "v"
+tag_template = "{prefix}{version}"
+branch_pattern = '^release/{major}\.x$'
+remote = "origin"
+check_remote = false
+
+[network]
+offline = false
+timeout = 30
+retries = 2
+retry_delay = 500
+proxy = "http://proxy:8080"
+cache_ttl = 300
+
+[channels.nightly]
+tag = "nightly-{n}"
+version = "{version}-nightly.{date}"
+targets = ["Cargo.toml"]
+
+[policy]
+version_pattern = "^1\\."
+tag_prefix_pattern = "^v$"
+forbid_prerelease_on = ["main"]
+max_jump = "minor"
+allow_downgrade = false
+
+[prerelease]
+labels = ["alpha", "beta", "rc"]
+
+[release]
+commit_message = "Release {version}"
+
+[exit_codes]
+network = 0
+
+[components.api]
+version = "2.0.0"
+tag_prefix = "api-v"
+exclude = ["api/vendor/**"]
+
+[[components.api.targets]]
+file = "api/package.json"
+key = "version"
+"#;
+        assert!(unknown_keys(&[content.to_string()]).unwrap().is_empty());
+        let document: DocumentMut = content.parse().unwrap();
+        let schema = config_schema();
+        let mut found = Vec::new();
+        undescribed(&schema, &schema, document.as_item(), "", &mut found);
+        assert!(found.is_empty(), "not in the schema: {:?}", found);
+
+        let mut found = Vec::new();
+        let typo: DocumentMut = "[git]\ntag_prefx = \"v\"\n".parse().unwrap();
+        undescribed(&schema, &schema, typo.as_item(), "", &mut found);
+        assert_eq!(found, ["git.tag_prefx"]);
+
+        // The source file is a target
+        let source: DocumentMut = "[source]\nfile = \"Cargo.toml\"\nkey = \"package.version\"\n"
+            .parse()
+            .unwrap();
+        let mut found = Vec::new();
+        undescribed(&schema, &schema, source.as_item(), "", &mut found);
+        assert!(found.is_empty());
+    }
+}
@@ -0,0 +1,419 @@
+use crate::error::{Error, Result};
+use crate::version::{Identifier, Version};
+use regex::Regex;
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::fmt;
+use std::sync::OnceLock;
+
+/// The notation a target stores the version in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scheme {
+    /// Semantic versioning, the notation of the source of truth
+    Semver,
+    /// Python's PEP 440 (`1.2.0-rc.1` is written `1.2.0rc1`)
+    Pep440,
+    /// Debian package versions (`1.2.0-rc.1` is written `1.2.0~rc.1`)
+    Debian,
+}
+
+impl Scheme {
+    /// All schemes, in the order they are listed in help and errors
+    pub const ALL: [Scheme; 3] = [Scheme::Semver, Scheme::Pep440, Scheme::Debian];
+
+    /// Get the scheme name as used in config files
+    pub fn name(self) -> &'static str {
+        match self {
+            Scheme::Semver => "semver",
+            Scheme::Pep440 => "pep440",
+            Scheme::Debian => "debian",
+        }
+    }
+
+    /// Parse a scheme name as used in config files
+    pub fn from_name(name: &str) -> std::result::Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|scheme| scheme.name() == name)
+            .ok_or_else(|| {
+                format!(
+                    "unknown scheme '{}', expected one of: {}",
+                    name,
+                    Self::ALL.map(Scheme::name).join(", ")
+                )
+            })
+    }
+
+    /// Compare two semver versions by the ordering of this scheme
+    ///
+    /// This is semver precedence, except that in PEP 440 a post-release
+    /// (`1.2.0+post.1`) sorts after its release and earlier post-releases.
+    pub fn compare(self, a: &Version, b: &Version) -> Ordering {
+        let ordering = a.precedence(b);
+        match self {
+            Scheme::Pep440 => {
+                let post = |v: &Version| Pep440::from_semver(v).ok().and_then(|p| p.post);
+                ordering.then_with(|| post(a).cmp(&post(b)))
+            }
+            Scheme::Semver | Scheme::Debian => ordering,
+        }
+    }
+
+    /// Write a semver version in this scheme
+    ///
+    /// Fails for versions the scheme cannot express, such as a PEP 440
+    /// version with the prerelease `nightly.1`.
+    pub fn from_semver(self, version: &str) -> Result<String> {
+        match self {
+            Scheme::Semver => Ok(version.to_string()),
+            Scheme::Pep440 => Ok(Pep440::from_semver(&Version::parse(version)?)?.to_string()),
+            Scheme::Debian => {
+                let version = Version::parse(version)?;
+                let mut value = version.core().to_string();
+                if version.is_prerelease() {
+                    value.push('~');
+                    value.push_str(&version.prerelease());
+                }
+                if !version.build.is_empty() {
+                    value.push('+');
+                    value.push_str(&version.build.join("."));
+                }
+                Ok(value)
+            }
+        }
+    }
+
+    /// Read a value written in this scheme back as a semver version
+    pub fn to_semver(self, value: &str) -> Result<String> {
+        let version = match self {
+            Scheme::Semver => value.to_string(),
+            Scheme::Pep440 => Pep440::parse(value)?.to_semver()?,
+            Scheme::Debian => value.replacen('~', "-", 1),
+        };
+        Version::parse(&version)?;
+        Ok(version)
+    }
+
+    /// Whether two values in this scheme stand for the same version
+    ///
+    /// PEP 440 values are compared in normalized form, so `1.2.0rc1`,
+    /// `1.2.0-RC1` and `1.2.0.rc.1` are all the same version.
+    pub fn equivalent(self, a: &str, b: &str) -> bool {
+        match self {
+            Scheme::Pep440 => match (Pep440::parse(a), Pep440::parse(b)) {
+                (Ok(a), Ok(b)) => a == b,
+                _ => a == b,
+            },
+            Scheme::Semver | Scheme::Debian => a == b,
+        }
+    }
+}
+
+impl fmt::Display for Scheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// A version in the notation of PEP 440, in normalized form
+///
+/// Prerelease labels are `a`, `b` or `rc`; missing numbers are `0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pep440 {
+    pub epoch: u64,
+    pub release: Vec<u64>,
+    /// Prerelease label and number (`("rc", 1)` in `1.2.0rc1`)
+    pub pre: Option<(&'static str, u64)>,
+    pub post: Option<u64>,
+    pub dev: Option<u64>,
+    /// Local version label (`ubuntu.1` in `1.2.0+ubuntu.1`), lowercase
+    pub local: Option<String>,
+}
+
+fn pep440_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(
+            r"(?ix)^v?
+            (?:(?P<epoch>[0-9]+)!)?
+            (?P<release>[0-9]+(?:\.[0-9]+)*)
+            (?:[-_.]?(?P<pre_l>alpha|a|beta|b|preview|pre|rc|c)[-_.]?(?P<pre_n>[0-9]+)?)?
+            (?:-(?P<post_n1>[0-9]+)|[-_.]?(?P<post_l>post|rev|r)[-_.]?(?P<post_n2>[0-9]+)?)?
+            (?P<dev>[-_.]?dev[-_.]?(?P<dev_n>[0-9]+)?)?
+            (?:\+(?P<local>[a-z0-9]+(?:[-_.][a-z0-9]+)*))?$",
+        )
+        .expect("PEP 440 pattern is valid")
+    })
+}
+
+fn invalid(version: &str, reason: &str) -> Error {
+    Error::InvalidVersion {
+        version: version.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+impl Pep440 {
+    /// Parse a PEP 440 version in any of its accepted spellings
+    pub fn parse(s: &str) -> Result<Self> {
+        let caps = pep440_regex()
+            .captures(s.trim())
+            .ok_or_else(|| invalid(s, "not a PEP 440 version"))?;
+        let number = |text: &str| {
+            text.parse::<u64>()
+                .map_err(|_| invalid(s, "version component is too large"))
+        };
+        let optional = |name: &str| caps.name(name).map(|m| number(m.as_str())).transpose();
+
+        let release = caps["release"]
+            .split('.')
+            .map(number)
+            .collect::<Result<Vec<_>>>()?;
+        let pre = match caps.name("pre_l") {
+            Some(label) => {
+                let label = match label.as_str().to_ascii_lowercase().as_str() {
+                    "a" | "alpha" => "a",
+                    "b" | "beta" => "b",
+                    _ => "rc",
+                };
+                Some((label, optional("pre_n")?.unwrap_or(0)))
+            }
+            None => None,
+        };
+        let post = match (optional("post_n1")?, caps.name("post_l")) {
+            (Some(n), _) => Some(n),
+            (None, Some(_)) => Some(optional("post_n2")?.unwrap_or(0)),
+            (None, None) => None,
+        };
+        let dev = match caps.name("dev") {
+            Some(_) => Some(optional("dev_n")?.unwrap_or(0)),
+            None => None,
+        };
+
+        Ok(Self {
+            epoch: optional("epoch")?.unwrap_or(0),
+            release,
+            pre,
+            post,
+            dev,
+            local: caps
+                .name("local")
+                .map(|m| m.as_str().to_ascii_lowercase().replace(['-', '_'], ".")),
+        })
+    }
+
+    /// Write a semver version in PEP 440
+    ///
+    /// The prerelease must be a label with an optional number, such as
+    /// `rc.1` or `beta`; other prereleases have no PEP 440 equivalent. Build
+    /// metadata starting with `post.N` makes a post-release, and the rest of
+    /// it becomes the local version label.
+    pub fn from_semver(version: &Version) -> Result<Self> {
+        let unsupported = || {
+            invalid(
+                &version.to_string(),
+                &format!(
+                    "prerelease '{}' has no PEP 440 equivalent",
+                    version.prerelease()
+                ),
+            )
+        };
+        let mut pre = None;
+        let mut ids = version.pre.iter().peekable();
+        while let Some(id) = ids.next() {
+            let Identifier::AlphaNumeric(label) = id else {
+                return Err(unsupported());
+            };
+            let number = match ids.peek() {
+                Some(Identifier::Numeric(n)) => {
+                    let n = *n;
+                    ids.next();
+                    n
+                }
+                _ => 0,
+            };
+            let label = label.to_ascii_lowercase();
+            let pre_label = match label.as_str() {
+                "a" | "alpha" => Some("a"),
+                "b" | "beta" => Some("b"),
+                "rc" | "c" | "pre" | "preview" => Some("rc"),
+                _ => None,
+            };
+            match pre_label {
+                Some(label) if pre.is_none() => pre = Some((label, number)),
+                _ => return Err(unsupported()),
+            }
+        }
+
+        let (post, local) = match version.build.as_slice() {
+            [label, n, local @ ..] if label.eq_ignore_ascii_case("post") => match n.parse() {
+                Ok(n) => (Some(n), local),
+                Err(_) => (None, version.build.as_slice()),
+            },
+            build => (None, build),
+        };
+        Ok(Self {
+            epoch: 0,
+            release: vec![version.major, version.minor, version.patch],
+            pre,
+            post,
+            dev: None,
+            local: (!local.is_empty())
+                .then(|| local.join(".").to_ascii_lowercase().replace('-', ".")),
+        })
+    }
+
+    /// The semver version this stands for (`1.2.0rc1` is `1.2.0-rc.1`)
+    ///
+    /// Releases with fewer than three components are padded with zeros. A
+    /// post-release sorts after its release, so it becomes build metadata
+    /// (`1.2.0.post1` is `1.2.0+post.1`), which doesn't lower the version.
+    /// Epochs, longer releases and dev releases, which sort before the
+    /// prereleases of their release, have no semver equivalent.
+    pub fn to_semver(&self) -> Result<String> {
+        if self.epoch != 0 || self.release.len() > 3 {
+            return Err(invalid(&self.to_string(), "has no semver equivalent"));
+        }
+        if self.dev.is_some() {
+            return Err(invalid(
+                &self.to_string(),
+                "dev releases have no semver equivalent",
+            ));
+        }
+        let mut release = self.release.clone();
+        release.resize(3, 0);
+        let mut version = format!("{}.{}.{}", release[0], release[1], release[2]);
+
+        if let Some((label, n)) = self.pre {
+            let label = match label {
+                "a" => "alpha",
+                "b" => "beta",
+                label => label,
+            };
+            version.push_str(&format!("-{}.{}", label, n));
+        }
+        let build: Vec<String> = self
+            .post
+            .map(|n| format!("post.{}", n))
+            .into_iter()
+            .chain(self.local.clone())
+            .collect();
+        if !build.is_empty() {
+            version.push('+');
+            version.push_str(&build.join("."));
+        }
+        Ok(version)
+    }
+}
+
+impl fmt::Display for Pep440 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.epoch != 0 {
+            write!(f, "{}!", self.epoch)?;
+        }
+        let release: Vec<String> = self.release.iter().map(u64::to_string).collect();
+        write!(f, "{}", release.join("."))?;
+        if let Some((label, n)) = self.pre {
+            write!(f, "{}{}", label, n)?;
+        }
+        if let Some(n) = self.post {
+            write!(f, ".post{}", n)?;
+        }
+        if let Some(n) = self.dev {
+            write!(f, ".dev{}", n)?;
+        }
+        if let Some(local) = &self.local {
+            write!(f, "+{}", local)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pep440_normalization() {
+        for (input, normalized) in [
+            ("1.2.0rc1", "1.2.0rc1"),
+            ("1.2.0-RC1", "1.2.0rc1"),
+            ("v1.2.0.rc.1", "1.2.0rc1"),
+            ("1.2.0-preview", "1.2.0rc0"),
+            ("1.2.0alpha2", "1.2.0a2"),
+            ("1.0-1", "1.0.post1"),
+            ("1.0.post", "1.0.post0"),
+            ("1.0r2", "1.0.post2"),
+            ("1.0a1.dev3", "1.0a1.dev3"),
+            ("1!2.0+Ubuntu-1", "1!2.0+ubuntu.1"),
+        ] {
+            assert_eq!(
+                Pep440::parse(input).unwrap().to_string(),
+                normalized,
+                "{}",
+                input
+            );
+        }
+        assert!(Pep440::parse("1.2.0-nightly").is_err());
+        assert!(Pep440::parse("latest").is_err());
+    }
+
+    #[test]
+    fn test_pep440_round_trip() {
+        let pep440 = Scheme::Pep440;
+        for (semver, written) in [
+            ("1.2.0", "1.2.0"),
+            ("1.2.0-rc.1", "1.2.0rc1"),
+            ("1.2.0-alpha.2", "1.2.0a2"),
+            ("1.2.0-beta", "1.2.0b0"),
+            ("1.2.0-rc.1+build-5", "1.2.0rc1+build.5"),
+            ("1.2.0+post.1", "1.2.0.post1"),
+            ("1.2.0-rc.1+post.2.ubuntu.1", "1.2.0rc1.post2+ubuntu.1"),
+        ] {
+            assert_eq!(pep440.from_semver(semver).unwrap(), written);
+        }
+        assert_eq!(pep440.to_semver("1.2.0rc1").unwrap(), "1.2.0-rc.1");
+        assert_eq!(pep440.to_semver("1.2.0.post1").unwrap(), "1.2.0+post.1");
+        assert_eq!(
+            pep440.to_semver("1.2.0rc1.post2+ubuntu.1").unwrap(),
+            "1.2.0-rc.1+post.2.ubuntu.1"
+        );
+        assert_eq!(pep440.to_semver("1.2a1").unwrap(), "1.2.0-alpha.1");
+        assert!(pep440.to_semver("1.2.3.4").is_err());
+        assert!(pep440.to_semver("1.2.0.dev4").is_err());
+        for unsupported in ["1.2.0-nightly.1", "1.2.0-dev.4", "1.2.0-rc.1.post.1"] {
+            assert!(matches!(
+                pep440.from_semver(unsupported),
+                Err(Error::InvalidVersion { .. })
+            ));
+        }
+
+        // A post-release sorts after its release, but semver can't tell
+        let release = Version::parse("1.3.0").unwrap();
+        let post = Version::parse(&pep440.to_semver("1.3.0.post1").unwrap()).unwrap();
+        let post2 = Version::parse(&pep440.to_semver("1.3.0.post2").unwrap()).unwrap();
+        assert!(post.precedence(&release).is_eq());
+        assert!(Scheme::Semver.compare(&post, &release).is_eq());
+        assert!(pep440.compare(&post, &release).is_gt());
+        assert!(pep440.compare(&post, &post2).is_lt());
+        assert!(pep440
+            .compare(&post, &Version::parse("1.3.1-rc.1").unwrap())
+            .is_lt());
+        assert_eq!(Scheme::from_name("pep440"), Ok(Scheme::Pep440));
+        assert!(Scheme::from_name("calver").is_err());
+        assert!(pep440.equivalent("1.2.0rc1", "1.2.0-RC1"));
+        assert!(!pep440.equivalent("1.2.0rc1", "1.2.0rc2"));
+    }
+
+    #[test]
+    fn test_debian() {
+        let debian = Scheme::Debian;
+        assert_eq!(debian.from_semver("1.2.0-rc.1").unwrap(), "1.2.0~rc.1");
+        assert_eq!(debian.to_semver("1.2.0~rc.1").unwrap(), "1.2.0-rc.1");
+        assert_eq!(
+            Scheme::Semver.from_semver("1.2.0-rc.1").unwrap(),
+            "1.2.0-rc.1"
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use crate::term::visible_width;
+
+/// A simple text table with left-aligned, space-padded columns
+#[derive(Debug, Default)]
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Create a table with the given column headers
+    pub fn new(headers: &[&str]) -> Self {
+        Self {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Append a row; missing cells are rendered empty
+    pub fn add_row(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    /// Compute the display width of each column
+    fn widths(&self) -> Vec<usize> {
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| visible_width(h)).collect();
+        for row in &self.rows {
+            for (i, cell) in row.iter().enumerate() {
+                let width = visible_width(cell);
+                match widths.get_mut(i) {
+                    Some(w) => *w = (*w).max(width),
+                    None => widths.push(width),
+                }
+            }
+        }
+        widths
+    }
+
+    /// Render the table, one line per row, without trailing whitespace
+    pub fn render(&self) -> String {
+        let widths = self.widths();
+        let mut output = String::new();
+
+        for row in std::iter::once(&self.headers).chain(&self.rows) {
+            let line = widths
+                .iter()
+                .enumerate()
+                .map(|(i, width)| {
+                    let cell = row.get(i).map(String::as_str).unwrap_or("");
+                    let padding = width.saturating_sub(visible_width(cell));
+                    format!("{}{}", cell, " ".repeat(padding))
+                })
+                .collect::<Vec<_>>()
+                .join("  ");
+            output.push_str(line.trim_end());
+            output.push('\n');
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_aligns_columns() {
+        let mut table = Table::new(&["FILE", "STATUS"]);
+        table.add_row(vec!["Cargo.toml".to_string(), "OK".to_string()]);
+        table.add_row(vec!["a.json".to_string(), "MISMATCH".to_string()]);
+
+        assert_eq!(
+            table.render(),
+            "FILE        STATUS\nCargo.toml  OK\na.json      MISMATCH\n"
+        );
+    }
+
+    #[test]
+    fn test_render_missing_cells() {
+        let mut table = Table::new(&["A", "B", "C"]);
+        table.add_row(vec!["x".to_string()]);
+
+        assert_eq!(table.render(), "A  B  C\nx\n");
+    }
+
+    #[test]
+    fn test_render_ignores_ansi_width() {
+        let mut table = Table::new(&["STATUS", "FILE"]);
+        table.add_row(vec!["\x1b[32mOK\x1b[0m".to_string(), "a".to_string()]);
+
+        assert_eq!(table.render(), "STATUS  FILE\n\x1b[32mOK\x1b[0m      a\n");
+    }
+}
@@ -0,0 +1,165 @@
+use crate::error::{Error, Result};
+use crate::git;
+use crate::version::Version;
+use std::cell::OnceCell;
+use std::convert::Infallible;
+
+/// Replace `{name}` placeholders using a lookup function
+///
+/// Placeholders the lookup does not know are left untouched, so templates
+/// can safely contain other brace syntax (e.g. regex quantifiers like `{2}`).
+pub fn render(template: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    match try_render(template, |name| Ok::<_, Infallible>(lookup(name))) {
+        Ok(output) => output,
+        Err(never) => match never {},
+    }
+}
+
+/// Like [`render`], but the lookup may fail for known placeholders
+pub fn try_render<E>(
+    template: &str,
+    lookup: impl Fn(&str) -> std::result::Result<Option<String>, E>,
+) -> std::result::Result<String, E> {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('}') {
+            Some(end) => match lookup(&after[..end])? {
+                Some(value) => {
+                    output.push_str(&value);
+                    rest = &after[end + 1..];
+                }
+                None => {
+                    output.push('{');
+                    rest = after;
+                }
+            },
+            None => {
+                output.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    output.push_str(rest);
+
+    Ok(output)
+}
+
+/// Placeholder values derived from a version:
+/// `{version}`, `{major}`, `{minor}`, `{patch}`, `{prerelease}`, `{build}`
+pub fn version_var(version: &Version, name: &str) -> Option<String> {
+    match name {
+        "version" => Some(version.to_string()),
+        "major" => Some(version.major.to_string()),
+        "minor" => Some(version.minor.to_string()),
+        "patch" => Some(version.patch.to_string()),
+        "prerelease" => Some(version.prerelease()),
+        "build" => Some(version.build.join(".")),
+        _ => None,
+    }
+}
+
+/// Names of the placeholders provided by [`version_var`]
+pub const VERSION_VARS: &[&str] = &["version", "major", "minor", "patch", "prerelease", "build"];
+
+/// Values available to target templates
+///
+/// Besides the version placeholders this provides `{date}` (today,
+/// `YYYY-MM-DD`), `{commit}` and `{short_commit}` (HEAD, resolved lazily).
+#[derive(Debug)]
+pub struct Context {
+    version: String,
+    date: String,
+    commit: OnceCell<Option<String>>,
+}
+
+impl Context {
+    /// Create a context for the given version and today's date
+    pub fn new(version: &str) -> Self {
+        Self {
+            version: version.to_string(),
+            date: chrono::Local::now().format("%Y-%m-%d").to_string(),
+            commit: OnceCell::new(),
+        }
+    }
+
+    /// The version this context renders
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    fn commit(&self) -> Result<String> {
+        let commit = self
+            .commit
+            .get_or_init(|| git::resolve_commit("HEAD").ok().flatten());
+        commit
+            .clone()
+            .ok_or_else(|| Error::GitCommand("HEAD does not point at a commit".to_string()))
+    }
+
+    fn lookup(&self, name: &str) -> Result<Option<String>> {
+        match name {
+            "version" => Ok(Some(self.version.clone())),
+            "date" => Ok(Some(self.date.clone())),
+            "commit" => self.commit().map(Some),
+            "short_commit" => self.commit().map(|c| Some(c[..c.len().min(7)].to_string())),
+            _ if VERSION_VARS.contains(&name) => {
+                let version = Version::parse(&self.version)?;
+                Ok(version_var(&version, name))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Render a template, failing if a placeholder cannot be resolved
+    pub fn render(&self, template: &str) -> Result<String> {
+        try_render(template, |name| self.lookup(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_version_vars() {
+        let version = Version::parse("1.2.3-rc.1").unwrap();
+        let rendered = render("v{major}.{minor} ({version}, {prerelease})", |name| {
+            version_var(&version, name)
+        });
+        assert_eq!(rendered, "v1.2 (1.2.3-rc.1, rc.1)");
+    }
+
+    #[test]
+    fn test_render_leaves_unknown_placeholders() {
+        let rendered = render(r"^\d{2}-{name}-{", |name| {
+            (name == "name").then(|| "x".to_string())
+        });
+        assert_eq!(rendered, r"^\d{2}-x-{");
+    }
+
+    #[test]
+    fn test_context_render() {
+        let context = Context {
+            version: "2.1.0".to_string(),
+            date: "2024-05-01".to_string(),
+            commit: OnceCell::from(Some("0123456789abcdef".to_string())),
+        };
+        assert_eq!(
+            context
+                .render("{version} {minor} {date} {short_commit} {other}")
+                .unwrap(),
+            "2.1.0 1 2024-05-01 0123456 {other}"
+        );
+    }
+
+    #[test]
+    fn test_context_requires_semver_for_components() {
+        let context = Context::new("not-semver");
+        assert_eq!(context.render("{version}").unwrap(), "not-semver");
+        assert!(context.render("{major}").is_err());
+    }
+}
@@ -0,0 +1,239 @@
+use crate::ci;
+use std::io::{self, IsTerminal};
+use std::sync::OnceLock;
+
+/// When to emit ANSI colors (`--color`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ColorChoice {
+    /// Color when stdout is a capable terminal
+    #[default]
+    Auto,
+    /// Always color, even when piped
+    Always,
+    /// Never color
+    Never,
+}
+
+/// Semantic styles used in command output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    Success,
+    Failure,
+    Warning,
+    Bold,
+}
+
+impl Style {
+    fn sgr(self) -> &'static str {
+        match self {
+            Style::Success => "32",
+            Style::Failure => "31",
+            Style::Warning => "33",
+            Style::Bold => "1",
+        }
+    }
+}
+
+/// Glyphs with an ASCII fallback
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Glyph {
+    Check,
+    Cross,
+    Arrow,
+}
+
+impl Glyph {
+    fn unicode(self) -> &'static str {
+        match self {
+            Glyph::Check => "✓",
+            Glyph::Cross => "✗",
+            Glyph::Arrow => "→",
+        }
+    }
+
+    fn ascii(self) -> &'static str {
+        match self {
+            Glyph::Check => "+",
+            Glyph::Cross => "x",
+            Glyph::Arrow => "->",
+        }
+    }
+}
+
+/// Terminal capabilities resolved from flags, environment and stdout
+///
+/// The default is plain output: no colors, ASCII glyphs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Term {
+    /// Whether ANSI colors are emitted
+    pub color: bool,
+    /// Whether unicode glyphs are emitted
+    pub unicode: bool,
+}
+
+impl Term {
+    /// Detect capabilities for the current process
+    pub fn detect(choice: ColorChoice, ascii: bool) -> Self {
+        Self::resolve(choice, ascii, io::stdout().is_terminal(), |name| {
+            std::env::var(name).ok()
+        })
+    }
+
+    /// Resolve capabilities from explicit inputs
+    ///
+    /// Color: `--color` wins, then `NO_COLOR`, then `CLICOLOR_FORCE`, then
+    /// a non-dumb TTY. Unicode: disabled by `--ascii`, dumb terminals, CI and
+    /// non-UTF-8 locales.
+    fn resolve(
+        choice: ColorChoice,
+        ascii: bool,
+        is_tty: bool,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Self {
+        let set = |name: &str| env(name).is_some_and(|v| !v.is_empty());
+        let dumb = env("TERM").is_some_and(|t| t == "dumb");
+
+        let color = match choice {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                if set("NO_COLOR") {
+                    false
+                } else if env("CLICOLOR_FORCE").is_some_and(|v| !v.is_empty() && v != "0") {
+                    true
+                } else {
+                    is_tty && !dumb
+                }
+            }
+        };
+
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .find_map(|name| env(name).filter(|v| !v.is_empty()));
+        let utf8_locale = locale.is_none_or(|l| {
+            let l = l.to_lowercase();
+            l.contains("utf-8") || l.contains("utf8")
+        });
+        let unicode = !ascii && !dumb && !set("CI") && utf8_locale;
+
+        Self { color, unicode }
+    }
+
+    /// Wrap text in the ANSI sequence for a style, if colors are enabled
+    pub fn paint(&self, text: &str, style: Style) -> String {
+        if self.color {
+            format!("\x1b[{}m{}\x1b[0m", style.sgr(), text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// Get a glyph, degraded to ASCII if unicode is disabled
+    pub fn glyph(&self, glyph: Glyph) -> &'static str {
+        if self.unicode {
+            glyph.unicode()
+        } else {
+            glyph.ascii()
+        }
+    }
+}
+
+static TERM: OnceLock<Term> = OnceLock::new();
+
+/// Set the process-wide terminal capabilities (first call wins)
+pub fn init(term: Term) {
+    let _ = TERM.set(term);
+}
+
+/// Get the process-wide terminal capabilities (plain output if never initialized)
+pub fn get() -> Term {
+    TERM.get().copied().unwrap_or_default()
+}
+
+/// Whether to render tables: stdout is a terminal and CI mode is off
+pub fn tables() -> bool {
+    io::stdout().is_terminal() && !ci::get().enabled
+}
+
+/// Display width of a string, ignoring ANSI escape sequences
+pub fn visible_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skip CSI sequences up to and including the final byte
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            width += 1;
+        }
+    }
+    width
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn resolve(choice: ColorChoice, ascii: bool, is_tty: bool, vars: &[(&str, &str)]) -> Term {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        Term::resolve(choice, ascii, is_tty, |name| vars.get(name).cloned())
+    }
+
+    #[test]
+    fn test_auto_color_follows_tty() {
+        assert!(resolve(ColorChoice::Auto, false, true, &[]).color);
+        assert!(!resolve(ColorChoice::Auto, false, false, &[]).color);
+    }
+
+    #[test]
+    fn test_no_color_and_force() {
+        assert!(!resolve(ColorChoice::Auto, false, true, &[("NO_COLOR", "1")]).color);
+        assert!(resolve(ColorChoice::Auto, false, false, &[("CLICOLOR_FORCE", "1")]).color);
+        assert!(!resolve(ColorChoice::Auto, false, false, &[("CLICOLOR_FORCE", "0")]).color);
+        assert!(resolve(ColorChoice::Always, false, false, &[("NO_COLOR", "1")]).color);
+        assert!(!resolve(ColorChoice::Never, false, true, &[]).color);
+    }
+
+    #[test]
+    fn test_dumb_terminal() {
+        let term = resolve(ColorChoice::Auto, false, true, &[("TERM", "dumb")]);
+        assert!(!term.color);
+        assert!(!term.unicode);
+    }
+
+    #[test]
+    fn test_unicode_detection() {
+        assert!(resolve(ColorChoice::Auto, false, true, &[("LANG", "en_US.UTF-8")]).unicode);
+        assert!(!resolve(ColorChoice::Auto, false, true, &[("LANG", "C")]).unicode);
+        assert!(!resolve(ColorChoice::Auto, false, true, &[("CI", "true")]).unicode);
+        assert!(!resolve(ColorChoice::Auto, true, true, &[]).unicode);
+    }
+
+    #[test]
+    fn test_paint_and_glyph() {
+        let plain = Term::default();
+        assert_eq!(plain.paint("OK", Style::Success), "OK");
+        assert_eq!(plain.glyph(Glyph::Check), "+");
+
+        let fancy = Term {
+            color: true,
+            unicode: true,
+        };
+        assert_eq!(fancy.paint("OK", Style::Success), "\x1b[32mOK\x1b[0m");
+        assert_eq!(fancy.glyph(Glyph::Check), "✓");
+    }
+
+    #[test]
+    fn test_visible_width() {
+        assert_eq!(visible_width("\x1b[32mOK\x1b[0m"), 2);
+        assert_eq!(visible_width("✓ OK"), 4);
+    }
+}
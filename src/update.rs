@@ -0,0 +1,247 @@
+use crate::config::NetworkConfig;
+use crate::error::{Error, Result};
+use crate::network;
+use crate::version::Version;
+use minisign_verify::{PublicKey, Signature};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// Name of the release asset holding the SHA-256 checksums of all binaries
+pub const CHECKSUMS: &str = "SHA256SUMS";
+
+/// Name of the release asset holding the minisign signature of [`CHECKSUMS`]
+pub const SIGNATURE: &str = "SHA256SUMS.minisig";
+
+/// The minisign public key release checksums are signed with, embedded at
+/// build time from `VERSYNC_UPDATE_PUBLIC_KEY`
+const PUBLIC_KEY: Option<&str> = option_env!("VERSYNC_UPDATE_PUBLIC_KEY");
+
+/// A published GitHub release
+#[derive(Debug, Deserialize)]
+pub struct Release {
+    #[serde(rename = "tag_name")]
+    pub tag: String,
+    #[serde(default)]
+    assets: Vec<Asset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Asset {
+    name: String,
+    browser_download_url: String,
+}
+
+impl Release {
+    /// Parse the GitHub API representation of a release
+    pub fn parse(json: &str) -> Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| Error::SelfUpdate(format!("Invalid release metadata: {}", e)))
+    }
+
+    /// The released version (tag without a leading `v`)
+    pub fn version(&self) -> Result<Version> {
+        Version::parse(self.tag.strip_prefix('v').unwrap_or(&self.tag))
+    }
+
+    fn asset_url(&self, name: &str) -> Result<&str> {
+        self.assets
+            .iter()
+            .find(|asset| asset.name == name)
+            .map(|asset| asset.browser_download_url.as_str())
+            .ok_or_else(|| {
+                Error::SelfUpdate(format!("Release {} has no asset '{}'", self.tag, name))
+            })
+    }
+
+    /// Download the binary for this platform and verify it against the
+    /// release's checksum file, whose signature is checked first
+    pub fn download_binary(&self, network: &NetworkConfig) -> Result<Vec<u8>> {
+        let public_key = PUBLIC_KEY.ok_or_else(|| {
+            Error::SelfUpdate(
+                "This build has no update signing key; reinstall versync instead".to_string(),
+            )
+        })?;
+        let name = asset_name();
+        let sums = network::fetch(network, self.asset_url(CHECKSUMS)?, &[])?;
+        let signature = network::fetch(network, self.asset_url(SIGNATURE)?, &[])?;
+        verify_signature(public_key, &sums, &String::from_utf8_lossy(&signature))?;
+        let expected = checksum_for(&String::from_utf8_lossy(&sums), &name).ok_or_else(|| {
+            Error::SelfUpdate(format!("{} has no entry for '{}'", CHECKSUMS, name))
+        })?;
+
+        let binary = network::fetch(network, self.asset_url(&name)?, &[])?;
+        let actual = sha256_hex(&binary);
+        if actual != expected {
+            return Err(Error::SelfUpdate(format!(
+                "Checksum mismatch for '{}': expected {}, got {}",
+                name, expected, actual
+            )));
+        }
+        Ok(binary)
+    }
+}
+
+/// The `owner/name` of the GitHub repository releases are published to
+fn repository() -> &'static str {
+    env!("CARGO_PKG_REPOSITORY")
+        .trim_start_matches("https://github.com/")
+        .trim_end_matches('/')
+}
+
+/// Fetch the latest (non-prerelease) release
+///
+/// `GITHUB_TOKEN` is used for authentication when set, which raises the API
+/// rate limit.
+pub fn latest_release(network: &NetworkConfig) -> Result<Release> {
+    let url = format!(
+        "https://api.github.com/repos/{}/releases/latest",
+        repository()
+    );
+    let mut headers = vec!["Accept: application/vnd.github+json".to_string()];
+    if let Some(token) = env::var("GITHUB_TOKEN").ok().filter(|t| !t.is_empty()) {
+        headers.push(format!("Authorization: Bearer {}", token));
+    }
+
+    let body = network::fetch(network, &url, &headers)?;
+    Release::parse(&String::from_utf8_lossy(&body))
+}
+
+/// Name of the release asset for the running platform
+/// (e.g. `versync-x86_64-linux`, `versync-aarch64-macos`)
+pub fn asset_name() -> String {
+    format!(
+        "versync-{}-{}{}",
+        env::consts::ARCH,
+        env::consts::OS,
+        env::consts::EXE_SUFFIX
+    )
+}
+
+/// Find the checksum of a file in `sha256sum` output
+pub fn checksum_for(sums: &str, name: &str) -> Option<String> {
+    sums.lines().find_map(|line| {
+        let (hash, file) = line.split_once(char::is_whitespace)?;
+        // `sha256sum` marks binary mode with a leading `*`
+        let file = file.trim_start().trim_start_matches('*');
+        (file == name).then(|| hash.to_lowercase())
+    })
+}
+
+/// Check a minisign signature of the checksum file against `public_key`
+/// (the base64 key line of a minisign `.pub` file)
+pub fn verify_signature(public_key: &str, sums: &[u8], signature: &str) -> Result<()> {
+    let invalid = |e: minisign_verify::Error| {
+        Error::SelfUpdate(format!("Signature check failed for {}: {}", CHECKSUMS, e))
+    };
+    let public_key = PublicKey::from_base64(public_key.trim()).map_err(invalid)?;
+    let signature = Signature::decode(signature).map_err(invalid)?;
+    public_key.verify(sums, &signature, false).map_err(invalid)
+}
+
+/// Hex-encoded SHA-256 digest
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Replace the running executable with a new binary
+///
+/// The binary is written next to the executable and renamed over it, so an
+/// interrupted update never leaves a truncated executable behind. Windows
+/// does not allow replacing a running executable, so it is moved aside to
+/// `.old` first.
+pub fn replace_executable(binary: &[u8]) -> Result<PathBuf> {
+    let exe = env::current_exe()?;
+    let staged = exe.with_extension("new");
+
+    fs::write(&staged, binary)?;
+    fs::set_permissions(&staged, fs::metadata(&exe)?.permissions())?;
+    if cfg!(windows) {
+        fs::rename(&exe, exe.with_extension("old"))?;
+    }
+    fs::rename(&staged, &exe)?;
+
+    Ok(exe)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_release() {
+        let release = Release::parse(
+            r#"{
+                "tag_name": "v1.4.0",
+                "name": "v1.4.0",
+                "assets": [
+                    {"name": "SHA256SUMS", "browser_download_url": "https://example.com/SHA256SUMS"},
+                    {"name": "versync-x86_64-linux", "browser_download_url": "https://example.com/bin"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(release.version().unwrap(), Version::new(1, 4, 0));
+        assert_eq!(
+            release.asset_url("versync-x86_64-linux").unwrap(),
+            "https://example.com/bin"
+        );
+        assert!(matches!(
+            release.asset_url("versync-sparc-solaris"),
+            Err(Error::SelfUpdate(_))
+        ));
+    }
+
+    #[test]
+    fn test_checksum_for() {
+        let sums = "\
+0123ABCD  versync-x86_64-linux
+4567cdef *versync-aarch64-macos
+";
+        assert_eq!(
+            checksum_for(sums, "versync-x86_64-linux").as_deref(),
+            Some("0123abcd")
+        );
+        assert_eq!(
+            checksum_for(sums, "versync-aarch64-macos").as_deref(),
+            Some("4567cdef")
+        );
+        assert_eq!(checksum_for(sums, "versync-x86_64-windows.exe"), None);
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        let public_key = "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3";
+        let signature = "untrusted comment: signature from minisign secret key
+RUQf6LRCGA9i559r3g7V1qNyJDApGip8MfqcadIgT9CuhV3EMhHoN1mGTkUidF/z7SrlQgXdy8ofjb7bNJJylDOocrCo8KLzZwo=
+trusted comment: timestamp:1556193335\tfile:test
+y/rUw2y8/hOUYjZU71eHp/Wo1KZ40fGy2VJEDl34XMJM+TX48Ss/17u3IvIfbVR1FkZZSNCisQbuQY+bHwhEBg==";
+        verify_signature(public_key, b"test", signature).unwrap();
+        assert!(matches!(
+            verify_signature(public_key, b"tampered", signature),
+            Err(Error::SelfUpdate(_))
+        ));
+        assert!(matches!(
+            verify_signature(public_key, b"test", "not a signature"),
+            Err(Error::SelfUpdate(_))
+        ));
+    }
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_repository() {
+        assert_eq!(repository(), "orangekame3/versync");
+    }
+}
@@ -0,0 +1,419 @@
+use crate::error::{Error, Result};
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// A prerelease identifier (dot-separated part after `-`)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Identifier {
+    Numeric(u64),
+    AlphaNumeric(String),
+}
+
+impl Identifier {
+    fn parse(s: &str, version: &str) -> Result<Self> {
+        if s.is_empty() {
+            return Err(invalid(version, "empty prerelease identifier"));
+        }
+        if !s.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err(invalid(version, "invalid character in prerelease"));
+        }
+        if s.chars().all(|c| c.is_ascii_digit()) {
+            if s.len() > 1 && s.starts_with('0') {
+                return Err(invalid(version, "numeric identifier has a leading zero"));
+            }
+            let n = s
+                .parse()
+                .map_err(|_| invalid(version, "numeric identifier is too large"))?;
+            Ok(Identifier::Numeric(n))
+        } else {
+            Ok(Identifier::AlphaNumeric(s.to_string()))
+        }
+    }
+}
+
+impl fmt::Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Identifier::Numeric(n) => write!(f, "{}", n),
+            Identifier::AlphaNumeric(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl Ord for Identifier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Identifier::Numeric(a), Identifier::Numeric(b)) => a.cmp(b),
+            (Identifier::Numeric(_), Identifier::AlphaNumeric(_)) => Ordering::Less,
+            (Identifier::AlphaNumeric(_), Identifier::Numeric(_)) => Ordering::Greater,
+            (Identifier::AlphaNumeric(a), Identifier::AlphaNumeric(b)) => a.cmp(b),
+        }
+    }
+}
+
+impl PartialOrd for Identifier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A semver component to increment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Major,
+    Minor,
+    Patch,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Level::Major => write!(f, "major"),
+            Level::Minor => write!(f, "minor"),
+            Level::Patch => write!(f, "patch"),
+        }
+    }
+}
+
+/// A semantic version (https://semver.org)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    /// Prerelease identifiers (`rc.1` in `1.0.0-rc.1`)
+    pub pre: Vec<Identifier>,
+    /// Build metadata (`build.5` in `1.0.0+build.5`), ignored for ordering
+    pub build: Vec<String>,
+}
+
+fn invalid(version: &str, reason: &str) -> Error {
+    Error::InvalidVersion {
+        version: version.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+impl Version {
+    /// Create a release version without prerelease or build metadata
+    pub fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+            pre: Vec::new(),
+            build: Vec::new(),
+        }
+    }
+
+    /// Parse a semantic version string
+    pub fn parse(s: &str) -> Result<Self> {
+        let (rest, build) = match s.split_once('+') {
+            Some((rest, build)) => (rest, Some(build)),
+            None => (s, None),
+        };
+        let (core, pre) = match rest.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (rest, None),
+        };
+
+        let parts: Vec<&str> = core.split('.').collect();
+        if parts.len() != 3 {
+            return Err(invalid(s, "expected MAJOR.MINOR.PATCH"));
+        }
+        let mut numbers = [0u64; 3];
+        for (n, part) in numbers.iter_mut().zip(&parts) {
+            if part.is_empty() || !part.chars().all(|c| c.is_ascii_digit()) {
+                return Err(invalid(s, "version components must be numeric"));
+            }
+            if part.len() > 1 && part.starts_with('0') {
+                return Err(invalid(s, "version component has a leading zero"));
+            }
+            *n = part
+                .parse()
+                .map_err(|_| invalid(s, "version component is too large"))?;
+        }
+
+        let pre = match pre {
+            Some(pre) => pre
+                .split('.')
+                .map(|id| Identifier::parse(id, s))
+                .collect::<Result<Vec<_>>>()?,
+            None => Vec::new(),
+        };
+
+        let build = match build {
+            Some(build) => {
+                let ids: Vec<String> = build.split('.').map(|id| id.to_string()).collect();
+                if ids.iter().any(|id| {
+                    id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+                }) {
+                    return Err(invalid(s, "invalid build metadata"));
+                }
+                ids
+            }
+            None => Vec::new(),
+        };
+
+        Ok(Self {
+            major: numbers[0],
+            minor: numbers[1],
+            patch: numbers[2],
+            pre,
+            build,
+        })
+    }
+
+    /// Whether this is a prerelease version
+    pub fn is_prerelease(&self) -> bool {
+        !self.pre.is_empty()
+    }
+
+    /// The prerelease part as a string (empty for releases)
+    pub fn prerelease(&self) -> String {
+        join(&self.pre)
+    }
+
+    /// Increment a component, resetting lower components and dropping
+    /// prerelease and build metadata (`1.2.3` + minor = `1.3.0`)
+    pub fn bump(&self, level: Level) -> Self {
+        match level {
+            Level::Major => Self::new(self.major + 1, 0, 0),
+            Level::Minor => Self::new(self.major, self.minor + 1, 0),
+            Level::Patch => Self::new(self.major, self.minor, self.patch + 1),
+        }
+    }
+
+    /// Increment a component and start a prerelease of the result
+    /// (`1.2.0` + minor + `rc` = `1.3.0-rc.1`)
+    pub fn bump_to_prerelease(&self, level: Level, label: &str) -> Result<Self> {
+        self.bump(level).with_prerelease(label, 1)
+    }
+
+    /// Increment the prerelease number, or switch to another label
+    ///
+    /// `1.2.0-rc.1` becomes `1.2.0-rc.2`; with a different label the number
+    /// restarts (`1.2.0-beta.3` + `rc` = `1.2.0-rc.1`). The result must have
+    /// higher precedence, so switching back to an earlier label fails.
+    pub fn bump_prerelease(&self, label: Option<&str>) -> Result<Self> {
+        if !self.is_prerelease() {
+            return Err(invalid(&self.to_string(), "not a prerelease"));
+        }
+
+        let current = match self.pre.first() {
+            Some(Identifier::AlphaNumeric(label)) => Some(label.as_str()),
+            _ => None,
+        };
+        let next = match (label, current) {
+            (Some(label), current) if current != Some(label) => {
+                self.core().with_prerelease(label, 1)?
+            }
+            _ => {
+                let mut next = self.core();
+                next.pre = self.pre.clone();
+                match next.pre.last_mut() {
+                    Some(Identifier::Numeric(n)) => *n += 1,
+                    _ => next.pre.push(Identifier::Numeric(1)),
+                }
+                next
+            }
+        };
+
+        if next.precedence(self).is_le() {
+            return Err(invalid(
+                &next.to_string(),
+                &format!("would not be newer than {}", self),
+            ));
+        }
+        Ok(next)
+    }
+
+    /// This version's core with a `label.n` prerelease
+    fn with_prerelease(&self, label: &str, n: u64) -> Result<Self> {
+        let mut version = self.core();
+        version.pre = vec![
+            Identifier::parse(label, &format!("{}-{}", self.core(), label))?,
+            Identifier::Numeric(n),
+        ];
+        Ok(version)
+    }
+
+    /// The `MAJOR.MINOR.PATCH` part, without prerelease or build metadata
+    pub fn core(&self) -> Self {
+        Self::new(self.major, self.minor, self.patch)
+    }
+
+    /// Compare by semver precedence, ignoring build metadata
+    pub fn precedence(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (self.pre.is_empty(), other.pre.is_empty()) {
+                (true, true) => Ordering::Equal,
+                // A release has higher precedence than its prereleases
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => self.pre.cmp(&other.pre),
+            })
+    }
+}
+
+fn join<T: fmt::Display>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+impl FromStr for Version {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if !self.pre.is_empty() {
+            write!(f, "-{}", join(&self.pre))?;
+        }
+        if !self.build.is_empty() {
+            write!(f, "+{}", self.build.join("."))?;
+        }
+        Ok(())
+    }
+}
+
+impl Ord for Version {
+    /// Semver precedence; build metadata only breaks ties for a total order
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.precedence(other)
+            .then_with(|| self.build.cmp(&other.build))
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(s: &str) -> Version {
+        Version::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        for s in [
+            "1.2.3",
+            "0.0.0",
+            "1.0.0-rc.1",
+            "1.0.0-alpha+build.5",
+            "2.0.0+20240101",
+        ] {
+            assert_eq!(v(s).to_string(), s);
+        }
+        let version = v("1.2.3-beta.2");
+        assert_eq!((version.major, version.minor, version.patch), (1, 2, 3));
+        assert_eq!(
+            version.pre,
+            vec![
+                Identifier::AlphaNumeric("beta".to_string()),
+                Identifier::Numeric(2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for s in [
+            "1.2",
+            "1.2.3.4",
+            "v1.2.3",
+            "01.2.3",
+            "1.2.3-",
+            "1.2.3-rc..1",
+            "1.x.3",
+        ] {
+            assert!(
+                matches!(Version::parse(s), Err(Error::InvalidVersion { .. })),
+                "{} should be invalid",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn test_bump() {
+        assert_eq!(v("1.2.3").bump(Level::Major), v("2.0.0"));
+        assert_eq!(v("1.2.3").bump(Level::Minor), v("1.3.0"));
+        assert_eq!(v("1.2.3-rc.1+b").bump(Level::Patch), v("1.2.4"));
+    }
+
+    #[test]
+    fn test_bump_to_prerelease() {
+        assert_eq!(
+            v("1.2.0").bump_to_prerelease(Level::Minor, "rc").unwrap(),
+            v("1.3.0-rc.1")
+        );
+        assert_eq!(
+            v("1.2.0-beta.2")
+                .bump_to_prerelease(Level::Major, "alpha")
+                .unwrap(),
+            v("2.0.0-alpha.1")
+        );
+        assert!(v("1.2.0").bump_to_prerelease(Level::Patch, "r c").is_err());
+    }
+
+    #[test]
+    fn test_bump_prerelease() {
+        assert_eq!(
+            v("1.2.0-rc.1").bump_prerelease(None).unwrap(),
+            v("1.2.0-rc.2")
+        );
+        assert_eq!(
+            v("1.2.0-rc.1+build.5").bump_prerelease(Some("rc")).unwrap(),
+            v("1.2.0-rc.2")
+        );
+        assert_eq!(
+            v("1.2.0-rc").bump_prerelease(None).unwrap(),
+            v("1.2.0-rc.1")
+        );
+        assert_eq!(
+            v("1.2.0-beta.3").bump_prerelease(Some("rc")).unwrap(),
+            v("1.2.0-rc.1")
+        );
+        assert!(v("1.2.0-rc.2").bump_prerelease(Some("beta")).is_err());
+        assert!(v("1.2.0").bump_prerelease(None).is_err());
+    }
+
+    #[test]
+    fn test_precedence() {
+        let ordered = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+            "1.0.1",
+            "1.1.0",
+            "2.0.0",
+        ];
+        for pair in ordered.windows(2) {
+            assert!(v(pair[0]) < v(pair[1]), "{} < {}", pair[0], pair[1]);
+        }
+        assert_eq!(v("1.0.0+a").precedence(&v("1.0.0+b")), Ordering::Equal);
+    }
+}
@@ -0,0 +1,411 @@
+//! End-to-end tests running the versync binary in scratch git repositories
+
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+const CONFIG: &str = r#"version = "1.0.0"
+
+[[targets]]
+file = "Cargo.toml"
+key = "package.version"
+"#;
+
+fn git(dir: &Path, args: &[&str]) {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .env("GIT_AUTHOR_NAME", "versync")
+        .env("GIT_AUTHOR_EMAIL", "versync@example.org")
+        .env("GIT_COMMITTER_NAME", "versync")
+        .env("GIT_COMMITTER_EMAIL", "versync@example.org")
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "git {:?}: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// Run versync in `dir`, with the cache kept inside `home`
+fn versync(home: &Path, dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_versync"))
+        .args(args)
+        .current_dir(dir)
+        .env("XDG_CACHE_HOME", home.join("cache"))
+        .env("GIT_AUTHOR_NAME", "versync")
+        .env("GIT_AUTHOR_EMAIL", "versync@example.org")
+        .env("GIT_COMMITTER_NAME", "versync")
+        .env("GIT_COMMITTER_EMAIL", "versync@example.org")
+        .env_remove("VERSYNC_VERSION")
+        .output()
+        .unwrap()
+}
+
+/// A git repository at `<home>/work` with the given files committed
+fn repository(files: &[(&str, &str)]) -> TempDir {
+    let home = tempfile::tempdir().unwrap();
+    let work = home.path().join("work");
+    for (name, content) in files {
+        let path = work.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+    git(&work, &["init", "-q", "-b", "main"]);
+    git(&work, &["add", "-A"]);
+    git(&work, &["commit", "-q", "-m", "Initial commit"]);
+    home
+}
+
+#[test]
+fn test_tag_checks_remote_past_the_cache() {
+    let config = format!("{}\n[git]\ncheck_remote = true\n", CONFIG);
+    let home = repository(&[
+        ("version.toml", &config),
+        ("Cargo.toml", "[package]\nversion = \"1.0.0\"\n"),
+    ]);
+    let work = home.path().join("work");
+    git(home.path(), &["init", "-q", "--bare", "remote.git"]);
+    git(&work, &["remote", "add", "origin", "../remote.git"]);
+
+    // Caches the remote's tag listing, which has no tags yet
+    let output = versync(home.path(), &work, &["tag", "--dry-run"]);
+    assert!(output.status.success(), "{:?}", output);
+
+    // Another machine pushes the tag
+    git(&work, &["tag", "v1.0.0"]);
+    git(&work, &["push", "-q", "origin", "v1.0.0"]);
+    git(&work, &["tag", "-d", "v1.0.0"]);
+
+    let output = versync(home.path(), &work, &["tag", "--dry-run"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Tag v1.0.0 already exists on remote 'origin'"));
+}
+
+#[test]
+fn test_status_reads_remote_tags_from_the_cache() {
+    let files = [
+        ("version.toml", CONFIG),
+        ("Cargo.toml", "[package]\nversion = \"1.0.0\"\n"),
+    ];
+    let home = repository(&files);
+    let work = home.path().join("work");
+    git(home.path(), &["init", "-q", "--bare", "remote.git"]);
+    git(&work, &["remote", "add", "origin", "../remote.git"]);
+
+    let output = versync(home.path(), &work, &["status"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("UNPUBLISHED v1.0.0 on 'origin'"));
+
+    // Pushed from elsewhere: the cached listing is used until --refresh
+    git(&work, &["tag", "v1.0.0"]);
+    git(&work, &["push", "-q", "origin", "v1.0.0"]);
+    let output = versync(home.path(), &work, &["status"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("UNPUBLISHED v1.0.0"));
+    let output = versync(home.path(), &work, &["status", "--refresh"]);
+    assert!(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .any(|line| line == "PUBLISHED v1.0.0 on 'origin'"));
+
+    // Another repository's `../remote.git` is another remote
+    let other = repository(&files);
+    let other_work = other.path().join("work");
+    git(other.path(), &["init", "-q", "--bare", "remote.git"]);
+    git(&other_work, &["remote", "add", "origin", "../remote.git"]);
+    let output = versync(home.path(), &other_work, &["status"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("UNPUBLISHED v1.0.0"));
+}
+
+#[test]
+fn test_paths_from_a_subdirectory() {
+    let config = format!(
+        "{}\n[[targets]]\nfile = \"crates/*/Cargo.toml\"\nkey = \"package.version\"\n",
+        CONFIG.replace("1.0.0", "0.1.0")
+    );
+    let home = repository(&[
+        ("version.toml", &config),
+        ("Cargo.toml", "[package]\nversion = \"0.1.0\"\n"),
+        ("crates/foo/Cargo.toml", "[package]\nversion = \"0.2.0\"\n"),
+        ("crates/foo/package.json", "{\"version\": \"0.2.0\"}\n"),
+    ]);
+    let work = home.path().join("work");
+    let foo = work.join("crates/foo");
+
+    let output = versync(
+        home.path(),
+        &foo,
+        &["target", "add", "package.json", "--key", "version"],
+    );
+    assert!(output.status.success(), "{:?}", output);
+    let config = fs::read_to_string(work.join("version.toml")).unwrap();
+    assert!(
+        config.contains("file = \"crates/foo/package.json\""),
+        "{}",
+        config
+    );
+
+    let output = versync(home.path(), &foo, &["adopt", "Cargo.toml"]);
+    assert!(output.status.success(), "{:?}", output);
+    let config = fs::read_to_string(work.join("version.toml")).unwrap();
+    assert!(config.starts_with("version = \"0.2.0\"\n"), "{}", config);
+
+    let output = versync(home.path(), &foo, &["apply"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(
+        fs::read_to_string(work.join("Cargo.toml")).unwrap(),
+        "[package]\nversion = \"0.2.0\"\n"
+    );
+
+    let output = versync(home.path(), &foo, &["changelog", "--write", "NOTES.md"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(foo.join("NOTES.md").exists());
+}
+
+#[test]
+fn test_counter_tag_at_head() {
+    let config = format!(
+        "{}\n[channels.hotfix]\ntag = \"{{prefix}}{{version}}-hotfix.{{n}}\"\n",
+        CONFIG
+    );
+    let home = repository(&[
+        ("version.toml", &config),
+        ("Cargo.toml", "[package]\nversion = \"1.0.0\"\n"),
+    ]);
+    let work = home.path().join("work");
+
+    let output = versync(
+        home.path(),
+        &work,
+        &["show", "--tag", "--channel", "hotfix"],
+    );
+    assert!(output.status.success(), "{:?}", output);
+    assert!(output.stdout.is_empty());
+    let output = versync(
+        home.path(),
+        &work,
+        &["export", "--format", "json", "--channel", "hotfix"],
+    );
+    assert!(output.status.success(), "{:?}", output);
+    assert!(String::from_utf8_lossy(&output.stdout).contains(r#""tag":null"#));
+    let output = versync(home.path(), &work, &["verify-tag", "--channel", "hotfix"]);
+    assert!(!output.status.success());
+
+    git(&work, &["tag", "v1.0.0-hotfix.2"]);
+    let output = versync(
+        home.path(),
+        &work,
+        &["show", "--tag", "--channel", "hotfix"],
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "v1.0.0-hotfix.2\n");
+    let output = versync(
+        home.path(),
+        &work,
+        &["export", "--format", "env", "--channel", "hotfix"],
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("TAG=v1.0.0-hotfix.2\n"));
+    let output = versync(
+        home.path(),
+        &work,
+        &["check", "--frozen", "--channel", "hotfix"],
+    );
+    assert!(output.status.success(), "{:?}", output);
+}
+
+#[test]
+fn test_export_in_scheme() {
+    let config = CONFIG.replace("\"1.0.0\"", "\"1.2.0-rc.1\"\nscheme = \"pep440\"");
+    let home = repository(&[
+        ("version.toml", &config),
+        ("Cargo.toml", "[package]\nversion = \"1.2.0rc1\"\n"),
+    ]);
+    let work = home.path().join("work");
+
+    let output = versync(home.path(), &work, &["export", "--format", "env"]);
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("VERSION=1.2.0rc1\n"), "{}", stdout);
+    assert!(stdout.contains("TAG=v1.2.0rc1\n"), "{}", stdout);
+}
+
+#[test]
+fn test_release_asks_before_pushing() {
+    let home = repository(&[
+        ("version.toml", CONFIG),
+        ("Cargo.toml", "[package]\nversion = \"1.0.0\"\n"),
+    ]);
+    let work = home.path().join("work");
+    git(home.path(), &["init", "-q", "--bare", "remote.git"]);
+    git(&work, &["remote", "add", "origin", "../remote.git"]);
+
+    // Without a terminal to answer the prompt, nothing is pushed
+    let output = versync(home.path(), &work, &["release"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Confirmation required: Push refs/tags/v1.0.0 to origin?"));
+    let remote = home.path().join("remote.git");
+    let tags = Command::new("git")
+        .args(["tag", "--list"])
+        .current_dir(&remote)
+        .output()
+        .unwrap();
+    assert!(tags.stdout.is_empty());
+
+    let output = versync(home.path(), &work, &["push", "--yes"]);
+    assert!(output.status.success(), "{:?}", output);
+}
+
+#[test]
+fn test_graduate_tag_checks_before_writing() {
+    let config = CONFIG.replace("1.0.0", "1.1.0-rc.1");
+    let home = repository(&[
+        ("version.toml", &config),
+        ("Cargo.toml", "[package]\nversion = \"1.1.0-rc.1\"\n"),
+    ]);
+    let work = home.path().join("work");
+    git(&work, &["tag", "v1.1.0"]);
+
+    let output = versync(home.path(), &work, &["graduate", "--tag"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("v1.1.0"));
+    assert_eq!(
+        fs::read_to_string(work.join("version.toml")).unwrap(),
+        config
+    );
+}
+
+#[test]
+fn test_changelog_in_scheme() {
+    let config = CONFIG.replace("\"1.0.0\"", "\"1.2.0+post.1\"\nscheme = \"pep440\"");
+    let home = repository(&[
+        ("version.toml", &config),
+        ("Cargo.toml", "[package]\nversion = \"1.2.0.post1\"\n"),
+    ]);
+    let work = home.path().join("work");
+    git(&work, &["tag", "v1.1.0"]);
+    git(
+        &work,
+        &["commit", "-q", "--allow-empty", "-m", "feat: add exports"],
+    );
+    git(&work, &["tag", "v1.2.0"]);
+    git(
+        &work,
+        &["commit", "-q", "--allow-empty", "-m", "fix: quote paths"],
+    );
+
+    // The post-release follows 1.2.0, which semver precedence would ignore
+    let output = versync(home.path(), &work, &["changelog"]);
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("quote paths"), "{}", stdout);
+    assert!(!stdout.contains("add exports"), "{}", stdout);
+}
+
+#[test]
+fn test_doctor_hint_names_the_config_directory() {
+    let config = format!(
+        "{}\n[[targets]]\nfile = \"missing.json\"\nkey = \"version\"\n",
+        CONFIG
+    );
+    let home = repository(&[
+        ("version.toml", &config),
+        ("Cargo.toml", "[package]\nversion = \"1.0.0\"\n"),
+        ("docs/README.md", "docs\n"),
+    ]);
+    let work = home.path().join("work");
+
+    let output = versync(home.path(), &work.join("docs"), &["doctor"]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout
+            .contains("paths are relative to the config file's directory (.., where it was found)"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn test_branch_pattern_outside_a_repository() {
+    let home = tempfile::tempdir().unwrap();
+    let config = format!(
+        "{}\n[git]\nbranch_pattern = '^release/{{major}}\\.x$'\n",
+        CONFIG
+    );
+    fs::write(home.path().join("version.toml"), config).unwrap();
+    fs::write(
+        home.path().join("Cargo.toml"),
+        "[package]\nversion = \"1.0.0\"\n",
+    )
+    .unwrap();
+
+    let output = versync(home.path(), home.path(), &["check"]);
+    assert!(output.status.success(), "{:?}", output);
+}
+
+#[test]
+fn test_compare_ref_with_an_absolute_config() {
+    let home = repository(&[
+        ("version.toml", CONFIG),
+        ("Cargo.toml", "[package]\nversion = \"1.0.0\"\n"),
+    ]);
+    let work = home.path().join("work");
+    fs::write(
+        work.join("version.toml"),
+        CONFIG.replace("\"1.0.0\"", "\"1.1.0\""),
+    )
+    .unwrap();
+    let config = work.join("version.toml");
+
+    let output = versync(
+        home.path(),
+        &work,
+        &[
+            "--config",
+            config.to_str().unwrap(),
+            "compare-ref",
+            "HEAD",
+            "--targets",
+        ],
+    );
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("version.toml: 1.1.0 > 1.0.0"), "{}", stdout);
+    assert!(
+        stdout.contains("Cargo.toml package.version: 1.1.0 > 1.0.0"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn test_bump_refuses_a_version_override() {
+    let home = repository(&[
+        ("version.toml", CONFIG),
+        ("Cargo.toml", "[package]\nversion = \"1.0.0\"\n"),
+    ]);
+    let work = home.path().join("work");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_versync"))
+        .args(["bump", "patch"])
+        .current_dir(&work)
+        .env("XDG_CACHE_HOME", home.path().join("cache"))
+        .env("VERSYNC_VERSION", "v2.0.0")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("bump writes the configured version and cannot run with a version override (unset VERSYNC_VERSION)"));
+    assert_eq!(
+        fs::read_to_string(work.join("version.toml")).unwrap(),
+        CONFIG
+    );
+
+    let output = versync(home.path(), &work, &["bump", "patch"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(fs::read_to_string(work.join("version.toml"))
+        .unwrap()
+        .starts_with("version = \"1.0.1\"\n"));
+}